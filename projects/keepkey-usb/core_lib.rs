@@ -6,3 +6,5 @@ pub mod transport;
 pub mod features;
 pub mod device_queue;
 pub mod chains;
+#[cfg(feature = "mock-device")]
+pub mod mock_device;
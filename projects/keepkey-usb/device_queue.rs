@@ -1,9 +1,13 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex as StdMutex};
 use std::time::{Duration, Instant};
 use tokio::sync::{mpsc, oneshot};
+use tokio::task::AbortHandle;
 use tokio::time::{timeout, sleep};
 use anyhow::{anyhow, Result};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use tracing::{info, warn, error, debug, instrument};
 
 use crate::messages::{Message, GetFeatures, GetAddress, Features};
@@ -656,38 +660,317 @@ impl DeviceWorker {
     }
 }
 
+/// How long a passphrase-unlocked session may stay cached on the device
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "snake_case")]
+pub enum PassphraseCachePolicy {
+    /// Clear the session as soon as the operation that needed the passphrase finishes
+    Never,
+    /// Keep the session until the device is locked, cleared or unplugged (firmware default)
+    UntilLock,
+    /// Clear the session this many minutes after the passphrase was entered
+    Minutes { minutes: u32 },
+}
+
+impl Default for PassphraseCachePolicy {
+    fn default() -> Self {
+        PassphraseCachePolicy::UntilLock
+    }
+}
+
+/// Per-device passphrase cache policies, keyed by device id
+static PASSPHRASE_POLICIES: Lazy<StdMutex<HashMap<String, PassphraseCachePolicy>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+
+/// Set the passphrase cache policy for a device; `None` restores the default
+pub fn set_passphrase_policy(device_id: &str, policy: Option<PassphraseCachePolicy>) {
+    let mut policies = PASSPHRASE_POLICIES.lock().unwrap();
+    match policy {
+        Some(policy) => { policies.insert(device_id.to_string(), policy); }
+        None => { policies.remove(device_id); }
+    }
+}
+
+/// Passphrase cache policy in effect for a device
+pub fn passphrase_policy(device_id: &str) -> PassphraseCachePolicy {
+    PASSPHRASE_POLICIES.lock().unwrap().get(device_id).copied().unwrap_or_default()
+}
+
+/// Current passphrase session of a device, as tracked by its queue
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassphraseSessionState {
+    pub policy: PassphraseCachePolicy,
+    /// Whether a passphrase was entered and the session not cleared since
+    pub cached: bool,
+    pub cached_for_secs: Option<u64>,
+    /// Seconds until the policy clears the session, for time-limited policies
+    pub expires_in_secs: Option<u64>,
+}
+
+/// Responses that continue an operation rather than finish it
+fn is_interactive_response(response: &Message) -> bool {
+    matches!(response,
+        Message::ButtonRequest(_) |
+        Message::PinMatrixRequest(_) |
+        Message::PassphraseRequest(_) |
+        Message::TxRequest(_) |
+        Message::EthereumTxRequest(_)
+    )
+}
+
+/// Requests that answer a device prompt within an ongoing operation
+fn is_interactive_ack(message: &Message) -> bool {
+    matches!(message,
+        Message::ButtonAck(_) |
+        Message::PinMatrixAck(_) |
+        Message::PassphraseAck(_) |
+        Message::TxAck(_) |
+        Message::EthereumTxAck(_)
+    )
+}
+
+/// Liveness of a queue worker, as observed from its handles
+#[derive(Debug)]
+struct QueueHealth {
+    last_response: Instant,
+    timeouts_since_response: u32,
+    /// Last time a command was sent or answered
+    last_activity: Instant,
+    /// Commands sent and not yet answered, timed out or dropped
+    in_flight: u32,
+}
+
+/// Latency samples kept per operation type
+const LATENCY_WINDOW: usize = 200;
+
+/// Recent round-trip times for one operation type
+#[derive(Debug, Default)]
+struct LatencySamples {
+    samples_ms: VecDeque<u64>,
+    count: u64,
+    failures: u64,
+}
+
+/// Rolling latency percentiles for one operation type, measured from the
+/// moment a command is queued until its reply arrives
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OperationLatency {
+    /// GetFeatures, GetAddress, or the raw message type, e.g. SignTx
+    pub operation: String,
+    /// Operations completed since the handle was created
+    pub count: u64,
+    /// Operations that failed or timed out
+    pub failures: u64,
+    pub p50_ms: u64,
+    pub p90_ms: u64,
+    pub p99_ms: u64,
+    pub max_ms: u64,
+}
+
+/// Nearest-rank percentile of sorted samples
+fn percentile(sorted: &[u64], pct: usize) -> u64 {
+    match sorted.len() {
+        0 => 0,
+        n => sorted[(n * pct).div_ceil(100).saturating_sub(1)],
+    }
+}
+
+/// Point-in-time view of a worker's liveness
+#[derive(Debug, Clone, Copy)]
+pub struct QueueHealthSnapshot {
+    /// Time since the worker last answered any command
+    pub since_last_response: Duration,
+    /// Commands that timed out or were dropped since that answer
+    pub timeouts_since_response: u32,
+    /// Commands sent and not yet answered, i.e. the queue depth
+    pub in_flight: u32,
+}
+
 /// Handle for communicating with a device worker
 #[derive(Clone, Debug)]
 pub struct DeviceQueueHandle {
     device_id: String,
     cmd_tx: mpsc::Sender<DeviceCmd>,
+    health: Arc<StdMutex<QueueHealth>>,
+    /// When the passphrase was last entered, if the session is still unlocked
+    passphrase_unlocked_at: Arc<StdMutex<Option<Instant>>>,
+    /// Operation type -> recent latencies
+    latencies: Arc<StdMutex<HashMap<String, LatencySamples>>>,
+    worker: Option<AbortHandle>,
 }
 
 impl DeviceQueueHandle {
     pub fn new(device_id: String, cmd_tx: mpsc::Sender<DeviceCmd>) -> Self {
-        Self { device_id, cmd_tx }
+        Self {
+            device_id,
+            cmd_tx,
+            health: Arc::new(StdMutex::new(QueueHealth {
+                last_response: Instant::now(),
+                timeouts_since_response: 0,
+                last_activity: Instant::now(),
+                in_flight: 0,
+            })),
+            passphrase_unlocked_at: Arc::new(StdMutex::new(None)),
+            latencies: Arc::new(StdMutex::new(HashMap::new())),
+            worker: None,
+        }
+    }
+    
+    /// Record whether the worker answered; an Ok outer result means it did,
+    /// even if the device itself reported an error
+    fn record_health(&self, answered: bool) {
+        if let Ok(mut health) = self.health.lock() {
+            health.in_flight = health.in_flight.saturating_sub(1);
+            health.last_activity = Instant::now();
+            if answered {
+                health.last_response = Instant::now();
+                health.timeouts_since_response = 0;
+            } else {
+                health.timeouts_since_response += 1;
+            }
+        }
+    }
+    
+    /// Queue a command for the worker, tracking it as in flight
+    async fn send_cmd(&self, cmd: DeviceCmd) -> Result<()> {
+        self.cmd_tx.send(cmd).await
+            .map_err(|_| anyhow!("Device worker unavailable"))?;
+        if let Ok(mut health) = self.health.lock() {
+            health.in_flight += 1;
+            health.last_activity = Instant::now();
+        }
+        Ok(())
+    }
+    
+    /// Record how long an operation took; failed operations only count as failures
+    fn record_latency(&self, operation: &str, elapsed: Duration, succeeded: bool) {
+        if let Ok(mut latencies) = self.latencies.lock() {
+            let entry = latencies.entry(operation.to_string()).or_default();
+            entry.count += 1;
+            if !succeeded {
+                entry.failures += 1;
+                return;
+            }
+            entry.samples_ms.push_back(elapsed.as_millis() as u64);
+            if entry.samples_ms.len() > LATENCY_WINDOW {
+                entry.samples_ms.pop_front();
+            }
+        }
+    }
+    
+    /// Rolling latency percentiles per operation type, busiest first
+    pub fn operation_latencies(&self) -> Vec<OperationLatency> {
+        let Ok(latencies) = self.latencies.lock() else {
+            return Vec::new();
+        };
+        let mut result: Vec<OperationLatency> = latencies
+            .iter()
+            .map(|(operation, entry)| {
+                let mut sorted: Vec<u64> = entry.samples_ms.iter().copied().collect();
+                sorted.sort_unstable();
+                OperationLatency {
+                    operation: operation.clone(),
+                    count: entry.count,
+                    failures: entry.failures,
+                    p50_ms: percentile(&sorted, 50),
+                    p90_ms: percentile(&sorted, 90),
+                    p99_ms: percentile(&sorted, 99),
+                    max_ms: sorted.last().copied().unwrap_or(0),
+                }
+            })
+            .collect();
+        result.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.operation.cmp(&b.operation)));
+        result
+    }
+    
+    /// Wait for the worker's reply, tracking liveness and latency
+    async fn await_reply<T>(&self, operation: &str, rx: oneshot::Receiver<Result<T>>, limit: Duration, timeout_msg: &str) -> Result<T> {
+        let started = Instant::now();
+        match timeout(limit, rx).await {
+            Ok(Ok(result)) => {
+                self.record_health(true);
+                self.record_latency(operation, started.elapsed(), result.is_ok());
+                result
+            }
+            Ok(Err(_)) => {
+                self.record_health(false);
+                self.record_latency(operation, started.elapsed(), false);
+                Err(anyhow!("Device worker channel closed"))
+            }
+            Err(_) => {
+                self.record_health(false);
+                self.record_latency(operation, started.elapsed(), false);
+                Err(anyhow!("{}", timeout_msg))
+            }
+        }
+    }
+    
+    /// Current liveness of the worker behind this handle
+    pub fn health(&self) -> QueueHealthSnapshot {
+        match self.health.lock() {
+            Ok(health) => QueueHealthSnapshot {
+                since_last_response: health.last_response.elapsed(),
+                timeouts_since_response: health.timeouts_since_response,
+                in_flight: health.in_flight,
+            },
+            Err(_) => QueueHealthSnapshot {
+                since_last_response: Duration::ZERO,
+                timeouts_since_response: 0,
+                in_flight: 0,
+            },
+        }
+    }
+    
+    /// A worker is wedged when its task is gone, or when commands keep timing
+    /// out and it hasn't answered for `threshold`
+    pub fn is_wedged(&self, threshold: Duration) -> bool {
+        if self.cmd_tx.is_closed() {
+            return true;
+        }
+        let health = self.health();
+        health.timeouts_since_response > 0 && health.since_last_response >= threshold
+    }
+    
+    /// A worker is idle when nothing is in flight and no command was sent or
+    /// answered for `threshold`
+    pub fn is_idle(&self, threshold: Duration) -> bool {
+        if self.cmd_tx.is_closed() {
+            return false;
+        }
+        match self.health.lock() {
+            Ok(health) => health.in_flight == 0 && health.last_activity.elapsed() >= threshold,
+            Err(_) => false,
+        }
+    }
+    
+    /// Forcefully stop the worker task, e.g. when it is wedged and can't process Shutdown
+    pub fn abort_worker(&self) {
+        if let Some(worker) = &self.worker {
+            worker.abort();
+        }
     }
     
     /// Get device features
     #[instrument(level = "debug", skip(self))]
     pub async fn get_features(&self) -> Result<Features> {
+        self.enforce_passphrase_policy().await?;
         let (tx, rx) = oneshot::channel();
         let cmd = DeviceCmd::GetFeatures {
             respond_to: tx,
             enqueued_at: Instant::now(),
         };
         
-        self.cmd_tx.send(cmd).await
-            .map_err(|_| anyhow!("Device worker unavailable"))?;
+        self.send_cmd(cmd).await?;
             
-        timeout(DEVICE_OPERATION_TIMEOUT, rx).await
-            .map_err(|_| anyhow!("Device operation timed out"))?
-            .map_err(|_| anyhow!("Device worker channel closed"))?
+        self.await_reply("GetFeatures", rx, DEVICE_OPERATION_TIMEOUT, "Device operation timed out").await
     }
     
     /// Get address for given path
     #[instrument(level = "debug", skip(self))]
     pub async fn get_address(&self, path: Vec<u32>, coin_name: String, script_type: Option<i32>, show_display: Option<bool>) -> Result<String> {
+        self.enforce_passphrase_policy().await?;
         let (tx, rx) = oneshot::channel();
         let cmd = DeviceCmd::GetAddress {
             path,
@@ -698,17 +981,96 @@ impl DeviceQueueHandle {
             enqueued_at: Instant::now(),
         };
         
-        self.cmd_tx.send(cmd).await
-            .map_err(|_| anyhow!("Device worker unavailable"))?;
+        self.send_cmd(cmd).await?;
             
-        timeout(DEVICE_OPERATION_TIMEOUT, rx).await
-            .map_err(|_| anyhow!("Device operation timed out"))?
-            .map_err(|_| anyhow!("Device worker channel closed"))?
+        self.await_reply("GetAddress", rx, DEVICE_OPERATION_TIMEOUT, "Device operation timed out").await
     }
     
     /// Send raw message to device
     #[instrument(level = "debug", skip(self, message))]
     pub async fn send_raw(&self, message: Message, bypass_cache: bool) -> Result<Message> {
+        // Don't clear the session in the middle of a multi-step operation
+        if !is_interactive_ack(&message) {
+            self.enforce_passphrase_policy().await?;
+        }
+        
+        let is_passphrase_ack = matches!(message, Message::PassphraseAck(_));
+        let is_clear_session = matches!(message, Message::ClearSession(_));
+        let response = self.send_raw_unchecked(message, bypass_cache).await?;
+        
+        {
+            let mut unlocked_at = self.passphrase_unlocked_at.lock().unwrap();
+            if is_passphrase_ack {
+                unlocked_at.get_or_insert_with(Instant::now);
+            } else if is_clear_session {
+                *unlocked_at = None;
+            }
+        }
+        
+        let unlocked = self.passphrase_unlocked_at.lock().unwrap().is_some();
+        if unlocked
+            && !is_interactive_response(&response)
+            && passphrase_policy(&self.device_id) == PassphraseCachePolicy::Never
+        {
+            // The operation itself succeeded; a failed clear is retried on the next command
+            if let Err(e) = self.clear_passphrase_session().await {
+                warn!("⚠️ Failed to clear passphrase session for {}: {}", self.device_id, e);
+            }
+        }
+        
+        Ok(response)
+    }
+    
+    /// Clear the device session if the passphrase cache policy says it has expired.
+    /// Every handle method that talks to a possibly unlocked session calls this first.
+    pub async fn enforce_passphrase_policy(&self) -> Result<()> {
+        let unlocked_at = *self.passphrase_unlocked_at.lock().unwrap();
+        let Some(unlocked_at) = unlocked_at else {
+            return Ok(());
+        };
+        
+        let expired = match passphrase_policy(&self.device_id) {
+            // Never is enforced when the operation finishes, see send_raw
+            PassphraseCachePolicy::Never | PassphraseCachePolicy::UntilLock => false,
+            PassphraseCachePolicy::Minutes { minutes } => {
+                unlocked_at.elapsed() >= Duration::from_secs(minutes as u64 * 60)
+            }
+        };
+        if expired {
+            info!("🔒 Passphrase cache for {} expired", self.device_id);
+            self.clear_passphrase_session().await?;
+        }
+        Ok(())
+    }
+    
+    /// Forget the cached passphrase (and PIN) on the device
+    async fn clear_passphrase_session(&self) -> Result<()> {
+        self.send_raw_unchecked(crate::messages::ClearSession {}.into(), true).await?;
+        *self.passphrase_unlocked_at.lock().unwrap() = None;
+        Ok(())
+    }
+    
+    /// Passphrase session state and the policy applied to it
+    pub fn passphrase_state(&self) -> PassphraseSessionState {
+        let policy = passphrase_policy(&self.device_id);
+        let cached_for = self.passphrase_unlocked_at.lock().unwrap().map(|at| at.elapsed());
+        let expires_in = match (policy, cached_for) {
+            (PassphraseCachePolicy::Minutes { minutes }, Some(cached_for)) => {
+                Some(Duration::from_secs(minutes as u64 * 60).saturating_sub(cached_for).as_secs())
+            }
+            _ => None,
+        };
+        
+        PassphraseSessionState {
+            policy,
+            cached: cached_for.is_some(),
+            cached_for_secs: cached_for.map(|d| d.as_secs()),
+            expires_in_secs: expires_in,
+        }
+    }
+    
+    async fn send_raw_unchecked(&self, message: Message, bypass_cache: bool) -> Result<Message> {
+        let operation = format!("{:?}", message.message_type());
         let (tx, rx) = oneshot::channel();
         let cmd = DeviceCmd::SendRaw {
             message,
@@ -717,12 +1079,9 @@ impl DeviceQueueHandle {
             bypass_cache,
         };
         
-        self.cmd_tx.send(cmd).await
-            .map_err(|_| anyhow!("Device worker unavailable"))?;
+        self.send_cmd(cmd).await?;
             
-        timeout(DEVICE_OPERATION_TIMEOUT, rx).await
-            .map_err(|_| anyhow!("Device operation timed out"))?
-            .map_err(|_| anyhow!("Device worker channel closed"))?
+        self.await_reply(&operation, rx, DEVICE_OPERATION_TIMEOUT, "Device operation timed out").await
     }
     
     /// Update device bootloader
@@ -736,13 +1095,10 @@ impl DeviceQueueHandle {
             enqueued_at: Instant::now(),
         };
         
-        self.cmd_tx.send(cmd).await
-            .map_err(|_| anyhow!("Device worker unavailable"))?;
+        self.send_cmd(cmd).await?;
             
         // Use longer timeout for firmware operations (2 minutes)
-        timeout(Duration::from_secs(120), rx).await
-            .map_err(|_| anyhow!("Bootloader update timed out"))?
-            .map_err(|_| anyhow!("Device worker channel closed"))?
+        self.await_reply("UpdateBootloader", rx, Duration::from_secs(120), "Bootloader update timed out").await
     }
     
     /// Update device firmware
@@ -756,13 +1112,10 @@ impl DeviceQueueHandle {
             enqueued_at: Instant::now(),
         };
         
-        self.cmd_tx.send(cmd).await
-            .map_err(|_| anyhow!("Device worker unavailable"))?;
+        self.send_cmd(cmd).await?;
             
         // Use longer timeout for firmware operations (2 minutes)
-        timeout(Duration::from_secs(120), rx).await
-            .map_err(|_| anyhow!("Firmware update timed out"))?
-            .map_err(|_| anyhow!("Device worker channel closed"))?
+        self.await_reply("UpdateFirmware", rx, Duration::from_secs(120), "Firmware update timed out").await
     }
     
     /// Shutdown the device worker
@@ -770,12 +1123,9 @@ impl DeviceQueueHandle {
         let (tx, rx) = oneshot::channel();
         let cmd = DeviceCmd::Shutdown { respond_to: tx };
         
-        self.cmd_tx.send(cmd).await
-            .map_err(|_| anyhow!("Device worker unavailable"))?;
+        self.send_cmd(cmd).await?;
             
-        timeout(Duration::from_secs(5), rx).await
-            .map_err(|_| anyhow!("Shutdown timed out"))?
-            .map_err(|_| anyhow!("Device worker channel closed"))?
+        self.await_reply("Shutdown", rx, Duration::from_secs(5), "Shutdown timed out").await
     }
     
     pub fn device_id(&self) -> &str {
@@ -794,13 +1144,20 @@ impl DeviceQueueFactory {
         let worker = DeviceWorker::new(device_id.clone(), device_info, cmd_rx);
         
         // Spawn the worker task
-        tokio::spawn(worker.run());
+        let task = tokio::spawn(worker.run());
         
-        DeviceQueueHandle::new(device_id, cmd_tx)
+        let mut handle = DeviceQueueHandle::new(device_id, cmd_tx);
+        handle.worker = Some(task.abort_handle());
+        handle
     }
     
     /// Create transport with WebUSB/USB/HID auto-detection
     pub fn create_transport_for_device(device_info: &FriendlyUsbDevice) -> Result<Box<dyn ProtocolAdapter + Send>> {
+        #[cfg(feature = "mock-device")]
+        if crate::mock_device::is_mock_device(&device_info.unique_id) {
+            return Ok(Box::new(crate::mock_device::MockTransport::new(device_info.unique_id.clone())));
+        }
+        
         // Find physical device for transport
         let devices = crate::features::list_devices();
         let physical_device = Self::find_physical_device_by_info(device_info, &devices)?;
@@ -700,6 +700,9 @@ pub fn list_connected_devices() -> Vec<FriendlyUsbDevice> {
         }
     }
     
+    #[cfg(feature = "mock-device")]
+    current_devices.extend(crate::mock_device::list_mock_devices());
+    
     current_devices
 }
 
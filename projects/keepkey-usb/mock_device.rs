@@ -0,0 +1,285 @@
+//! Virtual KeepKey for development and integration tests (feature `mock-device`).
+//!
+//! Mock devices show up in `list_connected_devices()` alongside real hardware and
+//! are served by [`MockTransport`] instead of USB. Keys come from a fixed test
+//! mnemonic so addresses and xpubs are deterministic, and every request is
+//! answered immediately without button presses.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use anyhow::{anyhow, Result};
+use bitcoin::bip32::{ChildNumber, DerivationPath, ExtendedPrivKey, ExtendedPubKey};
+use bitcoin::secp256k1::{All, Secp256k1};
+use bitcoin::Network;
+use once_cell::sync::Lazy;
+
+use crate::friendly_usb::{FriendlyUsbDevice, KEEPKEY_VID};
+use crate::messages::{self, Message};
+use crate::transport::ProtocolAdapter;
+
+/// Well-known test mnemonic backing every mock device
+pub const MOCK_MNEMONIC: &str = "all all all all all all all all all all all all";
+
+/// Prefix of mock device ids, so they never collide with USB serials
+pub const MOCK_DEVICE_PREFIX: &str = "mock-";
+
+/// Firmware version reported by mock devices
+const MOCK_FIRMWARE_VERSION: (u32, u32, u32) = (7, 10, 0);
+
+/// KeepKey product id reported by mock devices
+const MOCK_PID: u16 = 0x0002;
+
+// SpendAddress / SpendWitness / SpendP2SHWitness input script types
+const SCRIPT_SPEND_ADDRESS: i32 = 0;
+const SCRIPT_SPEND_WITNESS: i32 = 3;
+const SCRIPT_SPEND_P2SH_WITNESS: i32 = 4;
+
+#[derive(Debug, Clone)]
+struct MockDeviceState {
+    device: FriendlyUsbDevice,
+    label: String,
+}
+
+static MOCK_DEVICES: Lazy<Mutex<HashMap<String, MockDeviceState>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+static MOCK_MASTER_KEY: Lazy<ExtendedPrivKey> = Lazy::new(|| {
+    let mnemonic = bip39::Mnemonic::parse(MOCK_MNEMONIC).expect("mock mnemonic is valid");
+    ExtendedPrivKey::new_master(Network::Bitcoin, &mnemonic.to_seed(""))
+        .expect("mock seed produces a master key")
+});
+
+/// Register a new virtual KeepKey and return it as a connected device
+pub fn register_mock_device(label: Option<String>) -> FriendlyUsbDevice {
+    let mut devices = MOCK_DEVICES.lock().unwrap();
+
+    let mut index = devices.len() + 1;
+    while devices.contains_key(&format!("{}{}", MOCK_DEVICE_PREFIX, index)) {
+        index += 1;
+    }
+    let unique_id = format!("{}{}", MOCK_DEVICE_PREFIX, index);
+
+    let device = FriendlyUsbDevice::new(
+        unique_id.clone(),
+        KEEPKEY_VID,
+        MOCK_PID,
+        Some("KeepKey (mock)".to_string()),
+        Some("KeepKey".to_string()),
+        Some(unique_id.clone()),
+    );
+    let label = label.unwrap_or_else(|| format!("Mock KeepKey {}", index));
+
+    devices.insert(unique_id, MockDeviceState { device: device.clone(), label });
+    device
+}
+
+/// Remove a virtual KeepKey; returns false if it did not exist
+pub fn remove_mock_device(device_id: &str) -> bool {
+    MOCK_DEVICES.lock().unwrap().remove(device_id).is_some()
+}
+
+/// All currently registered virtual KeepKeys
+pub fn list_mock_devices() -> Vec<FriendlyUsbDevice> {
+    let mut devices: Vec<FriendlyUsbDevice> = MOCK_DEVICES
+        .lock()
+        .unwrap()
+        .values()
+        .map(|state| state.device.clone())
+        .collect();
+    devices.sort_by(|a, b| a.unique_id.cmp(&b.unique_id));
+    devices
+}
+
+/// Whether a device id belongs to a registered virtual KeepKey
+pub fn is_mock_device(device_id: &str) -> bool {
+    MOCK_DEVICES.lock().unwrap().contains_key(device_id)
+}
+
+/// Protocol adapter answering requests for a virtual KeepKey
+pub struct MockTransport {
+    device_id: String,
+    secp: Secp256k1<All>,
+}
+
+impl MockTransport {
+    pub fn new(device_id: String) -> Self {
+        Self {
+            device_id,
+            secp: Secp256k1::new(),
+        }
+    }
+
+    fn features(&self) -> Result<messages::Features> {
+        let label = MOCK_DEVICES
+            .lock()
+            .unwrap()
+            .get(&self.device_id)
+            .map(|state| state.label.clone())
+            .ok_or_else(|| anyhow!("Mock device {} was removed", self.device_id))?;
+
+        Ok(messages::Features {
+            vendor: Some("keepkey.com".to_string()),
+            major_version: Some(MOCK_FIRMWARE_VERSION.0),
+            minor_version: Some(MOCK_FIRMWARE_VERSION.1),
+            patch_version: Some(MOCK_FIRMWARE_VERSION.2),
+            bootloader_mode: Some(false),
+            device_id: Some(self.device_id.clone()),
+            pin_protection: Some(false),
+            passphrase_protection: Some(false),
+            label: Some(label),
+            initialized: Some(true),
+            pin_cached: Some(true),
+            passphrase_cached: Some(true),
+            model: Some("K1-14AM".to_string()),
+            firmware_variant: Some("Mock".to_string()),
+            ..Default::default()
+        })
+    }
+
+    fn derive(&self, address_n: &[u32]) -> Result<ExtendedPubKey> {
+        let path = DerivationPath::from(address_n.iter().map(|&i| ChildNumber::from(i)).collect::<Vec<_>>());
+        let xprv = MOCK_MASTER_KEY.derive_priv(&self.secp, &path)?;
+        Ok(ExtendedPubKey::from_priv(&self.secp, &xprv))
+    }
+
+    fn get_public_key(&self, req: &messages::GetPublicKey) -> Result<Message> {
+        if let Some(curve) = req.ecdsa_curve_name.as_deref() {
+            if curve != "secp256k1" {
+                return Ok(failure(&format!("Mock device does not support curve {}", curve)));
+            }
+        }
+
+        let xpub = self.derive(&req.address_n)?;
+        Ok(messages::PublicKey {
+            node: messages::HdNodeType {
+                depth: xpub.depth as u32,
+                fingerprint: u32::from_be_bytes(xpub.parent_fingerprint.to_bytes()),
+                child_num: u32::from(xpub.child_number),
+                chain_code: xpub.chain_code.to_bytes().to_vec(),
+                private_key: None,
+                public_key: Some(xpub.public_key.serialize().to_vec()),
+            },
+            xpub: Some(xpub.to_string()),
+        }
+        .into())
+    }
+
+    fn get_address(&self, req: &messages::GetAddress) -> Result<Message> {
+        let network = match req.coin_name.as_deref().unwrap_or("Bitcoin") {
+            "Bitcoin" => Network::Bitcoin,
+            "Testnet" => Network::Testnet,
+            other => return Ok(failure(&format!("Mock device does not support coin {}", other))),
+        };
+
+        let public_key = bitcoin::PublicKey::new(self.derive(&req.address_n)?.public_key);
+        let address = match req.script_type.unwrap_or(SCRIPT_SPEND_ADDRESS) {
+            SCRIPT_SPEND_ADDRESS => bitcoin::Address::p2pkh(&public_key, network),
+            SCRIPT_SPEND_WITNESS => bitcoin::Address::p2wpkh(&public_key, network)?,
+            SCRIPT_SPEND_P2SH_WITNESS => bitcoin::Address::p2shwpkh(&public_key, network)?,
+            other => return Ok(failure(&format!("Mock device does not support script type {}", other))),
+        };
+
+        Ok(messages::Address { address: address.to_string() }.into())
+    }
+
+    fn ethereum_get_address(&self, req: &messages::EthereumGetAddress) -> Result<Message> {
+        let public_key = self.derive(&req.address_n)?.public_key.serialize_uncompressed();
+        let hash = ethers_core::utils::keccak256(&public_key[1..]);
+
+        Ok(messages::EthereumAddress {
+            address: hash[12..].to_vec(),
+            ..Default::default()
+        }
+        .into())
+    }
+
+    fn apply_settings(&self, req: &messages::ApplySettings) -> Result<Message> {
+        if let Some(label) = &req.label {
+            if let Some(state) = MOCK_DEVICES.lock().unwrap().get_mut(&self.device_id) {
+                state.label = label.clone();
+            }
+        }
+        Ok(success("Settings applied"))
+    }
+}
+
+fn success(message: &str) -> Message {
+    messages::Success {
+        message: Some(message.to_string()),
+        ..Default::default()
+    }
+    .into()
+}
+
+fn failure(message: &str) -> Message {
+    messages::Failure {
+        message: Some(message.to_string()),
+        ..Default::default()
+    }
+    .into()
+}
+
+impl ProtocolAdapter for MockTransport {
+    fn reset(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn send(&mut self, msg: Message) -> Result<()> {
+        crate::transport::trace::trace_message(crate::transport::trace::TraceDirection::Send, &msg, msg.encoded_len());
+        Ok(())
+    }
+
+    fn handle(&mut self, msg: Message) -> Result<Message> {
+        self.send(msg.clone())?;
+
+        let response = match &msg {
+            Message::Initialize(_) | Message::GetFeatures(_) => self.features()?.into(),
+            Message::Ping(ping) => success(ping.message.as_deref().unwrap_or("")),
+            Message::ClearSession(_) => success("Session cleared"),
+            Message::ApplySettings(req) => self.apply_settings(req)?,
+            Message::GetPublicKey(req) => self.get_public_key(req)?,
+            Message::GetAddress(req) => self.get_address(req)?,
+            Message::EthereumGetAddress(req) => self.ethereum_get_address(req)?,
+            other => failure(&format!("Mock device does not support {:?}", other.message_type())),
+        };
+
+        crate::transport::trace::trace_message(crate::transport::trace::TraceDirection::Receive, &response, response.encoded_len());
+        Ok(response)
+    }
+
+    fn as_mut_dyn(&mut self) -> &mut dyn ProtocolAdapter {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mock_device_is_deterministic() {
+        let device = register_mock_device(Some("Test".to_string()));
+        assert!(is_mock_device(&device.unique_id));
+
+        let mut transport = MockTransport::new(device.unique_id.clone());
+        let request = messages::GetAddress {
+            address_n: vec![0x8000002C, 0x80000000, 0x80000000, 0, 0],
+            coin_name: Some("Bitcoin".to_string()),
+            script_type: Some(SCRIPT_SPEND_ADDRESS),
+            ..Default::default()
+        };
+
+        let first = transport.handle(request.clone().into()).unwrap();
+        let second = transport.handle(request.into()).unwrap();
+        match (first, second) {
+            (Message::Address(a), Message::Address(b)) => {
+                assert!(a.address.starts_with('1'));
+                assert_eq!(a.address, b.address);
+            }
+            other => panic!("unexpected responses: {:?}", other),
+        }
+
+        assert!(remove_mock_device(&device.unique_id));
+        assert!(!is_mock_device(&device.unique_id));
+    }
+}
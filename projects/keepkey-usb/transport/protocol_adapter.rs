@@ -23,6 +23,7 @@ where
         msg.encode(&mut out_buf)?;
         
         debug!("ProtocolAdapter::send: Encoded message size: {} bytes", out_buf.len());
+        super::trace::trace_message(super::trace::TraceDirection::Send, &msg, out_buf.len());
         
         self.write(&out_buf, msg.write_timeout())?;
 
@@ -45,7 +46,9 @@ where
         
         info!("ProtocolAdapter::handle: Received {} bytes response", in_buf.len());
 
+        let response_size = in_buf.len();
         let out = Message::decode(&mut in_buf.as_slice()).map_err(|x| anyhow!(x))?;
+        super::trace::trace_message(super::trace::TraceDirection::Receive, &out, response_size);
         info!("ProtocolAdapter::handle: Decoded response type: {:?}", out.message_type());
         
         // Clean, concise logging with key info
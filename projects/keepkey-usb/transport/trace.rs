@@ -0,0 +1,33 @@
+use crate::messages::Message;
+use once_cell::sync::Lazy;
+use std::sync::{Arc, RwLock};
+
+/// Direction of a traced protocol message
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraceDirection {
+    Send,
+    Receive,
+}
+
+/// Callback receiving every message exchanged with the device and its encoded size
+pub type TraceSink = Arc<dyn Fn(TraceDirection, &Message, usize) + Send + Sync>;
+
+static TRACE_SINK: Lazy<RwLock<Option<TraceSink>>> = Lazy::new(|| RwLock::new(None));
+
+/// Install (or with `None`, remove) the protocol trace sink
+pub fn set_trace_sink(sink: Option<TraceSink>) {
+    if let Ok(mut current) = TRACE_SINK.write() {
+        *current = sink;
+    }
+}
+
+/// Hand a message to the trace sink, if one is installed
+pub(crate) fn trace_message(direction: TraceDirection, msg: &Message, size: usize) {
+    let sink = match TRACE_SINK.read() {
+        Ok(sink) => sink.clone(),
+        Err(_) => return,
+    };
+    if let Some(sink) = sink {
+        sink(direction, msg, size);
+    }
+}
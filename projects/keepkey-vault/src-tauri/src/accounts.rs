@@ -0,0 +1,141 @@
+//! Names for device accounts.
+//!
+//! Accounts are otherwise only identified by derivation path. A name such as
+//! "Cold savings", with an optional description and color, is stored per
+//! device and account path, and attached wherever accounts are listed.
+
+use std::collections::HashMap;
+use serde::Deserialize;
+use utoipa::ToSchema;
+
+use crate::cache::{AccountMetadata, CacheManager};
+
+/// Longest account name accepted, in characters
+const MAX_NAME_LEN: usize = 48;
+
+/// Longest description accepted, in characters
+const MAX_DESCRIPTION_LEN: usize = 280;
+
+/// Name and metadata as submitted by the user
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountNameRequest {
+    pub device_id: String,
+    /// Account derivation path, e.g. m/84'/0'/0'
+    pub account_path: String,
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// "#rrggbb"
+    #[serde(default)]
+    pub color: Option<String>,
+}
+
+fn is_hex_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Trim and check a request, returning the cleaned-up values
+fn validate(request: AccountNameRequest) -> Result<AccountNameRequest, String> {
+    let account_path = request.account_path.trim().to_string();
+    crate::commands::parse_derivation_path(&account_path)?;
+
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err("Account name is required".to_string());
+    }
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("Account name must be at most {} characters", MAX_NAME_LEN));
+    }
+
+    let description = request.description.map(|d| d.trim().to_string()).filter(|d| !d.is_empty());
+    if description.as_ref().is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LEN) {
+        return Err(format!("Description must be at most {} characters", MAX_DESCRIPTION_LEN));
+    }
+
+    let color = request.color.map(|c| c.trim().to_lowercase()).filter(|c| !c.is_empty());
+    if color.as_ref().is_some_and(|c| !is_hex_color(c)) {
+        return Err("Color must look like #rrggbb".to_string());
+    }
+
+    Ok(AccountNameRequest { device_id: request.device_id, account_path, name, description, color })
+}
+
+pub async fn list(cache: &CacheManager, device_id: Option<&str>) -> Result<Vec<AccountMetadata>, String> {
+    cache
+        .list_account_metadata(device_id)
+        .await
+        .map_err(|e| format!("Failed to list account names: {}", e))
+}
+
+/// Account path -> name for one device
+pub async fn names(cache: &CacheManager, device_id: &str) -> HashMap<String, String> {
+    list(cache, Some(device_id))
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .map(|account| (account.account_path, account.name))
+        .collect()
+}
+
+/// Name an account, or rename it
+pub async fn set(cache: &CacheManager, request: AccountNameRequest) -> Result<AccountMetadata, String> {
+    let request = validate(request)?;
+    let now = chrono::Utc::now().timestamp();
+    let created_at = list(cache, Some(&request.device_id))
+        .await?
+        .into_iter()
+        .find(|account| account.account_path == request.account_path)
+        .map_or(now, |account| account.created_at);
+
+    let account = AccountMetadata {
+        device_id: request.device_id,
+        account_path: request.account_path,
+        name: request.name,
+        description: request.description,
+        color: request.color,
+        created_at,
+        updated_at: now,
+    };
+    cache
+        .save_account_metadata(&account)
+        .await
+        .map_err(|e| format!("Failed to save account name: {}", e))?;
+    Ok(account)
+}
+
+/// Returns whether the account had a name
+pub async fn remove(cache: &CacheManager, device_id: &str, account_path: &str) -> Result<bool, String> {
+    cache
+        .delete_account_metadata(device_id, account_path.trim())
+        .await
+        .map_err(|e| format!("Failed to delete account name: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(name: &str, color: Option<&str>) -> AccountNameRequest {
+        AccountNameRequest {
+            device_id: "kk".to_string(),
+            account_path: " m/84'/0'/0' ".to_string(),
+            name: name.to_string(),
+            description: Some("  ".to_string()),
+            color: color.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        let cleaned = validate(request("  Cold savings ", Some("#A0B1C2"))).unwrap();
+        assert_eq!(cleaned.name, "Cold savings");
+        assert_eq!(cleaned.account_path, "m/84'/0'/0'");
+        assert_eq!(cleaned.description, None);
+        assert_eq!(cleaned.color.as_deref(), Some("#a0b1c2"));
+
+        assert!(validate(request(" ", None)).is_err());
+        assert!(validate(request("Cold", Some("red"))).is_err());
+        assert!(validate(AccountNameRequest { account_path: "m/84'/x".to_string(), ..request("Cold", None) }).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Per-device activity timeline.
+//!
+//! Device lifecycle events and completed signatures are recorded in the cache
+//! and merged with the device's recorded errors into one chronological feed.
+
+use std::sync::Arc;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use utoipa::ToSchema;
+
+use crate::cache::{ActivityEntry, CacheManager};
+
+/// Oldest entries beyond this are dropped, per device
+const MAX_ENTRIES_PER_DEVICE: usize = 1000;
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+#[derive(Debug, Clone, Copy)]
+pub enum ActivityKind {
+    Connected,
+    Disconnected,
+    FirmwareUpdated,
+    LabelChanged,
+    SettingsChanged,
+    Signed,
+}
+
+impl ActivityKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ActivityKind::Connected => "connected",
+            ActivityKind::Disconnected => "disconnected",
+            ActivityKind::FirmwareUpdated => "firmware-updated",
+            ActivityKind::LabelChanged => "label-changed",
+            ActivityKind::SettingsChanged => "settings-changed",
+            ActivityKind::Signed => "signed",
+        }
+    }
+}
+
+/// One item of the merged timeline
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineItem {
+    /// Activity kind, or "error" for recorded failures
+    pub kind: String,
+    pub summary: String,
+    #[schema(value_type = Object)]
+    pub details: Option<serde_json::Value>,
+    pub correlation_id: Option<String>,
+    pub at: i64,
+}
+
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// The cache, if it has been opened; recording never opens it
+fn cache() -> Option<Arc<CacheManager>> {
+    let app = APP_HANDLE.get()?;
+    app.try_state::<Arc<once_cell::sync::OnceCell<Arc<CacheManager>>>>()?
+        .get()
+        .cloned()
+}
+
+/// Record an event for a device's timeline
+pub async fn record(device_id: &str, kind: ActivityKind, summary: impl Into<String>, details: Option<serde_json::Value>) {
+    let Some(cache) = cache() else {
+        return;
+    };
+    let entry = ActivityEntry {
+        id: 0,
+        device_id: device_id.to_string(),
+        kind: kind.as_str().to_string(),
+        summary: summary.into(),
+        details: details.map(|d| d.to_string()),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    if let Err(e) = cache.save_activity(&entry, MAX_ENTRIES_PER_DEVICE).await {
+        log::warn!("Failed to record {} activity for {}: {}", entry.kind, device_id, e);
+    }
+}
+
+/// Recorded activity and errors of a device, newest first
+pub async fn timeline(cache: &CacheManager, device_id: &str, limit: Option<usize>) -> Result<Vec<TimelineItem>, String> {
+    let limit = limit.unwrap_or(200).min(MAX_ENTRIES_PER_DEVICE);
+    let activity = cache
+        .list_activity(device_id, limit)
+        .await
+        .map_err(|e| format!("Failed to read activity: {}", e))?;
+    let errors = cache
+        .list_device_errors(device_id, limit)
+        .await
+        .map_err(|e| format!("Failed to read errors: {}", e))?;
+
+    let mut items: Vec<TimelineItem> = activity
+        .into_iter()
+        .map(|entry| TimelineItem {
+            kind: entry.kind,
+            summary: entry.summary,
+            details: entry.details.and_then(|d| serde_json::from_str(&d).ok()),
+            correlation_id: None,
+            at: entry.created_at,
+        })
+        .chain(errors.into_iter().map(|error| TimelineItem {
+            kind: "error".to_string(),
+            summary: error.message,
+            details: Some(serde_json::json!({ "category": error.category })),
+            correlation_id: error.correlation_id,
+            at: error.created_at,
+        }))
+        .collect();
+
+    items.sort_by(|a, b| b.at.cmp(&a.at));
+    items.truncate(limit);
+    Ok(items)
+}
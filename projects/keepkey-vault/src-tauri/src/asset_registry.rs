@@ -0,0 +1,306 @@
+//! Signed remote asset/path registry.
+//!
+//! The derivation paths frontload walks are bundled as default-paths.json.
+//! When a registry URL is configured, a signed manifest is fetched, verified
+//! against the publisher keys pinned into the build (plus any extra keys from
+//! preferences) and stored in the cache, and its paths replace the bundled ones
+//! without a vault release. A manifest whose version is older than the bundled
+//! registry, or not newer than the last accepted one, is rejected.
+
+use std::sync::RwLock;
+use std::time::Duration;
+use base64::Engine;
+use k256::ecdsa::signature::Verifier;
+use k256::ecdsa::{Signature, VerifyingKey};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+use crate::cache::frontload::{load_bundled_paths, DefaultPathsConfig};
+use crate::cache::CacheManager;
+
+/// Preference key holding the manifest URL
+const URL_PREFERENCE_KEY: &str = "asset_registry_url";
+
+/// Preference key holding additional hex SEC1 secp256k1 keys trusted to sign manifests
+const KEYS_PREFERENCE_KEY: &str = "asset_registry_keys";
+
+/// Publisher keys pinned at build time (comma-separated hex SEC1); always trusted,
+/// and the preference can only add to them
+const PINNED_KEYS: Option<&str> = option_env!("KEEPKEY_ASSET_REGISTRY_KEYS");
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A registry as published: base64 of the default-paths JSON, and a
+/// secp256k1 ECDSA (SHA-256) signature over those bytes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignedRegistry {
+    pub payload: String,
+    /// Hex, 64-byte compact or DER
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryStatus {
+    /// "remote" or "bundled"
+    pub source: String,
+    pub version: String,
+    pub path_count: usize,
+    pub fetched_at: Option<i64>,
+    pub url: Option<String>,
+    pub trusted_keys: usize,
+}
+
+struct ActiveRegistry {
+    config: DefaultPathsConfig,
+    fetched_at: i64,
+}
+
+static ACTIVE: Lazy<RwLock<Option<ActiveRegistry>>> = Lazy::new(|| RwLock::new(None));
+
+/// Paths from the verified remote registry, if one is loaded
+pub fn active_paths() -> Option<DefaultPathsConfig> {
+    ACTIVE.read().ok()?.as_ref().map(|active| active.config.clone())
+}
+
+fn registry_url() -> Option<String> {
+    crate::preferences::get_as::<String>(URL_PREFERENCE_KEY).filter(|url| !url.trim().is_empty())
+}
+
+fn pinned_keys() -> impl Iterator<Item = String> {
+    PINNED_KEYS
+        .unwrap_or_default()
+        .split(',')
+        .map(|key| key.trim().to_string())
+        .filter(|key| !key.is_empty())
+}
+
+fn trusted_keys() -> Vec<VerifyingKey> {
+    pinned_keys()
+        .chain(crate::preferences::get_as::<Vec<String>>(KEYS_PREFERENCE_KEY).unwrap_or_default())
+        .collect::<Vec<_>>()
+        .iter()
+        .filter_map(|key| {
+            let parsed = hex::decode(key.trim().trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| VerifyingKey::from_sec1_bytes(&bytes).ok());
+            if parsed.is_none() {
+                log::warn!("Ignoring invalid asset registry signing key {}", key);
+            }
+            parsed
+        })
+        .collect()
+}
+
+fn parse_version(version: &str) -> Result<semver::Version, String> {
+    semver::Version::parse(version.trim_start_matches('v'))
+        .map_err(|e| format!("Invalid registry version {}: {}", version, e))
+}
+
+/// Check the signature against the trusted keys and the contents against the bundled registry
+pub fn verify(manifest: &SignedRegistry, keys: &[VerifyingKey], bundled: &DefaultPathsConfig) -> Result<DefaultPathsConfig, String> {
+    if keys.is_empty() {
+        return Err("No trusted asset registry signing key is configured".to_string());
+    }
+    let payload = base64::engine::general_purpose::STANDARD
+        .decode(manifest.payload.trim())
+        .map_err(|e| format!("Invalid registry payload encoding: {}", e))?;
+    let signature_bytes = hex::decode(manifest.signature.trim().trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid registry signature encoding: {}", e))?;
+    let signature = Signature::from_slice(&signature_bytes)
+        .or_else(|_| Signature::from_der(&signature_bytes))
+        .map_err(|e| format!("Invalid registry signature: {}", e))?;
+
+    if !keys.iter().any(|key| key.verify(&payload, &signature).is_ok()) {
+        return Err("Registry signature does not match any trusted key".to_string());
+    }
+
+    let config: DefaultPathsConfig = serde_json::from_slice(&payload)
+        .map_err(|e| format!("Invalid registry contents: {}", e))?;
+    if parse_version(&config.version)? < parse_version(&bundled.version)? {
+        return Err(format!("Registry {} is older than the bundled {}", config.version, bundled.version));
+    }
+    if config.paths.is_empty() {
+        return Err("Registry contains no paths".to_string());
+    }
+    let mut ids = std::collections::HashSet::new();
+    if let Some(duplicate) = config.paths.iter().find(|path| !ids.insert(path.id.as_str())) {
+        return Err(format!("Registry lists path {} twice", duplicate.id));
+    }
+    Ok(config)
+}
+
+/// Refuse to go back to (or re-accept a different manifest for) a version at or
+/// below the last accepted one
+fn check_rollback(config: &DefaultPathsConfig, last_accepted: Option<&str>) -> Result<(), String> {
+    let Some(last_accepted) = last_accepted else {
+        return Ok(());
+    };
+    if parse_version(&config.version)? <= parse_version(last_accepted)? {
+        return Err(format!("Registry {} is not newer than the accepted {}", config.version, last_accepted));
+    }
+    Ok(())
+}
+
+fn activate(config: DefaultPathsConfig, fetched_at: i64) {
+    if let Ok(mut active) = ACTIVE.write() {
+        *active = Some(ActiveRegistry { config, fetched_at });
+    }
+}
+
+fn bundled() -> Result<DefaultPathsConfig, String> {
+    load_bundled_paths().map_err(|e| e.to_string())
+}
+
+/// Re-verify the stored manifest at startup; keys may have changed since it was saved
+pub async fn load_stored(cache: &CacheManager) {
+    let stored = match cache.get_asset_registry().await {
+        Ok(Some(stored)) => stored,
+        Ok(None) => return,
+        Err(e) => {
+            log::warn!("Failed to read stored asset registry: {}", e);
+            return;
+        }
+    };
+    let (manifest, fetched_at) = stored;
+    let verified = serde_json::from_str::<SignedRegistry>(&manifest)
+        .map_err(|e| format!("Invalid stored registry: {}", e))
+        .and_then(|manifest| verify(&manifest, &trusted_keys(), &bundled()?));
+    match verified {
+        Ok(config) => {
+            log::info!("📚 Using asset registry {} ({} paths)", config.version, config.paths.len());
+            activate(config, fetched_at);
+        }
+        Err(e) => log::warn!("Ignoring stored asset registry: {}", e),
+    }
+}
+
+/// Fetch, verify and store the remote registry, then switch frontload over to it
+pub async fn refresh(app: &AppHandle, cache: &CacheManager) -> Result<RegistryStatus, String> {
+    let url = registry_url().ok_or("No asset registry URL is configured")?;
+    log::info!("📚 Refreshing asset registry from {}", url);
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+    let raw = client.get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch asset registry: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch asset registry: {}", e))?
+        .text()
+        .await
+        .map_err(|e| format!("Failed to read asset registry: {}", e))?;
+
+    let manifest: SignedRegistry = serde_json::from_str(&raw)
+        .map_err(|e| format!("Invalid asset registry manifest: {}", e))?;
+    let config = verify(&manifest, &trusted_keys(), &bundled()?)?;
+
+    // Re-serving the manifest we already hold is not an update
+    if let Ok(Some((stored, _))) = cache.get_asset_registry().await {
+        if stored == raw {
+            log::info!("📚 Asset registry {} is already current", config.version);
+            return Ok(status());
+        }
+    }
+    let last_accepted = cache
+        .get_asset_registry_version()
+        .await
+        .map_err(|e| format!("Failed to read stored asset registry: {}", e))?;
+    check_rollback(&config, last_accepted.as_deref())?;
+
+    let fetched_at = chrono::Utc::now().timestamp();
+    cache
+        .save_asset_registry(&config.version, &raw, fetched_at)
+        .await
+        .map_err(|e| format!("Failed to store asset registry: {}", e))?;
+    let version = config.version.clone();
+    activate(config, fetched_at);
+
+    log::info!("📚 Asset registry updated to {}", version);
+    if let Err(e) = crate::commands::emit_or_queue_event(app, "assets:registry-updated", serde_json::json!({ "version": version })).await {
+        log::warn!("Failed to emit assets:registry-updated: {}", e);
+    }
+    Ok(status())
+}
+
+pub fn status() -> RegistryStatus {
+    let (source, config, fetched_at) = match ACTIVE.read().ok().and_then(|active| active.as_ref().map(|a| (a.config.clone(), a.fetched_at))) {
+        Some((config, fetched_at)) => ("remote", Some(config), Some(fetched_at)),
+        None => ("bundled", bundled().ok(), None),
+    };
+    RegistryStatus {
+        source: source.to_string(),
+        version: config.as_ref().map(|c| c.version.clone()).unwrap_or_default(),
+        path_count: config.as_ref().map_or(0, |c| c.paths.len()),
+        fetched_at,
+        url: registry_url(),
+        trusted_keys: trusted_keys().len(),
+    }
+}
+
+/// Load the stored registry, then refresh it when a source is configured
+pub fn spawn_startup_refresh(app: AppHandle, cache_cell: std::sync::Arc<once_cell::sync::OnceCell<std::sync::Arc<CacheManager>>>) {
+    tauri::async_runtime::spawn(async move {
+        let Ok(cache) = crate::commands::get_cache_manager(&cache_cell).await else {
+            return;
+        };
+        load_stored(&cache).await;
+        if registry_url().is_some() {
+            if let Err(e) = refresh(&app, &cache).await {
+                log::warn!("Asset registry refresh failed, keeping current paths: {}", e);
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::signature::Signer;
+    use k256::ecdsa::SigningKey;
+
+    fn signed(key: &SigningKey, payload: &[u8]) -> SignedRegistry {
+        let signature: Signature = key.sign(payload);
+        SignedRegistry {
+            payload: base64::engine::general_purpose::STANDARD.encode(payload),
+            signature: hex::encode(signature.to_bytes()),
+        }
+    }
+
+    #[test]
+    fn test_verify() {
+        let key = SigningKey::from_slice(&[7u8; 32]).unwrap();
+        let other = SigningKey::from_slice(&[9u8; 32]).unwrap();
+        let bundled = load_bundled_paths().unwrap();
+        let payload = serde_json::to_vec(&bundled).unwrap();
+
+        let manifest = signed(&key, &payload);
+        assert_eq!(verify(&manifest, &[*key.verifying_key()], &bundled).unwrap().paths.len(), bundled.paths.len());
+        assert!(verify(&manifest, &[*other.verifying_key()], &bundled).is_err());
+        assert!(verify(&manifest, &[], &bundled).is_err());
+
+        let mut tampered = manifest.clone();
+        tampered.payload = base64::engine::general_purpose::STANDARD.encode(serde_json::to_vec(&DefaultPathsConfig {
+            paths: vec![],
+            ..bundled.clone()
+        }).unwrap());
+        assert!(verify(&tampered, &[*key.verifying_key()], &bundled).is_err());
+
+        let older = DefaultPathsConfig { version: "0.1.0".to_string(), ..bundled.clone() };
+        let manifest = signed(&key, &serde_json::to_vec(&older).unwrap());
+        assert!(verify(&manifest, &[*key.verifying_key()], &bundled).is_err());
+    }
+
+    #[test]
+    fn test_check_rollback() {
+        let config = |version: &str| DefaultPathsConfig { version: version.to_string(), ..load_bundled_paths().unwrap() };
+        assert!(check_rollback(&config("2.0.0"), None).is_ok());
+        assert!(check_rollback(&config("2.0.1"), Some("2.0.0")).is_ok());
+        assert!(check_rollback(&config("2.0.0"), Some("2.0.0")).is_err());
+        assert!(check_rollback(&config("1.9.0"), Some("v2.0.0")).is_err());
+    }
+}
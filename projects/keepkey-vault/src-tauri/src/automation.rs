@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Preference key holding the configured automation hooks
+const HOOKS_PREFERENCE_KEY: &str = "automation_hooks";
+
+/// Timeout for a single webhook delivery
+const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Events that automation hooks may subscribe to
+pub const HOOKABLE_EVENTS: &[&str] = &[
+    "device:connected",
+    "device:ready",
+    "device:disconnected",
+    "device:pin-unlock-needed",
+    "device:features-updated",
+    "device:queue-restarted",
+    "frontload:chain-completed",
+    "frontload:completed",
+    "frontload:failed",
+    "portfolio:changed",
+];
+
+/// A user-configured webhook fired when a vault event occurs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutomationHook {
+    pub id: String,
+    pub name: String,
+    /// Endpoint receiving an HTTP POST with the event payload
+    pub url: String,
+    /// Event names this hook subscribes to ("*" for all hookable events)
+    pub events: Vec<String>,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    /// Optional shared secret sent as a bearer token (write-only, kept in the OS keychain)
+    #[serde(default, skip_serializing)]
+    pub secret: Option<String>,
+    #[serde(default)]
+    pub has_secret: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl AutomationHook {
+    fn secret_name(&self) -> String {
+        format!("automation_hook:{}", self.id)
+    }
+
+    fn subscribes_to(&self, event_name: &str) -> bool {
+        self.enabled && self.events.iter().any(|e| e == "*" || e == event_name)
+    }
+}
+
+/// Body posted to a hook endpoint
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct HookDelivery<'a> {
+    event: &'a str,
+    timestamp: i64,
+    payload: &'a serde_json::Value,
+}
+
+/// Load all configured hooks from preferences
+pub fn get_hooks() -> Vec<AutomationHook> {
+    crate::preferences::get_as(HOOKS_PREFERENCE_KEY).unwrap_or_default()
+}
+
+/// Persist the full list of hooks to preferences
+fn save_hooks(hooks: &[AutomationHook]) -> Result<(), String> {
+    crate::preferences::set(HOOKS_PREFERENCE_KEY, serde_json::json!(hooks))
+}
+
+/// Validate a hook before it gets stored
+fn validate_hook(hook: &AutomationHook) -> Result<(), String> {
+    let url = url::Url::parse(&hook.url)
+        .map_err(|e| format!("Invalid hook URL {}: {}", hook.url, e))?;
+
+    if !matches!(url.scheme(), "http" | "https") {
+        return Err(format!("Hook URL must be http or https: {}", hook.url));
+    }
+
+    if hook.events.is_empty() {
+        return Err("Hook must subscribe to at least one event".to_string());
+    }
+
+    for event in &hook.events {
+        if event != "*" && !HOOKABLE_EVENTS.contains(&event.as_str()) {
+            return Err(format!("Unknown hook event: {}", event));
+        }
+    }
+
+    Ok(())
+}
+
+/// Add a hook, or replace the existing hook with the same id
+pub fn save_hook(mut hook: AutomationHook) -> Result<AutomationHook, String> {
+    if hook.id.is_empty() {
+        hook.id = uuid::Uuid::new_v4().to_string();
+    }
+    validate_hook(&hook)?;
+
+    // Secrets never touch keepkey.json; an omitted secret keeps the stored one
+    match hook.secret.take() {
+        Some(secret) if secret.is_empty() => {
+            crate::secrets::delete_secret(&hook.secret_name())?;
+            hook.has_secret = false;
+        }
+        Some(secret) => {
+            crate::secrets::set_secret(&hook.secret_name(), &secret)?;
+            hook.has_secret = true;
+        }
+        None => {
+            hook.has_secret = crate::secrets::has_secret(&hook.secret_name());
+        }
+    }
+
+    let mut hooks = get_hooks();
+    match hooks.iter_mut().find(|h| h.id == hook.id) {
+        Some(existing) => *existing = hook.clone(),
+        None => hooks.push(hook.clone()),
+    }
+
+    save_hooks(&hooks)?;
+    log::info!("🪝 Saved automation hook {} ({})", hook.name, hook.url);
+    Ok(hook)
+}
+
+/// Remove a hook by id
+pub fn remove_hook(hook_id: &str) -> Result<(), String> {
+    let (removed, hooks): (Vec<AutomationHook>, Vec<AutomationHook>) = get_hooks()
+        .into_iter()
+        .partition(|h| h.id == hook_id);
+
+    for hook in removed {
+        crate::secrets::delete_secret(&hook.secret_name())?;
+    }
+
+    save_hooks(&hooks)
+}
+
+/// Move hook secrets stored inline in preferences into the keychain
+pub fn migrate_hook_secrets() -> Result<usize, String> {
+    let mut hooks = get_hooks();
+    let mut migrated = 0;
+
+    for hook in hooks.iter_mut() {
+        if let Some(secret) = hook.secret.take() {
+            crate::secrets::set_secret(&hook.secret_name(), &secret)?;
+            hook.has_secret = true;
+            migrated += 1;
+        }
+    }
+
+    if migrated > 0 {
+        save_hooks(&hooks)?;
+        log::info!("🔑 Migrated {} automation hook secret(s) into the OS keychain", migrated);
+    }
+
+    Ok(migrated)
+}
+
+/// POST an event to a single hook
+async fn deliver(hook: &AutomationHook, event_name: &str, payload: &serde_json::Value) -> Result<u16, String> {
+    let client = reqwest::Client::builder()
+        .timeout(HOOK_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let body = HookDelivery {
+        event: event_name,
+        timestamp: chrono::Utc::now().timestamp(),
+        payload,
+    };
+
+    let mut request = client
+        .post(&hook.url)
+        .header("X-KeepKey-Event", event_name)
+        .json(&body);
+
+    if hook.has_secret {
+        if let Some(secret) = crate::secrets::get_secret(&hook.secret_name())? {
+            request = request.bearer_auth(secret);
+        }
+    }
+
+    let response = request.send()
+        .await
+        .map_err(|e| format!("Hook request failed: {}", e))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("Hook returned HTTP {}", status.as_u16()));
+    }
+
+    Ok(status.as_u16())
+}
+
+/// Fire all hooks subscribed to an event without blocking the caller
+pub fn dispatch_event(event_name: &str, payload: &serde_json::Value) {
+    if !HOOKABLE_EVENTS.contains(&event_name) {
+        return;
+    }
+
+    let hooks: Vec<AutomationHook> = get_hooks()
+        .into_iter()
+        .filter(|h| h.subscribes_to(event_name))
+        .collect();
+
+    for hook in hooks {
+        let event_name = event_name.to_string();
+        let payload = payload.clone();
+        tauri::async_runtime::spawn(async move {
+            match deliver(&hook, &event_name, &payload).await {
+                Ok(status) => log::debug!("🪝 Hook {} received {} (HTTP {})", hook.name, event_name, status),
+                Err(e) => log::warn!("🪝 Hook {} failed for {}: {}", hook.name, event_name, e),
+            }
+        });
+    }
+}
+
+/// Send a test event to a hook and report the result
+pub async fn test_hook(hook_id: &str) -> Result<u16, String> {
+    let hook = get_hooks()
+        .into_iter()
+        .find(|h| h.id == hook_id)
+        .ok_or_else(|| format!("Hook not found: {}", hook_id))?;
+
+    let payload = serde_json::json!({ "test": true, "hookId": hook.id });
+    deliver(&hook, "test", &payload).await
+}
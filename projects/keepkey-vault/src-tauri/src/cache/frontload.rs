@@ -3,6 +3,7 @@ use anyhow::{Result, anyhow};
 use keepkey_rust::device_queue::DeviceQueueHandle;
 use super::{CacheManager, CacheMetadata};
 use super::types::FrontloadStatus;
+use super::schedule::FrontloadMode;
 use crate::commands::{DeviceQueueManager, DeviceRequest, DeviceResponse};
 use serde::{Deserialize, Serialize};
 use serde_json;
@@ -11,6 +12,8 @@ use serde_json;
 pub struct FrontloadController {
     cache: Arc<CacheManager>,
     queue_manager: DeviceQueueManager,
+    /// Used to emit per-chain progress events when set
+    app: Option<tauri::AppHandle>,
 }
 
 /// Derivation path from default-paths.json
@@ -39,30 +42,122 @@ pub struct DefaultPathsConfig {
     pub paths: Vec<DefaultPath>,
 }
 
-/// Load default paths from JSON file
-fn load_default_paths() -> Result<DefaultPathsConfig> {
+/// Load the default paths bundled with this build
+pub(crate) fn load_bundled_paths() -> Result<DefaultPathsConfig> {
     let json_content = include_str!("../../default-paths.json");
     let config: DefaultPathsConfig = serde_json::from_str(json_content)
         .map_err(|e| anyhow!("Failed to parse default-paths.json: {}", e))?;
     Ok(config)
 }
 
+/// Default paths from a verified remote registry, or the bundled ones
+fn load_default_paths() -> Result<DefaultPathsConfig> {
+    match crate::asset_registry::active_paths() {
+        Some(config) => Ok(config),
+        None => load_bundled_paths(),
+    }
+}
+
+/// Default paths, plus any Cosmos chains added through configuration
+/// and sandbox accounts when testnet mode is on
+fn planned_paths() -> Result<DefaultPathsConfig> {
+    let mut paths_config = load_default_paths()
+        .map_err(|e| anyhow!("Failed to load default paths: {}", e))?;
+    paths_config.paths.extend(crate::device::cosmos_chains::frontload_paths());
+    if !crate::device::testnet::is_enabled() {
+        paths_config.paths.retain(|path| !path.networks.iter().any(|n| crate::device::testnet::is_testnet_caip(n)));
+    }
+    paths_config.paths.extend(crate::device::testnet::frontload_paths());
+    Ok(paths_config)
+}
+
+/// Rough device round-trip time used for plan estimates
+const ESTIMATED_MS_PER_OPERATION: u64 = 400;
+
+/// UTXO chains cache an account xpub and a first address
+fn is_utxo_chain(blockchain: &str) -> bool {
+    matches!(blockchain, "bitcoin" | "bitcoincash" | "litecoin" | "dogecoin" | "dash" | "testnet")
+}
+
+/// A path a frontload would query
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedPath {
+    pub id: String,
+    pub blockchain: String,
+    pub derivation_path: String,
+    pub script_type: String,
+    pub operations: usize,
+}
+
+/// What a frontload would do, computed without touching the device
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontloadPlan {
+    pub device_id: String,
+    pub mode: FrontloadMode,
+    pub total_paths: usize,
+    pub cached_paths: usize,
+    pub missing_paths: Vec<PlannedPath>,
+    /// Chains that will be queried, in priority order
+    pub chains: Vec<String>,
+    pub device_operations: usize,
+    pub estimated_secs: u64,
+}
+
 impl FrontloadController {
     /// Create a new frontload controller
     pub fn new(cache: Arc<CacheManager>, queue_manager: DeviceQueueManager) -> Self {
         Self {
             cache,
             queue_manager,
+            app: None,
+        }
+    }
+    
+    /// Emit a frontload:chain-completed event as each chain finishes
+    pub fn with_app_handle(mut self, app: tauri::AppHandle) -> Self {
+        self.app = Some(app);
+        self
+    }
+    
+    /// Let listeners pick up a chain's cached accounts without waiting for the whole run
+    async fn emit_chain_completed(&self, device_id: &str, blockchain: &str, cached: usize, completed_chains: usize, total_chains: usize) {
+        let Some(app) = &self.app else {
+            return;
+        };
+        
+        let payload = serde_json::json!({
+            "deviceId": device_id,
+            "blockchain": blockchain,
+            "cached": cached,
+            "completedChains": completed_chains,
+            "totalChains": total_chains,
+        });
+        
+        if let Err(e) = crate::commands::emit_or_queue_event(app, "frontload:chain-completed", payload).await {
+            log::warn!("Failed to emit frontload:chain-completed: {}", e);
         }
     }
     
     /// Start frontloading for a device using default paths from JSON
     pub async fn frontload_device(&self, device_id: &str) -> Result<()> {
-        log::info!("🔄 Starting frontload for device: {}", device_id);
+        self.frontload_device_with_mode(device_id, FrontloadMode::Incremental).await
+    }
+    
+    /// Frontload a device, re-deriving cached paths when running in full mode
+    pub async fn frontload_device_with_mode(&self, device_id: &str, mode: FrontloadMode) -> Result<()> {
+        let result = self.frontload_chains(device_id, mode).await;
+        if result.is_err() {
+            crate::portfolio::fail_sync(device_id);
+        }
+        result
+    }
+    
+    async fn frontload_chains(&self, device_id: &str, mode: FrontloadMode) -> Result<()> {
+        log::info!("🔄 Starting {:?} frontload for device: {}", mode, device_id);
         
-        // Load default paths from JSON
-        let paths_config = load_default_paths()
-            .map_err(|e| anyhow!("Failed to load default paths: {}", e))?;
+        let paths_config = planned_paths()?;
         
         log::info!("📋 Loaded {} default paths from config", paths_config.paths.len());
         
@@ -110,35 +205,61 @@ impl FrontloadController {
         let total_paths = paths_config.paths.len();
         let mut errors = Vec::new();
         
-        // Process each path from default-paths.json
-        for (i, path_config) in paths_config.paths.iter().enumerate() {
-            log::debug!("🔄 Processing path {}/{}: {} ({})", 
-                i + 1, total_paths, path_config.id, path_config.note);
-            
-            // Skip if already cached (check cache first)
-            let derivation_path = self.address_n_list_to_string(&path_config.address_n_list);
-            if self.is_already_cached(device_id, &derivation_path, &path_config.blockchain, &path_config.script_type).await? {
-                log::debug!("⏭️ Skipping already cached path: {}", path_config.id);
-                continue;
-            }
+        // Process chains in the user's priority order so the important ones are ready first
+        let priority = super::schedule::get_chain_priority();
+        let chain_groups = super::schedule::group_by_priority(
+            paths_config.paths.iter().collect(),
+            |path| path.blockchain.as_str(),
+            &priority,
+        );
+        let total_chains = chain_groups.len();
+        let mut processed = 0;
+        crate::portfolio::begin_sync(device_id, chain_groups.iter().map(|(blockchain, _)| blockchain.as_str()));
+        
+        for (chain_index, (blockchain, chain_paths)) in chain_groups.iter().enumerate() {
+            let mut chain_cached = 0;
+            let errors_before = errors.len();
+            crate::portfolio::set_chain_state(device_id, blockchain, crate::portfolio::SyncState::InProgress);
             
-            // Frontload both account-level xpub and individual addresses
-            match self.frontload_path(&queue_handle, device_id, path_config).await {
-                Ok(count) => {
-                    total_cached += count;
-                    log::debug!("✅ Cached {} items for path: {}", count, path_config.id);
+            for path_config in chain_paths {
+                processed += 1;
+                log::debug!("🔄 Processing path {}/{}: {} ({})", 
+                    processed, total_paths, path_config.id, path_config.note);
+                
+                // Skip if already cached (check cache first), unless doing a full refresh
+                let derivation_path = self.address_n_list_to_string(&path_config.address_n_list);
+                if mode == FrontloadMode::Incremental && self.is_already_cached(device_id, &derivation_path, &path_config.blockchain, &path_config.script_type).await? {
+                    log::debug!("⏭️ Skipping already cached path: {}", path_config.id);
+                    continue;
                 }
-                Err(e) => {
-                    log::warn!("⚠️ Failed to frontload path {}: {}", path_config.id, e);
-                    errors.push(format!("{}: {}", path_config.id, e));
+                
+                // Frontload both account-level xpub and individual addresses
+                match self.frontload_path(&queue_handle, device_id, path_config).await {
+                    Ok(count) => {
+                        total_cached += count;
+                        chain_cached += count;
+                        log::debug!("✅ Cached {} items for path: {}", count, path_config.id);
+                    }
+                    Err(e) => {
+                        log::warn!("⚠️ Failed to frontload path {}: {}", path_config.id, e);
+                        errors.push(format!("{}: {}", path_config.id, e));
+                    }
                 }
+                
+                // Update progress
+                progress = (processed * 100) / total_paths;
+                let mut progress_metadata = metadata.clone();
+                progress_metadata.frontload_progress = progress as i32;
+                self.cache.update_cache_metadata(&progress_metadata).await?;
             }
             
-            // Update progress
-            progress = ((i + 1) * 100) / total_paths;
-            let mut progress_metadata = metadata.clone();
-            progress_metadata.frontload_progress = progress as i32;
-            self.cache.update_cache_metadata(&progress_metadata).await?;
+            let chain_state = if errors.len() > errors_before {
+                crate::portfolio::SyncState::Failed
+            } else {
+                crate::portfolio::SyncState::Complete
+            };
+            crate::portfolio::set_chain_state(device_id, blockchain, chain_state);
+            self.emit_chain_completed(device_id, blockchain, chain_cached, chain_index + 1, total_chains).await;
         }
         
         // Update final metadata
@@ -167,6 +288,62 @@ impl FrontloadController {
         Ok(())
     }
     
+    /// Report which paths a frontload would query, reading only the cache
+    pub async fn plan_frontload(&self, device_id: &str, mode: FrontloadMode) -> Result<FrontloadPlan> {
+        let paths_config = planned_paths()?;
+        let total_paths = paths_config.paths.len();
+        
+        // Read the cache directly so planning doesn't touch hit/miss stats
+        let cached: std::collections::HashSet<(String, String, Option<String>)> = self.cache
+            .list_cached_pubkeys(device_id)
+            .await?
+            .into_iter()
+            .map(|pubkey| (pubkey.derivation_path, pubkey.coin_name, pubkey.script_type))
+            .collect();
+        
+        let priority = super::schedule::get_chain_priority();
+        let chain_groups = super::schedule::group_by_priority(
+            paths_config.paths.iter().collect(),
+            |path| path.blockchain.as_str(),
+            &priority,
+        );
+        
+        let mut missing_paths = Vec::new();
+        let mut chains = Vec::new();
+        for (blockchain, chain_paths) in chain_groups {
+            let before = missing_paths.len();
+            for path_config in chain_paths {
+                let derivation_path = self.address_n_list_to_string(&path_config.address_n_list);
+                let key = (derivation_path.clone(), path_config.blockchain.clone(), Some(path_config.script_type.clone()));
+                if mode == FrontloadMode::Incremental && cached.contains(&key) {
+                    continue;
+                }
+                missing_paths.push(PlannedPath {
+                    id: path_config.id.clone(),
+                    blockchain: path_config.blockchain.clone(),
+                    derivation_path,
+                    script_type: path_config.script_type.clone(),
+                    operations: if is_utxo_chain(&path_config.blockchain) { 2 } else { 1 },
+                });
+            }
+            if missing_paths.len() > before {
+                chains.push(blockchain);
+            }
+        }
+        
+        let device_operations: usize = missing_paths.iter().map(|path| path.operations).sum();
+        Ok(FrontloadPlan {
+            device_id: device_id.to_string(),
+            mode,
+            total_paths,
+            cached_paths: total_paths - missing_paths.len(),
+            missing_paths,
+            chains,
+            device_operations,
+            estimated_secs: (device_operations as u64 * ESTIMATED_MS_PER_OPERATION).div_ceil(1000),
+        })
+    }
+    
     /// Get or create device queue handle
     async fn get_or_create_queue_handle(&self, device_id: &str) -> Result<DeviceQueueHandle> {
         let mut manager = self.queue_manager.lock().await;
@@ -233,7 +410,7 @@ impl FrontloadController {
         let master_path_str = self.address_n_list_to_string(&path_config.address_n_list_master);
         
         // For Bitcoin-like coins, get both XPUB (account level) and addresses (master level)
-        if matches!(path_config.blockchain.as_str(), "bitcoin" | "bitcoincash" | "litecoin" | "dogecoin" | "dash") {
+        if is_utxo_chain(&path_config.blockchain) {
             // 1. Get XPUB at account level (m/44'/0'/0')
             let xpub_request = DeviceRequest::GetPublicKey {
                 path: account_path_str.clone(),
@@ -297,7 +474,7 @@ impl FrontloadController {
         } else {
             // For other blockchains, use appropriate address request
             let request = match path_config.blockchain.as_str() {
-                "ethereum" | "arbitrum" | "optimism" | "polygon" | "avalanche" | "base" | "bsc" => {
+                "ethereum" | "arbitrum" | "optimism" | "polygon" | "avalanche" | "base" | "bsc" | "sepolia" => {
                     DeviceRequest::EthereumGetAddress {
                         path: master_path_str.clone(),
                         show_display: Some(path_config.show_display),
@@ -325,10 +502,17 @@ impl FrontloadController {
                     path: master_path_str.clone(),
                     show_display: Some(path_config.show_display),
                 },
-                _ => {
-                    log::debug!("Unsupported blockchain for frontload: {}", path_config.blockchain);
-                    return Ok(0);
-                }
+                other => match crate::device::cosmos_chains::find_chain(other) {
+                    Some(chain) => DeviceRequest::CosmosGetAddress {
+                        path: master_path_str.clone(),
+                        hrp: chain.hrp,
+                        show_display: Some(false),
+                    },
+                    None => {
+                        log::debug!("Unsupported blockchain for frontload: {}", path_config.blockchain);
+                        return Ok(0);
+                    }
+                },
             };
             
             match self.send_device_request(queue_handle, request).await {
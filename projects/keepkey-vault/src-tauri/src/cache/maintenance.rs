@@ -0,0 +1,74 @@
+use std::sync::Arc;
+use std::time::Duration;
+use super::CacheManager;
+
+/// How often the scheduler wakes up to check whether a vacuum is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Minimum time between automatic vacuums
+const VACUUM_INTERVAL_SECS: i64 = 7 * 24 * 60 * 60;
+
+/// Delay before the first check so startup isn't competing with the vacuum
+const STARTUP_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Preference key holding how many days a cleared device cache can still be restored
+const TOMBSTONE_RETENTION_PREFERENCE_KEY: &str = "cache_tombstone_retention_days";
+
+/// How long a cleared device cache can still be restored, in seconds
+pub fn tombstone_retention_secs() -> i64 {
+    let days = crate::preferences::get_as::<i64>(TOMBSTONE_RETENTION_PREFERENCE_KEY)
+        .filter(|days| *days > 0)
+        .unwrap_or(14);
+    days * 24 * 60 * 60
+}
+
+/// Check whether an automatic vacuum is due
+pub fn is_vacuum_due(last_vacuum: Option<i64>, now: i64) -> bool {
+    match last_vacuum {
+        Some(last) => now - last >= VACUUM_INTERVAL_SECS,
+        None => true,
+    }
+}
+
+/// Purge expired cache tombstones, then run a compaction if the last one is
+/// older than the vacuum interval
+pub async fn run_scheduled_vacuum(cache: &Arc<CacheManager>) {
+    let cutoff = chrono::Utc::now().timestamp() - tombstone_retention_secs();
+    match cache.purge_cache_tombstones(cutoff).await {
+        Ok(0) => {}
+        Ok(purged) => log::info!("🧹 Purged {} expired soft-deleted cache entries", purged),
+        Err(e) => log::warn!("Failed to purge soft-deleted cache entries: {}", e),
+    }
+
+    let last_vacuum = cache.get_last_vacuum().await;
+    if !is_vacuum_due(last_vacuum, chrono::Utc::now().timestamp()) {
+        return;
+    }
+
+    log::info!("🧹 Running scheduled cache compaction");
+    match cache.compact().await {
+        Ok(result) => log::info!(
+            "✅ Cache compacted: removed {} stale entries, reclaimed {} bytes in {}ms",
+            result.removed_entries, result.bytes_reclaimed, result.duration_ms
+        ),
+        Err(e) => log::error!("❌ Scheduled cache compaction failed: {}", e),
+    }
+}
+
+/// Spawn the background task that keeps the cache database compact
+pub fn spawn_vacuum_schedule(
+    cache_cell: Arc<once_cell::sync::OnceCell<Arc<CacheManager>>>,
+) {
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(STARTUP_DELAY).await;
+
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            match crate::commands::get_cache_manager(&cache_cell).await {
+                Ok(cache) => run_scheduled_vacuum(&cache).await,
+                Err(e) => log::warn!("Skipping scheduled cache compaction: {}", e),
+            }
+        }
+    });
+}
@@ -2,12 +2,23 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use anyhow::{Result, anyhow};
 use rusqlite::{Connection, params, OptionalExtension};
-use super::types::{CachedPubkey, CacheMetadata, CacheStatus, FrontloadStatus};
+use super::types::{CachedPubkey, CacheMetadata, CacheStatus, CacheDiskUsage, CacheCompactionResult, DeviceAlias, UtxoTag, PendingPaymentIntent, ErrorRecord, EncryptedNote, ActivityEntry, ProviderRequest, PortfolioGoal, AccountMetadata, FrontloadStatus, CacheMode, CacheDegradation};
 
 /// Thread-safe cache manager for SQLite operations
 pub struct CacheManager {
     db: Arc<Mutex<Connection>>,
     stats: Arc<Mutex<CacheStats>>,
+    degradation: Option<CacheDegradation>,
+    /// SQLite's change counter as last seen, and when it was seen to move (unix ms)
+    last_write: Arc<std::sync::Mutex<(i64, i64)>>,
+}
+
+/// What went wrong opening the on-disk database
+enum OpenFailure {
+    /// The file is damaged or isn't a database; recreating it should help
+    Corrupt(String),
+    /// The disk is full or unwritable; only memory will work
+    Storage(String),
 }
 
 #[derive(Default)]
@@ -18,23 +29,149 @@ struct CacheStats {
 
 impl CacheManager {
     /// Create a new cache manager
+    ///
+    /// A corrupted database is backed up and recreated; if the disk can't be
+    /// used at all the cache runs in memory so the rest of the app keeps working.
     pub async fn new() -> Result<Self> {
-        let db_path = Self::get_db_path()?;
-        let conn = Connection::open(&db_path)?;
-        
-        // Enable WAL mode for better concurrency
-        conn.pragma_update(None, "journal_mode", "WAL")?;
-        conn.pragma_update(None, "foreign_keys", "ON")?;
-        
-        // Apply migrations
-        Self::apply_migrations(&conn)?;
+        let (conn, degradation) = match Self::open_on_disk() {
+            Ok(conn) => (conn, None),
+            Err(OpenFailure::Corrupt(reason)) => {
+                log::error!("💥 Cache database is corrupted: {}", reason);
+                match Self::backup_and_recreate() {
+                    Ok((conn, backup_path)) => (conn, Some(CacheDegradation {
+                        mode: CacheMode::Recreated,
+                        reason,
+                        backup_path: backup_path.map(|p| p.display().to_string()),
+                    })),
+                    Err(e) => (Self::open_in_memory()?, Some(CacheDegradation {
+                        mode: CacheMode::InMemory,
+                        reason: format!("{} (recreate failed: {})", reason, e),
+                        backup_path: None,
+                    })),
+                }
+            }
+            Err(OpenFailure::Storage(reason)) => {
+                log::error!("💾 Cache database unavailable: {}", reason);
+                (Self::open_in_memory()?, Some(CacheDegradation {
+                    mode: CacheMode::InMemory,
+                    reason,
+                    backup_path: None,
+                }))
+            }
+        };
         
         Ok(Self {
             db: Arc::new(Mutex::new(conn)),
             stats: Arc::new(Mutex::new(CacheStats::default())),
+            degradation,
+            last_write: Arc::new(std::sync::Mutex::new((0, chrono::Utc::now().timestamp_millis()))),
         })
     }
     
+    /// When the database last changed, in unix milliseconds.
+    ///
+    /// Derived from SQLite's `total_changes()` counter, so any write through this
+    /// manager moves it forward; before the first write it is the startup time.
+    pub async fn last_write(&self) -> Result<i64> {
+        let changes: i64 = self.db.lock().await.query_row("SELECT total_changes()", [], |row| row.get(0))?;
+        let mut last_write = self.last_write.lock().unwrap();
+        if changes != last_write.0 {
+            let now = chrono::Utc::now().timestamp_millis();
+            *last_write = (changes, now.max(last_write.1 + 1));
+        }
+        Ok(last_write.1)
+    }
+
+    /// Why the cache is degraded, if it is
+    pub fn degradation(&self) -> Option<&CacheDegradation> {
+        self.degradation.as_ref()
+    }
+    
+    /// Storage mode chosen at startup
+    pub fn mode(&self) -> CacheMode {
+        self.degradation.as_ref().map(|d| d.mode).unwrap_or(CacheMode::Disk)
+    }
+    
+    /// Open the on-disk database, verify it and apply migrations
+    fn open_on_disk() -> std::result::Result<Connection, OpenFailure> {
+        let db_path = Self::get_db_path().map_err(|e| OpenFailure::Storage(e.to_string()))?;
+        let classify = |e: anyhow::Error| Self::classify_open_error(&e);
+        
+        let conn = Connection::open(&db_path).map_err(|e| classify(e.into()))?;
+        
+        // Enable WAL mode for better concurrency
+        conn.pragma_update(None, "journal_mode", "WAL").map_err(|e| classify(e.into()))?;
+        conn.pragma_update(None, "foreign_keys", "ON").map_err(|e| classify(e.into()))?;
+        
+        let integrity: String = conn.query_row("PRAGMA quick_check", [], |row| row.get(0))
+            .map_err(|e| classify(e.into()))?;
+        if integrity != "ok" {
+            return Err(OpenFailure::Corrupt(format!("integrity check failed: {}", integrity)));
+        }
+        
+        // Apply migrations
+        Self::apply_migrations(&conn).map_err(classify)?;
+        Ok(conn)
+    }
+    
+    /// Decide whether an open error means corruption or a storage problem
+    fn classify_open_error(e: &anyhow::Error) -> OpenFailure {
+        use rusqlite::ErrorCode;
+        
+        match e.downcast_ref::<rusqlite::Error>().and_then(|e| e.sqlite_error_code()) {
+            Some(ErrorCode::DatabaseCorrupt) | Some(ErrorCode::NotADatabase) => OpenFailure::Corrupt(e.to_string()),
+            _ => OpenFailure::Storage(e.to_string()),
+        }
+    }
+    
+    /// Move the damaged database aside and start a fresh one
+    fn backup_and_recreate() -> Result<(Connection, Option<std::path::PathBuf>)> {
+        let backed_up = Self::move_database_aside("corrupt")?;
+        
+        let conn = Self::open_on_disk().map_err(|failure| match failure {
+            OpenFailure::Corrupt(reason) | OpenFailure::Storage(reason) => anyhow!(reason),
+        })?;
+        Ok((conn, backed_up))
+    }
+    
+    /// Rename the database file (suffix `db.<reason>-<timestamp>`), deleting it
+    /// if the rename fails; the next open starts from an empty cache
+    pub fn move_database_aside(reason: &str) -> Result<Option<std::path::PathBuf>> {
+        let db_path = Self::get_db_path()?;
+        if !db_path.exists() {
+            return Ok(None);
+        }
+        let backup_path = db_path.with_extension(format!("db.{}-{}", reason, chrono::Utc::now().format("%Y%m%d%H%M%S")));
+        
+        let backed_up = match std::fs::rename(&db_path, &backup_path) {
+            Ok(()) => {
+                log::warn!("📦 Moved cache database to {}", backup_path.display());
+                Some(backup_path)
+            }
+            Err(e) => {
+                log::warn!("Could not back up cache database: {}", e);
+                std::fs::remove_file(&db_path)?;
+                None
+            }
+        };
+        
+        // Stale WAL/SHM files belong to the old database
+        for ext in ["db-wal", "db-shm"] {
+            let _ = std::fs::remove_file(db_path.with_extension(ext));
+        }
+        
+        Ok(backed_up)
+    }
+    
+    /// Last resort: a throwaway in-memory database with the same schema
+    fn open_in_memory() -> Result<Connection> {
+        let conn = Connection::open_in_memory()?;
+        conn.pragma_update(None, "foreign_keys", "ON")?;
+        Self::apply_migrations(&conn)?;
+        log::warn!("🧠 Cache running in memory - cached data will not survive a restart");
+        Ok(conn)
+    }
+    
     /// Get the database path
     fn get_db_path() -> Result<std::path::PathBuf> {
         let home_dir = dirs::home_dir()
@@ -52,9 +189,89 @@ impl CacheManager {
         // In a production system, you'd track which migrations have been applied
         let migration_sql = include_str!("sql/004_cache_tables.sql");
         conn.execute_batch(migration_sql)?;
+        conn.execute_batch(include_str!("sql/005_cache_maintenance.sql"))?;
+        conn.execute_batch(include_str!("sql/006_device_aliases.sql"))?;
+        conn.execute_batch(include_str!("sql/007_utxo_tags.sql"))?;
+        conn.execute_batch(include_str!("sql/008_payment_intents.sql"))?;
+        conn.execute_batch(include_str!("sql/009_error_log.sql"))?;
+        conn.execute_batch(include_str!("sql/010_device_notes.sql"))?;
+        conn.execute_batch(include_str!("sql/011_activity_log.sql"))?;
+        conn.execute_batch(include_str!("sql/012_provider_requests.sql"))?;
+        conn.execute_batch(include_str!("sql/013_asset_registry.sql"))?;
+        conn.execute_batch(include_str!("sql/014_cache_tombstones.sql"))?;
+        conn.execute_batch(include_str!("sql/015_portfolio_goals.sql"))?;
+        conn.execute_batch(include_str!("sql/016_account_metadata.sql"))?;
         Ok(())
     }
     
+    /// Follow persisted aliases to the ID a device's data is stored under
+    fn resolve_alias(conn: &Connection, device_id: &str) -> String {
+        let mut current = device_id.to_string();
+        // Bounded so a bad alias cycle can't hang us
+        for _ in 0..8 {
+            let next: Option<String> = conn.query_row(
+                "SELECT canonical_id FROM device_aliases WHERE alias_id = ?1",
+                params![current],
+                |row| row.get(0),
+            ).optional().ok().flatten();
+            
+            match next {
+                Some(next) if next != current => current = next,
+                _ => break,
+            }
+        }
+        current
+    }
+    
+    /// Persist an alias so cached data stays attributed to the canonical device
+    pub async fn save_device_alias(&self, alias_id: &str, canonical_id: &str, reason: Option<&str>) -> Result<()> {
+        let db = self.db.lock().await;
+        
+        // Never alias a device onto itself (directly or through a chain)
+        let canonical_id = Self::resolve_alias(&db, canonical_id);
+        if canonical_id == alias_id {
+            return Ok(());
+        }
+        
+        db.execute(
+            "INSERT OR REPLACE INTO device_aliases (alias_id, canonical_id, reason, created_at)
+             VALUES (?1, ?2, ?3, strftime('%s', 'now'))",
+            params![alias_id, canonical_id, reason],
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Resolve a device ID through persisted aliases
+    pub async fn resolve_device_id(&self, device_id: &str) -> String {
+        let db = self.db.lock().await;
+        Self::resolve_alias(&db, device_id)
+    }
+    
+    /// All aliases of the physical device a device ID belongs to
+    pub async fn get_device_aliases(&self, device_id: &str) -> Result<Vec<DeviceAlias>> {
+        let db = self.db.lock().await;
+        let canonical_id = Self::resolve_alias(&db, device_id);
+        
+        let mut stmt = db.prepare(
+            "SELECT alias_id, canonical_id, reason, created_at
+             FROM device_aliases WHERE canonical_id = ?1
+             ORDER BY created_at",
+        )?;
+        
+        let aliases = stmt.query_map(params![canonical_id], |row| {
+            Ok(DeviceAlias {
+                alias_id: row.get(0)?,
+                canonical_id: row.get(1)?,
+                reason: row.get(2)?,
+                created_at: row.get(3)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(aliases)
+    }
+    
     /// Get a cached pubkey
     pub async fn get_cached_pubkey(
         &self,
@@ -64,6 +281,7 @@ impl CacheManager {
         script_type: Option<&str>,
     ) -> Option<CachedPubkey> {
         let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
         
         let result: Option<CachedPubkey> = db.query_row(
             "SELECT id, device_id, derivation_path, coin_name, script_type, 
@@ -72,21 +290,7 @@ impl CacheManager {
              WHERE device_id = ?1 AND derivation_path = ?2 AND coin_name = ?3 
              AND (script_type = ?4 OR (?4 IS NULL AND script_type IS NULL))",
             params![device_id, derivation_path, coin_name, script_type],
-            |row| {
-                Ok(CachedPubkey {
-                    id: row.get(0)?,
-                    device_id: row.get(1)?,
-                    derivation_path: row.get(2)?,
-                    coin_name: row.get(3)?,
-                    script_type: row.get(4)?,
-                    xpub: row.get(5)?,
-                    address: row.get(6)?,
-                    chain_code: row.get(7)?,
-                    public_key: row.get(8)?,
-                    cached_at: row.get(9)?,
-                    last_used: row.get(10)?,
-                })
-            },
+            Self::pubkey_from_row,
         ).optional().ok().flatten();
         
         // Update stats
@@ -109,9 +313,79 @@ impl CacheManager {
         result
     }
     
+    /// List every cached pubkey/address for a device, most recently used first
+    pub async fn list_cached_pubkeys(&self, device_id: &str) -> Result<Vec<CachedPubkey>> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        
+        let mut stmt = db.prepare(
+            "SELECT id, device_id, derivation_path, coin_name, script_type, 
+                    xpub, address, chain_code, public_key, cached_at, last_used
+             FROM cached_pubkeys WHERE device_id = ?1
+             ORDER BY last_used DESC",
+        )?;
+        
+        let pubkeys = stmt.query_map(params![device_id], Self::pubkey_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(pubkeys)
+    }
+    
+    /// Cached entries, across devices, whose address matches ignoring case
+    pub async fn find_pubkeys_by_address(&self, address: &str) -> Result<Vec<CachedPubkey>> {
+        let db = self.db.lock().await;
+        
+        let mut stmt = db.prepare(
+            "SELECT id, device_id, derivation_path, coin_name, script_type, 
+                    xpub, address, chain_code, public_key, cached_at, last_used
+             FROM cached_pubkeys WHERE lower(address) = lower(?1)",
+        )?;
+        
+        let pubkeys = stmt.query_map(params![address], Self::pubkey_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(pubkeys)
+    }
+    
+    /// Every cached account xpub, across devices
+    pub async fn list_cached_xpubs(&self) -> Result<Vec<CachedPubkey>> {
+        let db = self.db.lock().await;
+        
+        let mut stmt = db.prepare(
+            "SELECT id, device_id, derivation_path, coin_name, script_type, 
+                    xpub, address, chain_code, public_key, cached_at, last_used
+             FROM cached_pubkeys WHERE xpub IS NOT NULL
+             ORDER BY device_id, derivation_path",
+        )?;
+        
+        let pubkeys = stmt.query_map([], Self::pubkey_from_row)?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(pubkeys)
+    }
+    
+    /// Map a row selected as id, device_id, derivation_path, coin_name, script_type,
+    /// xpub, address, chain_code, public_key, cached_at, last_used
+    fn pubkey_from_row(row: &rusqlite::Row) -> rusqlite::Result<CachedPubkey> {
+        Ok(CachedPubkey {
+            id: row.get(0)?,
+            device_id: row.get(1)?,
+            derivation_path: row.get(2)?,
+            coin_name: row.get(3)?,
+            script_type: row.get(4)?,
+            xpub: row.get(5)?,
+            address: row.get(6)?,
+            chain_code: row.get(7)?,
+            public_key: row.get(8)?,
+            cached_at: row.get(9)?,
+            last_used: row.get(10)?,
+        })
+    }
+    
     /// Save a pubkey to cache
     pub async fn save_pubkey(&self, pubkey: &CachedPubkey) -> Result<()> {
         let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, &pubkey.device_id);
         
         db.execute(
             "INSERT OR REPLACE INTO cached_pubkeys 
@@ -119,7 +393,7 @@ impl CacheManager {
               chain_code, public_key, cached_at, last_used)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
             params![
-                pubkey.device_id,
+                device_id,
                 pubkey.derivation_path,
                 pubkey.coin_name,
                 pubkey.script_type,
@@ -135,9 +409,571 @@ impl CacheManager {
         Ok(())
     }
     
+    /// Labels and freeze flags recorded for a device's UTXOs
+    pub async fn list_utxo_tags(&self, device_id: &str) -> Result<Vec<UtxoTag>> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        
+        let mut stmt = db.prepare(
+            "SELECT device_id, txid, vout, label, frozen, updated_at
+             FROM utxo_tags WHERE device_id = ?1
+             ORDER BY updated_at DESC",
+        )?;
+        
+        let tags = stmt.query_map(params![device_id], Self::utxo_tag_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(tags)
+    }
+    
+    /// UTXO labels and freeze flags of every device
+    pub async fn list_all_utxo_tags(&self) -> Result<Vec<UtxoTag>> {
+        let db = self.db.lock().await;
+        
+        let mut stmt = db.prepare(
+            "SELECT device_id, txid, vout, label, frozen, updated_at
+             FROM utxo_tags ORDER BY device_id, updated_at DESC",
+        )?;
+        let tags = stmt.query_map([], Self::utxo_tag_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(tags)
+    }
+    
+    fn utxo_tag_from_row(row: &rusqlite::Row) -> rusqlite::Result<UtxoTag> {
+        Ok(UtxoTag {
+            device_id: row.get(0)?,
+            txid: row.get(1)?,
+            vout: row.get(2)?,
+            label: row.get(3)?,
+            frozen: row.get(4)?,
+            updated_at: row.get(5)?,
+        })
+    }
+    
+    /// A device's frozen outpoints as "txid:vout"
+    pub async fn list_frozen_outpoints(&self, device_id: &str) -> Result<Vec<String>> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        
+        let mut stmt = db.prepare("SELECT txid, vout FROM utxo_tags WHERE device_id = ?1 AND frozen = 1")?;
+        let outpoints = stmt.query_map(params![device_id], |row| {
+            let txid: String = row.get(0)?;
+            let vout: u32 = row.get(1)?;
+            Ok(format!("{}:{}", txid, vout))
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(outpoints)
+    }
+    
+    /// Label and/or freeze a UTXO; a tag with no label that isn't frozen is removed
+    pub async fn save_utxo_tag(&self, tag: &UtxoTag) -> Result<()> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, &tag.device_id);
+        
+        if tag.label.is_none() && !tag.frozen {
+            db.execute(
+                "DELETE FROM utxo_tags WHERE device_id = ?1 AND txid = ?2 AND vout = ?3",
+                params![device_id, tag.txid, tag.vout],
+            )?;
+            return Ok(());
+        }
+        
+        db.execute(
+            "INSERT OR REPLACE INTO utxo_tags (device_id, txid, vout, label, frozen, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![device_id, tag.txid, tag.vout, tag.label, tag.frozen, tag.updated_at],
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Look up a payment intent
+    pub async fn get_payment_intent(&self, id: &str) -> Result<Option<PendingPaymentIntent>> {
+        let db = self.db.lock().await;
+        
+        let intent = db.query_row(
+            "SELECT id, caip, recipient, fiat_currency, fiat_amount, crypto_amount,
+                    locked_rate, status, created_at, expires_at, confirmed_at
+             FROM payment_intents WHERE id = ?1",
+            params![id],
+            |row| {
+                Ok(PendingPaymentIntent {
+                    id: row.get(0)?,
+                    caip: row.get(1)?,
+                    recipient: row.get(2)?,
+                    fiat_currency: row.get(3)?,
+                    fiat_amount: row.get(4)?,
+                    crypto_amount: row.get(5)?,
+                    locked_rate: row.get(6)?,
+                    status: row.get(7)?,
+                    created_at: row.get(8)?,
+                    expires_at: row.get(9)?,
+                    confirmed_at: row.get(10)?,
+                })
+            },
+        ).optional()?;
+        
+        Ok(intent)
+    }
+    
+    /// Insert or update a payment intent
+    pub async fn save_payment_intent(&self, intent: &PendingPaymentIntent) -> Result<()> {
+        let db = self.db.lock().await;
+        
+        db.execute(
+            "INSERT OR REPLACE INTO payment_intents
+             (id, caip, recipient, fiat_currency, fiat_amount, crypto_amount,
+              locked_rate, status, created_at, expires_at, confirmed_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            params![
+                intent.id,
+                intent.caip,
+                intent.recipient,
+                intent.fiat_currency,
+                intent.fiat_amount,
+                intent.crypto_amount,
+                intent.locked_rate,
+                intent.status,
+                intent.created_at,
+                intent.expires_at,
+                intent.confirmed_at,
+            ],
+        )?;
+        
+        Ok(())
+    }
+    
+    /// Drop intents that were never confirmed and expired before the cutoff
+    pub async fn prune_payment_intents(&self, before: i64) -> Result<usize> {
+        let db = self.db.lock().await;
+        let removed = db.execute(
+            "DELETE FROM payment_intents WHERE status != 'confirmed' AND expires_at < ?1",
+            params![before],
+        )?;
+        Ok(removed)
+    }
+    
+    /// Record a backend failure, keeping only the most recent `keep` entries
+    pub async fn save_error(&self, error: &ErrorRecord, keep: usize) -> Result<i64> {
+        let db = self.db.lock().await;
+        
+        db.execute(
+            "INSERT INTO error_log (category, message, device_id, correlation_id, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![error.category, error.message, error.device_id, error.correlation_id, error.created_at],
+        )?;
+        let id = db.last_insert_rowid();
+        
+        db.execute(
+            "DELETE FROM error_log WHERE id NOT IN (SELECT id FROM error_log ORDER BY id DESC LIMIT ?1)",
+            params![keep as i64],
+        )?;
+        
+        Ok(id)
+    }
+    
+    /// Most recent errors first
+    pub async fn list_errors(&self, limit: usize) -> Result<Vec<ErrorRecord>> {
+        let db = self.db.lock().await;
+        
+        let mut stmt = db.prepare(
+            "SELECT id, category, message, device_id, correlation_id, created_at
+             FROM error_log ORDER BY id DESC LIMIT ?1",
+        )?;
+        let errors = stmt.query_map(params![limit as i64], |row| {
+            Ok(ErrorRecord {
+                id: row.get(0)?,
+                category: row.get(1)?,
+                message: row.get(2)?,
+                device_id: row.get(3)?,
+                correlation_id: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(errors)
+    }
+    
+    /// Remove every recorded error
+    pub async fn clear_errors(&self) -> Result<usize> {
+        let db = self.db.lock().await;
+        Ok(db.execute("DELETE FROM error_log", [])?)
+    }
+    
+    /// Pairs of devices that cached the same xpub/address at the same path
+    pub async fn find_shared_accounts(&self) -> Result<Vec<(String, String)>> {
+        let db = self.db.lock().await;
+        
+        let mut stmt = db.prepare(
+            "SELECT DISTINCT a.device_id, b.device_id
+             FROM cached_pubkeys a
+             JOIN cached_pubkeys b
+               ON a.derivation_path = b.derivation_path
+              AND a.coin_name = b.coin_name
+              AND COALESCE(a.xpub, a.address) = COALESCE(b.xpub, b.address)
+              AND a.device_id < b.device_id
+             WHERE COALESCE(a.xpub, a.address) IS NOT NULL",
+        )?;
+        let pairs = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(pairs)
+    }
+    
+    /// Record a timeline event, keeping at most `keep` entries per device
+    pub async fn save_activity(&self, entry: &ActivityEntry, keep: usize) -> Result<i64> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, &entry.device_id);
+        
+        db.execute(
+            "INSERT INTO activity_log (device_id, kind, summary, details, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![device_id, entry.kind, entry.summary, entry.details, entry.created_at],
+        )?;
+        let id = db.last_insert_rowid();
+        
+        db.execute(
+            "DELETE FROM activity_log WHERE device_id = ?1 AND id NOT IN
+             (SELECT id FROM activity_log WHERE device_id = ?1 ORDER BY id DESC LIMIT ?2)",
+            params![device_id, keep as i64],
+        )?;
+        
+        Ok(id)
+    }
+    
+    /// Most recent activity of a device first
+    pub async fn list_activity(&self, device_id: &str, limit: usize) -> Result<Vec<ActivityEntry>> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        
+        let mut stmt = db.prepare(
+            "SELECT id, device_id, kind, summary, details, created_at
+             FROM activity_log WHERE device_id = ?1 ORDER BY created_at DESC, id DESC LIMIT ?2",
+        )?;
+        let entries = stmt.query_map(params![device_id, limit as i64], |row| {
+            Ok(ActivityEntry {
+                id: row.get(0)?,
+                device_id: row.get(1)?,
+                kind: row.get(2)?,
+                summary: row.get(3)?,
+                details: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(entries)
+    }
+    
+    /// Errors recorded for one device, newest first
+    pub async fn list_device_errors(&self, device_id: &str, limit: usize) -> Result<Vec<ErrorRecord>> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        
+        let mut stmt = db.prepare(
+            "SELECT id, category, message, device_id, correlation_id, created_at
+             FROM error_log WHERE device_id = ?1 ORDER BY id DESC LIMIT ?2",
+        )?;
+        let errors = stmt.query_map(params![device_id, limit as i64], |row| {
+            Ok(ErrorRecord {
+                id: row.get(0)?,
+                category: row.get(1)?,
+                message: row.get(2)?,
+                device_id: row.get(3)?,
+                correlation_id: row.get(4)?,
+                created_at: row.get(5)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(errors)
+    }
+    
+    /// Insert a note, or replace the contents of an existing one (id != 0)
+    pub async fn save_note(&self, note: &EncryptedNote) -> Result<i64> {
+        let db = self.db.lock().await;
+        
+        if note.id == 0 {
+            let device_id = Self::resolve_alias(&db, &note.device_id);
+            db.execute(
+                "INSERT INTO device_notes (device_id, account_path, salt, nonce, ciphertext, created_at, updated_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                params![device_id, note.account_path, note.salt, note.nonce, note.ciphertext, note.created_at, note.updated_at],
+            )?;
+            return Ok(db.last_insert_rowid());
+        }
+        
+        let updated = db.execute(
+            "UPDATE device_notes SET salt = ?1, nonce = ?2, ciphertext = ?3, updated_at = ?4 WHERE id = ?5",
+            params![note.salt, note.nonce, note.ciphertext, note.updated_at, note.id],
+        )?;
+        if updated == 0 {
+            return Err(anyhow!("Note {} not found", note.id));
+        }
+        Ok(note.id)
+    }
+    
+    pub async fn get_note(&self, id: i64) -> Result<Option<EncryptedNote>> {
+        let db = self.db.lock().await;
+        
+        Ok(db.query_row(
+            "SELECT id, device_id, account_path, salt, nonce, ciphertext, created_at, updated_at
+             FROM device_notes WHERE id = ?1",
+            params![id],
+            Self::note_from_row,
+        ).optional()?)
+    }
+    
+    /// Notes for a device (including its accounts), oldest first
+    pub async fn list_notes(&self, device_id: &str) -> Result<Vec<EncryptedNote>> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        
+        let mut stmt = db.prepare(
+            "SELECT id, device_id, account_path, salt, nonce, ciphertext, created_at, updated_at
+             FROM device_notes WHERE device_id = ?1 ORDER BY id",
+        )?;
+        let notes = stmt.query_map(params![device_id], Self::note_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(notes)
+    }
+    
+    /// Notes of every device, oldest first
+    pub async fn list_all_notes(&self) -> Result<Vec<EncryptedNote>> {
+        let db = self.db.lock().await;
+        
+        let mut stmt = db.prepare(
+            "SELECT id, device_id, account_path, salt, nonce, ciphertext, created_at, updated_at
+             FROM device_notes ORDER BY id",
+        )?;
+        let notes = stmt.query_map([], Self::note_from_row)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(notes)
+    }
+    
+    /// Insert a note from a backup under a new id, unless the same ciphertext is
+    /// already stored for the device. Returns whether it was inserted.
+    pub async fn restore_note(&self, note: &EncryptedNote) -> Result<bool> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, &note.device_id);
+        
+        let exists: bool = db.query_row(
+            "SELECT EXISTS(SELECT 1 FROM device_notes WHERE device_id = ?1 AND ciphertext = ?2)",
+            params![device_id, note.ciphertext],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Ok(false);
+        }
+        db.execute(
+            "INSERT INTO device_notes (device_id, account_path, salt, nonce, ciphertext, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![device_id, note.account_path, note.salt, note.nonce, note.ciphertext, note.created_at, note.updated_at],
+        )?;
+        Ok(true)
+    }
+    
+    pub async fn delete_note(&self, id: i64) -> Result<bool> {
+        let db = self.db.lock().await;
+        Ok(db.execute("DELETE FROM device_notes WHERE id = ?1", params![id])? > 0)
+    }
+    
+    fn note_from_row(row: &rusqlite::Row) -> rusqlite::Result<EncryptedNote> {
+        Ok(EncryptedNote {
+            id: row.get(0)?,
+            device_id: row.get(1)?,
+            account_path: row.get(2)?,
+            salt: row.get(3)?,
+            nonce: row.get(4)?,
+            ciphertext: row.get(5)?,
+            created_at: row.get(6)?,
+            updated_at: row.get(7)?,
+        })
+    }
+    
+    /// Record an outbound provider request, keeping at most `keep` per provider
+    pub async fn save_provider_request(&self, request: &ProviderRequest, keep: usize) -> Result<i64> {
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT INTO provider_requests (provider, endpoint, status, bytes, latency_ms, error, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                request.provider, request.endpoint, request.status, request.bytes as i64,
+                request.latency_ms as i64, request.error, request.created_at,
+            ],
+        )?;
+        let id = db.last_insert_rowid();
+        
+        db.execute(
+            "DELETE FROM provider_requests WHERE provider = ?1 AND id NOT IN
+             (SELECT id FROM provider_requests WHERE provider = ?1 ORDER BY id DESC LIMIT ?2)",
+            params![request.provider, keep as i64],
+        )?;
+        
+        Ok(id)
+    }
+    
+    /// Requests to a provider made at or after `since`, newest first
+    pub async fn list_provider_requests(&self, provider: &str, since: i64) -> Result<Vec<ProviderRequest>> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare(
+            "SELECT id, provider, endpoint, status, bytes, latency_ms, error, created_at
+             FROM provider_requests WHERE provider = ?1 AND created_at >= ?2
+             ORDER BY created_at DESC, id DESC",
+        )?;
+        let requests = stmt.query_map(params![provider, since], |row| {
+            Ok(ProviderRequest {
+                id: row.get(0)?,
+                provider: row.get(1)?,
+                endpoint: row.get(2)?,
+                status: row.get(3)?,
+                bytes: row.get::<_, i64>(4)? as u64,
+                latency_ms: row.get::<_, i64>(5)? as u64,
+                error: row.get(6)?,
+                created_at: row.get(7)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        
+        Ok(requests)
+    }
+    
+    /// Stored signed asset registry manifest and when it was fetched
+    pub async fn get_asset_registry(&self) -> Result<Option<(String, i64)>> {
+        let db = self.db.lock().await;
+        Ok(db.query_row(
+            "SELECT manifest, fetched_at FROM asset_registry WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        ).optional()?)
+    }
+    
+    /// Version of the last accepted remote asset registry
+    pub async fn get_asset_registry_version(&self) -> Result<Option<String>> {
+        let db = self.db.lock().await;
+        Ok(db.query_row(
+            "SELECT version FROM asset_registry WHERE id = 1",
+            [],
+            |row| row.get(0),
+        ).optional()?)
+    }
+    
+    /// Replace the stored asset registry manifest
+    pub async fn save_asset_registry(&self, version: &str, manifest: &str, fetched_at: i64) -> Result<()> {
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT OR REPLACE INTO asset_registry (id, version, manifest, fetched_at) VALUES (1, ?1, ?2, ?3)",
+            params![version, manifest, fetched_at],
+        )?;
+        Ok(())
+    }
+    
+    /// All portfolio goals, oldest first
+    pub async fn list_portfolio_goals(&self) -> Result<Vec<PortfolioGoal>> {
+        let db = self.db.lock().await;
+        let mut stmt = db.prepare(
+            "SELECT id, name, unit, target, deadline, current, milestone, achieved_at, created_at, updated_at
+             FROM portfolio_goals ORDER BY created_at, id",
+        )?;
+        let goals = stmt.query_map([], |row| {
+            Ok(PortfolioGoal {
+                id: row.get(0)?,
+                name: row.get(1)?,
+                unit: row.get(2)?,
+                target: row.get(3)?,
+                deadline: row.get(4)?,
+                current: row.get(5)?,
+                milestone: row.get(6)?,
+                achieved_at: row.get(7)?,
+                created_at: row.get(8)?,
+                updated_at: row.get(9)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(goals)
+    }
+    
+    /// Insert or replace a portfolio goal
+    pub async fn save_portfolio_goal(&self, goal: &PortfolioGoal) -> Result<()> {
+        let db = self.db.lock().await;
+        db.execute(
+            "INSERT OR REPLACE INTO portfolio_goals
+                (id, name, unit, target, deadline, current, milestone, achieved_at, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+            params![
+                goal.id, goal.name, goal.unit, goal.target, goal.deadline, goal.current,
+                goal.milestone, goal.achieved_at, goal.created_at, goal.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+    
+    /// Remove a portfolio goal; returns whether it existed
+    pub async fn delete_portfolio_goal(&self, id: &str) -> Result<bool> {
+        let db = self.db.lock().await;
+        Ok(db.execute("DELETE FROM portfolio_goals WHERE id = ?1", params![id])? > 0)
+    }
+    
+    /// Account names for a device, or for every device
+    pub async fn list_account_metadata(&self, device_id: Option<&str>) -> Result<Vec<AccountMetadata>> {
+        let db = self.db.lock().await;
+        let device_id = device_id.map(|id| Self::resolve_alias(&db, id));
+        let mut stmt = db.prepare(
+            "SELECT device_id, account_path, name, description, color, created_at, updated_at
+             FROM account_metadata
+             WHERE ?1 IS NULL OR device_id = ?1
+             ORDER BY device_id, account_path",
+        )?;
+        let accounts = stmt.query_map(params![device_id], |row| {
+            Ok(AccountMetadata {
+                device_id: row.get(0)?,
+                account_path: row.get(1)?,
+                name: row.get(2)?,
+                description: row.get(3)?,
+                color: row.get(4)?,
+                created_at: row.get(5)?,
+                updated_at: row.get(6)?,
+            })
+        })?
+        .collect::<rusqlite::Result<Vec<_>>>()?;
+        Ok(accounts)
+    }
+    
+    /// Insert or replace an account's name and metadata
+    pub async fn save_account_metadata(&self, account: &AccountMetadata) -> Result<()> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, &account.device_id);
+        db.execute(
+            "INSERT OR REPLACE INTO account_metadata
+                (device_id, account_path, name, description, color, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                device_id, account.account_path, account.name, account.description,
+                account.color, account.created_at, account.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+    
+    /// Remove an account's name; returns whether it had one
+    pub async fn delete_account_metadata(&self, device_id: &str, account_path: &str) -> Result<bool> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        Ok(db.execute(
+            "DELETE FROM account_metadata WHERE device_id = ?1 AND account_path = ?2",
+            params![device_id, account_path],
+        )? > 0)
+    }
+    
     /// Get cache metadata for a device
     pub async fn get_cache_metadata(&self, device_id: &str) -> Option<CacheMetadata> {
         let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
         
         db.query_row(
             "SELECT device_id, label, firmware_version, initialized, 
@@ -166,6 +1002,7 @@ impl CacheManager {
     /// Update cache metadata
     pub async fn update_cache_metadata(&self, metadata: &CacheMetadata) -> Result<()> {
         let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, &metadata.device_id);
         
         db.execute(
             "INSERT OR REPLACE INTO cache_metadata 
@@ -173,7 +1010,7 @@ impl CacheManager {
               frontload_status, frontload_progress, last_frontload, error_message)
              VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
             params![
-                metadata.device_id,
+                device_id,
                 metadata.label,
                 metadata.firmware_version,
                 metadata.initialized,
@@ -230,9 +1067,10 @@ impl CacheManager {
         })
     }
     
-    /// Clear cache for a specific device
+    /// Permanently clear cache for a specific device, e.g. after a wipe
     pub async fn clear_device_cache(&self, device_id: &str) -> Result<()> {
         let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
         
         db.execute(
             "DELETE FROM cached_pubkeys WHERE device_id = ?1",
@@ -244,9 +1082,102 @@ impl CacheManager {
             params![device_id],
         )?;
         
+        // Tombstones belong to the old seed; never offer them for restore
+        db.execute("DELETE FROM deleted_cached_pubkeys WHERE device_id = ?1", params![device_id])?;
+        db.execute("DELETE FROM deleted_cache_metadata WHERE device_id = ?1", params![device_id])?;
+        
         Ok(())
     }
     
+    /// Move a device's cache to the tombstone tables so the clear can be undone.
+    /// A previous tombstone for the device is replaced.
+    pub async fn soft_delete_device_cache(&self, device_id: &str) -> Result<usize> {
+        let mut db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        let now = chrono::Utc::now().timestamp();
+        let tx = db.transaction()?;
+        
+        tx.execute("DELETE FROM deleted_cached_pubkeys WHERE device_id = ?1", params![device_id])?;
+        tx.execute("DELETE FROM deleted_cache_metadata WHERE device_id = ?1", params![device_id])?;
+        let moved = tx.execute(
+            "INSERT INTO deleted_cached_pubkeys
+                (device_id, derivation_path, coin_name, script_type, xpub, address,
+                 chain_code, public_key, cached_at, last_used, deleted_at)
+             SELECT device_id, derivation_path, coin_name, script_type, xpub, address,
+                    chain_code, public_key, cached_at, last_used, ?2
+             FROM cached_pubkeys WHERE device_id = ?1",
+            params![device_id, now],
+        )?;
+        tx.execute(
+            "INSERT INTO deleted_cache_metadata
+                (device_id, label, firmware_version, initialized, frontload_status,
+                 frontload_progress, last_frontload, error_message, deleted_at)
+             SELECT device_id, label, firmware_version, initialized, frontload_status,
+                    frontload_progress, last_frontload, error_message, ?2
+             FROM cache_metadata WHERE device_id = ?1",
+            params![device_id, now],
+        )?;
+        tx.execute("DELETE FROM cached_pubkeys WHERE device_id = ?1", params![device_id])?;
+        tx.execute("DELETE FROM cache_metadata WHERE device_id = ?1", params![device_id])?;
+        tx.commit()?;
+        
+        Ok(moved)
+    }
+    
+    /// When a device's cache was soft-deleted, if it can still be restored
+    pub async fn get_cache_tombstone(&self, device_id: &str) -> Option<i64> {
+        let db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        db.query_row(
+            "SELECT MAX(deleted_at) FROM (
+                SELECT deleted_at FROM deleted_cached_pubkeys WHERE device_id = ?1
+                UNION ALL
+                SELECT deleted_at FROM deleted_cache_metadata WHERE device_id = ?1
+            )",
+            params![device_id],
+            |row| row.get(0),
+        ).optional().ok().flatten().flatten()
+    }
+    
+    /// Bring a soft-deleted cache back. Entries cached again since the clear are kept.
+    pub async fn restore_device_cache(&self, device_id: &str) -> Result<usize> {
+        let mut db = self.db.lock().await;
+        let device_id = Self::resolve_alias(&db, device_id);
+        let tx = db.transaction()?;
+        
+        let restored = tx.execute(
+            "INSERT OR IGNORE INTO cached_pubkeys
+                (device_id, derivation_path, coin_name, script_type, xpub, address,
+                 chain_code, public_key, cached_at, last_used)
+             SELECT device_id, derivation_path, coin_name, script_type, xpub, address,
+                    chain_code, public_key, cached_at, last_used
+             FROM deleted_cached_pubkeys WHERE device_id = ?1",
+            params![device_id],
+        )?;
+        tx.execute(
+            "INSERT OR IGNORE INTO cache_metadata
+                (device_id, label, firmware_version, initialized, frontload_status,
+                 frontload_progress, last_frontload, error_message)
+             SELECT device_id, label, firmware_version, initialized, frontload_status,
+                    frontload_progress, last_frontload, error_message
+             FROM deleted_cache_metadata WHERE device_id = ?1",
+            params![device_id],
+        )?;
+        tx.execute("DELETE FROM deleted_cached_pubkeys WHERE device_id = ?1", params![device_id])?;
+        tx.execute("DELETE FROM deleted_cache_metadata WHERE device_id = ?1", params![device_id])?;
+        tx.commit()?;
+        
+        Ok(restored)
+    }
+    
+    /// Permanently drop tombstones deleted before `cutoff`
+    pub async fn purge_cache_tombstones(&self, cutoff: i64) -> Result<usize> {
+        let db = self.db.lock().await;
+        let purged = db.execute("DELETE FROM deleted_cached_pubkeys WHERE deleted_at < ?1", params![cutoff])?;
+        db.execute("DELETE FROM deleted_cache_metadata WHERE deleted_at < ?1", params![cutoff])?;
+        Ok(purged)
+    }
+    
     /// Clean up old cache entries (older than 30 days)
     pub async fn cleanup_old_entries(&self) -> Result<i64> {
         let db = self.db.lock().await;
@@ -259,4 +1190,96 @@ impl CacheManager {
         
         Ok(count as i64)
     }
-} 
\ No newline at end of file
+    
+    /// Size of the database file plus its WAL sidecar
+    fn file_sizes() -> Result<(u64, u64)> {
+        let db_path = Self::get_db_path()?;
+        let wal_path = db_path.with_extension("db-wal");
+        
+        let db_bytes = std::fs::metadata(&db_path).map(|m| m.len()).unwrap_or(0);
+        let wal_bytes = std::fs::metadata(&wal_path).map(|m| m.len()).unwrap_or(0);
+        
+        Ok((db_bytes, wal_bytes))
+    }
+    
+    /// Get the timestamp of the last vacuum, if any
+    pub async fn get_last_vacuum(&self) -> Option<i64> {
+        let db = self.db.lock().await;
+        
+        db.query_row(
+            "SELECT value FROM cache_maintenance WHERE key = 'last_vacuum'",
+            [],
+            |row| row.get(0),
+        ).optional().ok().flatten()
+    }
+    
+    /// Report how much disk space the cache is using
+    pub async fn get_disk_usage(&self) -> Result<CacheDiskUsage> {
+        let last_vacuum = self.get_last_vacuum().await;
+        let (db_bytes, wal_bytes) = Self::file_sizes()?;
+        
+        let db = self.db.lock().await;
+        
+        let page_size: i64 = db.query_row("PRAGMA page_size", [], |row| row.get(0))?;
+        let page_count: i64 = db.query_row("PRAGMA page_count", [], |row| row.get(0))?;
+        let freelist_count: i64 = db.query_row("PRAGMA freelist_count", [], |row| row.get(0))?;
+        
+        let total_entries: i64 = db.query_row(
+            "SELECT COUNT(*) FROM cached_pubkeys",
+            [],
+            |row| row.get(0),
+        )?;
+        
+        let device_count: i64 = db.query_row(
+            "SELECT COUNT(DISTINCT device_id) FROM cached_pubkeys",
+            [],
+            |row| row.get(0),
+        )?;
+        
+        Ok(CacheDiskUsage {
+            db_path: Self::get_db_path()?.display().to_string(),
+            db_bytes,
+            wal_bytes,
+            total_bytes: db_bytes + wal_bytes,
+            page_size,
+            page_count,
+            freelist_count,
+            reclaimable_bytes: freelist_count * page_size,
+            total_entries,
+            device_count,
+            last_vacuum,
+        })
+    }
+    
+    /// Drop stale entries, then vacuum the database and truncate the WAL
+    pub async fn compact(&self) -> Result<CacheCompactionResult> {
+        let started = std::time::Instant::now();
+        let (db_before, wal_before) = Self::file_sizes()?;
+        
+        let removed_entries = self.cleanup_old_entries().await?;
+        
+        {
+            let db = self.db.lock().await;
+            
+            db.execute_batch("VACUUM;")?;
+            db.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))?;
+            
+            db.execute(
+                "INSERT OR REPLACE INTO cache_maintenance (key, value) VALUES ('last_vacuum', ?1)",
+                params![chrono::Utc::now().timestamp()],
+            )?;
+        }
+        
+        let (db_after, wal_after) = Self::file_sizes()?;
+        let bytes_before = db_before + wal_before;
+        let bytes_after = db_after + wal_after;
+        
+        Ok(CacheCompactionResult {
+            removed_entries,
+            bytes_before,
+            bytes_after,
+            bytes_reclaimed: bytes_before.saturating_sub(bytes_after),
+            duration_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+}
@@ -7,6 +7,78 @@ pub fn get_cache_migrations() -> Vec<Migration> {
             description: "create_cache_tables",
             sql: include_str!("sql/004_cache_tables.sql"),
             kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 5,
+            description: "create_cache_maintenance",
+            sql: include_str!("sql/005_cache_maintenance.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 6,
+            description: "create_device_aliases",
+            sql: include_str!("sql/006_device_aliases.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 7,
+            description: "create_utxo_tags",
+            sql: include_str!("sql/007_utxo_tags.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 8,
+            description: "create_payment_intents",
+            sql: include_str!("sql/008_payment_intents.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 9,
+            description: "create_error_log",
+            sql: include_str!("sql/009_error_log.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 10,
+            description: "create_device_notes",
+            sql: include_str!("sql/010_device_notes.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 11,
+            description: "create_activity_log",
+            sql: include_str!("sql/011_activity_log.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 12,
+            description: "create_provider_requests",
+            sql: include_str!("sql/012_provider_requests.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 13,
+            description: "create_asset_registry",
+            sql: include_str!("sql/013_asset_registry.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 14,
+            description: "create_cache_tombstones",
+            sql: include_str!("sql/014_cache_tombstones.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 15,
+            description: "create_portfolio_goals",
+            sql: include_str!("sql/015_portfolio_goals.sql"),
+            kind: MigrationKind::Up,
+        },
+        Migration {
+            version: 16,
+            description: "create_account_metadata",
+            sql: include_str!("sql/016_account_metadata.sql"),
+            kind: MigrationKind::Up,
         }
     ]
 } 
\ No newline at end of file
@@ -2,10 +2,12 @@ pub mod manager;
 pub mod frontload;
 pub mod migrations;
 pub mod types;
+pub mod maintenance;
+pub mod schedule;
 
 pub use manager::CacheManager;
 pub use frontload::FrontloadController;
-pub use types::{CachedPubkey, CacheMetadata, CacheStatus};
+pub use types::{CachedPubkey, CacheMetadata, CacheStatus, CacheDiskUsage, CacheCompactionResult, DeviceAlias, UtxoTag, PendingPaymentIntent, ErrorRecord, EncryptedNote, ActivityEntry, ProviderRequest, PortfolioGoal, AccountMetadata, CacheMode, CacheDegradation};
 
 use std::sync::Arc;
 
@@ -0,0 +1,246 @@
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use chrono::Timelike;
+use tauri::{AppHandle, Manager};
+use super::CacheManager;
+use crate::commands::DeviceQueueManager;
+
+/// Preference key holding per-device frontload schedules (keyed by device id)
+const SCHEDULE_PREFERENCE_KEY: &str = "frontload_schedules";
+
+/// Preference key holding the user's chain ranking for frontload
+const PRIORITY_PREFERENCE_KEY: &str = "frontload_chain_priority";
+
+/// How much work a frontload run does
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FrontloadMode {
+    /// Re-derive every default path, refreshing cached entries
+    Full,
+    /// Only derive paths that aren't cached yet
+    Incremental,
+}
+
+impl Default for FrontloadMode {
+    fn default() -> Self {
+        FrontloadMode::Incremental
+    }
+}
+
+impl std::str::FromStr for FrontloadMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "full" => Ok(FrontloadMode::Full),
+            "incremental" => Ok(FrontloadMode::Incremental),
+            _ => Err(format!("Invalid frontload mode: {}", s)),
+        }
+    }
+}
+
+/// Local-time window during which automatic frontload is skipped
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    /// Hour (0-23) the quiet window starts
+    pub start_hour: u32,
+    /// Hour (0-23) the quiet window ends (exclusive), may wrap past midnight
+    pub end_hour: u32,
+}
+
+impl QuietHours {
+    /// Check whether an hour of the day falls inside the quiet window
+    pub fn contains(&self, hour: u32) -> bool {
+        if self.start_hour == self.end_hour {
+            false
+        } else if self.start_hour < self.end_hour {
+            hour >= self.start_hour && hour < self.end_hour
+        } else {
+            // Window wraps past midnight, e.g. 22 -> 7
+            hour >= self.start_hour || hour < self.end_hour
+        }
+    }
+}
+
+/// Per-device frontload scheduling preferences
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontloadSchedule {
+    /// Frontload automatically when the device becomes ready
+    pub auto_on_connect: bool,
+    #[serde(default)]
+    pub mode: FrontloadMode,
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+}
+
+impl Default for FrontloadSchedule {
+    fn default() -> Self {
+        Self {
+            auto_on_connect: true,
+            mode: FrontloadMode::Incremental,
+            quiet_hours: None,
+        }
+    }
+}
+
+/// Get the frontload schedule for a device, falling back to the default
+pub fn get_schedule(device_id: &str) -> FrontloadSchedule {
+    crate::preferences::get(SCHEDULE_PREFERENCE_KEY)
+        .get(device_id)
+        .cloned()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Persist the frontload schedule for a device
+pub fn set_schedule(device_id: &str, schedule: &FrontloadSchedule) -> Result<(), String> {
+    if let Some(quiet) = &schedule.quiet_hours {
+        if quiet.start_hour > 23 || quiet.end_hour > 23 {
+            return Err("Quiet hours must be between 0 and 23".to_string());
+        }
+    }
+
+    let value = serde_json::to_value(schedule)
+        .map_err(|e| format!("Failed to serialize frontload schedule: {}", e))?;
+
+    let mut schedules = match crate::preferences::get(SCHEDULE_PREFERENCE_KEY) {
+        serde_json::Value::Object(schedules) => schedules,
+        serde_json::Value::Null => serde_json::Map::new(),
+        other => {
+            return Err(format!(
+                "Preference {} must be an object of schedules by device, found {}",
+                SCHEDULE_PREFERENCE_KEY, other
+            ))
+        }
+    };
+    schedules.insert(device_id.to_string(), value);
+
+    crate::preferences::set(SCHEDULE_PREFERENCE_KEY, serde_json::Value::Object(schedules))
+}
+
+/// Get the user's chain ranking for frontload (highest priority first)
+pub fn get_chain_priority() -> Vec<String> {
+    crate::preferences::get_as(PRIORITY_PREFERENCE_KEY).unwrap_or_default()
+}
+
+/// Persist the chain ranking for frontload, normalizing and de-duplicating it
+pub fn set_chain_priority(chains: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for chain in chains {
+        let chain = chain.trim().to_lowercase();
+        if !chain.is_empty() && !normalized.contains(&chain) {
+            normalized.push(chain);
+        }
+    }
+
+    crate::preferences::set(PRIORITY_PREFERENCE_KEY, serde_json::json!(normalized))?;
+
+    Ok(normalized)
+}
+
+/// Group items by chain, ranked chains first and the rest in their original order
+pub fn group_by_priority<T>(items: Vec<T>, chain_of: impl Fn(&T) -> &str, priority: &[String]) -> Vec<(String, Vec<T>)> {
+    let mut groups: Vec<(String, Vec<T>)> = Vec::new();
+    for item in items {
+        let chain = chain_of(&item).to_lowercase();
+        match groups.iter_mut().find(|(c, _)| *c == chain) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((chain, vec![item])),
+        }
+    }
+
+    // Stable sort keeps unranked chains in their original relative order
+    groups.sort_by_key(|(chain, _)| priority.iter().position(|p| p == chain).unwrap_or(priority.len()));
+    groups
+}
+
+/// Frontload a device on connect if its schedule allows it
+pub async fn maybe_auto_frontload(app: &AppHandle, device_id: &str) {
+    if crate::safe_mode::is_active() {
+        log::info!("🛟 Safe mode - skipping auto frontload for device {}", device_id);
+        return;
+    }
+    
+    let schedule = get_schedule(device_id);
+
+    if !schedule.auto_on_connect {
+        log::info!("⏸️ Auto frontload disabled for device {}", device_id);
+        return;
+    }
+
+    if let Some(quiet) = &schedule.quiet_hours {
+        let hour = chrono::Local::now().hour();
+        if quiet.contains(hour) {
+            log::info!("🌙 Skipping auto frontload for device {} during quiet hours ({}:00-{}:00)",
+                device_id, quiet.start_hour, quiet.end_hour);
+            return;
+        }
+    }
+
+    if crate::power::is_low_power() {
+        log::info!("🔋 Deferring auto frontload for device {} until normal power", device_id);
+        crate::power::defer_frontload(device_id);
+        return;
+    }
+
+    let cache_cell = app.state::<Arc<once_cell::sync::OnceCell<Arc<CacheManager>>>>();
+    let cache = match crate::commands::get_cache_manager(cache_cell.inner()).await {
+        Ok(cache) => cache,
+        Err(e) => {
+            log::warn!("Skipping auto frontload for device {}: {}", device_id, e);
+            return;
+        }
+    };
+    let queue_manager = app.state::<DeviceQueueManager>().inner().clone();
+
+    spawn_frontload(app, cache, queue_manager, device_id, schedule.mode);
+}
+
+/// Run a frontload in the background, emitting its result when done
+pub fn spawn_frontload(app: &AppHandle, cache: Arc<CacheManager>, queue_manager: DeviceQueueManager, device_id: &str, mode: FrontloadMode) {
+    let controller = super::FrontloadController::new(cache, queue_manager).with_app_handle(app.clone());
+    let device_id = device_id.to_string();
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = controller.frontload_device_with_mode(&device_id, mode).await;
+        emit_frontload_result(&app, &device_id, &result).await;
+    });
+}
+
+/// Emit frontload:completed or frontload:failed for a finished run
+pub async fn emit_frontload_result(app: &AppHandle, device_id: &str, result: &anyhow::Result<()>) {
+    let (event_name, payload) = match result {
+        Ok(()) => {
+            crate::device::duplicate_seeds::check_device(app, device_id).await;
+            crate::derivation::check_device(app, device_id).await;
+            ("frontload:completed", serde_json::json!({ "deviceId": device_id }))
+        }
+        Err(e) => {
+            log::error!("Frontload failed for device {}: {}", device_id, e);
+            crate::error_center::record(crate::error_center::ErrorCategory::Frontload, Some(device_id), format!("Frontload failed: {}", e)).await;
+            ("frontload:failed", serde_json::json!({ "deviceId": device_id, "error": e.to_string() }))
+        }
+    };
+
+    if let Err(e) = crate::commands::emit_or_queue_event(app, event_name, payload).await {
+        log::warn!("Failed to emit {}: {}", event_name, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_by_priority() {
+        let paths = vec!["bitcoin", "dash", "ethereum", "ripple", "Bitcoin"];
+        let priority = vec!["ethereum".to_string(), "bitcoin".to_string()];
+
+        let groups = group_by_priority(paths, |p| p, &priority);
+        let chains: Vec<&str> = groups.iter().map(|(c, _)| c.as_str()).collect();
+        assert_eq!(chains, vec!["ethereum", "bitcoin", "dash", "ripple"]);
+        assert_eq!(groups[1].1.len(), 2);
+    }
+}
@@ -0,0 +1,7 @@
+-- Migration 005: Track cache maintenance runs
+-- Key/value store for bookkeeping such as the last vacuum time
+
+CREATE TABLE IF NOT EXISTS cache_maintenance (
+    key TEXT PRIMARY KEY,
+    value INTEGER NOT NULL
+);
@@ -0,0 +1,12 @@
+-- Migration 006: Persist device ID aliases
+-- A device can re-enumerate with a new USB ID (e.g. after a firmware flash);
+-- aliases map those IDs back to the ID its cached data was recorded under
+
+CREATE TABLE IF NOT EXISTS device_aliases (
+    alias_id TEXT PRIMARY KEY,
+    canonical_id TEXT NOT NULL,
+    reason TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_device_aliases_canonical ON device_aliases(canonical_id);
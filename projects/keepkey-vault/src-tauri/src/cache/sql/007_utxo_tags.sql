@@ -0,0 +1,14 @@
+-- Migration 007: Labels and freeze flags for individual UTXOs
+-- Frozen outpoints are left out of coin selection when building transactions
+
+CREATE TABLE IF NOT EXISTS utxo_tags (
+    device_id TEXT NOT NULL,
+    txid TEXT NOT NULL,
+    vout INTEGER NOT NULL,
+    label TEXT,
+    frozen INTEGER NOT NULL DEFAULT 0,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (device_id, txid, vout)
+);
+
+CREATE INDEX IF NOT EXISTS idx_utxo_tags_frozen ON utxo_tags(frozen);
@@ -0,0 +1,18 @@
+-- Migration 008: Pending payment intents with a locked exchange rate
+-- A fiat-denominated send records the quoted rate so it can be re-checked before signing
+
+CREATE TABLE IF NOT EXISTS payment_intents (
+    id TEXT PRIMARY KEY,
+    caip TEXT NOT NULL,
+    recipient TEXT NOT NULL,
+    fiat_currency TEXT NOT NULL,
+    fiat_amount TEXT NOT NULL,
+    crypto_amount TEXT NOT NULL,
+    locked_rate REAL NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    created_at INTEGER NOT NULL,
+    expires_at INTEGER NOT NULL,
+    confirmed_at INTEGER
+);
+
+CREATE INDEX IF NOT EXISTS idx_payment_intents_status ON payment_intents(status);
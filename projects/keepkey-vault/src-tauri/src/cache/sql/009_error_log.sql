@@ -0,0 +1,13 @@
+-- Migration 009: Significant backend failures for the in-app issues panel
+-- Frontload failures, provider outages and signing errors, with the request's correlation ID
+
+CREATE TABLE IF NOT EXISTS error_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    category TEXT NOT NULL,
+    message TEXT NOT NULL,
+    device_id TEXT,
+    correlation_id TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_error_log_created ON error_log(created_at);
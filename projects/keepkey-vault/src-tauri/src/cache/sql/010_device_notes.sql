@@ -0,0 +1,15 @@
+-- Migration 010: Encrypted notes attached to devices and accounts
+-- Only ciphertext is stored; the key is derived from a user passphrase (salt kept per note)
+
+CREATE TABLE IF NOT EXISTS device_notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    device_id TEXT NOT NULL,
+    account_path TEXT,  -- NULL for notes about the device itself
+    salt BLOB NOT NULL,
+    nonce BLOB NOT NULL,
+    ciphertext BLOB NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_device_notes_device ON device_notes(device_id);
@@ -0,0 +1,13 @@
+-- Migration 011: Device lifecycle and signing activity for the timeline
+-- Connects/disconnects, firmware updates, label changes and completed signatures
+
+CREATE TABLE IF NOT EXISTS activity_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    device_id TEXT NOT NULL,
+    kind TEXT NOT NULL,
+    summary TEXT NOT NULL,
+    details TEXT,  -- JSON
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_activity_log_device ON activity_log(device_id, created_at);
@@ -0,0 +1,15 @@
+-- Migration 012: Outbound requests to market/indexer providers (Pioneer)
+-- Feeds the provider usage summary and error-rate warnings
+
+CREATE TABLE IF NOT EXISTS provider_requests (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    provider TEXT NOT NULL,
+    endpoint TEXT NOT NULL,
+    status INTEGER,  -- HTTP status, NULL when no response arrived
+    bytes INTEGER NOT NULL DEFAULT 0,
+    latency_ms INTEGER NOT NULL,
+    error TEXT,
+    created_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_provider_requests_provider ON provider_requests(provider, created_at);
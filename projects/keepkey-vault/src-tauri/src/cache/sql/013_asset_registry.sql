@@ -0,0 +1,9 @@
+-- Migration 013: Verified remote asset/path registry
+-- Single row holding the signed manifest so it survives restarts
+
+CREATE TABLE IF NOT EXISTS asset_registry (
+    id INTEGER PRIMARY KEY CHECK (id = 1),
+    version TEXT NOT NULL,
+    manifest TEXT NOT NULL,  -- signed manifest JSON as fetched
+    fetched_at INTEGER NOT NULL
+);
@@ -0,0 +1,32 @@
+-- Migration 014: Soft-deleted device caches
+-- Clearing a device cache moves its rows here so the clear can be undone
+-- until the tombstones expire
+
+CREATE TABLE IF NOT EXISTS deleted_cached_pubkeys (
+    device_id TEXT NOT NULL,
+    derivation_path TEXT NOT NULL,
+    coin_name TEXT NOT NULL,
+    script_type TEXT,
+    xpub TEXT,
+    address TEXT,
+    chain_code BLOB,
+    public_key BLOB,
+    cached_at INTEGER NOT NULL,
+    last_used INTEGER NOT NULL,
+    deleted_at INTEGER NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS deleted_cache_metadata (
+    device_id TEXT PRIMARY KEY,
+    label TEXT,
+    firmware_version TEXT,
+    initialized BOOLEAN,
+    frontload_status TEXT,
+    frontload_progress INTEGER DEFAULT 0,
+    last_frontload INTEGER,
+    error_message TEXT,
+    deleted_at INTEGER NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_deleted_cached_pubkeys_device
+ON deleted_cached_pubkeys(device_id);
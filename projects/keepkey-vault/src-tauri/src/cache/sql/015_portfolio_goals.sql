@@ -0,0 +1,15 @@
+-- Migration 015: Portfolio savings goals
+-- Progress is recomputed from the portfolio total on every refresh
+
+CREATE TABLE IF NOT EXISTS portfolio_goals (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    unit TEXT NOT NULL CHECK(unit IN ('usd', 'btc')),
+    target REAL NOT NULL,
+    deadline INTEGER,
+    current REAL NOT NULL DEFAULT 0,
+    milestone INTEGER NOT NULL DEFAULT 0,  -- highest percentage milestone crossed
+    achieved_at INTEGER,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
@@ -0,0 +1,13 @@
+-- Migration 016: User-chosen names and metadata for device accounts
+-- Keyed by account path so names survive cache clears and re-frontloads
+
+CREATE TABLE IF NOT EXISTS account_metadata (
+    device_id TEXT NOT NULL,
+    account_path TEXT NOT NULL,
+    name TEXT NOT NULL,
+    description TEXT,
+    color TEXT,  -- "#rrggbb"
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (device_id, account_path)
+);
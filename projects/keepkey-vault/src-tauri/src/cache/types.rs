@@ -69,6 +69,77 @@ pub struct CacheStatus {
     pub frontload_progress: i32,
 }
 
+/// A device ID that resolves to another (canonical) device ID
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceAlias {
+    pub alias_id: String,
+    pub canonical_id: String,
+    pub reason: Option<String>,
+    pub created_at: i64,
+}
+
+/// User-assigned label and freeze flag for one UTXO
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UtxoTag {
+    pub device_id: String,
+    pub txid: String,
+    pub vout: u32,
+    pub label: Option<String>,
+    /// Excluded from coin selection
+    pub frozen: bool,
+    pub updated_at: i64,
+}
+
+/// A fiat-denominated send with the exchange rate quoted when it was started
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingPaymentIntent {
+    pub id: String,
+    pub caip: String,
+    pub recipient: String,
+    /// ISO 4217 code, e.g. "USD"
+    pub fiat_currency: String,
+    pub fiat_amount: String,
+    /// Amount to send at the locked rate, in display units
+    pub crypto_amount: String,
+    /// Fiat per unit of the asset
+    pub locked_rate: f64,
+    /// "pending", "confirmed" or "cancelled"
+    pub status: String,
+    pub created_at: i64,
+    pub expires_at: i64,
+    pub confirmed_at: Option<i64>,
+}
+
+/// On-disk footprint of the cache database
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CacheDiskUsage {
+    pub db_path: String,
+    pub db_bytes: u64,
+    pub wal_bytes: u64,
+    pub total_bytes: u64,
+    pub page_size: i64,
+    pub page_count: i64,
+    pub freelist_count: i64,
+    /// Space a vacuum would give back (free pages)
+    pub reclaimable_bytes: i64,
+    pub total_entries: i64,
+    pub device_count: i64,
+    pub last_vacuum: Option<i64>,
+}
+
+/// Result of a cache compaction run
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+pub struct CacheCompactionResult {
+    pub removed_entries: i64,
+    pub bytes_before: u64,
+    pub bytes_after: u64,
+    pub bytes_reclaimed: u64,
+    pub duration_ms: u64,
+}
+
 impl CachedPubkey {
     /// Convert from DeviceResponse to CachedPubkey
     pub fn from_device_response(
@@ -166,4 +237,122 @@ impl CachedPubkey {
             }
         }
     }
-} 
\ No newline at end of file
+} 
+
+/// How the cache is being stored after startup checks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum CacheMode {
+    /// Normal on-disk database
+    Disk,
+    /// The database was unreadable and has been recreated empty
+    Recreated,
+    /// The disk could not be used; data lives in memory until restart
+    InMemory,
+}
+
+/// Why the cache isn't running normally, reported with the cache:degraded event
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheDegradation {
+    pub mode: CacheMode,
+    pub reason: String,
+    /// Copy of the damaged database kept for recovery
+    pub backup_path: Option<String>,
+}
+
+/// A significant backend failure shown in the issues panel
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorRecord {
+    pub id: i64,
+    /// "frontload", "provider" or "signing"
+    pub category: String,
+    pub message: String,
+    pub device_id: Option<String>,
+    /// Correlation ID of the REST request that failed, if any
+    pub correlation_id: Option<String>,
+    pub created_at: i64,
+}
+
+/// A note about a device or one of its accounts, as stored (encrypted)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedNote {
+    pub id: i64,
+    pub device_id: String,
+    /// Account derivation path, or None for a note about the device itself
+    pub account_path: Option<String>,
+    pub salt: Vec<u8>,
+    pub nonce: Vec<u8>,
+    pub ciphertext: Vec<u8>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// A device lifecycle or signing event recorded for the activity timeline
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityEntry {
+    pub id: i64,
+    pub device_id: String,
+    /// "connected", "disconnected", "firmware-updated", "label-changed", "settings-changed" or "signed"
+    pub kind: String,
+    pub summary: String,
+    /// Extra JSON describing the event
+    pub details: Option<String>,
+    pub created_at: i64,
+}
+
+/// One outbound request to an external data provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderRequest {
+    pub id: i64,
+    /// e.g. "pioneer"
+    pub provider: String,
+    /// Path without query string or xpubs
+    pub endpoint: String,
+    /// HTTP status, None when the request failed before a response
+    pub status: Option<u16>,
+    /// Response body size
+    pub bytes: u64,
+    pub latency_ms: u64,
+    pub error: Option<String>,
+    pub created_at: i64,
+}
+
+/// A savings goal tracked against the portfolio total
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioGoal {
+    pub id: String,
+    pub name: String,
+    /// "usd" or "btc"
+    pub unit: String,
+    pub target: f64,
+    /// Unix timestamp the goal should be reached by
+    pub deadline: Option<i64>,
+    /// Portfolio amount in `unit` at the last refresh
+    pub current: f64,
+    /// Highest percentage milestone crossed (0, 25, 50, 75 or 100)
+    pub milestone: u8,
+    pub achieved_at: Option<i64>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// A user-chosen name for one device account
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountMetadata {
+    pub device_id: String,
+    /// Account derivation path, e.g. m/84'/0'/0'
+    pub account_path: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// "#rrggbb"
+    pub color: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
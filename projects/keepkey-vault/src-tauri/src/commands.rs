@@ -17,6 +17,7 @@ use std::fs;
 use serde_json::Value;
 use log;
 use crate::device::updates::{update_device_bootloader, update_device_firmware};
+use crate::device::errors::{DeviceError, DeviceErrorCode};
 
 // Add timeout constant
 const DEVICE_OPERATION_TIMEOUT_SECS: u64 = 30; // Increased from 5 to 30 seconds
@@ -593,7 +594,7 @@ pub async fn get_queue_status(
         
         Ok(QueueStatus {
             device_id: Some(device_id.clone()),
-            total_queued: 0, // Would need to track this per device
+            total_queued: manager.get(&device_id).map(|h| h.health().in_flight as usize).unwrap_or(0),
             active_operations: if manager.contains_key(&device_id) { 1 } else { 0 },
             status: if manager.contains_key(&device_id) { "active".to_string() } else { "idle".to_string() },
             last_response,
@@ -602,7 +603,7 @@ pub async fn get_queue_status(
         // Return general status
         Ok(QueueStatus {
             device_id: None,
-            total_queued: 0,
+            total_queued: manager.values().map(|h| h.health().in_flight as usize).sum(),
             active_operations: manager.len(),
             status: if manager.is_empty() { "idle".to_string() } else { "active".to_string() },
             last_response: None,
@@ -610,6 +611,18 @@ pub async fn get_queue_status(
     }
 }
 
+/// Per-operation latency percentiles for a device's queue
+#[tauri::command]
+pub async fn get_queue_metrics(
+    device_id: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::device::queue_metrics::QueueMetrics, String> {
+    crate::device::queue_metrics::snapshot(queue_manager.inner(), Some(&device_id))
+        .await
+        .pop()
+        .ok_or_else(|| format!("No queue for device {}", device_id))
+}
+
 /// Get connected devices (frontend expects this name)
 #[tauri::command]
 pub async fn get_connected_devices() -> Result<Vec<serde_json::Value>, String> {
@@ -873,10 +886,7 @@ pub async fn get_device_info_by_id(
             let error_msg = e.to_string();
             
             // Check for device access errors (already claimed)
-            if error_msg.contains("Device Already In Use") || 
-               error_msg.contains("already claimed") ||
-               error_msg.contains("Device Access Failed") ||
-               error_msg.contains("🔒") {
+            if DeviceError::classify(error_msg.as_str()).code == DeviceErrorCode::DeviceBusy {
                 
                 println!("❌ Device {} is already in use by another application: {}", device_id, e);
                 
@@ -906,6 +916,7 @@ pub async fn get_device_info_by_id(
                     "deviceId": device_id,
                     "error": user_friendly_error,
                     "errorType": "DEVICE_CLAIMED",
+                    "code": DeviceErrorCode::DeviceBusy,
                     "status": "error"
                 });
                 let _ = app.emit("device:access-error", error_event_payload);
@@ -914,6 +925,7 @@ pub async fn get_device_info_by_id(
                 let response_data = serde_json::json!({
                     "error": user_friendly_error,
                     "errorType": "DEVICE_CLAIMED",
+                    "code": DeviceErrorCode::DeviceBusy,
                     "operation": "get_device_info_by_id"
                 });
                 
@@ -958,12 +970,46 @@ pub async fn get_device_info_by_id(
     }
 }
 
-/// Wipe device (factory reset)
+/// Start a wipe: issue the challenge that `wipe_device` must be called with
+#[tauri::command]
+pub async fn request_wipe_challenge(
+    device_id: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::device::wipe_interlock::WipeChallenge, String> {
+    let handle = queue_manager.lock().await.get(&device_id).cloned();
+    let label = match handle {
+        Some(handle) => handle.get_features().await.ok().and_then(|f| f.label),
+        None => None,
+    };
+    Ok(crate::device::wipe_interlock::issue_challenge(&device_id, label))
+}
+
+/// Get wipe confirmation settings
+#[tauri::command]
+pub async fn get_wipe_interlock_preferences() -> Result<crate::device::wipe_interlock::WipeInterlockPreferences, String> {
+    Ok(crate::device::wipe_interlock::get_preferences())
+}
+
+/// Update wipe confirmation settings
+#[tauri::command]
+pub async fn set_wipe_interlock_preferences(
+    preferences: crate::device::wipe_interlock::WipeInterlockPreferences,
+) -> Result<crate::device::wipe_interlock::WipeInterlockPreferences, String> {
+    crate::device::wipe_interlock::set_preferences(&preferences)?;
+    Ok(preferences)
+}
+
+/// Wipe device (factory reset). Requires a token from `request_wipe_challenge`,
+/// the typed device label if configured, and confirmation on the device.
 #[tauri::command]
 pub async fn wipe_device(
     device_id: String,
+    token: String,
+    confirm_label: Option<String>,
     queue_manager: State<'_, DeviceQueueManager>,
 ) -> Result<(), String> {
+    crate::device::wipe_interlock::redeem(&device_id, &token, confirm_label.as_deref())?;
+    
     println!("Wiping device: {}", device_id);
     
     let request_id = uuid::Uuid::new_v4().to_string();
@@ -1113,6 +1159,39 @@ pub async fn wipe_device(
     }
 }
 
+/// Set the device language
+#[tauri::command]
+pub async fn set_device_language(
+    device_id: String,
+    language: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::device::settings_verify::SettingsSnapshot, DeviceError> {
+    let setting = crate::device::settings::DeviceSetting::Language(language);
+    Ok(crate::device::settings::apply(&device_id, queue_manager.inner(), setting).await?)
+}
+
+/// Set how long the device stays unlocked while idle
+#[tauri::command]
+pub async fn set_auto_lock_delay(
+    device_id: String,
+    delay_ms: u32,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::device::settings_verify::SettingsSnapshot, DeviceError> {
+    let setting = crate::device::settings::DeviceSetting::AutoLockDelayMs(delay_ms);
+    Ok(crate::device::settings::apply(&device_id, queue_manager.inner(), setting).await?)
+}
+
+/// Turn passphrase protection on or off
+#[tauri::command]
+pub async fn set_passphrase_protection(
+    device_id: String,
+    enabled: bool,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::device::settings_verify::SettingsSnapshot, DeviceError> {
+    let setting = crate::device::settings::DeviceSetting::Passphrase(enabled);
+    Ok(crate::device::settings::apply(&device_id, queue_manager.inner(), setting).await?)
+}
+
 /// Set device label
 #[tauri::command]
 pub async fn set_device_label(
@@ -1207,16 +1286,18 @@ pub async fn set_device_label(
         }
     };
     
+    // Current label, for the audit log
+    let before = crate::device::settings_verify::snapshot(&queue_handle).await.ok();
+    
     // Create ApplySettings message with the label
-    let apply_settings = keepkey_rust::messages::Message::ApplySettings(
-        keepkey_rust::messages::ApplySettings {
-            language: None,
-            label: Some(label.clone()),
-            use_passphrase: None,
-            auto_lock_delay_ms: None,
-            u2f_counter: None,
-        }
-    );
+    let requested = keepkey_rust::messages::ApplySettings {
+        language: None,
+        label: Some(label.clone()),
+        use_passphrase: None,
+        auto_lock_delay_ms: None,
+        u2f_counter: None,
+    };
+    let apply_settings = keepkey_rust::messages::Message::ApplySettings(requested.clone());
     
     // Log the raw message being sent
     let message_data = serde_json::json!({
@@ -1244,6 +1325,20 @@ pub async fn set_device_label(
             
             match response {
                 keepkey_rust::messages::Message::Success(_) => {
+                    if let Err(error) = crate::device::settings_verify::verify_applied(&queue_handle, &device_id, &requested, before).await {
+                        println!("❌ Failed to set device label for {}: {}", device_id, error);
+                        
+                        let response_data = serde_json::json!({
+                            "error": error,
+                            "operation": "set_device_label"
+                        });
+                        
+                        if let Err(e) = log_device_response(&device_id, &request_id, false, &response_data, Some(&error)).await {
+                            eprintln!("Failed to log set device label error response: {}", e);
+                        }
+                        
+                        return Err(error);
+                    }
                     println!("✅ Device label set successfully for {}: '{}'", device_id, label);
                     
                     // Log the successful response
@@ -1476,7 +1571,7 @@ pub fn evaluate_device_status(device_id: String, features: Option<&DeviceFeature
     };
     
     if let Some(features) = features {
-        let latest_bootloader_version = "2.1.4".to_string(); // Fixed: 2.1.4 is the actual latest official bootloader
+        let latest_bootloader_version = crate::device::releases::latest_bootloader_version();
         
         // CRITICAL FIX: Check bootloader version regardless of current mode
         // For OOB devices, we can infer bootloader version from firmware version
@@ -1498,33 +1593,19 @@ pub fn evaluate_device_status(device_id: String, features: Option<&DeviceFeature
                 bl_version.clone()
             } else {
                 // For modern firmware without explicit bootloader version, assume it's recent enough
-                "2.1.4".to_string() // Assume recent bootloader if not specified
+                latest_bootloader_version.clone() // Assume recent bootloader if not specified
             }
         };
         
         // Check if bootloader needs update using proper semantic version comparison
         let needs_bootloader_update = if features.bootloader_mode {
             // For devices in bootloader mode, only update bootloader if it's truly old (like 1.x)
-            // Modern bootloaders (2.1.4) don't need bootloader updates - they need firmware updates
+            // Modern bootloaders (2.x) don't need bootloader updates - they need firmware updates
             current_bootloader_version.starts_with("1.")
         } else if current_bootloader_version == "Unknown bootloader" {
             false // Can't determine, assume no update needed
         } else {
-            match semver::Version::parse(&current_bootloader_version) {
-                Ok(current_ver) => {
-                    if let Ok(latest_ver) = semver::Version::parse(&latest_bootloader_version) {
-                        current_ver < latest_ver
-                    } else {
-                        // Fallback to string comparison if parsing fails
-                        current_bootloader_version != latest_bootloader_version && 
-                        current_bootloader_version != "2.1.4"
-                    }
-                }
-                Err(_) => {
-                    // For versions like "1.0.3", definitely needs update
-                    !current_bootloader_version.starts_with("2.1.")
-                }
-            }
+            crate::device::releases::is_older(&current_bootloader_version, &latest_bootloader_version)
         };
         
         println!("🔧 Bootloader check: {} -> needs update: {} (bootloader_mode: {})", 
@@ -1542,7 +1623,7 @@ pub fn evaluate_device_status(device_id: String, features: Option<&DeviceFeature
         
         // Check firmware version 
         let current_version = features.version.clone();
-        let latest_version = "7.10.0".to_string(); // Latest firmware version
+        let latest_version = crate::device::releases::latest_firmware_version();
         
         let needs_firmware_update = if features.bootloader_mode {
             // CRITICAL: Devices in bootloader mode need firmware updates to get out of bootloader mode
@@ -1554,7 +1635,7 @@ pub fn evaluate_device_status(device_id: String, features: Option<&DeviceFeature
                 // OOB device - firmware update only after bootloader update
                 false // Bootloader has higher priority
             } else {
-                !current_version.starts_with("7.10.")
+                crate::device::releases::is_older(&current_version, &latest_version)
             }
         };
         
@@ -1721,6 +1802,21 @@ pub async fn cleanup_device_logs() -> Result<String, String> {
     Ok("Old device logs cleaned up successfully".to_string())
 }
 
+/// Get the protocol trace settings and trace file path
+#[tauri::command]
+pub async fn get_protocol_trace_status() -> Result<crate::protocol_trace::ProtocolTraceStatus, String> {
+    crate::protocol_trace::status()
+}
+
+/// Turn protocol tracing on or off; message contents stay redacted unless include_sensitive is set
+#[tauri::command]
+pub async fn set_protocol_trace(
+    enabled: bool,
+    include_sensitive: Option<bool>,
+) -> Result<crate::protocol_trace::ProtocolTraceStatus, String> {
+    crate::protocol_trace::set_enabled(enabled, include_sensitive.unwrap_or(false))
+}
+
 /// Parse transaction from hex string
 /// Returns (metadata, inputs, outputs) where metadata is (version, input_count, output_count, lock_time)
 pub fn parse_transaction_from_hex(hex_data: &str) -> Result<((u32, u32, u32, u32), Vec<keepkey_rust::messages::TxInputType>, Vec<keepkey_rust::messages::TxOutputBinType>), String> {
@@ -1871,7 +1967,7 @@ pub async fn frontend_ready(app: AppHandle) -> Result<(), String> {
         
         for event in state.queued_events.drain(..) {
             println!("📡 Sending queued event: {} (queued at: {})", event.event_name, event.timestamp);
-            if let Err(e) = app.emit(&event.event_name, &event.payload) {
+            if let Err(e) = crate::windows::emit_routed(&app, &event.event_name, &event.payload) {
                 println!("❌ Failed to emit queued event {}: {}", event.event_name, e);
             }
         }
@@ -1886,11 +1982,15 @@ pub async fn frontend_ready(app: AppHandle) -> Result<(), String> {
 
 /// Helper function to emit events (either immediately or queue them)
 pub async fn emit_or_queue_event(app: &AppHandle, event_name: &str, payload: serde_json::Value) -> Result<(), String> {
+    // Automation hooks and notifications fire immediately, regardless of frontend readiness
+    crate::automation::dispatch_event(event_name, &payload);
+    crate::notifications::notify_event(event_name, &payload);
+    
     let state = FRONTEND_READY_STATE.read().await;
     
     if state.is_ready {
         // Frontend is ready, emit immediately
-        app.emit(event_name, &payload)
+        crate::windows::emit_routed(app, event_name, &payload)
             .map_err(|e| format!("Failed to emit event {}: {}", event_name, e))?;
         println!("📡 Emitted event: {}", event_name);
     } else {
@@ -1938,7 +2038,7 @@ fn get_config_file_path() -> Result<PathBuf, String> {
 }
 
 /// Load configuration from file
-fn load_config() -> Result<serde_json::Value, String> {
+pub fn load_config() -> Result<serde_json::Value, String> {
     let config_path = get_config_file_path()?;
     
     if !config_path.exists() {
@@ -1959,8 +2059,9 @@ fn load_config() -> Result<serde_json::Value, String> {
 }
 
 /// Save configuration to file
-fn save_config(config: &serde_json::Value) -> Result<(), String> {
+pub fn save_config(config: &serde_json::Value) -> Result<(), String> {
     let config_path = get_config_file_path()?;
+    let previous = load_config().ok();
     
     let config_str = serde_json::to_string_pretty(config)
         .map_err(|e| format!("Failed to serialize config: {}", e))?;
@@ -1968,6 +2069,7 @@ fn save_config(config: &serde_json::Value) -> Result<(), String> {
     fs::write(&config_path, config_str)
         .map_err(|e| format!("Failed to write config file: {}", e))?;
     
+    crate::preferences::notify_changes(previous.as_ref(), config);
     Ok(())
 }
 
@@ -2010,15 +2112,13 @@ pub async fn set_onboarding_completed() -> Result<(), String> {
 /// Get a preference value
 #[tauri::command]
 pub async fn get_preference(key: String) -> Result<Option<String>, String> {
-    let config = load_config()?;
-    
-    let value = config.get(&key)
-        .and_then(|v| match v {
-            Value::String(s) => Some(s.clone()),
-            Value::Bool(b) => Some(b.to_string()),
-            Value::Number(n) => Some(n.to_string()),
-            _ => None,
-        });
+    // Unset keys stay None so callers can tell them apart from stored values
+    let value = match crate::preferences::get_stored(&key) {
+        Some(Value::String(s)) => Some(s),
+        Some(Value::Bool(b)) => Some(b.to_string()),
+        Some(Value::Number(n)) => Some(n.to_string()),
+        _ => None,
+    };
     
     Ok(value)
 }
@@ -2026,23 +2126,34 @@ pub async fn get_preference(key: String) -> Result<Option<String>, String> {
 /// Set a preference value
 #[tauri::command]
 pub async fn set_preference(key: String, value: String) -> Result<(), String> {
-    let mut config = load_config()?;
-    
-    if let Some(obj) = config.as_object_mut() {
-        // Try to parse as different types
-        let parsed_value = if value == "true" || value == "false" {
-            serde_json::Value::Bool(value == "true")
-        } else if let Ok(num) = value.parse::<i64>() {
-            serde_json::Value::Number(serde_json::Number::from(num))
-        } else {
-            serde_json::Value::String(value)
-        };
-        
-        obj.insert(key, parsed_value);
-    }
+    // Try to parse as different types
+    let parsed_value = if value == "true" || value == "false" {
+        serde_json::Value::Bool(value == "true")
+    } else if let Ok(num) = value.parse::<i64>() {
+        serde_json::Value::Number(serde_json::Number::from(num))
+    } else {
+        serde_json::Value::String(value)
+    };
     
-    save_config(&config)?;
-    Ok(())
+    crate::preferences::set(&key, parsed_value)
+}
+
+/// Get a preference as JSON, falling back to its registered default
+#[tauri::command]
+pub async fn get_typed_preference(key: String) -> Result<Value, String> {
+    Ok(crate::preferences::get(&key))
+}
+
+/// Set a preference from JSON after validating it against the registry (null removes it)
+#[tauri::command]
+pub async fn set_typed_preference(key: String, value: Value) -> Result<(), String> {
+    crate::preferences::set(&key, value)
+}
+
+/// List registered preferences with their types and defaults
+#[tauri::command]
+pub async fn get_preference_schema() -> Result<Vec<crate::preferences::PreferenceSpec>, String> {
+    Ok(crate::preferences::REGISTRY.to_vec())
 }
 
 /// Debug onboarding state
@@ -2124,6 +2235,586 @@ pub async fn get_api_status() -> Result<serde_json::Value, String> {
     Ok(status)
 }
 
+/// Get the screen-capture protection state of the vault window
+#[tauri::command]
+pub async fn get_screen_protection_status() -> Result<crate::screen_protection::ScreenProtectionStatus, String> {
+    Ok(crate::screen_protection::get_status())
+}
+
+/// Enable or disable screen-capture protection on the vault window
+#[tauri::command]
+pub async fn set_screen_capture_protection(
+    enabled: bool,
+) -> Result<crate::screen_protection::ScreenProtectionStatus, String> {
+    Ok(crate::screen_protection::set_manual_protection(enabled))
+}
+
+/// Clipboard TTL used when the caller doesn't provide one
+const DEFAULT_CLIPBOARD_TTL_SECS: u64 = 60;
+
+/// Upper bound on how long sensitive data may stay on the clipboard
+const MAX_CLIPBOARD_TTL_SECS: u64 = 600;
+
+/// Copy an address/xpub to the clipboard and clear it after a timeout
+#[tauri::command]
+pub async fn copy_sensitive(
+    app: AppHandle,
+    value: String,
+    ttl_secs: Option<u64>,
+) -> Result<(), String> {
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+    
+    let ttl_secs = ttl_secs
+        .unwrap_or(DEFAULT_CLIPBOARD_TTL_SECS)
+        .clamp(1, MAX_CLIPBOARD_TTL_SECS);
+    
+    app.clipboard().write_text(value.clone())
+        .map_err(|e| format!("Failed to write to clipboard: {}", e))?;
+    
+    // Never include the copied value in the event payload
+    let _ = app.emit("clipboard:copied", serde_json::json!({
+        "ttlSecs": ttl_secs,
+        "expiresAt": chrono::Utc::now().timestamp() + ttl_secs as i64,
+    }));
+    
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(std::time::Duration::from_secs(ttl_secs)).await;
+        
+        // Only clear if the user hasn't copied something else in the meantime
+        let still_ours = app.clipboard().read_text()
+            .map(|current| current == value)
+            .unwrap_or(false);
+        
+        if still_ours {
+            match app.clipboard().clear() {
+                Ok(()) => {
+                    log::info!("📋 Cleared sensitive data from clipboard after {}s", ttl_secs);
+                    let _ = app.emit("clipboard:cleared", serde_json::json!({ "ttlSecs": ttl_secs }));
+                }
+                Err(e) => log::warn!("Failed to clear clipboard: {}", e),
+            }
+        }
+    });
+    
+    Ok(())
+}
+
+/// Get TLS status and certificate fingerprint for client pinning
+#[tauri::command]
+pub async fn get_api_tls_info() -> Result<crate::server::tls::TlsInfo, String> {
+    crate::server::tls::get_tls_info()
+}
+
+/// Enable or disable the HTTPS listener (takes effect on next restart)
+#[tauri::command]
+pub async fn set_api_tls_enabled(enabled: bool) -> Result<crate::server::tls::TlsInfo, String> {
+    log::info!("Setting API TLS enabled: {}", enabled);
+    let mut config = load_config()?;
+    
+    if let Some(obj) = config.as_object_mut() {
+        obj.insert("api_tls_enabled".to_string(), serde_json::Value::Bool(enabled));
+    }
+    
+    save_config(&config)?;
+    
+    // Generate the certificate up front so the fingerprint is available before restart
+    if enabled {
+        crate::server::tls::ensure_certificate()?;
+    }
+    
+    crate::server::tls::get_tls_info()
+}
+
+/// Get the origins allowed to call the local API
+#[tauri::command]
+pub async fn get_cors_allowed_origins() -> Result<Vec<String>, String> {
+    Ok(crate::server::cors::get_allowed_origins())
+}
+
+/// Add an origin to the CORS allowlist
+#[tauri::command]
+pub async fn add_cors_allowed_origin(origin: String) -> Result<Vec<String>, String> {
+    crate::server::cors::add_allowed_origin(&origin)
+}
+
+/// Remove an origin from the CORS allowlist
+#[tauri::command]
+pub async fn remove_cors_allowed_origin(origin: String) -> Result<Vec<String>, String> {
+    log::info!("Removing CORS origin: {}", origin);
+    crate::server::cors::remove_allowed_origin(&origin)
+}
+
+/// Restore the default KeepKey origins on the CORS allowlist
+#[tauri::command]
+pub async fn reset_cors_allowed_origins() -> Result<Vec<String>, String> {
+    crate::server::cors::reset_allowed_origins()
+}
+
+/// List REST endpoint groups that are switched off
+#[tauri::command]
+pub async fn get_disabled_endpoint_groups() -> Result<Vec<crate::server::endpoint_flags::EndpointGroup>, String> {
+    Ok(crate::server::endpoint_flags::disabled_groups())
+}
+
+/// Switch off REST endpoint groups (e.g. everything but bitcoin for a Bitcoin-only install)
+#[tauri::command]
+pub async fn set_disabled_endpoint_groups(
+    groups: Vec<crate::server::endpoint_flags::EndpointGroup>,
+) -> Result<Vec<crate::server::endpoint_flags::EndpointGroup>, String> {
+    crate::server::endpoint_flags::set_disabled_groups(groups)
+}
+
+/// Whether testnet/signet and Sepolia accounts are enabled
+#[tauri::command]
+pub async fn get_testnet_mode() -> Result<bool, String> {
+    Ok(crate::device::testnet::is_enabled())
+}
+
+/// Turn testnet mode on or off; the next frontload picks up the sandbox accounts
+#[tauri::command]
+pub async fn set_testnet_mode(enabled: bool) -> Result<bool, String> {
+    crate::device::testnet::set_enabled(enabled)?;
+    Ok(enabled)
+}
+
+/// Sandbox networks with their CAIP ids and faucets
+#[tauri::command]
+pub async fn get_testnet_networks() -> Result<Vec<crate::device::testnet::TestnetNetworkInfo>, String> {
+    Ok(crate::device::testnet::networks())
+}
+
+/// Approve or reject a batch of transactions waiting on `batch:approval-requested`
+#[tauri::command]
+pub async fn respond_batch_approval(batch_id: String, approved: bool) -> Result<(), String> {
+    crate::device::batch_signing::respond(&batch_id, approved)
+}
+
+/// Approve or reject an app pairing waiting on `pairing:approval-requested`
+#[tauri::command]
+pub async fn respond_pairing_approval(request_id: String, approved: bool) -> Result<(), String> {
+    crate::server::auth::respond(&request_id, approved)
+}
+
+/// UTXOs of a device's Bitcoin accounts with labels and freeze flags
+#[tauri::command]
+pub async fn list_utxos(
+    device_id: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::utxos::Utxo>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::utxos::list_utxos(&cache, &device_id).await
+}
+
+/// Label a UTXO or freeze it out of coin selection
+#[tauri::command]
+pub async fn tag_utxo(
+    device_id: String,
+    txid: String,
+    vout: u32,
+    label: Option<String>,
+    frozen: Option<bool>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::cache::UtxoTag, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::utxos::tag_utxo(&cache, &device_id, &txid, vout, crate::utxos::UtxoTagUpdate { label, frozen }).await
+}
+
+/// Outpoints ("txid:vout") of a device the tx builder must not spend
+#[tauri::command]
+pub async fn get_frozen_utxos(
+    device_id: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<String>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    cache.list_frozen_outpoints(&device_id).await.map_err(|e| format!("Failed to read frozen UTXOs: {}", e))
+}
+
+/// Decode a bitcoin:/ethereum: payment URI (e.g. from a QR code) into a payment intent
+#[tauri::command]
+pub async fn parse_payment_uri(uri: String) -> Result<crate::payment_uri::PaymentIntent, String> {
+    crate::payment_uri::parse(&uri)
+}
+
+/// Lock the quoted exchange rate for a fiat-denominated send
+#[tauri::command]
+pub async fn create_payment_intent(
+    request: crate::rate_lock::CreatePaymentIntent,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::cache::PendingPaymentIntent, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::rate_lock::create_intent(&cache, request).await
+}
+
+/// Re-check the locked rate before signing; returns a warning instead of confirming if it moved too far
+#[tauri::command]
+pub async fn confirm_payment_intent(
+    id: String,
+    current_rate: f64,
+    accept: Option<bool>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::rate_lock::RateCheck, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::rate_lock::confirm_intent(&cache, &id, current_rate, accept.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn cancel_payment_intent(
+    id: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<(), String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::rate_lock::cancel_intent(&cache, &id).await
+}
+
+#[tauri::command]
+pub async fn get_rate_lock_preferences() -> Result<crate::rate_lock::RateLockPreferences, String> {
+    Ok(crate::rate_lock::get_preferences())
+}
+
+#[tauri::command]
+pub async fn set_rate_lock_preferences(preferences: crate::rate_lock::RateLockPreferences) -> Result<(), String> {
+    crate::rate_lock::set_preferences(&preferences)
+}
+
+#[tauri::command]
+pub async fn get_reconciliation_preferences() -> Result<crate::reconciliation::ReconciliationPreferences, String> {
+    Ok(crate::reconciliation::get_preferences())
+}
+
+/// Turn the second-provider balance check on or off, or change its tolerance
+#[tauri::command]
+pub async fn set_reconciliation_preferences(preferences: crate::reconciliation::ReconciliationPreferences) -> Result<(), String> {
+    crate::reconciliation::set_preferences(&preferences)
+}
+
+/// Check portfolio balances against the second provider (empty when reconciliation is off)
+#[tauri::command]
+pub async fn reconcile_portfolio(
+    balances: Vec<crate::reconciliation::PortfolioBalance>,
+) -> Result<Vec<crate::reconciliation::AssetReconciliation>, String> {
+    crate::reconciliation::reconcile_portfolio(balances).await
+}
+
+/// Confirmations required per network (CAIP-2) before a transaction is final
+#[tauri::command]
+pub async fn get_confirmation_thresholds() -> Result<std::collections::HashMap<String, u32>, String> {
+    Ok(crate::confirmations::get_thresholds())
+}
+
+/// Set one network's threshold; omit `confirmations` to restore the default
+#[tauri::command]
+pub async fn set_confirmation_threshold(
+    network: String,
+    confirmations: Option<u32>,
+) -> Result<std::collections::HashMap<String, u32>, String> {
+    crate::confirmations::set_threshold(&network, confirmations)
+}
+
+/// Which startup gates are ready; `app:ready` fires once all of them are
+#[tauri::command]
+pub async fn get_startup_readiness() -> Result<crate::readiness::ReadinessStatus, String> {
+    Ok(crate::readiness::status())
+}
+
+/// Whether the app booted in safe mode, with diagnostics
+#[tauri::command]
+pub async fn get_safe_mode_status() -> Result<crate::safe_mode::SafeModeStatus, String> {
+    Ok(crate::safe_mode::status())
+}
+
+/// Reset what may be crashing the app; takes effect on the next launch
+#[tauri::command]
+pub async fn safe_mode_reset(
+    clear_cache: bool,
+    reset_preferences: bool,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<(), String> {
+    if !crate::safe_mode::is_active() {
+        return Err("Resets are only available in safe mode".to_string());
+    }
+    if clear_cache {
+        if cache_manager.inner().get().is_some() {
+            return Err("The cache is already open; restart in safe mode before clearing it".to_string());
+        }
+        crate::cache::CacheManager::move_database_aside("safe-mode")
+            .map_err(|e| format!("Failed to clear cache: {}", e))?;
+    }
+    if reset_preferences {
+        crate::safe_mode::reset_preferences()?;
+    }
+    Ok(())
+}
+
+/// Boot normally on the next launch
+#[tauri::command]
+pub async fn exit_safe_mode() -> Result<(), String> {
+    crate::safe_mode::exit()
+}
+
+/// Push the latest portfolio total into the window title (when enabled) and
+/// goal progress, and per-asset balances into change detection; returns the summary
+#[tauri::command]
+pub async fn update_portfolio_summary(
+    app: tauri::AppHandle,
+    total_usd: f64,
+    total_btc: Option<f64>,
+    btc_price_usd: Option<f64>,
+    device_id: Option<String>,
+    assets: Option<Vec<crate::portfolio::AssetBalance>>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<String, String> {
+    let summary = crate::window_title::update_portfolio_total(&app, total_usd, btc_price_usd)?;
+    if let (Some(device_id), Some(assets)) = (device_id, assets) {
+        crate::portfolio::record(&app, &device_id, assets).await;
+    }
+    
+    // Goals are best effort; the title updates even when the cache is unavailable
+    match get_cache_manager(cache_manager.inner()).await {
+        Ok(cache) => {
+            if let Err(e) = crate::goals::update_progress(&app, &cache, total_usd, total_btc).await {
+                log::warn!("Failed to update goal progress: {}", e);
+            }
+        }
+        Err(e) => log::warn!("Skipping goal progress: {}", e),
+    }
+    Ok(summary)
+}
+
+/// List portfolio savings goals with their latest progress
+#[tauri::command]
+pub async fn list_portfolio_goals(
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::cache::PortfolioGoal>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::goals::list(&cache).await
+}
+
+/// Create a portfolio savings goal
+#[tauri::command]
+pub async fn create_portfolio_goal(
+    goal: crate::goals::NewGoal,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::cache::PortfolioGoal, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::goals::create(&cache, goal).await
+}
+
+/// Delete a portfolio savings goal; returns whether it existed
+#[tauri::command]
+pub async fn delete_portfolio_goal(
+    id: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<bool, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::goals::delete(&cache, &id).await
+}
+
+/// Account names, for one device or all of them
+#[tauri::command]
+pub async fn list_account_names(
+    device_id: Option<String>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::cache::AccountMetadata>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::accounts::list(&cache, device_id.as_deref()).await
+}
+
+/// Name or rename an account
+#[tauri::command]
+pub async fn set_account_name(
+    request: crate::accounts::AccountNameRequest,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::cache::AccountMetadata, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::accounts::set(&cache, request).await
+}
+
+/// Remove an account's name; returns whether it had one
+#[tauri::command]
+pub async fn delete_account_name(
+    device_id: String,
+    account_path: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<bool, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::accounts::remove(&cache, &device_id, &account_path).await
+}
+
+/// Open (or focus) a portfolio or signing window, optionally bound to one device
+#[tauri::command]
+pub async fn open_vault_window(
+    app: tauri::AppHandle,
+    role: crate::windows::WindowRole,
+    device_id: Option<String>,
+) -> Result<crate::windows::WindowContext, String> {
+    crate::windows::open_window(&app, role, device_id)
+}
+
+/// Point an open window at another device (None follows every device)
+#[tauri::command]
+pub async fn set_vault_window_device(label: String, device_id: Option<String>) -> Result<crate::windows::WindowContext, String> {
+    crate::windows::set_window_device(&label, device_id)
+}
+
+/// Recorded backend failures for the issues panel, newest first
+#[tauri::command]
+pub async fn list_errors(
+    limit: Option<usize>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::cache::ErrorRecord>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::error_center::list(&cache, limit).await
+}
+
+/// Dismiss every recorded error; returns how many were removed
+#[tauri::command]
+pub async fn clear_errors(
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<usize, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::error_center::clear(&cache).await
+}
+
+/// Groups of cached devices that hold the same seed
+#[tauri::command]
+pub async fn list_duplicate_seeds(
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::device::duplicate_seeds::DuplicateSeedGroup>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::device::duplicate_seeds::find_duplicates(&cache).await
+}
+
+/// Receive or change addresses below a cached account xpub, derived without the device
+#[tauri::command]
+pub async fn derive_addresses_offline(
+    device_id: String,
+    request: crate::derivation::DeriveAddressesRequest,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::derivation::DerivedAddress>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::derivation::derive_addresses(&cache, &device_id, &request).await
+}
+
+/// Which cached device and path an address belongs to, if any
+#[tauri::command]
+pub async fn lookup_address(
+    address: String,
+    depth: Option<u32>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::derivation::AddressMatch>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::derivation::lookup_address(&cache, &address, depth).await
+}
+
+/// Check software derivation against test vectors, and connected devices in debug builds
+#[tauri::command]
+pub async fn run_derivation_self_check(
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::self_check::SelfCheckReport, String> {
+    Ok(crate::self_check::run(queue_manager.inner(), true).await)
+}
+
+/// Record an outbound provider request made by the frontend
+#[tauri::command]
+pub async fn record_provider_request(
+    provider: String,
+    endpoint: String,
+    status: Option<u16>,
+    bytes: u64,
+    latency_ms: u64,
+    error: Option<String>,
+) -> Result<(), String> {
+    crate::provider_usage::record(&provider, &endpoint, status, bytes, latency_ms, error).await;
+    Ok(())
+}
+
+/// Request counts, latency and errors of a provider over a window (default one day)
+#[tauri::command]
+pub async fn get_provider_usage(
+    provider: String,
+    window_secs: Option<i64>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::provider_usage::ProviderUsage, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::provider_usage::usage(&cache, &provider, window_secs).await
+}
+
+/// Which asset/path registry frontload uses, and where updates come from
+#[tauri::command]
+pub async fn get_asset_registry_status() -> Result<crate::asset_registry::RegistryStatus, String> {
+    Ok(crate::asset_registry::status())
+}
+
+/// Fetch and verify the signed asset registry now
+#[tauri::command]
+pub async fn refresh_asset_registry(
+    app: tauri::AppHandle,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::asset_registry::RegistryStatus, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::asset_registry::refresh(&app, &cache).await
+}
+
+/// Device lifecycle events, signatures and errors of a device, newest first
+#[tauri::command]
+pub async fn get_timeline(
+    device_id: String,
+    limit: Option<usize>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::activity::TimelineItem>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::activity::timeline(&cache, &device_id, limit).await
+}
+
+/// Add an encrypted note about a device, or one of its accounts when `account_path` is set
+#[tauri::command]
+pub async fn create_note(
+    device_id: String,
+    account_path: Option<String>,
+    text: String,
+    passphrase: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::notes::Note, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::notes::create(&cache, &device_id, account_path, &text, &passphrase).await
+}
+
+#[tauri::command]
+pub async fn list_notes(
+    device_id: String,
+    account_path: Option<String>,
+    passphrase: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::notes::Note>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::notes::list(&cache, &device_id, account_path.as_deref(), &passphrase).await
+}
+
+#[tauri::command]
+pub async fn update_note(
+    id: i64,
+    text: String,
+    passphrase: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::notes::Note, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::notes::update(&cache, id, &text, &passphrase).await
+}
+
+#[tauri::command]
+pub async fn delete_note(
+    id: i64,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<(), String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::notes::delete(&cache, id).await
+}
+
+#[tauri::command]
+pub async fn list_vault_windows() -> Result<Vec<crate::windows::WindowContext>, String> {
+    Ok(crate::windows::list_windows())
+}
+
 // Bootloader and firmware update functions have been moved to device/updates.rs for better organization
 
 // PIN Creation Flow Types and Commands
@@ -2134,6 +2825,9 @@ pub struct PinCreationSession {
     pub session_id: String,
     pub current_step: PinStep,
     pub is_active: bool,
+    /// Backoff after rejected PINs, filled in by get_pin_session_status
+    #[serde(default)]
+    pub lockout: Option<crate::device::pin_lockout::PinLockout>,
 }
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq)]
@@ -2165,10 +2859,16 @@ lazy_static::lazy_static! {
 pub async fn initialize_device_pin(
     device_id: String, 
     label: Option<String>,
+    word_count: Option<u32>,
     queue_manager: tauri::State<'_, DeviceQueueManager>,
 ) -> Result<PinCreationSession, String> {
     log::info!("Starting PIN creation for device: {} with label: {:?}", device_id, label);
     
+    // Seed length defaults to 12 words
+    let word_count = word_count.unwrap_or(12);
+    let strength = crate::device::capabilities::word_count_to_strength(word_count)
+        .ok_or_else(|| format!("Invalid word count {}. Must be 12, 18, or 24", word_count))?;
+    
     // Check if device is already in PIN flow
     if is_device_in_pin_flow(&device_id) {
         return Err("Device is already in PIN creation flow".to_string());
@@ -2186,6 +2886,7 @@ pub async fn initialize_device_pin(
         session_id: session_id.clone(),
         current_step: PinStep::AwaitingFirst,
         is_active: true,
+        lockout: None,
     };
     
     // Store session
@@ -2223,7 +2924,7 @@ pub async fn initialize_device_pin(
     // Create ResetDevice message with PIN protection enabled
     let reset_device = keepkey_rust::messages::ResetDevice {
         display_random: Some(false),  // Don't show confusing entropy screen to users
-        strength: Some(128),  // 128 bits = 12 words (Bitcoin-only)
+        strength: Some(strength),  // 128/192/256 bits = 12/18/24 words
         passphrase_protection: Some(false),
         pin_protection: Some(true),  // This triggers PIN creation flow
         language: Some("english".to_string()),
@@ -2352,6 +3053,7 @@ pub async fn send_pin_matrix_response(
                             let pin_cached = features.pin_cached.unwrap_or(false);
                             if pin_cached {
                                 log::info!("✅ PIN unlock successful, device is now unlocked");
+                                crate::device::pin_lockout::record_success(&device_id);
                                 
                                 // Update session state to completed
                                 if let Ok(mut sessions) = PIN_SESSIONS.lock() {
@@ -2382,7 +3084,11 @@ pub async fn send_pin_matrix_response(
                                 // Unmark device from PIN flow on failure
                                 let _ = unmark_device_in_pin_flow(&device_id);
                                 
-                                Err("PIN unlock failed - incorrect PIN".to_string())
+                                let lockout = crate::device::pin_lockout::record_failure(&device_id);
+                                Err(format!(
+                                    "PIN unlock failed - incorrect PIN. Try again in {}",
+                                    crate::device::pin_lockout::describe_wait(lockout.remaining_secs),
+                                ))
                             }
                         }
                         keepkey_rust::messages::Message::Failure(f) => {
@@ -2398,7 +3104,15 @@ pub async fn send_pin_matrix_response(
                             // Unmark device from PIN flow on failure
                             let _ = unmark_device_in_pin_flow(&device_id);
                             
-                            Err(format!("PIN unlock failed: {}", f.message.as_deref().unwrap_or("Unknown error")))
+                            let message = f.message.as_deref().unwrap_or("Unknown error");
+                            if DeviceError::from_failure(message).code == DeviceErrorCode::PinInvalid {
+                                let lockout = crate::device::pin_lockout::record_failure(&device_id);
+                                return Err(format!(
+                                    "PIN unlock failed: {}. Try again in {}",
+                                    message, crate::device::pin_lockout::describe_wait(lockout.remaining_secs),
+                                ));
+                            }
+                            Err(format!("PIN unlock failed: {}", message))
                         }
                         _ => {
                             log::error!("❌ Unexpected response to PIN unlock: {:?}", response);
@@ -2627,6 +3341,7 @@ pub async fn start_pin_unlock(
         session_id: session_id.clone(),
         current_step: PinStep::AwaitingUnlock,
         is_active: true,
+        lockout: None,
     };
     
     // Store session
@@ -2731,6 +3446,7 @@ pub async fn send_pin_unlock_response(
                                     let pin_cached = features.pin_cached.unwrap_or(false);
                                     if pin_cached {
                                         log::info!("✅ PIN unlock successful, device is now unlocked");
+                                        crate::device::pin_lockout::record_success(&device_id);
                                         
                                         // Update session state to completed
                                         if let Ok(mut sessions) = PIN_SESSIONS.lock() {
@@ -2761,7 +3477,11 @@ pub async fn send_pin_unlock_response(
                                         // Unmark device from PIN flow on failure
                                         let _ = unmark_device_in_pin_flow(&device_id);
                                         
-                                        Err("PIN unlock failed - incorrect PIN".to_string())
+                                        let lockout = crate::device::pin_lockout::record_failure(&device_id);
+                                        Err(format!(
+                                            "PIN unlock failed - incorrect PIN. Try again in {}",
+                                            crate::device::pin_lockout::describe_wait(lockout.remaining_secs),
+                                        ))
                                     }
                                 }
                                 keepkey_rust::messages::Message::Failure(f) => {
@@ -2777,7 +3497,15 @@ pub async fn send_pin_unlock_response(
                                     // Unmark device from PIN flow on failure
                                     let _ = unmark_device_in_pin_flow(&device_id);
                                     
-                                    Err(format!("PIN unlock failed: {}", f.message.as_deref().unwrap_or("Unknown error")))
+                                    let message = f.message.as_deref().unwrap_or("Unknown error");
+                                    if DeviceError::from_failure(message).code == DeviceErrorCode::PinInvalid {
+                                        let lockout = crate::device::pin_lockout::record_failure(&device_id);
+                                        return Err(format!(
+                                            "PIN unlock failed: {}. Try again in {}",
+                                            message, crate::device::pin_lockout::describe_wait(lockout.remaining_secs),
+                                        ));
+                                    }
+                                    Err(format!("PIN unlock failed: {}", message))
                                 }
                                 _ => {
                                     log::error!("❌ Unexpected response to PIN unlock: {:?}", features_response);
@@ -2890,7 +3618,10 @@ pub async fn send_pin_unlock_response(
 #[tauri::command]
 pub async fn get_pin_session_status(session_id: String) -> Result<Option<PinCreationSession>, String> {
     let sessions = PIN_SESSIONS.lock().map_err(|_| "Failed to lock PIN sessions".to_string())?;
-    Ok(sessions.get(&session_id).cloned())
+    Ok(sessions.get(&session_id).cloned().map(|mut session| {
+        session.lockout = crate::device::pin_lockout::status(&session.device_id);
+        session
+    }))
 }
 
 /// Cancel PIN creation session
@@ -2981,6 +3712,7 @@ pub fn unmark_device_in_pin_flow(device_id: &str) -> Result<(), String> {
 // ========== Recovery Commands (Direct Implementation) ==========
 
 use std::collections::HashMap;
+use crate::device::recovery_input;
 
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct RecoverySession {
@@ -3047,6 +3779,7 @@ pub async fn start_device_recovery(
     word_count: u32,
     passphrase_protection: bool,
     label: String,
+    backup_type: Option<crate::device::capabilities::BackupType>,
     queue_manager: tauri::State<'_, DeviceQueueManager>,
 ) -> Result<RecoverySession, String> {
     log::info!("Starting device recovery for device: {} with {} words", device_id, word_count);
@@ -3067,10 +3800,13 @@ pub async fn start_device_recovery(
         }
     }
     
-    // Validate word count
-    if ![12, 18, 24].contains(&word_count) {
-        return Err("Invalid word count. Must be 12, 18, or 24".to_string());
-    }
+    // Validate word count and backup type against what the device supports
+    let capabilities = crate::device::capabilities::fetch_capabilities(&device_id, queue_manager.inner()).await?;
+    crate::device::capabilities::validate_recovery_options(
+        &capabilities,
+        word_count,
+        backup_type.unwrap_or_default(),
+    )?;
     
     // Generate session ID
     let session_id = format!("recovery_{}_{}", 
@@ -3137,7 +3873,7 @@ pub async fn start_device_recovery(
         language: Some("english".to_string()),
         label: Some(label),
         enforce_wordlist: None,      // Don't set - might not be supported
-        use_character_cipher: Some(true),  // Re-enable - device expects substitution cipher
+        use_character_cipher: Some(true),  // Scrambled keyboard - the host never sees real seed letters
         auto_lock_delay_ms: None,    // Don't set - might not be supported
         u2f_counter: None,           // Don't set - not needed for recovery
         dry_run: Some(false),        // Essential - distinguishes from verification
@@ -3202,8 +3938,9 @@ pub async fn send_recovery_character(
     action: Option<RecoveryAction>,
     queue_manager: tauri::State<'_, DeviceQueueManager>,
 ) -> Result<RecoveryProgress, String> {
-    log::info!("Sending recovery character for session: {} - char: {:?}, action: {:?}", 
-        session_id, character, action);
+    // Never log the character itself
+    log::info!("Sending recovery input for session: {} - character: {}, action: {:?}", 
+        session_id, character.is_some(), action);
     
     // Get session
     let (device_id, current_word, current_char) = {
@@ -3260,14 +3997,9 @@ pub async fn send_recovery_character(
         }
         None => {
             // Regular character input
-            if let Some(ch) = character {
-                // Validate character
-                if ch.len() != 1 || !ch.chars().next().unwrap().is_alphabetic() {
-                    return Err("Invalid character. Must be a single letter a-z".to_string());
-                }
-                
+            if let Some(ch) = character.as_deref() {
                 keepkey_rust::messages::CharacterAck {
-                    character: Some(ch.to_lowercase()),
+                    character: Some(recovery_input::normalize_character(ch)?),
                     delete: Some(false),
                     done: Some(false),
                 }
@@ -3292,7 +4024,8 @@ pub async fn send_recovery_character(
                     Ok(RecoveryProgress {
                         word_pos: req.word_pos,
                         character_pos: req.character_pos,
-                        auto_completed: false,
+                        // Device completes the word once its unique prefix has been entered
+                        auto_completed: req.character_pos as usize >= recovery_input::UNIQUE_PREFIX_LEN,
                         is_complete: false,
                         error: None,
                     })
@@ -3730,6 +4463,21 @@ pub async fn send_verification_pin(
     Err("Verification PIN sending not yet implemented".to_string())
 }
 
+/// Get the capability map for a device (recovery options etc.)
+#[tauri::command]
+pub async fn get_device_capabilities(
+    device_id: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::device::capabilities::DeviceCapabilities, String> {
+    crate::device::capabilities::fetch_capabilities(&device_id, queue_manager.inner()).await
+}
+
+/// Get the firmware release catalog, optionally forcing a refresh of the remote manifest
+#[tauri::command]
+pub async fn get_firmware_releases(refresh: Option<bool>) -> Result<crate::device::releases::FirmwareCatalog, String> {
+    Ok(crate::device::releases::get_catalog_refreshed(refresh.unwrap_or(false)).await)
+}
+
 /// Get seed verification status
 #[tauri::command]
 pub async fn get_verification_status(session_id: String) -> Result<Option<SeedVerificationSession>, String> {
@@ -3799,6 +4547,9 @@ pub fn mark_device_in_recovery_flow(device_id: &str) -> Result<(), String> {
     let mut flows = RECOVERY_DEVICE_FLOWS.lock().map_err(|_| "Failed to lock recovery device flows".to_string())?;
     flows.insert(device_id.to_string());
     log::info!("Device {} marked as in recovery flow", device_id);
+    
+    // Recovery words entered via the cipher must never show up in screen recordings
+    crate::screen_protection::enter_sensitive_flow(&format!("recovery:{}", device_id));
     Ok(())
 }
 
@@ -3817,6 +4568,8 @@ pub fn unmark_device_in_recovery_flow(device_id: &str) -> Result<(), String> {
     flows.remove(device_id);
     log::info!("Device {} removed from recovery flow", device_id);
     
+    crate::screen_protection::exit_sensitive_flow(&format!("recovery:{}", device_id));
+    
     // Also clean up any aliases
     if let Ok(mut aliases) = RECOVERY_DEVICE_ALIASES.lock() {
         aliases.retain(|_, v| v != device_id);
@@ -3834,6 +4587,22 @@ pub fn add_recovery_device_alias(alias_id: &str, canonical_id: &str) -> Result<(
     Ok(())
 }
 
+/// Persist a device ID alias so cached data stays attributed across re-enumeration
+pub async fn persist_device_alias(app: &AppHandle, alias_id: &str, canonical_id: &str, reason: &str) -> Result<(), String> {
+    use tauri::Manager;
+    
+    let cache_cell = app.try_state::<Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>()
+        .ok_or_else(|| "Cache manager not available".to_string())?;
+    let cache = get_cache_manager(cache_cell.inner()).await?;
+    
+    cache.save_device_alias(alias_id, canonical_id, Some(reason))
+        .await
+        .map_err(|e| format!("Failed to persist device alias: {}", e))?;
+    
+    log::info!("Persisted device alias: {} -> {} ({})", alias_id, canonical_id, reason);
+    Ok(())
+}
+
 /// Get canonical device ID from alias
 pub fn get_canonical_device_id(device_id: &str) -> String {
     if let Ok(aliases) = RECOVERY_DEVICE_ALIASES.lock() {
@@ -3885,17 +4654,17 @@ pub async fn send_pin_matrix_ack(
     device_id: String,
     positions: Vec<u8>,  // Positions 1-9 that user clicked
     queue_manager: tauri::State<'_, DeviceQueueManager>,
-) -> Result<bool, String> {
+) -> Result<bool, DeviceError> {
     log::info!("Sending PIN matrix ACK for device: {} with {} positions", device_id, positions.len());
     
     // Validate positions
     if positions.is_empty() || positions.len() > 9 {
-        return Err("PIN must be between 1 and 9 digits".to_string());
+        return Err(DeviceError::new(DeviceErrorCode::InvalidRequest, "PIN must be between 1 and 9 digits"));
     }
     
     for &pos in &positions {
         if pos < 1 || pos > 9 {
-            return Err("Invalid PIN position: positions must be 1-9".to_string());
+            return Err(DeviceError::new(DeviceErrorCode::InvalidRequest, "Invalid PIN position: positions must be 1-9"));
         }
     }
     
@@ -3909,7 +4678,7 @@ pub async fn send_pin_matrix_ack(
     let queue_handle = queue_manager_guard.get(&device_id)
         .ok_or_else(|| {
             let _ = unmark_device_in_pin_flow(&device_id);
-            format!("Device not found: {}", device_id)
+            DeviceError::new(DeviceErrorCode::DeviceNotFound, format!("Device not found: {}", device_id))
         })?;
         
     // Create PinMatrixAck message
@@ -3919,6 +4688,7 @@ pub async fn send_pin_matrix_ack(
     match queue_handle.send_raw(pin_ack.into(), true).await {
         Ok(keepkey_rust::messages::Message::Success(_)) => {
             log::info!("✅ PIN accepted! Device unlocked successfully");
+            crate::device::pin_lockout::record_success(&device_id);
             // Unmark device from PIN flow as PIN has been accepted
             let _ = unmark_device_in_pin_flow(&device_id);
             Ok(true)
@@ -3931,9 +4701,13 @@ pub async fn send_pin_matrix_ack(
             // Determine if it's an incorrect PIN or other error
             let error_msg = f.message.unwrap_or_else(|| "PIN verification failed".to_string());
             if error_msg.contains("PIN") || error_msg.contains("Invalid") {
-                Err("Incorrect PIN. Please try again.".to_string())
+                let lockout = crate::device::pin_lockout::record_failure(&device_id);
+                Err(DeviceError::new(
+                    DeviceErrorCode::PinInvalid,
+                    format!("Incorrect PIN. Try again in {}.", crate::device::pin_lockout::describe_wait(lockout.remaining_secs)),
+                ))
             } else {
-                Err(format!("PIN verification failed: {}", error_msg))
+                Err(DeviceError::from_failure(format!("PIN verification failed: {}", error_msg)))
             }
         }
         Ok(other_msg) => {
@@ -3943,16 +4717,17 @@ pub async fn send_pin_matrix_ack(
             // Some devices might respond with Address or other success messages
             if matches!(other_msg, keepkey_rust::messages::Message::Address(_)) {
                 log::info!("✅ PIN accepted (got Address response)");
+                crate::device::pin_lockout::record_success(&device_id);
                 Ok(true)
             } else {
-                Err(format!("Unexpected response: {:?}", other_msg.message_type()))
+                Err(DeviceError::new(DeviceErrorCode::DeviceFailure, format!("Unexpected response: {:?}", other_msg.message_type())))
             }
         }
         Err(e) => {
             log::error!("Failed to send PIN matrix ACK for device {}: {}", device_id, e);
             // Clean up PIN flow marking on error
             let _ = unmark_device_in_pin_flow(&device_id);
-            Err(format!("Failed to send PIN: {}", e))
+            Err(DeviceError::classify(format!("Failed to send PIN: {}", e)))
         }
     }
 }
@@ -3962,7 +4737,7 @@ pub async fn send_pin_matrix_ack(
 pub async fn trigger_pin_request(
     device_id: String,
     queue_manager: tauri::State<'_, DeviceQueueManager>,
-) -> Result<bool, String> {
+) -> Result<bool, DeviceError> {
     log::info!("Triggering PIN request for device: {}", device_id);
     
     // Check if already in PIN flow
@@ -3980,7 +4755,7 @@ pub async fn trigger_pin_request(
         .ok_or_else(|| {
             // Clean up PIN flow marking on error
             let _ = unmark_device_in_pin_flow(&device_id);
-            format!("Device not found: {}", device_id)
+            DeviceError::new(DeviceErrorCode::DeviceNotFound, format!("Device not found: {}", device_id))
         })?;
         
     // Create a simple GetAddress request that will trigger PIN on locked device
@@ -4008,20 +4783,20 @@ pub async fn trigger_pin_request(
             } else {
                 log::warn!("PIN trigger failed with: {:?}", f.message);
                 let _ = unmark_device_in_pin_flow(&device_id);
-                Err(format!("Failed to trigger PIN: {}", f.message.unwrap_or_default()))
+                Err(DeviceError::from_failure(format!("Failed to trigger PIN: {}", f.message.unwrap_or_default())))
             }
         }
         Ok(other_msg) => {
             log::warn!("Unexpected response when triggering PIN request: {:?}", other_msg.message_type());
             // Clean up PIN flow marking on unexpected response
             let _ = unmark_device_in_pin_flow(&device_id);
-            Err(format!("Unexpected response: {:?}", other_msg.message_type()))
+            Err(DeviceError::new(DeviceErrorCode::DeviceFailure, format!("Unexpected response: {:?}", other_msg.message_type())))
         }
         Err(e) => {
             log::error!("Failed to trigger PIN request for device {}: {}", device_id, e);
             // Clean up PIN flow marking on error
             let _ = unmark_device_in_pin_flow(&device_id);
-            Err(format!("Failed to trigger PIN request: {}", e))
+            Err(DeviceError::classify(format!("Failed to trigger PIN request: {}", e)))
         }
     }
 }
@@ -4260,6 +5035,50 @@ pub async fn get_or_create_device_queue(device_id: &str, queue_manager: &DeviceQ
     Ok(handle)
 }
 
+/// Register a virtual KeepKey backed by a fixed test seed (requires the `mock-device` feature)
+#[tauri::command]
+pub async fn create_mock_device(label: Option<String>) -> Result<keepkey_rust::friendly_usb::FriendlyUsbDevice, String> {
+    #[cfg(feature = "mock-device")]
+    {
+        let device = keepkey_rust::mock_device::register_mock_device(label);
+        log::info!("🧪 Registered mock device {}", device.unique_id);
+        Ok(device)
+    }
+    #[cfg(not(feature = "mock-device"))]
+    {
+        let _ = label;
+        Err("Mock devices are not available in this build (enable the mock-device feature)".to_string())
+    }
+}
+
+/// Remove a virtual KeepKey and shut down its queue worker
+#[tauri::command]
+pub async fn remove_mock_device(
+    device_id: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<bool, String> {
+    #[cfg(feature = "mock-device")]
+    {
+        if !keepkey_rust::mock_device::is_mock_device(&device_id) {
+            return Err(format!("{} is not a mock device", device_id));
+        }
+
+        let handle = queue_manager.lock().await.remove(&device_id);
+        if let Some(handle) = handle {
+            let _ = handle.shutdown().await;
+        }
+
+        let removed = keepkey_rust::mock_device::remove_mock_device(&device_id);
+        log::info!("🧪 Removed mock device {}", device_id);
+        Ok(removed)
+    }
+    #[cfg(not(feature = "mock-device"))]
+    {
+        let _ = (device_id, queue_manager);
+        Err("Mock devices are not available in this build (enable the mock-device feature)".to_string())
+    }
+}
+
 // ========== Cache Commands ==========
 
 /// Helper function to get or initialize cache manager
@@ -4288,6 +5107,18 @@ pub async fn get_cache_manager(
     }
 }
 
+/// Report whether the cache is running normally, was recreated, or is in memory
+#[tauri::command]
+pub async fn get_cache_health(
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<serde_json::Value, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    Ok(serde_json::json!({
+        "mode": cache.mode(),
+        "degradation": cache.degradation(),
+    }))
+}
+
 /// Get cache status for a device
 #[tauri::command]
 pub async fn get_cache_status(
@@ -4301,39 +5132,385 @@ pub async fn get_cache_status(
         .map_err(|e| format!("Failed to get cache status: {}", e))
 }
 
+/// Get every persisted ID alias of the physical device a device ID belongs to
+#[tauri::command]
+pub async fn get_device_aliases(
+    device_id: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::cache::DeviceAlias>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    cache
+        .get_device_aliases(&device_id)
+        .await
+        .map_err(|e| format!("Failed to get device aliases: {}", e))
+}
+
 /// Trigger frontload for a device
 #[tauri::command]
 pub async fn trigger_frontload(
+    app: AppHandle,
     device_id: String,
+    mode: Option<String>,
     cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
     queue_manager: State<'_, DeviceQueueManager>,
 ) -> Result<(), String> {
+    // Default to the device's configured mode when none is given
+    let mode = match mode {
+        Some(m) => m.parse::<crate::cache::schedule::FrontloadMode>()?,
+        None => crate::cache::schedule::get_schedule(&device_id).mode,
+    };
+    
     let cache = get_cache_manager(cache_manager.inner()).await?;
-    let frontload_controller = crate::cache::FrontloadController::new(
-        cache,
-        queue_manager.inner().clone(),
-    );
     
     // Run frontload in background
-    let device_id_clone = device_id.clone();
-    tauri::async_runtime::spawn(async move {
-        if let Err(e) = frontload_controller.frontload_device(&device_id_clone).await {
-            log::error!("Frontload failed for device {}: {}", device_id_clone, e);
-        }
-    });
+    crate::cache::schedule::spawn_frontload(&app, cache, queue_manager.inner().clone(), &device_id, mode);
     
     Ok(())
 }
 
-/// Clear cache for a specific device
+/// Report what a frontload would do without touching the device
+#[tauri::command]
+pub async fn plan_frontload(
+    device_id: String,
+    mode: Option<String>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::cache::frontload::FrontloadPlan, String> {
+    let mode = match mode {
+        Some(m) => m.parse::<crate::cache::schedule::FrontloadMode>()?,
+        None => crate::cache::schedule::get_schedule(&device_id).mode,
+    };
+    
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::cache::FrontloadController::new(cache, queue_manager.inner().clone())
+        .plan_frontload(&device_id, mode)
+        .await
+        .map_err(|e| format!("Failed to plan frontload: {}", e))
+}
+
+/// Get the frontload scheduling preferences for a device
+#[tauri::command]
+pub async fn get_frontload_schedule(
+    device_id: String,
+) -> Result<crate::cache::schedule::FrontloadSchedule, String> {
+    Ok(crate::cache::schedule::get_schedule(&device_id))
+}
+
+/// Set the frontload scheduling preferences for a device
+#[tauri::command]
+pub async fn set_frontload_schedule(
+    device_id: String,
+    schedule: crate::cache::schedule::FrontloadSchedule,
+) -> Result<(), String> {
+    log::info!("Setting frontload schedule for {}: {:?}", device_id, schedule);
+    crate::cache::schedule::set_schedule(&device_id, &schedule)
+}
+
+/// Get the chain ranking frontload follows (highest priority first)
+#[tauri::command]
+pub async fn get_frontload_chain_priority() -> Result<Vec<String>, String> {
+    Ok(crate::cache::schedule::get_chain_priority())
+}
+
+/// Rank chains so frontload derives the most important ones first
+#[tauri::command]
+pub async fn set_frontload_chain_priority(chains: Vec<String>) -> Result<Vec<String>, String> {
+    log::info!("Setting frontload chain priority: {:?}", chains);
+    crate::cache::schedule::set_chain_priority(chains)
+}
+
+/// List the Cosmos SDK chains the vault derives addresses for
+#[tauri::command]
+pub async fn get_cosmos_chains() -> Result<Vec<crate::device::cosmos_chains::CosmosChain>, String> {
+    Ok(crate::device::cosmos_chains::list_chains())
+}
+
+/// Replace the user-configured Cosmos SDK chains (built-in chains always stay enabled)
+#[tauri::command]
+pub async fn set_cosmos_chains(
+    chains: Vec<crate::device::cosmos_chains::CosmosChain>,
+) -> Result<Vec<crate::device::cosmos_chains::CosmosChain>, String> {
+    log::info!("Setting {} configured Cosmos chain(s)", chains.len());
+    crate::device::cosmos_chains::set_configured_chains(chains)
+}
+
+/// Get the SSH agent settings and socket path
+#[tauri::command]
+pub async fn get_ssh_agent_status() -> Result<crate::ssh_agent::SshAgentStatus, String> {
+    crate::ssh_agent::status()
+}
+
+/// Update the SSH agent settings, starting or stopping it as needed
+#[tauri::command]
+pub async fn set_ssh_agent_config(
+    config: crate::ssh_agent::SshAgentConfig,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::ssh_agent::SshAgentStatus, String> {
+    log::info!("Setting SSH agent config: enabled={}, curve={:?}", config.enabled, config.curve);
+    crate::ssh_agent::apply_config(config, queue_manager.inner().clone())
+}
+
+/// Get the enrolled device-backed OpenPGP key, if any
+#[tauri::command]
+pub async fn get_gpg_identity() -> Result<Option<crate::gpg::GpgIdentity>, String> {
+    Ok(crate::gpg::get_identity())
+}
+
+/// Derive and self-certify an OpenPGP key for a user ID, returning the public key to export
+#[tauri::command]
+pub async fn gpg_enroll(
+    user_id: String,
+    curve: crate::identity::IdentityCurve,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::gpg::GpgIdentity, String> {
+    log::info!("Enrolling OpenPGP key for {} ({:?})", user_id, curve);
+    crate::gpg::enroll(&user_id, curve, queue_manager.inner()).await
+}
+
+/// Create an armored detached signature over base64-encoded data with the enrolled key
+#[tauri::command]
+pub async fn gpg_sign_detached(
+    data_base64: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<String, String> {
+    use base64::Engine;
+    let data = base64::engine::general_purpose::STANDARD.decode(data_base64.trim())
+        .map_err(|e| format!("Invalid base64 data: {}", e))?;
+    crate::gpg::sign_detached(&data, queue_manager.inner()).await
+}
+
+/// Get the per-category native notification toggles
+#[tauri::command]
+pub async fn get_notification_preferences() -> Result<crate::notifications::NotificationPreferences, String> {
+    Ok(crate::notifications::get_preferences())
+}
+
+/// Update the per-category native notification toggles
+#[tauri::command]
+pub async fn set_notification_preferences(
+    preferences: crate::notifications::NotificationPreferences,
+) -> Result<crate::notifications::NotificationPreferences, String> {
+    crate::notifications::set_preferences(&preferences)?;
+    Ok(preferences)
+}
+
+/// Get the status message catalog for a locale (defaults to the language preference)
+#[tauri::command]
+pub async fn get_status_messages(locale: Option<String>) -> Result<std::collections::HashMap<String, String>, String> {
+    let locale = locale.unwrap_or_else(crate::status_messages::current_locale);
+    Ok(crate::status_messages::catalog_for(&locale))
+}
+
+/// Get the current power state and device polling configuration
+#[tauri::command]
+pub async fn get_power_status() -> Result<crate::power::PowerStatus, String> {
+    Ok(crate::power::status())
+}
+
+/// Update the device polling preferences
+#[tauri::command]
+pub async fn set_polling_config(config: crate::power::PollingConfig) -> Result<crate::power::PowerStatus, String> {
+    crate::power::set_config(&config)?;
+    Ok(crate::power::status())
+}
+
+/// Force low-power mode on or off for this session (null follows preferences)
+#[tauri::command]
+pub async fn set_low_power_mode(enabled: Option<bool>) -> Result<crate::power::PowerStatus, String> {
+    crate::power::set_runtime_override(enabled);
+    Ok(crate::power::status())
+}
+
+/// Get the configured passphrase cache policies
+#[tauri::command]
+pub async fn get_passphrase_cache_policy() -> Result<crate::device::passphrase_policy::PassphrasePolicyPreferences, String> {
+    Ok(crate::device::passphrase_policy::get_preferences())
+}
+
+/// Set the passphrase cache policy for a device, or the default when no device is given
+#[tauri::command]
+pub async fn set_passphrase_cache_policy(
+    device_id: Option<String>,
+    policy: Option<keepkey_rust::device_queue::PassphraseCachePolicy>,
+) -> Result<crate::device::passphrase_policy::PassphrasePolicyPreferences, String> {
+    crate::device::passphrase_policy::set_policy(device_id.as_deref(), policy)
+}
+
+/// Get the encrypted settings backup configuration
+#[tauri::command]
+pub async fn get_settings_backup() -> Result<crate::settings_backup::BackupSettings, String> {
+    Ok(crate::settings_backup::get_settings())
+}
+
+/// Enable or reconfigure encrypted settings backups
+#[tauri::command]
+pub async fn set_settings_backup(
+    settings: crate::settings_backup::BackupSettings,
+) -> Result<crate::settings_backup::BackupSettings, String> {
+    crate::settings_backup::set_settings(&settings)?;
+    Ok(settings)
+}
+
+/// Encrypt preferences and labels with the passphrase and store them at the configured destination
+#[tauri::command]
+pub async fn backup_settings(
+    passphrase: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<usize, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::settings_backup::backup(&passphrase, &cache).await
+}
+
+/// Restore preferences and labels from a backup (the configured destination unless one is given)
+#[tauri::command]
+pub async fn restore_settings(
+    passphrase: String,
+    destination: Option<crate::settings_backup::BackupDestination>,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<usize, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    crate::settings_backup::restore(&passphrase, destination, &cache).await
+}
+
+/// List wallets with their devices and cached accounts
+#[tauri::command]
+pub async fn get_wallets(
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<Vec<crate::wallets::WalletSummary>, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await.ok();
+    let mut summaries = Vec::new();
+    for wallet in crate::wallets::get_wallets() {
+        summaries.push(crate::wallets::summarize(wallet, cache.as_deref()).await);
+    }
+    Ok(summaries)
+}
+
+/// Create or update a wallet grouping devices and watch-only xpubs
+#[tauri::command]
+pub async fn save_wallet(wallet: crate::wallets::Wallet) -> Result<crate::wallets::Wallet, String> {
+    crate::wallets::save_wallet(wallet)
+}
+
+/// Remove a wallet (devices and cached data are kept)
+#[tauri::command]
+pub async fn remove_wallet(wallet_id: String) -> Result<bool, String> {
+    crate::wallets::remove_wallet(&wallet_id)
+}
+
+/// List configured automation hooks
+#[tauri::command]
+pub async fn get_automation_hooks() -> Result<Vec<crate::automation::AutomationHook>, String> {
+    Ok(crate::automation::get_hooks())
+}
+
+/// Add or update an automation hook
+#[tauri::command]
+pub async fn save_automation_hook(
+    hook: crate::automation::AutomationHook,
+) -> Result<crate::automation::AutomationHook, String> {
+    crate::automation::save_hook(hook)
+}
+
+/// Remove an automation hook
+#[tauri::command]
+pub async fn remove_automation_hook(hook_id: String) -> Result<(), String> {
+    crate::automation::remove_hook(&hook_id)
+}
+
+/// Send a test event to an automation hook, returning the HTTP status
+#[tauri::command]
+pub async fn test_automation_hook(hook_id: String) -> Result<u16, String> {
+    crate::automation::test_hook(&hook_id).await
+}
+
+/// Store a managed secret (e.g. the Pioneer API key) in the OS keychain
+#[tauri::command]
+pub async fn store_secret(name: String, value: String) -> Result<(), String> {
+    crate::secrets::validate_managed_secret(&name)?;
+    log::info!("🔑 Storing secret: {}", name);
+    crate::secrets::set_secret(&name, &value)
+}
+
+/// Check whether a managed secret is present (the value is never returned)
+#[tauri::command]
+pub async fn has_stored_secret(name: String) -> Result<bool, String> {
+    crate::secrets::validate_managed_secret(&name)?;
+    Ok(crate::secrets::has_secret(&name))
+}
+
+/// The Pioneer API key for the frontend Pioneer client, if one is stored
+#[tauri::command]
+pub async fn get_pioneer_api_key() -> Result<Option<String>, String> {
+    Ok(crate::secrets::pioneer_api_key())
+}
+
+/// Remove a managed secret from the OS keychain
+#[tauri::command]
+pub async fn delete_stored_secret(name: String) -> Result<(), String> {
+    crate::secrets::validate_managed_secret(&name)?;
+    log::info!("🔑 Deleting secret: {}", name);
+    crate::secrets::delete_secret(&name)
+}
+
+/// Clear cache for a specific device; it can be restored with restore_device_cache
+/// until the tombstone expires
 #[tauri::command]
 pub async fn clear_device_cache(
     device_id: String,
     cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
 ) -> Result<(), String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    let moved = cache
+        .soft_delete_device_cache(&device_id)
+        .await
+        .map_err(|e| format!("Failed to clear device cache: {}", e))?;
+    log::info!("🗑️ Cleared {} cached entries for {} (restorable)", moved, device_id);
+    Ok(())
+}
+
+/// Undo a clear_device_cache, returning how many entries came back
+#[tauri::command]
+pub async fn restore_device_cache(
+    device_id: String,
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<usize, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    let deleted_at = cache
+        .get_cache_tombstone(&device_id)
+        .await
+        .ok_or_else(|| format!("No cleared cache to restore for device {}", device_id))?;
+    if chrono::Utc::now().timestamp() - deleted_at > crate::cache::maintenance::tombstone_retention_secs() {
+        return Err(format!("The cleared cache for device {} has expired", device_id));
+    }
+    
+    let restored = cache
+        .restore_device_cache(&device_id)
+        .await
+        .map_err(|e| format!("Failed to restore device cache: {}", e))?;
+    log::info!("♻️ Restored {} cached entries for {}", restored, device_id);
+    Ok(restored)
+}
+
+/// Remove stale cache entries and vacuum the cache database
+#[tauri::command]
+pub async fn compact_cache(
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::cache::CacheCompactionResult, String> {
     let cache = get_cache_manager(cache_manager.inner()).await?;
     cache
-        .clear_device_cache(&device_id)
+        .compact()
         .await
-        .map_err(|e| format!("Failed to clear device cache: {}", e))
-}
\ No newline at end of file
+        .map_err(|e| format!("Failed to compact cache: {}", e))
+}
+
+/// Get disk usage of the cache database
+#[tauri::command]
+pub async fn get_cache_disk_usage(
+    cache_manager: State<'_, Arc<once_cell::sync::OnceCell<Arc<crate::cache::CacheManager>>>>,
+) -> Result<crate::cache::CacheDiskUsage, String> {
+    let cache = get_cache_manager(cache_manager.inner()).await?;
+    cache
+        .get_disk_usage()
+        .await
+        .map_err(|e| format!("Failed to get cache disk usage: {}", e))
+}
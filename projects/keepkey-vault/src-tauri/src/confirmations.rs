@@ -0,0 +1,130 @@
+//! Per-network confirmation thresholds.
+//!
+//! A transaction counts as final once it has at least the configured number
+//! of confirmations for its network (CAIP-2 id). Transactions seen below the
+//! threshold are remembered, and `tx:finalized` is emitted when a later
+//! observation shows them crossing it.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// Preference key holding thresholds keyed by CAIP-2 network id
+const PREFERENCE_KEY: &str = "confirmation_thresholds";
+
+/// Bitcoin mainnet CAIP-2 id
+pub const BITCOIN_NETWORK: &str = "bip122:000000000019d6689c085ae165831e93";
+
+/// Threshold for networks without a default or user setting
+const FALLBACK_THRESHOLD: u32 = 1;
+
+/// Defaults applied unless the user overrides them
+const DEFAULT_THRESHOLDS: &[(&str, u32)] = &[
+    (BITCOIN_NETWORK, 3),
+    ("eip155:1", 12),
+];
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+lazy_static::lazy_static! {
+    /// (network, txid) seen with fewer confirmations than required
+    static ref PENDING: Mutex<HashSet<(String, String)>> = Mutex::new(HashSet::new());
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TxFinalized {
+    pub device_id: String,
+    pub network: String,
+    pub txid: String,
+    pub confirmations: u64,
+    pub threshold: u32,
+}
+
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Effective thresholds: the defaults overlaid with user settings
+pub fn get_thresholds() -> HashMap<String, u32> {
+    let mut thresholds: HashMap<String, u32> = DEFAULT_THRESHOLDS
+        .iter()
+        .map(|(network, n)| (network.to_string(), *n))
+        .collect();
+    let configured: HashMap<String, u32> = crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default();
+    thresholds.extend(configured);
+    thresholds
+}
+
+/// Set the threshold for one network; None restores the default
+pub fn set_threshold(network: &str, confirmations: Option<u32>) -> Result<HashMap<String, u32>, String> {
+    if !network.contains(':') {
+        return Err(format!("Expected a CAIP-2 network id, got {}", network));
+    }
+    if confirmations == Some(0) {
+        return Err("Confirmation threshold must be at least 1".to_string());
+    }
+
+    let mut configured: HashMap<String, u32> = crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default();
+    match confirmations {
+        Some(n) => configured.insert(network.to_string(), n),
+        None => configured.remove(network),
+    };
+    crate::preferences::set(PREFERENCE_KEY, serde_json::json!(configured))?;
+    Ok(get_thresholds())
+}
+
+pub fn threshold_for(network: &str) -> u32 {
+    get_thresholds().get(network).copied().unwrap_or(FALLBACK_THRESHOLD)
+}
+
+/// Whether a transaction just crossed its threshold, updating the pending set
+fn track(pending: &mut HashSet<(String, String)>, network: &str, txid: &str, confirmations: u64, threshold: u32) -> bool {
+    let key = (network.to_string(), txid.to_lowercase());
+    if confirmations < threshold as u64 {
+        pending.insert(key);
+        false
+    } else {
+        pending.remove(&key)
+    }
+}
+
+/// Record a transaction's current confirmations; returns whether it is final
+pub async fn observe(device_id: &str, network: &str, txid: &str, confirmations: u64) -> bool {
+    let threshold = threshold_for(network);
+    let crossed = track(&mut PENDING.lock().unwrap(), network, txid, confirmations, threshold);
+
+    if crossed {
+        if let Some(app) = APP_HANDLE.get() {
+            let event = TxFinalized {
+                device_id: device_id.to_string(),
+                network: network.to_string(),
+                txid: txid.to_string(),
+                confirmations,
+                threshold,
+            };
+            if let Err(e) = crate::commands::emit_or_queue_event(app, "tx:finalized", serde_json::json!(event)).await {
+                log::warn!("Failed to emit tx:finalized: {}", e);
+            }
+        }
+    }
+
+    confirmations >= threshold as u64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_track_only_reports_crossing() {
+        let mut pending = HashSet::new();
+        // Already final when first seen: nothing to announce
+        assert!(!track(&mut pending, BITCOIN_NETWORK, "aa", 6, 3));
+        assert!(!track(&mut pending, BITCOIN_NETWORK, "bb", 1, 3));
+        assert!(track(&mut pending, BITCOIN_NETWORK, "BB", 3, 3));
+        assert!(!track(&mut pending, BITCOIN_NETWORK, "bb", 4, 3));
+    }
+}
@@ -0,0 +1,399 @@
+//! Software address derivation from cached account xpubs.
+//!
+//! Receive/change addresses are derived with BIP32 public derivation so they
+//! can be rotated and scanned while the device is unplugged. After frontload,
+//! addresses the device reported are re-derived here and any disagreement is
+//! recorded as a derivation error.
+
+use base58::{FromBase58, ToBase58};
+use bech32::{ToBase32, Variant};
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::elliptic_curve::PrimeField;
+use k256::{FieldBytes, ProjectivePoint, PublicKey, Scalar};
+use ripemd::Ripemd160;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256, Sha512};
+use tauri::{AppHandle, Manager};
+use utoipa::ToSchema;
+
+use crate::cache::CacheManager;
+
+/// Most addresses derived per request
+pub const MAX_DERIVED_ADDRESSES: u32 = 100;
+
+/// Addresses scanned per chain when looking an address up, by default and at most
+pub const DEFAULT_LOOKUP_DEPTH: u32 = 100;
+pub const MAX_LOOKUP_DEPTH: u32 = 1000;
+
+const HARDENED: u32 = 0x8000_0000;
+
+/// Account-level extended public key
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedPubKey {
+    pub chain_code: [u8; 32],
+    pub public_key: PublicKey,
+}
+
+impl ExtendedPubKey {
+    /// Parse a base58check xpub; the SLIP-132 version prefix is ignored
+    pub fn parse(xpub: &str) -> Result<Self, String> {
+        let data = xpub.trim().from_base58().map_err(|_| "Invalid base58 encoding".to_string())?;
+        if data.len() != 82 {
+            return Err(format!("Invalid extended key length {}", data.len()));
+        }
+        let (payload, checksum) = data.split_at(78);
+        if crate::slip132::sha256d(payload)[..4] != *checksum {
+            return Err("Invalid extended key checksum".to_string());
+        }
+
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&payload[13..45]);
+        let public_key = PublicKey::from_sec1_bytes(&payload[45..78])
+            .map_err(|_| "Extended key does not hold a valid public key".to_string())?;
+        Ok(Self { chain_code, public_key })
+    }
+
+    /// BIP32 CKDpub for a non-hardened index
+    pub fn derive_child(&self, index: u32) -> Result<Self, String> {
+        if index >= HARDENED {
+            return Err("Hardened children cannot be derived from an xpub".to_string());
+        }
+        let mut mac = Hmac::<Sha512>::new_from_slice(&self.chain_code)
+            .map_err(|e| format!("Failed to key HMAC: {}", e))?;
+        mac.update(self.public_key.to_encoded_point(true).as_bytes());
+        mac.update(&index.to_be_bytes());
+        let output = mac.finalize().into_bytes();
+        let (tweak, chain_code) = output.split_at(32);
+
+        // IL >= n or a point at infinity makes the index invalid (BIP32)
+        let tweak: Option<Scalar> = Scalar::from_repr(FieldBytes::clone_from_slice(tweak)).into();
+        let tweak = tweak.ok_or_else(|| format!("Index {} is invalid for this key", index))?;
+        let point = ProjectivePoint::GENERATOR * tweak + self.public_key.to_projective();
+        let public_key = PublicKey::from_affine(point.to_affine())
+            .map_err(|_| format!("Index {} is invalid for this key", index))?;
+
+        let mut child_chain_code = [0u8; 32];
+        child_chain_code.copy_from_slice(chain_code);
+        Ok(Self { chain_code: child_chain_code, public_key })
+    }
+}
+
+/// Address version bytes and segwit prefix of a UTXO coin
+struct CoinParams {
+    p2pkh: u8,
+    p2sh: u8,
+    bech32_hrp: Option<&'static str>,
+}
+
+fn coin_params(coin_name: &str) -> Option<CoinParams> {
+    let params = match coin_name.to_lowercase().as_str() {
+        "bitcoin" => CoinParams { p2pkh: 0x00, p2sh: 0x05, bech32_hrp: Some("bc") },
+        "testnet" => CoinParams { p2pkh: 0x6f, p2sh: 0xc4, bech32_hrp: Some("tb") },
+        "litecoin" => CoinParams { p2pkh: 0x30, p2sh: 0x32, bech32_hrp: Some("ltc") },
+        "dogecoin" => CoinParams { p2pkh: 0x1e, p2sh: 0x16, bech32_hrp: None },
+        "dash" => CoinParams { p2pkh: 0x4c, p2sh: 0x10, bech32_hrp: None },
+        _ => return None,
+    };
+    Some(params)
+}
+
+pub fn is_supported_coin(coin_name: &str) -> bool {
+    coin_params(coin_name).is_some()
+}
+
+fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+fn base58check(version: u8, hash: &[u8]) -> String {
+    let mut data = vec![version];
+    data.extend_from_slice(hash);
+    let checksum = crate::slip132::sha256d(&data);
+    data.extend_from_slice(&checksum[..4]);
+    data.to_base58()
+}
+
+/// Address of a public key for a coin and script type
+pub fn encode_address(public_key: &PublicKey, coin_name: &str, script_type: &str) -> Result<String, String> {
+    let params = coin_params(coin_name)
+        .ok_or_else(|| format!("Software derivation does not support {}", coin_name))?;
+    let key_hash = hash160(public_key.to_encoded_point(true).as_bytes());
+
+    match script_type {
+        "p2pkh" => Ok(base58check(params.p2pkh, &key_hash)),
+        "p2sh-p2wpkh" => {
+            let mut redeem_script = vec![0x00, 0x14];
+            redeem_script.extend_from_slice(&key_hash);
+            Ok(base58check(params.p2sh, &hash160(&redeem_script)))
+        }
+        "p2wpkh" => {
+            let hrp = params
+                .bech32_hrp
+                .ok_or_else(|| format!("{} has no native segwit addresses", coin_name))?;
+            let mut data = vec![bech32::u5::try_from_u8(0).map_err(|e| e.to_string())?];
+            data.extend(key_hash.to_base32());
+            bech32::encode(hrp, data, Variant::Bech32).map_err(|e| format!("Failed to encode address: {}", e))
+        }
+        other => Err(format!("Unsupported script type: {}", other)),
+    }
+}
+
+/// Address at `change/index` below an account xpub
+pub fn derive_address(xpub: &str, coin_name: &str, script_type: &str, change: u32, index: u32) -> Result<String, String> {
+    let key = ExtendedPubKey::parse(xpub)?.derive_child(change)?.derive_child(index)?;
+    encode_address(&key.public_key, coin_name, script_type)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedAddress {
+    pub derivation_path: String,
+    pub address: String,
+}
+
+/// A run of addresses below a cached account xpub
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeriveAddressesRequest {
+    /// Account path the xpub was cached under, e.g. m/84'/0'/0'
+    pub account_path: String,
+    pub coin_name: String,
+    pub script_type: String,
+    /// 0 for receive, 1 for change
+    pub change: u32,
+    pub start: u32,
+    pub count: u32,
+}
+
+/// Consecutive addresses below a cached account xpub, without the device
+pub async fn derive_addresses(cache: &CacheManager, device_id: &str, request: &DeriveAddressesRequest) -> Result<Vec<DerivedAddress>, String> {
+    if request.count > MAX_DERIVED_ADDRESSES {
+        return Err(format!("At most {} addresses can be derived at once", MAX_DERIVED_ADDRESSES));
+    }
+    let xpub = cache
+        .get_cached_pubkey(device_id, &request.account_path, &request.coin_name, Some(&request.script_type))
+        .await
+        .and_then(|cached| cached.xpub)
+        .ok_or_else(|| format!(
+            "No cached xpub for {} {} at {}",
+            request.coin_name, request.script_type, request.account_path,
+        ))?;
+
+    let change_key = ExtendedPubKey::parse(&xpub)?.derive_child(request.change)?;
+    (request.start..request.start.saturating_add(request.count))
+        .map(|index| {
+            let key = change_key.derive_child(index)?;
+            Ok(DerivedAddress {
+                derivation_path: format!("{}/{}/{}", request.account_path, request.change, index),
+                address: encode_address(&key.public_key, &request.coin_name, &request.script_type)?,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivationMismatch {
+    pub derivation_path: String,
+    pub coin_name: String,
+    pub script_type: String,
+    pub device_address: String,
+    pub derived_address: String,
+}
+
+/// `change` and `index` of an address path directly below an account path
+fn address_suffix(account_path: &str, address_path: &str) -> Option<(u32, u32)> {
+    let rest = address_path.strip_prefix(account_path)?.strip_prefix('/')?;
+    let (change, index) = rest.split_once('/')?;
+    Some((change.parse().ok()?, index.parse().ok()?))
+}
+
+/// Re-derive every cached device address that sits below a cached account xpub
+pub async fn cross_check(cache: &CacheManager, device_id: &str) -> Result<(usize, Vec<DerivationMismatch>), String> {
+    let pubkeys = cache
+        .list_cached_pubkeys(device_id)
+        .await
+        .map_err(|e| format!("Failed to read cached pubkeys: {}", e))?;
+
+    let mut checked = 0;
+    let mut mismatches = Vec::new();
+    for account in pubkeys.iter().filter(|p| p.xpub.is_some() && is_supported_coin(&p.coin_name)) {
+        let (Some(xpub), Some(script_type)) = (&account.xpub, &account.script_type) else {
+            continue;
+        };
+        for cached in pubkeys.iter().filter(|p| p.coin_name == account.coin_name && p.script_type == account.script_type) {
+            let (Some(device_address), Some((change, index))) =
+                (&cached.address, address_suffix(&account.derivation_path, &cached.derivation_path))
+            else {
+                continue;
+            };
+            let derived_address = derive_address(xpub, &account.coin_name, script_type, change, index)?;
+            checked += 1;
+            if derived_address != *device_address {
+                mismatches.push(DerivationMismatch {
+                    derivation_path: cached.derivation_path.clone(),
+                    coin_name: account.coin_name.clone(),
+                    script_type: script_type.clone(),
+                    device_address: device_address.clone(),
+                    derived_address,
+                });
+            }
+        }
+    }
+    Ok((checked, mismatches))
+}
+
+/// Where a looked-up address belongs
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressMatch {
+    pub device_id: String,
+    pub derivation_path: String,
+    pub coin_name: String,
+    pub script_type: Option<String>,
+    /// "cached" when the device reported it, "derived" when found below a cached xpub
+    pub source: String,
+}
+
+/// Bech32 and hex (EIP-55) addresses compare case-insensitively, base58 doesn't
+fn same_address(a: &str, b: &str) -> bool {
+    if a == b {
+        return true;
+    }
+    let lower = a.to_lowercase();
+    let case_insensitive = lower.starts_with("0x")
+        || ["bc1", "tb1", "bcrt1", "ltc1"].iter().any(|hrp| lower.starts_with(hrp));
+    case_insensitive && a.eq_ignore_ascii_case(b)
+}
+
+/// First `depth` receive and change addresses below an account xpub matching `address`
+fn scan_account(xpub: &str, coin_name: &str, script_type: &str, address: &str, depth: u32) -> Result<Option<(u32, u32)>, String> {
+    let account_key = ExtendedPubKey::parse(xpub)?;
+    for change in [0, 1] {
+        let change_key = account_key.derive_child(change)?;
+        for index in 0..depth {
+            let key = change_key.derive_child(index)?;
+            if same_address(&encode_address(&key.public_key, coin_name, script_type)?, address) {
+                return Ok(Some((change, index)));
+            }
+        }
+    }
+    Ok(None)
+}
+
+/// Find which cached device and path an address belongs to. Addresses the
+/// devices reported are matched first; otherwise the first `depth` receive
+/// and change addresses of every cached UTXO account are derived and compared.
+pub async fn lookup_address(cache: &CacheManager, address: &str, depth: Option<u32>) -> Result<Vec<AddressMatch>, String> {
+    let address = address.trim();
+    if address.is_empty() {
+        return Err("Address is required".to_string());
+    }
+    let depth = depth.unwrap_or(DEFAULT_LOOKUP_DEPTH).min(MAX_LOOKUP_DEPTH);
+
+    let cached = cache
+        .find_pubkeys_by_address(address)
+        .await
+        .map_err(|e| format!("Failed to search cached addresses: {}", e))?;
+    let matches: Vec<AddressMatch> = cached
+        .into_iter()
+        .filter(|p| p.address.as_deref().is_some_and(|a| same_address(a, address)))
+        .map(|p| AddressMatch {
+            device_id: p.device_id,
+            derivation_path: p.derivation_path,
+            coin_name: p.coin_name,
+            script_type: p.script_type,
+            source: "cached".to_string(),
+        })
+        .collect();
+    if !matches.is_empty() {
+        return Ok(matches);
+    }
+
+    let accounts = cache
+        .list_cached_xpubs()
+        .await
+        .map_err(|e| format!("Failed to read cached xpubs: {}", e))?;
+    let mut matches = Vec::new();
+    for account in accounts.iter().filter(|p| is_supported_coin(&p.coin_name)) {
+        let (Some(xpub), Some(script_type)) = (&account.xpub, &account.script_type) else {
+            continue;
+        };
+        match scan_account(xpub, &account.coin_name, script_type, address, depth) {
+            Ok(Some((change, index))) => matches.push(AddressMatch {
+                device_id: account.device_id.clone(),
+                derivation_path: format!("{}/{}/{}", account.derivation_path, change, index),
+                coin_name: account.coin_name.clone(),
+                script_type: Some(script_type.clone()),
+                source: "derived".to_string(),
+            }),
+            Ok(None) => {}
+            Err(e) => log::debug!("Skipping {} {} during address lookup: {}", account.device_id, account.derivation_path, e),
+        }
+    }
+    Ok(matches)
+}
+
+/// Cross-check a freshly cached device and record any mismatch
+pub async fn check_device(app: &AppHandle, device_id: &str) {
+    let Some(cache) = app
+        .try_state::<std::sync::Arc<once_cell::sync::OnceCell<std::sync::Arc<CacheManager>>>>()
+        .and_then(|cell| cell.get().cloned())
+    else {
+        return;
+    };
+
+    match cross_check(&cache, device_id).await {
+        Ok((checked, mismatches)) => {
+            log::debug!("Derivation cross-check for {}: {} address(es), {} mismatch(es)", device_id, checked, mismatches.len());
+            for mismatch in mismatches {
+                let message = format!(
+                    "Software derivation disagrees with the device at {} ({}): device {}, derived {}",
+                    mismatch.derivation_path, mismatch.coin_name, mismatch.device_address, mismatch.derived_address,
+                );
+                log::error!("{}", message);
+                crate::error_center::record(crate::error_center::ErrorCategory::Derivation, Some(device_id), message).await;
+            }
+        }
+        Err(e) => log::warn!("Derivation cross-check failed for {}: {}", device_id, e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bip32_public_derivation() {
+        // BIP32 test vector 1: m/0H -> m/0H/1
+        let parent = ExtendedPubKey::parse("xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw").unwrap();
+        let expected = ExtendedPubKey::parse("xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ").unwrap();
+        assert_eq!(parent.derive_child(1).unwrap(), expected);
+        assert!(parent.derive_child(HARDENED).is_err());
+    }
+
+    #[test]
+    fn test_derive_address_bip84() {
+        // BIP84 test vector, account m/84'/0'/0'
+        let zpub = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+        assert_eq!(derive_address(zpub, "Bitcoin", "p2wpkh", 0, 0).unwrap(), "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu");
+        assert_eq!(derive_address(zpub, "bitcoin", "p2wpkh", 1, 0).unwrap(), "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el");
+    }
+
+    #[test]
+    fn test_scan_account() {
+        let zpub = "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs";
+        let found = scan_account(zpub, "bitcoin", "p2wpkh", "BC1Q8C6FSHW2DLWUN7EKN9QWF37CU2RN755UPCP6EL", 5).unwrap();
+        assert_eq!(found, Some((1, 0)));
+        assert_eq!(scan_account(zpub, "bitcoin", "p2wpkh", "1JAd7XCBzGudGpJQSDSfpmJhiygtLQWaGL", 5).unwrap(), None);
+        assert!(!same_address("1JAd7XCBzGudGpJQSDSfpmJhiygtLQWaGL", "1jad7xcbzgudgpjqsdsfpmjhiygtlqwagl"));
+    }
+
+    #[test]
+    fn test_address_suffix() {
+        assert_eq!(address_suffix("m/84'/0'/0'", "m/84'/0'/0'/1/7"), Some((1, 7)));
+        assert_eq!(address_suffix("m/84'/0'/0'", "m/84'/0'/0'"), None);
+        assert_eq!(address_suffix("m/84'/0'/0'", "m/84'/0'/1'/0/0"), None);
+    }
+}
@@ -99,7 +99,12 @@ pub async fn process_address_request(
             }
         }
         
-        DeviceRequest::CosmosGetAddress { path, hrp: _, show_display } => {
+        DeviceRequest::CosmosGetAddress { path, hrp, show_display } => {
+            // Registry chains reuse the cosmos message; the device would display a cosmos1 address
+            if hrp != "cosmos" && show_display.unwrap_or(false) {
+                return Err(format!("On-device display is not available for {} addresses", hrp));
+            }
+            
             let path_parts = parse_derivation_path(path)?;
             let path_str = format!("m/{}", path_parts.iter()
                 .map(|&n| if n & 0x80000000 != 0 {
@@ -126,8 +131,10 @@ pub async fn process_address_request(
             match response {
                 keepkey_rust::messages::Message::CosmosAddress(address) => {
                     let elapsed = start_time.elapsed();
-                    let default_addr = String::new();
-                    let addr = address.address.as_ref().unwrap_or(&default_addr);
+                    let mut addr = address.address.unwrap_or_default();
+                    if hrp != "cosmos" && !addr.is_empty() {
+                        addr = crate::device::cosmos_chains::convert_hrp(&addr, hrp)?;
+                    }
                     log::info!("  ✅ CosmosGetAddress completed in {:.3}s - Address: {}...{}", 
                         elapsed.as_secs_f64(),
                         &addr[..10.min(addr.len())],
@@ -136,7 +143,7 @@ pub async fn process_address_request(
                         request_id: request_id.to_string(),
                         device_id: device_id.to_string(),
                         path: path.to_string(),
-                        address: address.address.unwrap_or_default(),
+                        address: addr,
                         success: true,
                         error: None,
                     })
@@ -417,10 +424,14 @@ pub async fn process_address_request_with_cache(
     let start_time = std::time::Instant::now();
     
     // Extract path and coin type for cache lookup
+    let cosmos_chain;
     let (path, coin_name, script_type) = match request {
         DeviceRequest::GetAddress { path, coin_name, script_type, .. } => (path.as_str(), coin_name.as_str(), script_type.as_deref()),
         DeviceRequest::EthereumGetAddress { path, .. } => (path.as_str(), "ethereum", Some("ethereum")),
-        DeviceRequest::CosmosGetAddress { path, .. } => (path.as_str(), "cosmos", Some("cosmos")),
+        DeviceRequest::CosmosGetAddress { path, hrp, .. } => {
+            cosmos_chain = crate::device::cosmos_chains::chain_name_for_hrp(hrp);
+            (path.as_str(), cosmos_chain.as_str(), Some(hrp.as_str()))
+        }
         DeviceRequest::OsmosisGetAddress { path, .. } => (path.as_str(), "osmosis", Some("bech32")),
         DeviceRequest::ThorchainGetAddress { path, .. } => (path.as_str(), "thorchain", Some("thorchain")),
         DeviceRequest::MayachainGetAddress { path, .. } => (path.as_str(), "mayachain", Some("mayachain")),
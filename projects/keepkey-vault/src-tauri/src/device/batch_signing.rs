@@ -0,0 +1,83 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::Serialize;
+use tokio::sync::oneshot;
+use utoipa::ToSchema;
+
+/// How long a batch waits for the user to approve it in the vault
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// One line of the approval summary shown before a batch is signed
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemSummary {
+    pub index: usize,
+    /// "utxo" or "ethereum"
+    pub kind: String,
+    /// Coin name or EVM chain id
+    pub network: String,
+    /// Non-change destinations
+    pub recipients: Vec<String>,
+    /// Amount leaving the wallet, in satoshis or wei
+    pub amount: String,
+}
+
+lazy_static::lazy_static! {
+    static ref PENDING_BATCHES: Mutex<HashMap<String, oneshot::Sender<bool>>> = Mutex::new(HashMap::new());
+}
+
+/// Ask the user to approve a whole batch at once and wait for the answer
+///
+/// The device still confirms each transaction on screen; this replaces the
+/// per-request vault prompts with one summary of everything that will be signed.
+pub async fn request_approval(app: &tauri::AppHandle, batch_id: &str, items: &[BatchItemSummary]) -> Result<(), String> {
+    let (tx, rx) = oneshot::channel();
+    PENDING_BATCHES.lock().unwrap().insert(batch_id.to_string(), tx);
+
+    let payload = serde_json::json!({
+        "batchId": batch_id,
+        "count": items.len(),
+        "items": items,
+        "expiresInSecs": APPROVAL_TIMEOUT.as_secs(),
+    });
+    if let Err(e) = crate::commands::emit_or_queue_event(app, "batch:approval-requested", payload).await {
+        PENDING_BATCHES.lock().unwrap().remove(batch_id);
+        return Err(format!("Failed to request batch approval: {}", e));
+    }
+
+    let result = tokio::time::timeout(APPROVAL_TIMEOUT, rx).await;
+    PENDING_BATCHES.lock().unwrap().remove(batch_id);
+
+    match result {
+        Ok(Ok(true)) => Ok(()),
+        Ok(Ok(false)) => Err("Batch was rejected".to_string()),
+        Ok(Err(_)) => Err("Batch approval was cancelled".to_string()),
+        Err(_) => Err("Batch approval timed out".to_string()),
+    }
+}
+
+/// Deliver the user's decision for a pending batch
+pub fn respond(batch_id: &str, approved: bool) -> Result<(), String> {
+    let sender = PENDING_BATCHES
+        .lock()
+        .unwrap()
+        .remove(batch_id)
+        .ok_or_else(|| format!("No pending batch {}", batch_id))?;
+    sender
+        .send(approved)
+        .map_err(|_| format!("Batch {} is no longer waiting for approval", batch_id))
+}
+
+/// Let the UI follow a batch as each transaction is signed
+pub async fn emit_progress(app: &tauri::AppHandle, batch_id: &str, index: usize, total: usize, success: bool) {
+    let payload = serde_json::json!({
+        "batchId": batch_id,
+        "index": index,
+        "total": total,
+        "success": success,
+    });
+    if let Err(e) = crate::commands::emit_or_queue_event(app, "batch:progress", payload).await {
+        log::warn!("Failed to emit batch:progress: {}", e);
+    }
+}
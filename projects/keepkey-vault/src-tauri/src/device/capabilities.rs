@@ -0,0 +1,252 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use keepkey_rust::features::DeviceFeatures;
+use crate::commands::DeviceQueueManager;
+
+/// Seed backup schemes a recovery or initialization can use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BackupType {
+    Bip39,
+    Slip39,
+}
+
+impl Default for BackupType {
+    fn default() -> Self {
+        BackupType::Bip39
+    }
+}
+
+/// Recovery and initialization options the device supports
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecoveryOptions {
+    pub word_counts: Vec<u32>,
+    pub backup_types: Vec<BackupType>,
+    /// Scrambled-keyboard (cipher) entry on the host
+    pub character_cipher: bool,
+    /// Seed verification via dry-run recovery
+    pub dry_run: bool,
+}
+
+/// Structured capability map derived from device features
+#[derive(Debug, Clone, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceCapabilities {
+    pub device_id: String,
+    pub model: Option<String>,
+    pub firmware_version: String,
+    pub bootloader_mode: bool,
+    /// Chains the firmware can derive addresses and sign for
+    pub supported_chains: Vec<String>,
+    pub taproot: bool,
+    pub slip39: bool,
+    /// Whether passphrase protection can be enabled
+    pub passphrase: bool,
+    /// Whether passphrase protection is currently enabled
+    pub passphrase_enabled: bool,
+    pub max_label_length: usize,
+    /// Languages ApplySettings accepts
+    pub languages: Vec<String>,
+    /// Whether the auto-lock delay can be changed
+    pub auto_lock: bool,
+    #[schema(value_type = Object)]
+    pub recovery: RecoveryOptions,
+}
+
+/// Maximum device label length accepted by the firmware
+pub const MAX_LABEL_LENGTH: usize = 32;
+
+/// Languages KeepKey firmware ships
+pub const SUPPORTED_LANGUAGES: &[&str] = &["english"];
+
+/// Minimum firmware version the vault supports each chain on
+const CHAIN_MIN_FIRMWARE: &[(&str, &str)] = &[
+    ("bitcoin", "6.0.0"),
+    ("bitcoincash", "6.0.0"),
+    ("litecoin", "6.0.0"),
+    ("dogecoin", "6.0.0"),
+    ("dash", "6.0.0"),
+    ("ethereum", "6.0.0"),
+    ("ripple", "6.0.0"),
+    ("binance", "7.0.0"),
+    ("cosmos", "7.0.0"),
+    ("thorchain", "7.0.0"),
+    ("osmosis", "7.0.0"),
+    ("mayachain", "7.9.0"),
+];
+
+lazy_static::lazy_static! {
+    /// Last known capabilities per device, refreshed whenever features are read
+    static ref CAPABILITY_CACHE: Mutex<HashMap<String, DeviceCapabilities>> = Mutex::new(HashMap::new());
+}
+
+/// Parse a firmware version string like "7.10.0" or "v7.10.0"
+fn parse_version(version: &str) -> Option<semver::Version> {
+    semver::Version::parse(version.trim_start_matches('v')).ok()
+}
+
+/// Chains supported by a firmware version
+fn supported_chains(firmware_version: &str) -> Vec<String> {
+    let Some(version) = parse_version(firmware_version) else {
+        return Vec::new();
+    };
+
+    CHAIN_MIN_FIRMWARE.iter()
+        .filter(|(_, min)| parse_version(min).map(|min| version >= min).unwrap_or(false))
+        .map(|(chain, _)| chain.to_string())
+        .collect()
+}
+
+/// Map a BIP39 word count to ResetDevice strength in bits
+pub fn word_count_to_strength(word_count: u32) -> Option<u32> {
+    match word_count {
+        12 => Some(128),
+        18 => Some(192),
+        24 => Some(256),
+        _ => None,
+    }
+}
+
+/// Build the capability map from device features
+pub fn capabilities_from_features(device_id: &str, features: &DeviceFeatures) -> DeviceCapabilities {
+    // Nothing but firmware updates can run while in bootloader mode
+    let recovery = if features.bootloader_mode {
+        RecoveryOptions {
+            word_counts: Vec::new(),
+            backup_types: Vec::new(),
+            character_cipher: false,
+            dry_run: false,
+        }
+    } else {
+        RecoveryOptions {
+            word_counts: vec![12, 18, 24],
+            // KeepKey firmware has no SLIP-39 support yet
+            backup_types: vec![BackupType::Bip39],
+            character_cipher: true,
+            dry_run: true,
+        }
+    };
+
+    let supported_chains = if features.bootloader_mode {
+        Vec::new()
+    } else {
+        supported_chains(&features.version)
+    };
+
+    DeviceCapabilities {
+        device_id: device_id.to_string(),
+        model: features.model.clone(),
+        firmware_version: features.version.clone(),
+        bootloader_mode: features.bootloader_mode,
+        supported_chains,
+        // No released KeepKey firmware signs taproot inputs
+        taproot: false,
+        slip39: recovery.backup_types.contains(&BackupType::Slip39),
+        passphrase: !features.bootloader_mode,
+        passphrase_enabled: features.passphrase_protection,
+        max_label_length: MAX_LABEL_LENGTH,
+        languages: if features.bootloader_mode {
+            Vec::new()
+        } else {
+            SUPPORTED_LANGUAGES.iter().map(|l| l.to_string()).collect()
+        },
+        // Firmware that reports the delay also accepts changing it
+        auto_lock: !features.bootloader_mode && features.auto_lock_delay_ms.is_some(),
+        recovery,
+    }
+}
+
+/// Record capabilities for a device from freshly read features
+pub fn update_cached_capabilities(device_id: &str, features: &DeviceFeatures) -> DeviceCapabilities {
+    let capabilities = capabilities_from_features(device_id, features);
+    if let Ok(mut cache) = CAPABILITY_CACHE.lock() {
+        cache.insert(device_id.to_string(), capabilities.clone());
+    }
+    capabilities
+}
+
+/// Last known capabilities for a device, without talking to it
+pub fn cached_capabilities(device_id: &str) -> Option<DeviceCapabilities> {
+    CAPABILITY_CACHE.lock().ok()?.get(device_id).cloned()
+}
+
+/// Reject a chain the device's firmware is known not to support
+///
+/// Chains outside the capability table and devices whose capabilities
+/// haven't been read yet are let through.
+pub fn ensure_chain_supported(device_id: &str, chain: &str) -> Result<(), String> {
+    if !CHAIN_MIN_FIRMWARE.iter().any(|(known, _)| *known == chain) {
+        return Ok(());
+    }
+
+    match cached_capabilities(device_id) {
+        Some(caps) if !caps.supported_chains.iter().any(|c| c == chain) => Err(format!(
+            "{} is not supported by firmware {}", chain, caps.firmware_version
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Reject a label longer than the firmware accepts
+pub fn ensure_label_fits(label: &str) -> Result<(), String> {
+    if label.chars().count() > MAX_LABEL_LENGTH {
+        return Err(format!("Label must be at most {} characters", MAX_LABEL_LENGTH));
+    }
+    Ok(())
+}
+
+/// Check requested recovery/initialization options against device capabilities
+pub fn validate_recovery_options(
+    capabilities: &DeviceCapabilities,
+    word_count: u32,
+    backup_type: BackupType,
+) -> Result<(), String> {
+    if capabilities.bootloader_mode {
+        return Err("Device is in bootloader mode".to_string());
+    }
+
+    if !capabilities.recovery.backup_types.contains(&backup_type) {
+        return Err(format!("Backup type {:?} is not supported by firmware {}",
+            backup_type, capabilities.firmware_version));
+    }
+
+    if !capabilities.recovery.word_counts.contains(&word_count) {
+        return Err(format!("Invalid word count {}. Supported: {:?}",
+            word_count, capabilities.recovery.word_counts));
+    }
+
+    Ok(())
+}
+
+/// Fetch features from the device and derive its capabilities
+pub async fn fetch_capabilities(
+    device_id: &str,
+    queue_manager: &DeviceQueueManager,
+) -> Result<DeviceCapabilities, String> {
+    let queue_handle = crate::commands::get_or_create_device_queue(device_id, queue_manager).await?;
+
+    let raw_features = queue_handle.get_features()
+        .await
+        .map_err(|e| format!("Failed to get device features: {}", e))?;
+    let features = crate::commands::convert_features_to_device_features(raw_features);
+
+    Ok(update_cached_capabilities(device_id, &features))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_supported_chains_by_version() {
+        let old = supported_chains("6.7.0");
+        assert!(old.contains(&"ethereum".to_string()));
+        assert!(!old.contains(&"cosmos".to_string()));
+
+        let new = supported_chains("v7.10.0");
+        assert!(new.contains(&"mayachain".to_string()));
+        assert!(supported_chains("garbage").is_empty());
+    }
+}
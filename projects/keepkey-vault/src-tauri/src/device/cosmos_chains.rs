@@ -0,0 +1,172 @@
+use bech32::{FromBase32, ToBase32};
+use serde::{Deserialize, Serialize};
+
+/// Preference holding user-configured Cosmos SDK chains
+pub const COSMOS_CHAINS_PREFERENCE_KEY: &str = "cosmos_chains";
+
+const HARDENED: u32 = 0x80000000;
+
+/// A Cosmos SDK chain the vault can derive addresses for
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CosmosChain {
+    pub name: String,
+    pub symbol: String,
+    /// Bech32 address prefix, e.g. "akash"
+    pub hrp: String,
+    pub chain_id: String,
+    pub slip44: u32,
+    /// Has its own firmware message (cosmos, osmosis, thorchain, mayachain)
+    #[serde(default)]
+    pub native: bool,
+}
+
+impl CosmosChain {
+    /// Account 0 address path, m/44'/slip44'/0'/0/0
+    pub fn default_address_n(&self) -> Vec<u32> {
+        vec![44 | HARDENED, self.slip44 | HARDENED, HARDENED, 0, 0]
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct CosmosChainRegistry {
+    chains: Vec<CosmosChain>,
+}
+
+/// Chains bundled with the app
+fn bundled_chains() -> Vec<CosmosChain> {
+    serde_json::from_str::<CosmosChainRegistry>(include_str!("../../cosmos-chains.json"))
+        .map(|registry| registry.chains)
+        .unwrap_or_else(|e| {
+            log::error!("Failed to parse bundled cosmos-chains.json: {}", e);
+            Vec::new()
+        })
+}
+
+/// Chains added through configuration
+fn configured_chains() -> Vec<CosmosChain> {
+    crate::preferences::get_as::<Vec<CosmosChain>>(COSMOS_CHAINS_PREFERENCE_KEY).unwrap_or_default()
+}
+
+/// All known Cosmos chains: bundled ones first, then configured ones
+pub fn list_chains() -> Vec<CosmosChain> {
+    let mut chains = bundled_chains();
+    for chain in configured_chains() {
+        if !chains.iter().any(|c| c.name == chain.name || c.hrp == chain.hrp) {
+            chains.push(CosmosChain { native: false, ..chain });
+        }
+    }
+    chains
+}
+
+/// Look up a chain by name
+pub fn find_chain(name: &str) -> Option<CosmosChain> {
+    let name = name.to_lowercase();
+    list_chains().into_iter().find(|c| c.name == name)
+}
+
+/// Chain name for a bech32 prefix, falling back to the prefix itself
+pub fn chain_name_for_hrp(hrp: &str) -> String {
+    list_chains()
+        .into_iter()
+        .find(|c| c.hrp == hrp)
+        .map(|c| c.name)
+        .unwrap_or_else(|| hrp.to_string())
+}
+
+fn validate_chain(chain: &CosmosChain) -> Result<(), String> {
+    let is_slug = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit());
+    if !is_slug(&chain.name) {
+        return Err(format!("Invalid chain name '{}': use lowercase letters and digits", chain.name));
+    }
+    if !is_slug(&chain.hrp) {
+        return Err(format!("Invalid bech32 prefix '{}' for {}", chain.hrp, chain.name));
+    }
+    if chain.chain_id.trim().is_empty() {
+        return Err(format!("Missing chain ID for {}", chain.name));
+    }
+    if chain.slip44 >= HARDENED {
+        return Err(format!("Invalid slip44 {} for {}", chain.slip44, chain.name));
+    }
+    Ok(())
+}
+
+/// Replace the configured chains; bundled chains can't be redefined
+pub fn set_configured_chains(chains: Vec<CosmosChain>) -> Result<Vec<CosmosChain>, String> {
+    let bundled = bundled_chains();
+    let mut configured: Vec<CosmosChain> = Vec::new();
+
+    for chain in chains {
+        let chain = CosmosChain {
+            name: chain.name.trim().to_lowercase(),
+            hrp: chain.hrp.trim().to_lowercase(),
+            native: false,
+            ..chain
+        };
+        validate_chain(&chain)?;
+
+        if bundled.iter().any(|c| c.name == chain.name || c.hrp == chain.hrp) {
+            return Err(format!("{} is already a built-in chain", chain.name));
+        }
+        if configured.iter().any(|c| c.name == chain.name || c.hrp == chain.hrp) {
+            return Err(format!("Duplicate chain {}", chain.name));
+        }
+        configured.push(chain);
+    }
+
+    crate::preferences::set(COSMOS_CHAINS_PREFERENCE_KEY, serde_json::json!(configured))?;
+
+    Ok(list_chains())
+}
+
+/// Re-encode a bech32 address with another prefix (same key hash, different chain)
+pub fn convert_hrp(address: &str, hrp: &str) -> Result<String, String> {
+    let (_, data, variant) = bech32::decode(address)
+        .map_err(|e| format!("Invalid bech32 address {}: {}", address, e))?;
+    let bytes = Vec::<u8>::from_base32(&data)
+        .map_err(|e| format!("Invalid bech32 payload: {}", e))?;
+    bech32::encode(hrp, bytes.to_base32(), variant)
+        .map_err(|e| format!("Failed to encode {} address: {}", hrp, e))
+}
+
+/// Default frontload paths for configured (non-native) chains
+pub fn frontload_paths() -> Vec<crate::cache::frontload::DefaultPath> {
+    list_chains()
+        .into_iter()
+        .filter(|chain| !chain.native)
+        .map(|chain| crate::cache::frontload::DefaultPath {
+            id: format!("{}_account_0", chain.name),
+            note: format!("{} ({}) account 0", chain.name, chain.symbol),
+            blockchain: chain.name.clone(),
+            symbol: chain.symbol.clone(),
+            networks: vec![format!("cosmos:{}", chain.chain_id)],
+            script_type: chain.hrp.clone(),
+            address_n_list: chain.default_address_n(),
+            address_n_list_master: chain.default_address_n(),
+            curve: "secp256k1".to_string(),
+            show_display: false,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_hrp_round_trip() {
+        let cosmos = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+        let akash = convert_hrp(cosmos, "akash").unwrap();
+        assert_eq!(akash, "akash1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5jepelx");
+        assert_eq!(convert_hrp(&akash, "cosmos").unwrap(), cosmos);
+        assert!(convert_hrp("not-an-address", "akash").is_err());
+    }
+
+    #[test]
+    fn test_bundled_chains_are_native() {
+        let chains = bundled_chains();
+        assert_eq!(chains.len(), 4);
+        assert!(chains.iter().all(|c| c.native));
+        assert_eq!(chains[0].default_address_n(), vec![44 | HARDENED, 118 | HARDENED, HARDENED, 0, 0]);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Preference key holding custom firmware records (keyed by device id)
+const PREFERENCE_KEY: &str = "custom_firmware";
+
+/// How long a custom firmware challenge stays valid
+const CHALLENGE_TTL: Duration = Duration::from_secs(300);
+
+/// Phrase the user has to type before a custom image is flashed
+pub const CONFIRMATION_PHRASE: &str = "I understand this firmware is not official";
+
+/// Signed KeepKey images start with this magic, followed by a 256-byte header
+const IMAGE_MAGIC: &[u8] = b"KPKY";
+const IMAGE_HEADER_LEN: usize = 256;
+
+/// What the user is about to flash, with the warning they must acknowledge
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFirmwareChallenge {
+    pub token: String,
+    pub device_id: String,
+    pub file_name: String,
+    pub size: usize,
+    /// Hash of the image payload, comparable to release catalog hashes
+    pub sha256: String,
+    /// Release version when the hash matches an official build
+    pub known_release: Option<String>,
+    /// Whether the image has the signed-image header at all
+    pub has_header: bool,
+    pub warning: String,
+    pub confirmation_phrase: String,
+    pub expires_in_secs: u64,
+}
+
+/// Left in preferences once a non-official image has been flashed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomFirmwareRecord {
+    pub sha256: String,
+    pub file_name: String,
+    pub installed_at: i64,
+}
+
+#[derive(Debug)]
+struct PendingFlash {
+    device_id: String,
+    sha256: String,
+    issued_at: Instant,
+}
+
+lazy_static::lazy_static! {
+    static ref PENDING_FLASHES: Mutex<HashMap<String, PendingFlash>> = Mutex::new(HashMap::new());
+}
+
+/// Hash of the image as published in the release catalog (payload after the header)
+pub fn image_hash(image: &[u8]) -> (String, bool) {
+    let has_header = image.len() > IMAGE_HEADER_LEN && image.starts_with(IMAGE_MAGIC);
+    let payload = if has_header { &image[IMAGE_HEADER_LEN..] } else { image };
+    (hex::encode(Sha256::digest(payload)), has_header)
+}
+
+/// Inspect a custom image and issue a single-use challenge for flashing it
+pub fn issue_challenge(device_id: &str, file_name: &str, image: &[u8]) -> CustomFirmwareChallenge {
+    let (sha256, has_header) = image_hash(image);
+    let known_release = crate::device::releases::firmware_version_for_hash(&sha256);
+
+    let mut warning = String::from(
+        "This is NOT an official KeepKey firmware release. Unsigned or modified firmware can leak \
+         your keys, and the device will show an unofficial-firmware warning on every boot. Only \
+         continue if you built this image yourself or fully trust its source.",
+    );
+    if !has_header {
+        warning.push_str(" The file has no KeepKey image header and may not be firmware at all.");
+    }
+    if let Some(version) = &known_release {
+        warning = format!("This image matches official release {}; use the regular update instead.", version);
+    }
+
+    let token = uuid::Uuid::new_v4().to_string();
+    let mut pending = PENDING_FLASHES.lock().unwrap();
+    pending.retain(|_, p| p.issued_at.elapsed() < CHALLENGE_TTL);
+    pending.insert(token.clone(), PendingFlash {
+        device_id: device_id.to_string(),
+        sha256: sha256.clone(),
+        issued_at: Instant::now(),
+    });
+
+    CustomFirmwareChallenge {
+        token,
+        device_id: device_id.to_string(),
+        file_name: file_name.to_string(),
+        size: image.len(),
+        sha256,
+        known_release,
+        has_header,
+        warning,
+        confirmation_phrase: CONFIRMATION_PHRASE.to_string(),
+        expires_in_secs: CHALLENGE_TTL.as_secs(),
+    }
+}
+
+fn check(pending: &PendingFlash, device_id: &str, image: &[u8], typed_phrase: &str) -> Result<(), String> {
+    if pending.device_id != device_id {
+        return Err("Custom firmware confirmation was issued for a different device".to_string());
+    }
+    if pending.issued_at.elapsed() >= CHALLENGE_TTL {
+        return Err("Custom firmware confirmation expired, please start again".to_string());
+    }
+    if image_hash(image).0 != pending.sha256 {
+        return Err("The firmware file changed since it was inspected".to_string());
+    }
+    if typed_phrase.trim() != CONFIRMATION_PHRASE {
+        return Err("Typed confirmation does not match".to_string());
+    }
+    Ok(())
+}
+
+/// Consume a challenge; the token can't be reused whether or not it checks out
+pub fn redeem(device_id: &str, token: &str, image: &[u8], typed_phrase: &str) -> Result<(), String> {
+    let pending = PENDING_FLASHES
+        .lock()
+        .unwrap()
+        .remove(token)
+        .ok_or_else(|| "Unknown or already used custom firmware confirmation".to_string())?;
+
+    check(&pending, device_id, image, typed_phrase)
+}
+
+fn load_records() -> HashMap<String, CustomFirmwareRecord> {
+    crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default()
+}
+
+/// Custom firmware flashed onto a device, if its current firmware isn't official
+pub fn record_for(device_id: &str) -> Option<CustomFirmwareRecord> {
+    load_records().remove(device_id)
+}
+
+/// Remember that a device now runs a non-official image
+pub fn save_record(device_id: &str, record: CustomFirmwareRecord) -> Result<(), String> {
+    let mut records = load_records();
+    records.insert(device_id.to_string(), record);
+    crate::preferences::set(PREFERENCE_KEY, serde_json::json!(records))
+}
+
+/// Forget the record once an official release has been installed again
+pub fn clear_record(device_id: &str) -> Result<(), String> {
+    let mut records = load_records();
+    if records.remove(device_id).is_some() {
+        crate::preferences::set(PREFERENCE_KEY, serde_json::json!(records))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_image_hash_skips_header() {
+        let mut image = b"KPKY".to_vec();
+        image.resize(IMAGE_HEADER_LEN, 0);
+        image.extend_from_slice(b"payload");
+        let (hash, has_header) = image_hash(&image);
+        assert!(has_header);
+        assert_eq!(hash, hex::encode(Sha256::digest(b"payload")));
+        assert!(!image_hash(b"payload").1);
+    }
+
+    #[test]
+    fn test_check_requires_phrase_and_same_image() {
+        let pending = PendingFlash {
+            device_id: "dev1".to_string(),
+            sha256: image_hash(b"image").0,
+            issued_at: Instant::now(),
+        };
+        assert!(check(&pending, "dev1", b"image", CONFIRMATION_PHRASE).is_ok());
+        assert!(check(&pending, "dev1", b"image", "yes").is_err());
+        assert!(check(&pending, "dev1", b"other", CONFIRMATION_PHRASE).is_err());
+        assert!(check(&pending, "dev2", b"image", CONFIRMATION_PHRASE).is_err());
+    }
+}
@@ -0,0 +1,96 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use utoipa::ToSchema;
+
+use crate::cache::CacheManager;
+
+/// Devices that derive identical accounts, i.e. hold the same seed (and passphrase)
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateSeedGroup {
+    pub device_ids: Vec<String>,
+}
+
+/// Merge device pairs into groups of devices sharing a seed
+fn group_pairs(pairs: &[(String, String)]) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    for (a, b) in pairs {
+        let found: Vec<usize> = groups
+            .iter()
+            .enumerate()
+            .filter(|(_, g)| g.contains(a) || g.contains(b))
+            .map(|(i, _)| i)
+            .collect();
+
+        let mut merged = vec![a.clone(), b.clone()];
+        for i in found.into_iter().rev() {
+            merged.extend(groups.remove(i));
+        }
+        merged.sort();
+        merged.dedup();
+        groups.push(merged);
+    }
+    groups.sort();
+    groups
+}
+
+/// All groups of cached devices that share a seed
+pub async fn find_duplicates(cache: &CacheManager) -> Result<Vec<DuplicateSeedGroup>, String> {
+    let pairs = cache
+        .find_shared_accounts()
+        .await
+        .map_err(|e| format!("Failed to compare cached accounts: {}", e))?;
+    Ok(group_pairs(&pairs)
+        .into_iter()
+        .map(|device_ids| DuplicateSeedGroup { device_ids })
+        .collect())
+}
+
+/// Other devices holding the same seed as `device_id`
+pub fn shared_with(groups: &[DuplicateSeedGroup], device_id: &str) -> Vec<String> {
+    groups
+        .iter()
+        .find(|g| g.device_ids.iter().any(|id| id == device_id))
+        .map(|g| g.device_ids.iter().filter(|id| *id != device_id).cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Warn the frontend when a freshly cached device shares its seed with another
+pub async fn check_device(app: &AppHandle, device_id: &str) {
+    let Some(cache) = app
+        .try_state::<std::sync::Arc<once_cell::sync::OnceCell<std::sync::Arc<CacheManager>>>>()
+        .and_then(|cell| cell.get().cloned())
+    else {
+        return;
+    };
+
+    let groups = match find_duplicates(&cache).await {
+        Ok(groups) => groups,
+        Err(e) => {
+            log::warn!("Duplicate seed check failed for {}: {}", device_id, e);
+            return;
+        }
+    };
+    let others = shared_with(&groups, device_id);
+    if others.is_empty() {
+        return;
+    }
+
+    log::warn!("⚠️ Device {} shares its seed with {:?}", device_id, others);
+    let payload = serde_json::json!({ "deviceId": device_id, "sharesSeedWith": others });
+    if let Err(e) = crate::commands::emit_or_queue_event(app, "device:duplicate-seed", payload).await {
+        log::warn!("Failed to emit device:duplicate-seed: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_pairs() {
+        let pair = |a: &str, b: &str| (a.to_string(), b.to_string());
+        let groups = group_pairs(&[pair("a", "b"), pair("c", "d"), pair("b", "c"), pair("e", "f")]);
+        assert_eq!(groups, vec![vec!["a", "b", "c", "d"], vec!["e", "f"]]);
+    }
+}
@@ -0,0 +1,182 @@
+//! Structured device errors shared by Tauri commands and the REST API.
+//!
+//! Device failures reach us as free text from the transport, the queue and
+//! the firmware. `DeviceError::classify` maps that text to a stable code once,
+//! here, so frontends switch on `code` instead of matching message fragments.
+
+use std::fmt;
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::server::api::addresses::ErrorResponse;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DeviceErrorCode {
+    DeviceNotFound,
+    /// Claimed by another application
+    DeviceBusy,
+    PinRequired,
+    PinInvalid,
+    PassphraseRequired,
+    /// Rejected on the device screen
+    ActionCancelled,
+    Timeout,
+    /// USB/HID failure or a dead queue worker
+    TransportError,
+    /// Firmware or bootloader must be updated first
+    FirmwareTooOld,
+    NotInitialized,
+    InvalidRequest,
+    /// The firmware answered with a Failure message
+    DeviceFailure,
+    /// Nothing more specific matched; kept as the code REST clients already see
+    #[serde(rename = "DEVICE_ERROR")]
+    Unknown,
+}
+
+impl DeviceErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DeviceErrorCode::DeviceNotFound => "DEVICE_NOT_FOUND",
+            DeviceErrorCode::DeviceBusy => "DEVICE_BUSY",
+            DeviceErrorCode::PinRequired => "PIN_REQUIRED",
+            DeviceErrorCode::PinInvalid => "PIN_INVALID",
+            DeviceErrorCode::PassphraseRequired => "PASSPHRASE_REQUIRED",
+            DeviceErrorCode::ActionCancelled => "ACTION_CANCELLED",
+            DeviceErrorCode::Timeout => "TIMEOUT",
+            DeviceErrorCode::TransportError => "TRANSPORT_ERROR",
+            DeviceErrorCode::FirmwareTooOld => "FIRMWARE_TOO_OLD",
+            DeviceErrorCode::NotInitialized => "NOT_INITIALIZED",
+            DeviceErrorCode::InvalidRequest => "INVALID_REQUEST",
+            DeviceErrorCode::DeviceFailure => "DEVICE_FAILURE",
+            DeviceErrorCode::Unknown => "DEVICE_ERROR",
+        }
+    }
+
+    pub fn http_status(&self) -> StatusCode {
+        match self {
+            DeviceErrorCode::DeviceNotFound => StatusCode::NOT_FOUND,
+            DeviceErrorCode::DeviceBusy => StatusCode::CONFLICT,
+            DeviceErrorCode::PinRequired | DeviceErrorCode::PassphraseRequired => StatusCode::LOCKED,
+            DeviceErrorCode::PinInvalid => StatusCode::UNAUTHORIZED,
+            DeviceErrorCode::ActionCancelled => StatusCode::CONFLICT,
+            DeviceErrorCode::Timeout => StatusCode::GATEWAY_TIMEOUT,
+            DeviceErrorCode::TransportError => StatusCode::BAD_GATEWAY,
+            DeviceErrorCode::FirmwareTooOld | DeviceErrorCode::NotInitialized => StatusCode::PRECONDITION_FAILED,
+            DeviceErrorCode::InvalidRequest => StatusCode::BAD_REQUEST,
+            DeviceErrorCode::DeviceFailure | DeviceErrorCode::Unknown => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Message fragments (lowercase) for each code, checked in order
+const PATTERNS: &[(DeviceErrorCode, &[&str])] = &[
+    (DeviceErrorCode::DeviceNotFound, &["device not found", "not connected", "no keepkey devices found"]),
+    (DeviceErrorCode::DeviceBusy, &["already in use", "device access failed", "already claimed"]),
+    (DeviceErrorCode::ActionCancelled, &["action cancelled", "cancelled by user", "canceled by user"]),
+    (DeviceErrorCode::PinInvalid, &["pin invalid", "invalid pin", "incorrect pin", "pin mismatch"]),
+    (DeviceErrorCode::PinRequired, &["needs pin unlock", "pin required", "pinmatrixrequest"]),
+    (DeviceErrorCode::PassphraseRequired, &["passphrase required", "passphraserequest"]),
+    (DeviceErrorCode::Timeout, &["timed out", "timeout"]),
+    (DeviceErrorCode::FirmwareTooOld, &["not supported by firmware", "firmware update", "bootloader update", "unsupported by firmware"]),
+    (DeviceErrorCode::NotInitialized, &["not initialized", "until initialization"]),
+    (DeviceErrorCode::TransportError, &["worker channel closed", "worker unavailable", "hid api", "hid fallback", "usb", "transport"]),
+    (DeviceErrorCode::DeviceFailure, &["failure:"]),
+];
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct DeviceError {
+    pub code: DeviceErrorCode,
+    pub message: String,
+}
+
+impl DeviceError {
+    pub fn new(code: DeviceErrorCode, message: impl Into<String>) -> Self {
+        Self { code, message: message.into() }
+    }
+
+    /// Code a free-text device error
+    pub fn classify(message: impl Into<String>) -> Self {
+        let message = message.into();
+        let lower = message.to_lowercase();
+        let code = PATTERNS
+            .iter()
+            .find(|(_, fragments)| fragments.iter().any(|f| lower.contains(f)))
+            .map_or(DeviceErrorCode::Unknown, |(code, _)| *code);
+        Self { code, message }
+    }
+
+    /// Code the text of a firmware Failure message
+    pub fn from_failure(message: impl Into<String>) -> Self {
+        let mut error = Self::classify(message);
+        if error.code == DeviceErrorCode::Unknown {
+            error.code = DeviceErrorCode::DeviceFailure;
+        }
+        error
+    }
+}
+
+impl fmt::Display for DeviceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for DeviceError {}
+
+impl From<String> for DeviceError {
+    fn from(message: String) -> Self {
+        Self::classify(message)
+    }
+}
+
+impl From<&str> for DeviceError {
+    fn from(message: &str) -> Self {
+        Self::classify(message)
+    }
+}
+
+impl From<anyhow::Error> for DeviceError {
+    fn from(error: anyhow::Error) -> Self {
+        Self::classify(error.to_string())
+    }
+}
+
+impl IntoResponse for DeviceError {
+    fn into_response(self) -> Response {
+        (self.code.http_status(), Json(ErrorResponse::new(self.message, self.code.as_str()))).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify() {
+        let code = |message: &str| DeviceError::classify(message).code;
+        assert_eq!(code("🔒 KeepKey Device Already In Use\n\nThe KeepKey device ..."), DeviceErrorCode::DeviceBusy);
+        assert_eq!(code("Device not found: 343737340F4736331F003B00"), DeviceErrorCode::DeviceNotFound);
+        assert_eq!(code("Device operation timed out"), DeviceErrorCode::Timeout);
+        assert_eq!(code("Failure: Action cancelled by user"), DeviceErrorCode::ActionCancelled);
+        assert_eq!(code("Failure: PIN invalid"), DeviceErrorCode::PinInvalid);
+        assert_eq!(code("Device cannot process requests until firmware update is completed."), DeviceErrorCode::FirmwareTooOld);
+        assert_eq!(code("Device worker channel closed"), DeviceErrorCode::TransportError);
+        assert_eq!(code("Failure: Invalid signature"), DeviceErrorCode::DeviceFailure);
+        assert_eq!(code("Something else"), DeviceErrorCode::Unknown);
+        assert_eq!(DeviceError::from_failure("Invalid signature").code, DeviceErrorCode::DeviceFailure);
+    }
+
+    #[test]
+    fn test_codes_serialize_as_str() {
+        for code in PATTERNS.iter().map(|(code, _)| code).chain([&DeviceErrorCode::Unknown]) {
+            assert_eq!(serde_json::to_value(code).unwrap(), serde_json::json!(code.as_str()));
+        }
+        let error = serde_json::to_value(DeviceError::new(DeviceErrorCode::PinRequired, "Unlock the device")).unwrap();
+        assert_eq!(error, serde_json::json!({ "code": "PIN_REQUIRED", "message": "Unlock the device" }));
+    }
+}
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OnceCell;
+use crate::commands::{DeviceRequest, DeviceResponse};
+
+type InFlightResult = Result<DeviceResponse, String>;
+
+lazy_static::lazy_static! {
+    /// Device roundtrips currently in progress, keyed by device and request contents
+    static ref IN_FLIGHT: Mutex<HashMap<String, Arc<OnceCell<InFlightResult>>>> = Mutex::new(HashMap::new());
+}
+
+/// Key identifying identical address/pubkey requests to the same device
+///
+/// Only deterministic derivations are coalesced (never entropy or signing),
+/// and requests that show something on the device screen are skipped since
+/// each one needs its own confirmation.
+pub fn coalesce_key(device_id: &str, request: &DeviceRequest) -> Option<String> {
+    let value = serde_json::to_value(request).ok()?;
+    let (variant, fields) = value.as_object()?.iter().next()?;
+
+    let is_derivation = variant.ends_with("GetAddress") || variant == "GetPublicKey" || variant == "GetXpub";
+    if !is_derivation {
+        return None;
+    }
+
+    let shows_on_device = fields.get("show_display")
+        .and_then(|show| show.as_bool())
+        .unwrap_or(false);
+    if shows_on_device {
+        return None;
+    }
+
+    Some(format!("{}:{}", device_id, value))
+}
+
+/// A shared response re-addressed to another waiter's request ID
+fn for_request(response: &DeviceResponse, request_id: &str) -> DeviceResponse {
+    let Ok(mut value) = serde_json::to_value(response) else {
+        return response.clone();
+    };
+    // Externally tagged: {"Variant": {"request_id": ..., ...}}
+    let fields = value.as_object_mut()
+        .and_then(|variant| variant.values_mut().next())
+        .and_then(|fields| fields.as_object_mut());
+    if let Some(fields) = fields {
+        fields.insert("request_id".to_string(), serde_json::Value::from(request_id));
+    }
+    serde_json::from_value(value).unwrap_or_else(|_| response.clone())
+}
+
+/// Run `run` unless an identical request is already in flight, in which case
+/// wait for and share its result, re-addressed to `request_id`
+pub async fn coalesce<F, Fut>(device_id: &str, request: &DeviceRequest, request_id: &str, run: F) -> InFlightResult
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = InFlightResult>,
+{
+    let Some(key) = coalesce_key(device_id, request) else {
+        return run().await;
+    };
+
+    let cell = {
+        let mut in_flight = IN_FLIGHT.lock().map_err(|_| "Failed to lock in-flight requests".to_string())?;
+        in_flight.entry(key.clone()).or_insert_with(|| Arc::new(OnceCell::new())).clone()
+    };
+
+    let mut ran = false;
+    let result = cell.get_or_init(|| {
+        ran = true;
+        run()
+    }).await.clone();
+
+    if ran {
+        // Later requests go back through the cache instead of reusing this result
+        if let Ok(mut in_flight) = IN_FLIGHT.lock() {
+            if in_flight.get(&key).map(|c| Arc::ptr_eq(c, &cell)).unwrap_or(false) {
+                in_flight.remove(&key);
+            }
+        }
+    } else {
+        log::debug!("Shared in-flight device response for {}", device_id);
+        return result.map(|response| for_request(&response, request_id));
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coalesce_key_skips_on_device_display() {
+        let request = |show_display| DeviceRequest::GetAddress {
+            path: "m/44'/0'/0'/0/0".to_string(),
+            coin_name: "Bitcoin".to_string(),
+            script_type: None,
+            show_display,
+        };
+
+        assert!(coalesce_key("dev", &request(Some(true))).is_none());
+        assert!(coalesce_key("dev", &request(None)).is_some());
+        assert_eq!(coalesce_key("dev", &request(None)), coalesce_key("dev", &request(None)));
+        assert_ne!(coalesce_key("dev", &request(None)), coalesce_key("other", &request(None)));
+        assert!(coalesce_key("dev", &DeviceRequest::GetEntropy { size: 32 }).is_none());
+    }
+
+    #[test]
+    fn test_for_request_rewrites_request_id() {
+        let response = DeviceResponse::Address {
+            request_id: "leader".to_string(),
+            device_id: "dev".to_string(),
+            path: "m/44'/0'/0'/0/0".to_string(),
+            address: "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2".to_string(),
+            success: true,
+            error: None,
+        };
+        match for_request(&response, "waiter") {
+            DeviceResponse::Address { request_id, address, .. } => {
+                assert_eq!(request_id, "waiter");
+                assert_eq!(address, "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2");
+            }
+            other => panic!("unexpected response {:?}", other),
+        }
+    }
+}
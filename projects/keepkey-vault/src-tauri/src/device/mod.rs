@@ -2,4 +2,24 @@ pub mod queue;
 pub mod updates;
 pub mod address_operations;
 pub mod system_operations;
-pub mod transaction_operations; 
\ No newline at end of file
+pub mod transaction_operations; 
+pub mod recovery_input;
+pub mod capabilities;
+pub mod releases;
+pub mod preflight;
+pub mod inflight;
+pub mod cosmos_chains;
+pub mod watchdog;
+pub mod reenumeration;
+pub mod ownership_proof;
+pub mod passphrase_policy;
+pub mod wipe_interlock;
+pub mod testnet;
+pub mod batch_signing;
+pub mod custom_firmware;
+pub mod duplicate_seeds;
+pub mod settings_verify;
+pub mod queue_metrics;
+pub mod errors;
+pub mod pin_lockout;
+pub mod settings;
@@ -0,0 +1,187 @@
+use base64::Engine;
+use keepkey_rust::device_queue::DeviceQueueHandle;
+use keepkey_rust::messages::{self, Message};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Bundle format version, bumped on incompatible changes
+pub const PROOF_BUNDLE_VERSION: u32 = 1;
+
+/// Hardened m/44' — its parent fingerprint is the master key fingerprint
+const FINGERPRINT_PATH: [u32; 1] = [0x8000002C];
+
+/// Chains that support signed ownership proofs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProofChain {
+    Bitcoin,
+    Ethereum,
+}
+
+/// An address to prove ownership of
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofTarget {
+    pub chain: ProofChain,
+    /// Derivation path, e.g. "m/84'/0'/0'/0/0"
+    pub derivation_path: String,
+    /// Bitcoin script type ("p2pkh", "p2sh-p2wpkh", "p2wpkh"); defaults to p2pkh
+    #[serde(default)]
+    pub script_type: Option<String>,
+}
+
+/// A signed statement for a single address
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressProof {
+    pub chain: ProofChain,
+    pub address: String,
+    pub derivation_path: String,
+    pub script_type: Option<String>,
+    /// Base64 for Bitcoin (BIP-137), 0x-hex for Ethereum (personal_sign)
+    pub signature: String,
+}
+
+/// Proofs for a set of addresses, all over the same message
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipProofBundle {
+    pub version: u32,
+    pub message: String,
+    pub device_id: String,
+    /// Master key fingerprint (hex), identifies the seed without revealing keys
+    pub master_fingerprint: String,
+    pub created_at: i64,
+    pub proofs: Vec<AddressProof>,
+}
+
+fn script_type_to_proto(script_type: Option<&str>) -> Result<i32, String> {
+    match script_type.unwrap_or("p2pkh") {
+        "p2pkh" => Ok(messages::InputScriptType::Spendaddress as i32),
+        "p2sh-p2wpkh" => Ok(messages::InputScriptType::Spendp2shwitness as i32),
+        "p2wpkh" => Ok(messages::InputScriptType::Spendwitness as i32),
+        other => Err(format!("Unsupported script type for message signing: {}", other)),
+    }
+}
+
+fn failure_text(response: &Message) -> String {
+    match response {
+        Message::Failure(f) => f.message.clone().unwrap_or_else(|| "Device returned failure".to_string()),
+        other => format!("Unexpected response: {:?}", other.message_type()),
+    }
+}
+
+/// Master key fingerprint of the seed on the device
+async fn master_fingerprint(queue_handle: &DeviceQueueHandle) -> Result<String, String> {
+    let msg = messages::GetPublicKey {
+        address_n: FINGERPRINT_PATH.to_vec(),
+        ecdsa_curve_name: Some("secp256k1".to_string()),
+        show_display: Some(false),
+        ..Default::default()
+    };
+
+    match queue_handle.send_raw(msg.into(), false).await.map_err(|e| e.to_string())? {
+        Message::PublicKey(pk) => Ok(format!("{:08x}", pk.node.fingerprint)),
+        other => Err(failure_text(&other)),
+    }
+}
+
+async fn sign_bitcoin(
+    queue_handle: &DeviceQueueHandle,
+    target: &ProofTarget,
+    address_n: Vec<u32>,
+    message: &str,
+) -> Result<AddressProof, String> {
+    let msg = messages::SignMessage {
+        address_n,
+        message: message.as_bytes().to_vec(),
+        coin_name: Some("Bitcoin".to_string()),
+        script_type: Some(script_type_to_proto(target.script_type.as_deref())?),
+    };
+
+    match queue_handle.send_raw(msg.into(), false).await.map_err(|e| e.to_string())? {
+        Message::MessageSignature(sig) => Ok(AddressProof {
+            chain: target.chain,
+            address: sig.address.ok_or("No address in signature response")?,
+            derivation_path: target.derivation_path.clone(),
+            script_type: Some(target.script_type.clone().unwrap_or_else(|| "p2pkh".to_string())),
+            signature: base64::engine::general_purpose::STANDARD
+                .encode(sig.signature.ok_or("No signature in response")?),
+        }),
+        other => Err(failure_text(&other)),
+    }
+}
+
+async fn sign_ethereum(
+    queue_handle: &DeviceQueueHandle,
+    target: &ProofTarget,
+    address_n: Vec<u32>,
+    message: &str,
+) -> Result<AddressProof, String> {
+    let msg = messages::EthereumSignMessage {
+        address_n,
+        message: crate::eth_signatures::personal_message_bytes(message),
+    };
+
+    match queue_handle.send_raw(msg.into(), false).await.map_err(|e| e.to_string())? {
+        Message::EthereumMessageSignature(sig) => Ok(AddressProof {
+            chain: target.chain,
+            address: format!("0x{}", hex::encode(sig.address.ok_or("No address in signature response")?)),
+            derivation_path: target.derivation_path.clone(),
+            script_type: None,
+            signature: format!("0x{}", hex::encode(sig.signature.ok_or("No signature in response")?)),
+        }),
+        other => Err(failure_text(&other)),
+    }
+}
+
+/// Sign `message` with every target address and collect the results into a bundle.
+/// Each signature needs confirmation on the device.
+pub async fn build_bundle(
+    queue_handle: &DeviceQueueHandle,
+    message: &str,
+    targets: &[ProofTarget],
+) -> Result<OwnershipProofBundle, String> {
+    if message.trim().is_empty() {
+        return Err("Proof message is required".to_string());
+    }
+    if targets.is_empty() {
+        return Err("At least one address is required".to_string());
+    }
+
+    let master_fingerprint = master_fingerprint(queue_handle).await?;
+
+    let mut proofs = Vec::with_capacity(targets.len());
+    for target in targets {
+        let address_n = crate::commands::parse_derivation_path(&target.derivation_path)?;
+        let proof = match target.chain {
+            ProofChain::Bitcoin => sign_bitcoin(queue_handle, target, address_n, message).await,
+            ProofChain::Ethereum => sign_ethereum(queue_handle, target, address_n, message).await,
+        }
+        .map_err(|e| format!("Failed to sign proof for {}: {}", target.derivation_path, e))?;
+        proofs.push(proof);
+    }
+
+    log::info!("Built ownership proof bundle with {} address(es) on {}", proofs.len(), queue_handle.device_id());
+
+    Ok(OwnershipProofBundle {
+        version: PROOF_BUNDLE_VERSION,
+        message: message.to_string(),
+        device_id: queue_handle.device_id().to_string(),
+        master_fingerprint,
+        created_at: chrono::Utc::now().timestamp(),
+        proofs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_script_type_to_proto() {
+        assert_eq!(script_type_to_proto(None), Ok(messages::InputScriptType::Spendaddress as i32));
+        assert_eq!(script_type_to_proto(Some("p2wpkh")), Ok(messages::InputScriptType::Spendwitness as i32));
+        assert!(script_type_to_proto(Some("p2tr")).is_err());
+    }
+}
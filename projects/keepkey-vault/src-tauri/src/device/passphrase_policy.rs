@@ -0,0 +1,118 @@
+use std::collections::HashMap;
+use std::time::Duration;
+use keepkey_rust::device_queue::PassphraseCachePolicy;
+use serde::{Deserialize, Serialize};
+use crate::commands::DeviceQueueManager;
+
+/// Preference key holding passphrase cache policies
+const PREFERENCE_KEY: &str = "passphrase_cache_policy";
+
+/// How often time-limited passphrase caches are checked for expiry
+const ENFORCE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Persisted passphrase cache policies
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PassphrasePolicyPreferences {
+    /// Applies to devices without their own policy
+    #[serde(default)]
+    pub default: PassphraseCachePolicy,
+    #[serde(default)]
+    pub devices: HashMap<String, PassphraseCachePolicy>,
+}
+
+impl PassphrasePolicyPreferences {
+    pub fn policy_for(&self, device_id: &str) -> PassphraseCachePolicy {
+        self.devices.get(device_id).copied().unwrap_or(self.default)
+    }
+}
+
+/// Load passphrase cache policies from preferences
+pub fn get_preferences() -> PassphrasePolicyPreferences {
+    crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default()
+}
+
+fn validate(policy: PassphraseCachePolicy) -> Result<(), String> {
+    match policy {
+        PassphraseCachePolicy::Minutes { minutes: 0 } => {
+            Err("Cache duration must be at least one minute; use \"never\" to disable caching".to_string())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Set the policy for one device, or the default when `device_id` is None.
+/// Passing no policy for a device makes it follow the default again.
+pub fn set_policy(device_id: Option<&str>, policy: Option<PassphraseCachePolicy>) -> Result<PassphrasePolicyPreferences, String> {
+    if let Some(policy) = policy {
+        validate(policy)?;
+    }
+
+    let mut preferences = get_preferences();
+    match device_id {
+        Some(device_id) => match policy {
+            Some(policy) => { preferences.devices.insert(device_id.to_string(), policy); }
+            None => { preferences.devices.remove(device_id); }
+        },
+        None => preferences.default = policy.unwrap_or_default(),
+    }
+
+    crate::preferences::set(PREFERENCE_KEY, serde_json::json!(preferences))?;
+
+    for device in keepkey_rust::features::list_connected_devices() {
+        keepkey_rust::device_queue::set_passphrase_policy(&device.unique_id, Some(preferences.policy_for(&device.unique_id)));
+    }
+
+    log::info!("🔐 Passphrase cache policy for {} set to {:?}", device_id.unwrap_or("all devices"), policy);
+    Ok(preferences)
+}
+
+/// Push the configured policy for a device into the queue layer
+pub fn apply_to_device(device_id: &str) {
+    let policy = get_preferences().policy_for(device_id);
+    keepkey_rust::device_queue::set_passphrase_policy(device_id, Some(policy));
+}
+
+/// Periodically clear passphrase sessions whose cache period has run out
+pub fn spawn_policy_enforcer(queue_manager: DeviceQueueManager) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(ENFORCE_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let handles: Vec<_> = {
+                let manager = queue_manager.lock().await;
+                manager
+                    .iter()
+                    .filter(|(device_id, _)| !crate::commands::is_device_in_pin_flow(device_id))
+                    .map(|(_, handle)| handle.clone())
+                    .collect()
+            };
+
+            for handle in handles {
+                apply_to_device(handle.device_id());
+                if let Err(e) = handle.enforce_passphrase_policy().await {
+                    log::warn!("Failed to enforce passphrase policy on {}: {}", handle.device_id(), e);
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_for_falls_back_to_default() {
+        let mut preferences = PassphrasePolicyPreferences {
+            default: PassphraseCachePolicy::Minutes { minutes: 10 },
+            devices: HashMap::new(),
+        };
+        preferences.devices.insert("abc".to_string(), PassphraseCachePolicy::Never);
+
+        assert_eq!(preferences.policy_for("abc"), PassphraseCachePolicy::Never);
+        assert_eq!(preferences.policy_for("xyz"), PassphraseCachePolicy::Minutes { minutes: 10 });
+        assert!(validate(PassphraseCachePolicy::Minutes { minutes: 0 }).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+//! PIN failure backoff tracking.
+//!
+//! After each wrong PIN the firmware makes the next attempt wait roughly
+//! 2^failures - 1 seconds. The device never tells the host its failure
+//! count, so consecutive rejected PINs are counted here per device and the
+//! wait is estimated from them. While a wait runs, `pin:lockout-countdown`
+//! is emitted every second so the UI can say "try again in 8 minutes".
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use once_cell::sync::OnceCell;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+#[derive(Debug, Clone, Copy)]
+struct Failures {
+    count: u32,
+    last_failure_at: i64,
+}
+
+lazy_static::lazy_static! {
+    /// Device -> consecutive rejected PINs since the last accepted one
+    static ref FAILURES: Mutex<HashMap<String, Failures>> = Mutex::new(HashMap::new());
+}
+
+/// Estimated backoff for a device after rejected PINs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PinLockout {
+    pub device_id: String,
+    pub failed_attempts: u32,
+    /// Full wait imposed after the last failure
+    pub wait_secs: u64,
+    /// Seconds left before the device accepts another attempt
+    pub remaining_secs: u64,
+    pub retry_at: i64,
+}
+
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Seconds the firmware waits after `failures` consecutive wrong PINs
+fn backoff_secs(failures: u32) -> u64 {
+    match failures {
+        0 => 0,
+        n => (1u64 << n.min(31)) - 1,
+    }
+}
+
+fn lockout(device_id: &str, failures: Failures, now: i64) -> PinLockout {
+    let wait_secs = backoff_secs(failures.count);
+    let retry_at = failures.last_failure_at + wait_secs as i64;
+    PinLockout {
+        device_id: device_id.to_string(),
+        failed_attempts: failures.count,
+        wait_secs,
+        remaining_secs: (retry_at - now).max(0) as u64,
+        retry_at,
+    }
+}
+
+/// "45 seconds", "8 minutes", "3 hours"
+pub fn describe_wait(secs: u64) -> String {
+    let (amount, unit) = match secs {
+        0..=89 => (secs, "second"),
+        90..=5399 => ((secs + 30) / 60, "minute"),
+        _ => ((secs + 1800) / 3600, "hour"),
+    };
+    format!("{} {}{}", amount, unit, if amount == 1 { "" } else { "s" })
+}
+
+/// Current backoff for a device, if it has rejected PINs outstanding
+pub fn status(device_id: &str) -> Option<PinLockout> {
+    let failures = *FAILURES.lock().ok()?.get(device_id)?;
+    Some(lockout(device_id, failures, chrono::Utc::now().timestamp()))
+}
+
+/// Count a rejected PIN and start announcing the wait
+pub fn record_failure(device_id: &str) -> PinLockout {
+    let now = chrono::Utc::now().timestamp();
+    let failures = {
+        let mut all = FAILURES.lock().unwrap();
+        let failures = all.entry(device_id.to_string()).or_insert(Failures { count: 0, last_failure_at: now });
+        failures.count += 1;
+        failures.last_failure_at = now;
+        *failures
+    };
+    let current = lockout(device_id, failures, now);
+    log::warn!(
+        "🔐 Device {} rejected PIN ({} in a row), next attempt in ~{}",
+        device_id, current.failed_attempts, describe_wait(current.wait_secs),
+    );
+    if current.remaining_secs > 0 {
+        spawn_countdown(device_id.to_string(), failures.count);
+    }
+    current
+}
+
+/// Forget failures once the device accepts a PIN
+pub fn record_success(device_id: &str) {
+    if let Ok(mut all) = FAILURES.lock() {
+        all.remove(device_id);
+    }
+}
+
+/// Emit the remaining wait every second until it runs out or another attempt is made
+fn spawn_countdown(device_id: String, attempt: u32) {
+    let Some(app) = APP_HANDLE.get().cloned() else {
+        return;
+    };
+    tauri::async_runtime::spawn(async move {
+        let mut ticker = tokio::time::interval(Duration::from_secs(1));
+        loop {
+            ticker.tick().await;
+            let Some(current) = status(&device_id).filter(|l| l.failed_attempts == attempt) else {
+                return;
+            };
+            let done = current.remaining_secs == 0;
+            if let Err(e) = crate::commands::emit_or_queue_event(&app, "pin:lockout-countdown", serde_json::json!(current)).await {
+                log::warn!("Failed to emit pin:lockout-countdown: {}", e);
+            }
+            if done {
+                return;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff() {
+        assert_eq!(backoff_secs(0), 0);
+        assert_eq!(backoff_secs(1), 1);
+        assert_eq!(backoff_secs(9), 511);
+        assert_eq!(backoff_secs(40), (1u64 << 31) - 1);
+
+        let current = lockout("kk", Failures { count: 3, last_failure_at: 100 }, 103);
+        assert_eq!(current.wait_secs, 7);
+        assert_eq!(current.remaining_secs, 4);
+        assert_eq!(current.retry_at, 107);
+        assert_eq!(lockout("kk", Failures { count: 3, last_failure_at: 100 }, 200).remaining_secs, 0);
+    }
+
+    #[test]
+    fn test_describe_wait() {
+        assert_eq!(describe_wait(1), "1 second");
+        assert_eq!(describe_wait(45), "45 seconds");
+        assert_eq!(describe_wait(511), "9 minutes");
+        assert_eq!(describe_wait(16383), "5 hours");
+    }
+}
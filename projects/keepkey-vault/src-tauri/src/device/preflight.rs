@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use crate::commands::DeviceQueueManager;
+
+/// Disconnects within this window count against USB stability
+const DISCONNECT_WINDOW_SECS: i64 = 5 * 60;
+
+/// More disconnects than this in the window fails the stability check
+const MAX_RECENT_DISCONNECTS: usize = 2;
+
+/// How long a confirm token stays valid after a passing pre-flight
+const CONFIRM_TOKEN_TTL_SECS: i64 = 2 * 60;
+
+const FEATURES_TIMEOUT: Duration = Duration::from_secs(5);
+
+lazy_static::lazy_static! {
+    /// Recent disconnect timestamps per device
+    static ref DISCONNECTS: Mutex<HashMap<String, Vec<i64>>> = Mutex::new(HashMap::new());
+    /// Outstanding confirm tokens: token -> (device_id, target_version, expires_at)
+    static ref CONFIRM_TOKENS: Mutex<HashMap<String, (String, String, i64)>> = Mutex::new(HashMap::new());
+}
+
+/// Result of a single pre-flight check
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreflightCheck {
+    pub name: String,
+    pub passed: bool,
+    pub detail: String,
+}
+
+impl PreflightCheck {
+    fn new(name: &str, result: Result<String, String>) -> Self {
+        let (passed, detail) = match result {
+            Ok(detail) => (true, detail),
+            Err(detail) => (false, detail),
+        };
+        Self { name: name.to_string(), passed, detail }
+    }
+}
+
+/// Outcome of the bootloader update pre-flight
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BootloaderPreflight {
+    pub device_id: String,
+    pub target_version: String,
+    pub passed: bool,
+    pub checks: Vec<PreflightCheck>,
+    /// Token to pass to update_device_bootloader (only when all checks pass)
+    pub confirm_token: Option<String>,
+    pub token_expires_at: Option<i64>,
+}
+
+/// Record that a device was disconnected
+pub fn record_disconnect(device_id: &str) {
+    let now = chrono::Utc::now().timestamp();
+    if let Ok(mut disconnects) = DISCONNECTS.lock() {
+        let entries = disconnects.entry(device_id.to_string()).or_default();
+        entries.retain(|t| now - t <= DISCONNECT_WINDOW_SECS);
+        entries.push(now);
+    }
+}
+
+/// Number of disconnects for a device within the stability window
+pub fn recent_disconnect_count(device_id: &str) -> usize {
+    let now = chrono::Utc::now().timestamp();
+    DISCONNECTS.lock()
+        .ok()
+        .and_then(|disconnects| disconnects.get(device_id)
+            .map(|entries| entries.iter().filter(|t| now - **t <= DISCONNECT_WINDOW_SECS).count()))
+        .unwrap_or(0)
+}
+
+/// Check a bootloader image against the hash published in the release catalog
+pub fn verify_bootloader_image(target_version: &str, image: &[u8]) -> Result<String, String> {
+    let manifest = crate::device::releases::current_manifest();
+    let target = crate::device::releases::normalize_version(target_version);
+
+    let expected = [&manifest.latest.bootloader, &manifest.beta.bootloader]
+        .into_iter()
+        .find(|entry| crate::device::releases::normalize_version(&entry.version) == target)
+        .map(|entry| entry.hash.to_lowercase())
+        .ok_or_else(|| format!("No catalog hash for bootloader {}", target))?;
+
+    let actual = hex::encode(Sha256::digest(image));
+    if actual != expected {
+        return Err(format!("Bootloader image hash mismatch: expected {}, got {}", expected, actual));
+    }
+
+    Ok(actual)
+}
+
+fn check_usb_stability(device_id: &str) -> Result<String, String> {
+    let count = recent_disconnect_count(device_id);
+    if count > MAX_RECENT_DISCONNECTS {
+        return Err(format!(
+            "Device disconnected {} times in the last {} minutes. Try another cable or USB port.",
+            count, DISCONNECT_WINDOW_SECS / 60
+        ));
+    }
+    Ok(format!("{} recent disconnect(s)", count))
+}
+
+async fn check_bootloader_mode(device_id: &str, queue_manager: &DeviceQueueManager) -> Result<String, String> {
+    let queue_handle = crate::commands::get_or_create_device_queue(device_id, queue_manager).await?;
+
+    match tokio::time::timeout(FEATURES_TIMEOUT, queue_handle.get_features()).await {
+        Ok(Ok(features)) if features.bootloader_mode.unwrap_or(false) => {
+            Ok("Device is in bootloader mode".to_string())
+        }
+        Ok(Ok(_)) => Err("Device is not in bootloader mode. Hold the button while reconnecting.".to_string()),
+        Ok(Err(e)) => {
+            let error_str = e.to_string();
+            // Very old bootloaders don't understand GetFeatures at all
+            if error_str.contains("Unknown message") || error_str.contains("Unexpected response") {
+                Ok("Legacy bootloader detected".to_string())
+            } else {
+                Err(format!("Failed to get device features: {}", error_str))
+            }
+        }
+        Err(_) => Err("Timeout while fetching device features".to_string()),
+    }
+}
+
+/// Run every check required before flashing a bootloader and issue a confirm token if they pass
+pub async fn run_bootloader_preflight(
+    device_id: &str,
+    target_version: &str,
+    queue_manager: &DeviceQueueManager,
+) -> BootloaderPreflight {
+    let image_check = crate::device::updates::load_bootloader_image(target_version)
+        .and_then(|image| verify_bootloader_image(target_version, &image))
+        .map(|hash| format!("sha256 {}", hash));
+
+    let checks = vec![
+        PreflightCheck::new("imageHash", image_check),
+        PreflightCheck::new("bootloaderMode", check_bootloader_mode(device_id, queue_manager).await),
+        PreflightCheck::new("usbStability", check_usb_stability(device_id)),
+    ];
+
+    let passed = checks.iter().all(|c| c.passed);
+    let (confirm_token, token_expires_at) = if passed {
+        let token = uuid::Uuid::new_v4().to_string();
+        let expires_at = chrono::Utc::now().timestamp() + CONFIRM_TOKEN_TTL_SECS;
+        if let Ok(mut tokens) = CONFIRM_TOKENS.lock() {
+            tokens.insert(token.clone(), (device_id.to_string(), target_version.to_string(), expires_at));
+        }
+        (Some(token), Some(expires_at))
+    } else {
+        (None, None)
+    };
+
+    log::info!("🛫 Bootloader pre-flight for {} -> {}: {}", device_id, target_version,
+        if passed { "passed" } else { "failed" });
+
+    BootloaderPreflight {
+        device_id: device_id.to_string(),
+        target_version: target_version.to_string(),
+        passed,
+        checks,
+        confirm_token,
+        token_expires_at,
+    }
+}
+
+/// Redeem a confirm token for a bootloader update (single use)
+pub fn consume_confirm_token(device_id: &str, target_version: &str, token: &str) -> Result<(), String> {
+    let now = chrono::Utc::now().timestamp();
+    let mut tokens = CONFIRM_TOKENS.lock()
+        .map_err(|_| "Failed to lock confirm tokens".to_string())?;
+    tokens.retain(|_, (_, _, expires_at)| *expires_at > now);
+
+    match tokens.remove(token) {
+        Some((token_device, token_version, _)) if token_device == device_id && token_version == target_version => Ok(()),
+        Some(_) => Err("Confirm token was issued for a different device or version".to_string()),
+        None => Err("Missing or expired confirm token. Run the bootloader pre-flight first.".to_string()),
+    }
+}
+
+/// Re-check the conditions that can change between pre-flight and flashing
+pub fn recheck_before_flash(device_id: &str, target_version: &str, image: &[u8]) -> Result<(), String> {
+    verify_bootloader_image(target_version, image)?;
+    check_usb_stability(device_id)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_confirm_token_is_single_use_and_bound() {
+        let token = "test-token".to_string();
+        let expires_at = chrono::Utc::now().timestamp() + 60;
+        CONFIRM_TOKENS.lock().unwrap().insert(token.clone(), ("dev".to_string(), "2.1.4".to_string(), expires_at));
+
+        assert!(consume_confirm_token("dev", "2.1.4", &token).is_ok());
+        assert!(consume_confirm_token("dev", "2.1.4", &token).is_err());
+
+        CONFIRM_TOKENS.lock().unwrap().insert(token.clone(), ("dev".to_string(), "2.1.4".to_string(), expires_at));
+        assert!(consume_confirm_token("other", "2.1.4", &token).is_err());
+    }
+
+    #[test]
+    fn test_usb_stability() {
+        assert!(check_usb_stability("stable-device").is_ok());
+        for _ in 0..=MAX_RECENT_DISCONNECTS {
+            record_disconnect("flaky-device");
+        }
+        assert!(check_usb_stability("flaky-device").is_err());
+    }
+}
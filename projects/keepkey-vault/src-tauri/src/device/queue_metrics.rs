@@ -0,0 +1,110 @@
+//! Per-operation device queue latency.
+//!
+//! Each queue handle keeps rolling latencies per operation type (GetFeatures,
+//! GetAddress, SignTx, ...). Comparing a slow GetFeatures with a slow SignTx
+//! tells a bad cable or hub apart from firmware doing real work.
+
+use std::fmt::Write;
+use keepkey_rust::device_queue::OperationLatency;
+use serde::Serialize;
+
+use crate::commands::DeviceQueueManager;
+
+/// Per-operation latencies and queue depth of one device's worker
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueMetrics {
+    pub device_id: String,
+    pub in_flight: u32,
+    pub operations: Vec<OperationLatency>,
+}
+
+/// Metrics for one device, or every device with a queue
+pub async fn snapshot(queue_manager: &DeviceQueueManager, device_id: Option<&str>) -> Vec<QueueMetrics> {
+    let manager = queue_manager.lock().await;
+    let mut metrics: Vec<QueueMetrics> = manager
+        .iter()
+        .filter(|(id, _)| device_id.map_or(true, |wanted| wanted == id.as_str()))
+        .map(|(id, handle)| QueueMetrics {
+            device_id: id.clone(),
+            in_flight: handle.health().in_flight,
+            operations: handle.operation_latencies(),
+        })
+        .collect();
+    metrics.sort_by(|a, b| a.device_id.cmp(&b.device_id));
+    metrics
+}
+
+fn escape_label(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+/// Render queue metrics in the Prometheus text exposition format
+pub fn render_prometheus(metrics: &[QueueMetrics]) -> String {
+    let mut out = String::new();
+
+    out.push_str("# HELP keepkey_device_queue_in_flight Commands sent to the device worker and not yet answered\n");
+    out.push_str("# TYPE keepkey_device_queue_in_flight gauge\n");
+    for device in metrics {
+        let _ = writeln!(out, "keepkey_device_queue_in_flight{{device_id=\"{}\"}} {}", escape_label(&device.device_id), device.in_flight);
+    }
+
+    out.push_str("# HELP keepkey_device_operation_latency_ms Rolling device operation latency, queued to answered\n");
+    out.push_str("# TYPE keepkey_device_operation_latency_ms summary\n");
+    for device in metrics {
+        let device_id = escape_label(&device.device_id);
+        for op in &device.operations {
+            let operation = escape_label(&op.operation);
+            for (quantile, value) in [("0.5", op.p50_ms), ("0.9", op.p90_ms), ("0.99", op.p99_ms)] {
+                let _ = writeln!(
+                    out,
+                    "keepkey_device_operation_latency_ms{{device_id=\"{}\",operation=\"{}\",quantile=\"{}\"}} {}",
+                    device_id, operation, quantile, value,
+                );
+            }
+            let _ = writeln!(out, "keepkey_device_operation_latency_ms_count{{device_id=\"{}\",operation=\"{}\"}} {}", device_id, operation, op.count);
+        }
+    }
+
+    out.push_str("# HELP keepkey_device_operation_failures_total Device operations that failed or timed out\n");
+    out.push_str("# TYPE keepkey_device_operation_failures_total counter\n");
+    for device in metrics {
+        let device_id = escape_label(&device.device_id);
+        for op in &device.operations {
+            let _ = writeln!(
+                out,
+                "keepkey_device_operation_failures_total{{device_id=\"{}\",operation=\"{}\"}} {}",
+                device_id, escape_label(&op.operation), op.failures,
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let metrics = vec![QueueMetrics {
+            device_id: "343737340F4736331F003B00".to_string(),
+            in_flight: 1,
+            operations: vec![OperationLatency {
+                operation: "SignTx".to_string(),
+                count: 4,
+                failures: 1,
+                p50_ms: 120,
+                p90_ms: 300,
+                p99_ms: 310,
+                max_ms: 310,
+            }],
+        }];
+        let text = render_prometheus(&metrics);
+        assert!(text.contains("keepkey_device_queue_in_flight{device_id=\"343737340F4736331F003B00\"} 1\n"));
+        assert!(text.contains("operation=\"SignTx\",quantile=\"0.9\"} 300\n"));
+        assert!(text.contains("keepkey_device_operation_latency_ms_count{device_id=\"343737340F4736331F003B00\",operation=\"SignTx\"} 4\n"));
+        assert!(text.contains("keepkey_device_operation_failures_total{device_id=\"343737340F4736331F003B00\",operation=\"SignTx\"} 1\n"));
+        assert_eq!(escape_label("a\"b"), "a\\\"b");
+    }
+}
@@ -0,0 +1,32 @@
+//! Recovery character entry.
+//!
+//! Recovery always uses the scrambled ("cipher") keyboard shown on the device:
+//! the user types the letter at the position the device displays, so neither
+//! the webview nor the backend ever sees the real seed letters. The device
+//! auto-completes each word once its unique prefix has been entered.
+
+/// BIP39 English words are uniquely identified by their first four letters,
+/// after which the device auto-completes the word
+pub const UNIQUE_PREFIX_LEN: usize = 4;
+
+/// Validate and normalize a single typed character (ASCII a-z only)
+pub fn normalize_character(ch: &str) -> Result<String, String> {
+    let mut chars = ch.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Ok(c.to_ascii_lowercase().to_string()),
+        _ => Err("Invalid character. Must be a single letter a-z".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_character() {
+        assert_eq!(normalize_character("A").unwrap(), "a");
+        assert!(normalize_character("é").is_err());
+        assert!(normalize_character("ab").is_err());
+        assert!(normalize_character("").is_err());
+    }
+}
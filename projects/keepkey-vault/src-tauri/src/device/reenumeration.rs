@@ -0,0 +1,101 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use keepkey_rust::friendly_usb::FriendlyUsbDevice;
+use tauri::{AppHandle, Emitter};
+use crate::commands::DeviceQueueManager;
+
+lazy_static::lazy_static! {
+    /// USB serial number last seen for each device ID this session
+    static ref SERIAL_BY_DEVICE_ID: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
+fn usable_serial(device: &FriendlyUsbDevice) -> Option<&str> {
+    device.serial_number.as_deref().map(str::trim).filter(|s| !s.is_empty())
+}
+
+/// Remember a device's serial and return other IDs it was previously seen under
+///
+/// IDs that are still connected are never reported, so two devices that
+/// happen to share a serial don't retire each other's workers.
+pub fn record_and_find_stale_ids(device: &FriendlyUsbDevice, connected: &[FriendlyUsbDevice]) -> Vec<String> {
+    let Some(serial) = usable_serial(device) else {
+        return Vec::new();
+    };
+    let Ok(mut serials) = SERIAL_BY_DEVICE_ID.lock() else {
+        return Vec::new();
+    };
+
+    let stale: Vec<String> = serials
+        .iter()
+        .filter(|(id, known_serial)| {
+            known_serial.as_str() == serial
+                && id.as_str() != device.unique_id
+                && !connected.iter().any(|d| &d.unique_id == *id)
+        })
+        .map(|(id, _)| id.clone())
+        .collect();
+
+    for id in &stale {
+        serials.remove(id);
+    }
+    serials.insert(device.unique_id.clone(), serial.to_string());
+
+    stale
+}
+
+/// Retire workers left behind when a device re-enumerated under a new ID,
+/// and keep its cached data attributed to the old ID
+pub async fn retire_stale_workers(
+    app: &AppHandle,
+    device: &FriendlyUsbDevice,
+    connected: &[FriendlyUsbDevice],
+    queue_manager: &DeviceQueueManager,
+) {
+    for stale_id in record_and_find_stale_ids(device, connected) {
+        log::info!("🔁 Device {} re-enumerated as {} (same serial) - retiring stale worker", stale_id, device.unique_id);
+
+        let stale_handle = queue_manager.lock().await.remove(&stale_id);
+        if let Some(handle) = stale_handle {
+            let _ = tokio::time::timeout(Duration::from_secs(2), handle.shutdown()).await;
+            handle.abort_worker();
+        }
+
+        if let Err(e) = crate::commands::persist_device_alias(app, &device.unique_id, &stale_id, "serial-match").await {
+            log::warn!("Failed to alias {} to {}: {}", device.unique_id, stale_id, e);
+        }
+
+        let _ = app.emit("device:id-changed", serde_json::json!({
+            "oldId": stale_id,
+            "newId": device.unique_id,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(id: &str, serial: &str) -> FriendlyUsbDevice {
+        FriendlyUsbDevice::new(id.to_string(), 0x2b24, 0x0002, None, None, Some(serial.to_string()))
+    }
+
+    #[test]
+    fn test_stale_ids_match_by_serial() {
+        let old = device("reenum-old", "REENUM-SERIAL");
+        let new = device("reenum-new", "REENUM-SERIAL");
+        let other = device("reenum-other", "OTHER-SERIAL");
+
+        assert!(record_and_find_stale_ids(&old, &[old.clone()]).is_empty());
+        assert!(record_and_find_stale_ids(&other, &[old.clone(), other.clone()]).is_empty());
+
+        // Still connected under the old ID: not stale
+        assert!(record_and_find_stale_ids(&new, &[old.clone(), new.clone()]).is_empty());
+
+        let renamed = device("reenum-new-2", "REENUM-SERIAL");
+        let stale = record_and_find_stale_ids(&renamed, &[renamed.clone()]);
+        assert_eq!(stale.len(), 2);
+        assert!(stale.contains(&"reenum-old".to_string()));
+        assert!(stale.contains(&"reenum-new".to_string()));
+    }
+}
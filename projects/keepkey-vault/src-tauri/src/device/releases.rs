@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+use once_cell::sync::Lazy;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+/// Release manifest shipped with the app alongside the firmware binaries
+const BUNDLED_MANIFEST: &str = include_str!("../../firmware/releases.json");
+
+/// Default location of the published release manifest
+const DEFAULT_MANIFEST_URL: &str = "https://raw.githubusercontent.com/keepkey/keepkey-desktop/master/firmware/releases.json";
+
+/// Preference key overriding the remote manifest location
+const MANIFEST_URL_PREFERENCE_KEY: &str = "firmware_manifest_url";
+
+/// How long a fetched remote manifest is used before refreshing
+const REMOTE_MANIFEST_TTL_SECS: i64 = 6 * 60 * 60;
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A single firmware or bootloader image in the manifest
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReleaseImage {
+    pub version: String,
+    pub url: String,
+    pub hash: String,
+    /// Minimum bootloader version this firmware image requires
+    #[serde(default)]
+    pub required_bootloader: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseChannel {
+    pub firmware: ReleaseImage,
+    pub bootloader: ReleaseImage,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KnownHashes {
+    /// Bootloader hash -> version
+    #[serde(default)]
+    pub bootloader: HashMap<String, String>,
+    /// Firmware hash -> version
+    #[serde(default)]
+    pub firmware: HashMap<String, String>,
+}
+
+/// Parsed releases.json
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReleaseManifest {
+    pub latest: ReleaseChannel,
+    pub beta: ReleaseChannel,
+    #[serde(default)]
+    pub hashes: KnownHashes,
+    /// Release notes keyed by version
+    #[serde(default)]
+    pub changelogs: HashMap<String, String>,
+}
+
+/// A firmware release in the catalog
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FirmwareRelease {
+    pub version: String,
+    pub hashes: Vec<String>,
+    pub changelog: Option<String>,
+    pub release_notes_url: String,
+    pub required_bootloader: Option<String>,
+}
+
+/// A bootloader release in the catalog
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BootloaderRelease {
+    pub version: String,
+    pub hashes: Vec<String>,
+}
+
+/// Firmware and bootloader release catalog
+#[derive(Debug, Clone, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FirmwareCatalog {
+    /// "bundled" or "remote"
+    pub source: String,
+    pub fetched_at: Option<i64>,
+    pub latest_firmware: String,
+    pub latest_bootloader: String,
+    pub beta_firmware: String,
+    pub firmware: Vec<FirmwareRelease>,
+    pub bootloaders: Vec<BootloaderRelease>,
+}
+
+struct RemoteManifest {
+    manifest: ReleaseManifest,
+    fetched_at: i64,
+}
+
+static BUNDLED: Lazy<ReleaseManifest> = Lazy::new(|| {
+    serde_json::from_str(BUNDLED_MANIFEST).expect("bundled firmware/releases.json is invalid")
+});
+
+static REMOTE: Lazy<RwLock<Option<RemoteManifest>>> = Lazy::new(|| RwLock::new(None));
+
+/// Strip a leading "v" so versions compare and display consistently
+pub fn normalize_version(version: &str) -> String {
+    version.trim().trim_start_matches('v').to_string()
+}
+
+fn parse_version(version: &str) -> Option<Version> {
+    Version::parse(&normalize_version(version)).ok()
+}
+
+/// The manifest currently in effect: the remote one when fetched, else the bundled one
+pub fn current_manifest() -> ReleaseManifest {
+    REMOTE.read()
+        .ok()
+        .and_then(|remote| remote.as_ref().map(|r| r.manifest.clone()))
+        .unwrap_or_else(|| BUNDLED.clone())
+}
+
+/// Latest released firmware version (without the "v" prefix)
+pub fn latest_firmware_version() -> String {
+    normalize_version(&current_manifest().latest.firmware.version)
+}
+
+/// Latest released bootloader version (without the "v" prefix)
+pub fn latest_bootloader_version() -> String {
+    normalize_version(&current_manifest().latest.bootloader.version)
+}
+
+/// Whether `current` is older than `latest`
+pub fn is_older(current: &str, latest: &str) -> bool {
+    match (parse_version(current), parse_version(latest)) {
+        (Some(current), Some(latest)) => current < latest,
+        _ => normalize_version(current) != normalize_version(latest),
+    }
+}
+
+/// Firmware version a hash belongs to, if it's a known release
+pub fn firmware_version_for_hash(hash: &str) -> Option<String> {
+    let manifest = current_manifest();
+    let hash = hash.to_lowercase();
+    manifest.hashes.firmware.get(&hash)
+        .cloned()
+        .or_else(|| [&manifest.latest.firmware, &manifest.beta.firmware].iter()
+            .find(|image| image.hash == hash)
+            .map(|image| image.version.clone()))
+        .map(|v| normalize_version(&v))
+}
+
+/// Bootloader version a hash belongs to, if it's a known release
+pub fn bootloader_version_for_hash(hash: &str) -> Option<String> {
+    let manifest = current_manifest();
+    let hash = hash.to_lowercase();
+    manifest.hashes.bootloader.get(&hash)
+        .cloned()
+        .or_else(|| [&manifest.latest.bootloader, &manifest.beta.bootloader].iter()
+            .find(|image| image.hash == hash)
+            .map(|image| image.version.clone()))
+        .map(|v| normalize_version(&v))
+}
+
+/// Group a hash -> version map into version -> hashes, newest first
+fn group_by_version(hashes: &HashMap<String, String>, extra: &[&ReleaseImage]) -> Vec<(String, Vec<String>)> {
+    let mut by_version: HashMap<String, Vec<String>> = HashMap::new();
+    for (hash, version) in hashes {
+        by_version.entry(normalize_version(version)).or_default().push(hash.clone());
+    }
+    for image in extra {
+        let hashes = by_version.entry(normalize_version(&image.version)).or_default();
+        if !hashes.contains(&image.hash) {
+            hashes.push(image.hash.clone());
+        }
+    }
+
+    let mut versions: Vec<(String, Vec<String>)> = by_version.into_iter()
+        .map(|(version, mut hashes)| {
+            hashes.sort();
+            (version, hashes)
+        })
+        .collect();
+    versions.sort_by(|(a, _), (b, _)| parse_version(b).cmp(&parse_version(a)));
+    versions
+}
+
+/// Build the catalog served to clients
+pub fn build_catalog(manifest: &ReleaseManifest, source: &str, fetched_at: Option<i64>) -> FirmwareCatalog {
+    let firmware = group_by_version(&manifest.hashes.firmware, &[&manifest.latest.firmware, &manifest.beta.firmware])
+        .into_iter()
+        .map(|(version, hashes)| {
+            let required_bootloader = [&manifest.latest.firmware, &manifest.beta.firmware].iter()
+                .find(|image| normalize_version(&image.version) == version)
+                .and_then(|image| image.required_bootloader.as_deref())
+                .map(normalize_version);
+            let changelog = manifest.changelogs.get(&version)
+                .or_else(|| manifest.changelogs.get(&format!("v{}", version)))
+                .cloned();
+
+            FirmwareRelease {
+                release_notes_url: format!("https://github.com/keepkey/keepkey-firmware/releases/tag/v{}", version),
+                version,
+                hashes,
+                changelog,
+                required_bootloader,
+            }
+        })
+        .collect();
+
+    let bootloaders = group_by_version(&manifest.hashes.bootloader, &[&manifest.latest.bootloader, &manifest.beta.bootloader])
+        .into_iter()
+        .map(|(version, hashes)| BootloaderRelease { version, hashes })
+        .collect();
+
+    FirmwareCatalog {
+        source: source.to_string(),
+        fetched_at,
+        latest_firmware: normalize_version(&manifest.latest.firmware.version),
+        latest_bootloader: normalize_version(&manifest.latest.bootloader.version),
+        beta_firmware: normalize_version(&manifest.beta.firmware.version),
+        firmware,
+        bootloaders,
+    }
+}
+
+/// The catalog for the manifest currently in effect
+pub fn get_catalog() -> FirmwareCatalog {
+    if let Ok(remote) = REMOTE.read() {
+        if let Some(remote) = remote.as_ref() {
+            return build_catalog(&remote.manifest, "remote", Some(remote.fetched_at));
+        }
+    }
+    build_catalog(&BUNDLED, "bundled", None)
+}
+
+/// Reject a remote manifest that disagrees with the bundled one about known hashes
+fn validate_remote_manifest(remote: &ReleaseManifest) -> Result<(), String> {
+    let checks = [
+        ("firmware", &remote.hashes.firmware, &BUNDLED.hashes.firmware),
+        ("bootloader", &remote.hashes.bootloader, &BUNDLED.hashes.bootloader),
+    ];
+
+    for (kind, remote_hashes, bundled_hashes) in checks {
+        for (hash, version) in remote_hashes {
+            let version = normalize_version(version);
+            let known_version = bundled_hashes.values().any(|v| normalize_version(v) == version);
+            if known_version && !bundled_hashes.contains_key(hash) {
+                return Err(format!("Remote manifest lists unknown hash {} for {} {}", hash, kind, version));
+            }
+        }
+    }
+
+    for image in [&remote.latest.firmware, &remote.latest.bootloader] {
+        if parse_version(&image.version).is_none() {
+            return Err(format!("Remote manifest has invalid version {}", image.version));
+        }
+    }
+
+    Ok(())
+}
+
+fn manifest_url() -> String {
+    crate::preferences::get_as::<String>(MANIFEST_URL_PREFERENCE_KEY)
+        .unwrap_or_else(|| DEFAULT_MANIFEST_URL.to_string())
+}
+
+/// Fetch the published manifest and use it in place of the bundled one
+pub async fn refresh_remote_manifest() -> Result<FirmwareCatalog, String> {
+    let url = manifest_url();
+    log::info!("📦 Refreshing firmware release manifest from {}", url);
+
+    let client = reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let manifest: ReleaseManifest = client.get(&url)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to fetch release manifest: {}", e))?
+        .error_for_status()
+        .map_err(|e| format!("Failed to fetch release manifest: {}", e))?
+        .json()
+        .await
+        .map_err(|e| format!("Invalid release manifest: {}", e))?;
+
+    validate_remote_manifest(&manifest)?;
+
+    let fetched_at = chrono::Utc::now().timestamp();
+    let catalog = build_catalog(&manifest, "remote", Some(fetched_at));
+
+    if let Ok(mut remote) = REMOTE.write() {
+        *remote = Some(RemoteManifest { manifest, fetched_at });
+    }
+
+    log::info!("📦 Firmware catalog updated: latest firmware {}, bootloader {}",
+        catalog.latest_firmware, catalog.latest_bootloader);
+    Ok(catalog)
+}
+
+/// Refresh the remote manifest if it's missing or stale, falling back to what we have
+pub async fn get_catalog_refreshed(force: bool) -> FirmwareCatalog {
+    let stale = REMOTE.read()
+        .map(|remote| match remote.as_ref() {
+            Some(r) => chrono::Utc::now().timestamp() - r.fetched_at > REMOTE_MANIFEST_TTL_SECS,
+            None => true,
+        })
+        .unwrap_or(true);
+
+    if force || stale {
+        match refresh_remote_manifest().await {
+            Ok(catalog) => return catalog,
+            Err(e) => log::warn!("⚠️ Using cached firmware catalog: {}", e),
+        }
+    }
+
+    get_catalog()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bundled_catalog() {
+        let catalog = build_catalog(&BUNDLED, "bundled", None);
+        assert_eq!(catalog.latest_firmware, normalize_version(&BUNDLED.latest.firmware.version));
+        assert_eq!(catalog.firmware.first().map(|r| r.version.clone()), Some(catalog.latest_firmware.clone()));
+        assert!(firmware_version_for_hash(&BUNDLED.latest.firmware.hash).is_some());
+    }
+
+    #[test]
+    fn test_is_older() {
+        assert!(is_older("7.9.3", "7.10.0"));
+        assert!(!is_older("v7.10.0", "7.10.0"));
+        assert!(is_older("1.0.3", "v2.1.4"));
+    }
+}
@@ -0,0 +1,132 @@
+//! Single-property device settings.
+//!
+//! ApplySettings takes every property at once. These change one property,
+//! check it against the device's capabilities first and go through the same
+//! read-back verification as the full request, so each change is audited on
+//! its own.
+
+use keepkey_rust::messages::{ApplySettings, Message};
+
+use crate::commands::DeviceQueueManager;
+use crate::device::capabilities::DeviceCapabilities;
+use crate::device::settings_verify::SettingsSnapshot;
+
+/// Shortest auto-lock delay the vault will set
+pub const MIN_AUTO_LOCK_DELAY_MS: u32 = 10_000;
+
+/// Longest auto-lock delay the vault will set (one week)
+pub const MAX_AUTO_LOCK_DELAY_MS: u32 = 7 * 24 * 60 * 60 * 1000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeviceSetting {
+    Language(String),
+    AutoLockDelayMs(u32),
+    Passphrase(bool),
+}
+
+impl DeviceSetting {
+    fn message(&self) -> ApplySettings {
+        match self {
+            DeviceSetting::Language(language) => ApplySettings { language: Some(language.clone()), ..Default::default() },
+            DeviceSetting::AutoLockDelayMs(delay_ms) => ApplySettings { auto_lock_delay_ms: Some(*delay_ms), ..Default::default() },
+            DeviceSetting::Passphrase(enabled) => ApplySettings { use_passphrase: Some(*enabled), ..Default::default() },
+        }
+    }
+}
+
+/// Reject a setting the device can't take
+fn check(capabilities: &DeviceCapabilities, setting: &DeviceSetting) -> Result<(), String> {
+    if capabilities.bootloader_mode {
+        return Err("Device is in bootloader mode".to_string());
+    }
+    match setting {
+        DeviceSetting::Language(language) => {
+            if !capabilities.languages.iter().any(|l| l == language) {
+                return Err(format!(
+                    "Language {} is not supported by firmware {} (supported: {})",
+                    language, capabilities.firmware_version, capabilities.languages.join(", "),
+                ));
+            }
+        }
+        DeviceSetting::AutoLockDelayMs(delay_ms) => {
+            if !capabilities.auto_lock {
+                return Err(format!("Auto-lock delay is not supported by firmware {}", capabilities.firmware_version));
+            }
+            if !(MIN_AUTO_LOCK_DELAY_MS..=MAX_AUTO_LOCK_DELAY_MS).contains(delay_ms) {
+                return Err(format!(
+                    "Auto-lock delay must be between {}s and {}s",
+                    MIN_AUTO_LOCK_DELAY_MS / 1000, MAX_AUTO_LOCK_DELAY_MS / 1000,
+                ));
+            }
+        }
+        DeviceSetting::Passphrase(_) => {
+            if !capabilities.passphrase {
+                return Err(format!("Passphrase protection is not supported by firmware {}", capabilities.firmware_version));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply one setting and return the settings read back from the device
+pub async fn apply(device_id: &str, queue_manager: &DeviceQueueManager, setting: DeviceSetting) -> Result<SettingsSnapshot, String> {
+    let capabilities = match crate::device::capabilities::cached_capabilities(device_id) {
+        Some(capabilities) => capabilities,
+        None => crate::device::capabilities::fetch_capabilities(device_id, queue_manager).await?,
+    };
+    check(&capabilities, &setting)?;
+
+    let queue_handle = crate::commands::get_or_create_device_queue(device_id, queue_manager).await?;
+    let before = crate::device::settings_verify::snapshot(&queue_handle).await.ok();
+    let requested = setting.message();
+    log::info!("⚙️ Applying {:?} on {}", setting, device_id);
+
+    match queue_handle.send_raw(requested.clone().into(), true).await {
+        Ok(Message::Success(_)) => {
+            crate::device::settings_verify::verify_applied(&queue_handle, device_id, &requested, before).await
+        }
+        Ok(Message::Failure(failure)) => Err(format!("Failure: {}", failure.message.unwrap_or_default())),
+        Ok(other) => Err(format!("Unexpected response to ApplySettings: {:?}", other.message_type())),
+        Err(e) => Err(format!("Failed to apply settings: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::device::capabilities::RecoveryOptions;
+
+    fn capabilities(auto_lock: bool) -> DeviceCapabilities {
+        DeviceCapabilities {
+            device_id: "kk".to_string(),
+            model: None,
+            firmware_version: "7.10.0".to_string(),
+            bootloader_mode: false,
+            supported_chains: Vec::new(),
+            taproot: false,
+            slip39: false,
+            passphrase: true,
+            passphrase_enabled: false,
+            max_label_length: 32,
+            languages: vec!["english".to_string()],
+            auto_lock,
+            recovery: RecoveryOptions {
+                word_counts: Vec::new(),
+                backup_types: Vec::new(),
+                character_cipher: false,
+                dry_run: false,
+            },
+        }
+    }
+
+    #[test]
+    fn test_check() {
+        let caps = capabilities(true);
+        assert!(check(&caps, &DeviceSetting::Language("english".to_string())).is_ok());
+        assert!(check(&caps, &DeviceSetting::Language("klingon".to_string())).is_err());
+        assert!(check(&caps, &DeviceSetting::AutoLockDelayMs(600_000)).is_ok());
+        assert!(check(&caps, &DeviceSetting::AutoLockDelayMs(1_000)).is_err());
+        assert!(check(&caps, &DeviceSetting::Passphrase(true)).is_ok());
+        assert!(check(&capabilities(false), &DeviceSetting::AutoLockDelayMs(600_000)).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! Read-back verification for ApplySettings.
+//!
+//! A Success ack only means the device accepted the message; features are
+//! read again afterwards and compared with what was requested before the
+//! change is reported as applied. Each changed property gets its own
+//! activity entry with the before/after values.
+
+use keepkey_rust::device_queue::DeviceQueueHandle;
+use keepkey_rust::messages::{ApplySettings, Features};
+use serde::Serialize;
+
+use crate::activity::ActivityKind;
+
+/// The device settings ApplySettings can change
+#[derive(Debug, Clone, Default, PartialEq, Serialize, utoipa::ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsSnapshot {
+    pub label: Option<String>,
+    pub language: Option<String>,
+    pub passphrase_protection: Option<bool>,
+    pub auto_lock_delay_ms: Option<u32>,
+}
+
+impl From<&Features> for SettingsSnapshot {
+    fn from(features: &Features) -> Self {
+        Self {
+            label: features.label.clone(),
+            language: features.language.clone(),
+            passphrase_protection: features.passphrase_protection,
+            auto_lock_delay_ms: features.auto_lock_delay_ms,
+        }
+    }
+}
+
+/// Current settings, read fresh from the device
+pub async fn snapshot(queue_handle: &DeviceQueueHandle) -> Result<SettingsSnapshot, String> {
+    queue_handle
+        .get_features()
+        .await
+        .map(|features| SettingsSnapshot::from(&features))
+        .map_err(|e| format!("Failed to read device features: {}", e))
+}
+
+/// Requested settings that the device does not report
+fn mismatches(requested: &ApplySettings, after: &SettingsSnapshot) -> Vec<String> {
+    let mut fields = Vec::new();
+    if requested.label.is_some() && requested.label != after.label {
+        fields.push("label".to_string());
+    }
+    if requested.language.is_some() && requested.language != after.language {
+        fields.push("language".to_string());
+    }
+    if requested.use_passphrase.is_some() && requested.use_passphrase != after.passphrase_protection {
+        fields.push("passphrase".to_string());
+    }
+    if requested.auto_lock_delay_ms.is_some() && requested.auto_lock_delay_ms != after.auto_lock_delay_ms {
+        fields.push("autoLockDelayMs".to_string());
+    }
+    fields
+}
+
+/// One activity entry per requested property
+fn changes(requested: &ApplySettings) -> Vec<(ActivityKind, String)> {
+    let mut changes = Vec::new();
+    if let Some(label) = &requested.label {
+        changes.push((ActivityKind::LabelChanged, format!("Label changed to \"{}\"", label)));
+    }
+    if let Some(language) = &requested.language {
+        changes.push((ActivityKind::SettingsChanged, format!("Language changed to {}", language)));
+    }
+    if let Some(enabled) = requested.use_passphrase {
+        let state = if enabled { "enabled" } else { "disabled" };
+        changes.push((ActivityKind::SettingsChanged, format!("Passphrase protection {}", state)));
+    }
+    if let Some(delay_ms) = requested.auto_lock_delay_ms {
+        changes.push((ActivityKind::SettingsChanged, format!("Auto-lock delay set to {}s", delay_ms / 1000)));
+    }
+    if changes.is_empty() {
+        changes.push((ActivityKind::SettingsChanged, "Device settings changed".to_string()));
+    }
+    changes
+}
+
+/// After the device acked `requested`, confirm the change took effect and
+/// record it. `before` is None when the settings could not be read beforehand.
+pub async fn verify_applied(
+    queue_handle: &DeviceQueueHandle,
+    device_id: &str,
+    requested: &ApplySettings,
+    before: Option<SettingsSnapshot>,
+) -> Result<SettingsSnapshot, String> {
+    let after = snapshot(queue_handle).await?;
+    let details = serde_json::json!({ "before": before, "after": after });
+
+    let mismatched = mismatches(requested, &after);
+    if !mismatched.is_empty() {
+        let error = format!("Device acknowledged the settings but did not apply: {}", mismatched.join(", "));
+        log::warn!("{} ({})", error, device_id);
+        return Err(error);
+    }
+
+    for (kind, summary) in changes(requested) {
+        crate::activity::record(device_id, kind, summary, Some(details.clone())).await;
+    }
+    Ok(after)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mismatches_only_checks_requested_fields() {
+        let after = SettingsSnapshot {
+            label: Some("vault".to_string()),
+            language: Some("english".to_string()),
+            passphrase_protection: Some(false),
+            auto_lock_delay_ms: Some(600_000),
+        };
+        let requested = ApplySettings {
+            label: Some("vault".to_string()),
+            ..Default::default()
+        };
+        assert!(mismatches(&requested, &after).is_empty());
+
+        let requested = ApplySettings {
+            label: Some("other".to_string()),
+            use_passphrase: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(mismatches(&requested, &after), vec!["label", "passphrase"]);
+    }
+
+    #[test]
+    fn test_changes_one_entry_per_property() {
+        let requested = ApplySettings {
+            language: Some("english".to_string()),
+            auto_lock_delay_ms: Some(600_000),
+            ..Default::default()
+        };
+        let summaries: Vec<String> = changes(&requested).into_iter().map(|(_, summary)| summary).collect();
+        assert_eq!(summaries, vec!["Language changed to english", "Auto-lock delay set to 600s"]);
+        assert_eq!(changes(&ApplySettings::default()).len(), 1);
+    }
+}
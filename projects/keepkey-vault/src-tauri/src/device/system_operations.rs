@@ -238,19 +238,22 @@ pub async fn process_system_request(
                 ..Default::default()
             };
             
+            let before = crate::device::settings_verify::snapshot(queue_handle).await.ok();
             let response = queue_handle
-                .send_raw(msg.into(), false)
+                .send_raw(msg.clone().into(), false)
                 .await
                 .map_err(|e| format!("Failed to apply settings: {}", e))?;
                 
             match response {
                 keepkey_rust::messages::Message::Success(success) => {
+                    // The ack alone doesn't prove the change stuck; read it back
+                    let verified = crate::device::settings_verify::verify_applied(queue_handle, device_id, &msg, before).await;
                     Ok(DeviceResponse::Success {
                         request_id: request_id.to_string(),
                         device_id: device_id.to_string(),
                         message: success.message,
-                        success: true,
-                        error: None,
+                        success: verified.is_ok(),
+                        error: verified.err(),
                     })
                 }
                 keepkey_rust::messages::Message::Failure(failure) => {
@@ -345,6 +348,11 @@ pub async fn process_system_request_with_cache(
     let start_time = std::time::Instant::now();
     
     match request {
+        DeviceRequest::GetPublicKey { ecdsa_curve_name: Some(curve), .. } if curve != "secp256k1" => {
+            // The cache is keyed by path only, so keys on other curves always come from the device
+            process_system_request(queue_handle, request, request_id, device_id).await
+        }
+        
         DeviceRequest::GetPublicKey { path, coin_name, script_type, .. } => {
             // Check cache first
             let actual_coin = coin_name.as_deref().unwrap_or("Bitcoin");
@@ -0,0 +1,189 @@
+//! Developer sandbox networks (Bitcoin testnet/signet and Sepolia).
+//!
+//! Everything here is gated on the `testnet_mode` preference so a normal
+//! install never derives or displays testnet accounts. Testnet CAIP ids are
+//! listed in [`TESTNET_NETWORKS`] so portfolio totals can leave them out.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::cache::frontload::DefaultPath;
+
+const HARDENED: u32 = 0x8000_0000;
+
+/// Preference key toggling testnet support
+pub const TESTNET_MODE_KEY: &str = "testnet_mode";
+
+/// Bitcoin testnet3 genesis (CAIP-2)
+pub const BITCOIN_TESTNET_CAIP: &str = "bip122:000000000933ea01ad0ee984209779ba";
+
+/// Bitcoin signet genesis (CAIP-2); signet shares testnet address encoding
+pub const BITCOIN_SIGNET_CAIP: &str = "bip122:00000008819873e925422c1ff0f99f7c";
+
+/// Sepolia (CAIP-2)
+pub const SEPOLIA_CAIP: &str = "eip155:11155111";
+
+/// CAIP-2 ids of every sandbox network; never counted in USD totals
+pub const TESTNET_NETWORKS: &[&str] = &[BITCOIN_TESTNET_CAIP, BITCOIN_SIGNET_CAIP, SEPOLIA_CAIP];
+
+/// Firmware coin name used for Bitcoin testnet and signet
+pub const BITCOIN_TESTNET_COIN: &str = "Testnet";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TestnetNetwork {
+    Testnet,
+    Signet,
+    Sepolia,
+}
+
+impl TestnetNetwork {
+    pub fn caip(&self) -> &'static str {
+        match self {
+            TestnetNetwork::Testnet => BITCOIN_TESTNET_CAIP,
+            TestnetNetwork::Signet => BITCOIN_SIGNET_CAIP,
+            TestnetNetwork::Sepolia => SEPOLIA_CAIP,
+        }
+    }
+
+    pub fn is_bitcoin(&self) -> bool {
+        !matches!(self, TestnetNetwork::Sepolia)
+    }
+}
+
+/// Summary of a sandbox network for the settings UI
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TestnetNetworkInfo {
+    pub network: TestnetNetwork,
+    pub caip: String,
+    pub symbol: String,
+    pub faucet_url: String,
+}
+
+/// Whether testnet accounts and endpoints are enabled
+pub fn is_enabled() -> bool {
+    crate::preferences::get_as::<bool>(TESTNET_MODE_KEY).unwrap_or(false)
+}
+
+pub fn set_enabled(enabled: bool) -> Result<(), String> {
+    crate::preferences::set(TESTNET_MODE_KEY, serde_json::Value::Bool(enabled))
+}
+
+/// Reject sandbox requests while testnet mode is off
+pub fn ensure_enabled() -> Result<(), String> {
+    if is_enabled() {
+        Ok(())
+    } else {
+        Err("Testnet mode is disabled; enable the testnet_mode preference first".to_string())
+    }
+}
+
+/// Whether a CAIP-2 or CAIP-19 id belongs to a sandbox network
+pub fn is_testnet_caip(caip: &str) -> bool {
+    let chain = caip.split('/').next().unwrap_or(caip);
+    TESTNET_NETWORKS.contains(&chain)
+}
+
+pub fn networks() -> Vec<TestnetNetworkInfo> {
+    vec![
+        TestnetNetworkInfo {
+            network: TestnetNetwork::Testnet,
+            caip: BITCOIN_TESTNET_CAIP.to_string(),
+            symbol: "tBTC".to_string(),
+            faucet_url: "https://coinfaucet.eu/en/btc-testnet/".to_string(),
+        },
+        TestnetNetworkInfo {
+            network: TestnetNetwork::Signet,
+            caip: BITCOIN_SIGNET_CAIP.to_string(),
+            symbol: "sBTC".to_string(),
+            faucet_url: "https://signetfaucet.com/".to_string(),
+        },
+        TestnetNetworkInfo {
+            network: TestnetNetwork::Sepolia,
+            caip: SEPOLIA_CAIP.to_string(),
+            symbol: "SepoliaETH".to_string(),
+            faucet_url: "https://sepoliafaucet.com/".to_string(),
+        },
+    ]
+}
+
+/// BIP32 path for a sandbox receive address
+pub fn address_path(network: TestnetNetwork, script_type: &str, account: u32, index: u32) -> Result<Vec<u32>, String> {
+    if network.is_bitcoin() {
+        let purpose = match script_type {
+            "p2pkh" => 44,
+            "p2sh-p2wpkh" => 49,
+            "p2wpkh" => 84,
+            other => return Err(format!("Unsupported script type for Bitcoin testnet: {}", other)),
+        };
+        Ok(vec![purpose | HARDENED, 1 | HARDENED, account | HARDENED, 0, index])
+    } else {
+        Ok(vec![44 | HARDENED, 60 | HARDENED, account | HARDENED, 0, index])
+    }
+}
+
+/// Frontload paths for sandbox accounts, empty unless testnet mode is on
+pub fn frontload_paths() -> Vec<DefaultPath> {
+    if !is_enabled() {
+        return Vec::new();
+    }
+
+    let bitcoin_networks = vec![BITCOIN_TESTNET_CAIP.to_string(), BITCOIN_SIGNET_CAIP.to_string()];
+    let mut paths: Vec<DefaultPath> = [("p2pkh", 44, "legacy"), ("p2sh-p2wpkh", 49, "segwit"), ("p2wpkh", 84, "native segwit")]
+        .into_iter()
+        .map(|(script_type, purpose, note)| DefaultPath {
+            id: format!("testnet_{}_account_0", script_type.replace('-', "_")),
+            note: format!("Bitcoin testnet/signet {} account 0", note),
+            blockchain: "testnet".to_string(),
+            symbol: "tBTC".to_string(),
+            networks: bitcoin_networks.clone(),
+            script_type: script_type.to_string(),
+            address_n_list: vec![purpose | HARDENED, 1 | HARDENED, HARDENED],
+            address_n_list_master: vec![purpose | HARDENED, 1 | HARDENED, HARDENED, 0, 0],
+            curve: "secp256k1".to_string(),
+            show_display: false,
+        })
+        .collect();
+
+    paths.push(DefaultPath {
+        id: "sepolia_account_0".to_string(),
+        note: "Sepolia account 0".to_string(),
+        blockchain: "sepolia".to_string(),
+        symbol: "SepoliaETH".to_string(),
+        networks: vec![SEPOLIA_CAIP.to_string()],
+        script_type: "ethereum".to_string(),
+        address_n_list: vec![44 | HARDENED, 60 | HARDENED, HARDENED],
+        address_n_list_master: vec![44 | HARDENED, 60 | HARDENED, HARDENED, 0, 0],
+        curve: "secp256k1".to_string(),
+        show_display: false,
+    });
+
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_testnet_caip() {
+        assert!(is_testnet_caip("bip122:000000000933ea01ad0ee984209779ba/slip44:1"));
+        assert!(is_testnet_caip(SEPOLIA_CAIP));
+        assert!(!is_testnet_caip("bip122:000000000019d6689c085ae165831e93/slip44:0"));
+        assert!(!is_testnet_caip("eip155:1/slip44:60"));
+    }
+
+    #[test]
+    fn test_address_path() {
+        assert_eq!(
+            address_path(TestnetNetwork::Testnet, "p2wpkh", 0, 3).unwrap(),
+            vec![84 | HARDENED, 1 | HARDENED, HARDENED, 0, 3]
+        );
+        assert_eq!(
+            address_path(TestnetNetwork::Sepolia, "p2pkh", 1, 0).unwrap(),
+            vec![44 | HARDENED, 60 | HARDENED, 1 | HARDENED, 0, 0]
+        );
+        assert!(address_path(TestnetNetwork::Signet, "p2tr", 0, 0).is_err());
+    }
+}
@@ -6,6 +6,46 @@ pub async fn process_transaction_request(
     request: &DeviceRequest,
     request_id: &str,
     device_id: &str,
+) -> Result<DeviceResponse, String> {
+    let result = sign_transaction_request(queue_handle, request, request_id, device_id).await;
+    let failure = match &result {
+        Err(e) => Some(e.clone()),
+        Ok(DeviceResponse::SignedTransaction { success: false, error, .. })
+        | Ok(DeviceResponse::EthereumSignedTransaction { success: false, error, .. }) => {
+            Some(error.clone().unwrap_or_else(|| "Signing failed".to_string()))
+        }
+        Ok(_) => None,
+    };
+    match failure {
+        Some(message) => {
+            crate::error_center::record(crate::error_center::ErrorCategory::Signing, Some(device_id), message).await;
+        }
+        None => record_signed(device_id, request, result.as_ref().ok()).await,
+    }
+    result
+}
+
+/// Add a completed signature to the device's activity timeline
+async fn record_signed(device_id: &str, request: &DeviceRequest, response: Option<&DeviceResponse>) {
+    let (summary, details) = match (request, response) {
+        (DeviceRequest::SignTransaction { coin, outputs, .. }, Some(DeviceResponse::SignedTransaction { txid, .. })) => (
+            format!("Signed {} transaction", coin),
+            serde_json::json!({ "coin": coin, "outputs": outputs.len(), "txid": txid }),
+        ),
+        (DeviceRequest::EthereumSignTransaction { chain_id, to, value, .. }, _) => (
+            "Signed Ethereum transaction".to_string(),
+            serde_json::json!({ "chainId": chain_id, "to": to, "value": value }),
+        ),
+        _ => return,
+    };
+    crate::activity::record(device_id, crate::activity::ActivityKind::Signed, summary, Some(details)).await;
+}
+
+async fn sign_transaction_request(
+    queue_handle: &DeviceQueueHandle,
+    request: &DeviceRequest,
+    request_id: &str,
+    device_id: &str,
 ) -> Result<DeviceResponse, String> {
     let response = match request {
         // Bitcoin/UTXO signing
@@ -177,7 +217,7 @@ pub async fn process_transaction_request(
         // Ethereum message signing
         DeviceRequest::EthereumSignMessage { message, address } => {
             let eth_msg = keepkey_rust::messages::EthereumSignMessage {
-                message: message.as_bytes().to_vec(),
+                message: crate::eth_signatures::personal_message_bytes(message),
                 address_n: vec![], // TODO: We'd need to derive this from the address
             };
             
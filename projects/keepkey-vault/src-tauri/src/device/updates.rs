@@ -6,33 +6,8 @@ use uuid;
 use crate::logging::{log_device_request, log_device_response};
 use crate::commands::DeviceQueueManager;
 
-/// Update device bootloader using the device queue
-#[tauri::command]
-pub async fn update_device_bootloader(
-    device_id: String,
-    target_version: String,
-    queue_manager: State<'_, DeviceQueueManager>,
-) -> Result<bool, String> {
-    println!("🔄 Starting bootloader update for device {}: target version {}", device_id, target_version);
-    
-    let request_id = uuid::Uuid::new_v4().to_string();
-    
-    // Log the request
-    let request_data = serde_json::json!({
-        "device_id": device_id,
-        "target_version": target_version,
-        "operation": "update_device_bootloader"
-    });
-    
-    if let Err(e) = log_device_request(&device_id, &request_id, "UpdateBootloader", &request_data).await {
-        eprintln!("Failed to log bootloader update request: {}", e);
-    }
-    
-    // Validate target version
-    let _target_semver = Version::parse(&target_version)
-        .map_err(|e| format!("Invalid target bootloader version: {}", e))?;
-    
-    // Load the bootloader binary from the firmware directory (bundled with app)
+/// Load a bundled bootloader updater image by version
+pub(crate) fn load_bootloader_image(target_version: &str) -> Result<Vec<u8>, String> {
     let bootloader_filename = format!("bl_v{}", target_version);
     
     // Debug: Log current working directory and environment
@@ -84,10 +59,10 @@ pub async fn update_device_bootloader(
     
     let firmware_path = possible_firmware_paths.iter().find(|path| path.exists()).cloned();
     
-    let bootloader_bytes = if let Some(path) = firmware_path {
+    if let Some(path) = firmware_path {
         println!("📂 Loading bootloader from: {}", path.display());
         fs::read(&path)
-            .map_err(|e| format!("Failed to read bootloader file {}: {}", path.display(), e))?
+            .map_err(|e| format!("Failed to read bootloader file {}: {}", path.display(), e))
     } else {
         // Check available bootloader versions from all possible firmware directories
         let mut possible_firmware_dirs = vec![
@@ -141,17 +116,68 @@ pub async fn update_device_bootloader(
             target_version
         );
         
-        // Log the error response
-        let response_data = serde_json::json!({
-            "error": error_msg,
-            "operation": "update_device_bootloader"
-        });
-        
-        if let Err(e) = log_device_response(&device_id, &request_id, false, &response_data, Some(&error_msg)).await {
-            eprintln!("Failed to log bootloader update error response: {}", e);
+        Err(error_msg)
+    }
+}
+
+/// Run the bootloader update pre-flight, returning a confirm token when every check passes
+#[tauri::command]
+pub async fn bootloader_update_preflight(
+    device_id: String,
+    target_version: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<crate::device::preflight::BootloaderPreflight, String> {
+    Ok(crate::device::preflight::run_bootloader_preflight(&device_id, &target_version, queue_manager.inner()).await)
+}
+
+/// Update device bootloader using the device queue
+#[tauri::command]
+pub async fn update_device_bootloader(
+    device_id: String,
+    target_version: String,
+    confirm_token: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<bool, String> {
+    println!("🔄 Starting bootloader update for device {}: target version {}", device_id, target_version);
+    
+    let request_id = uuid::Uuid::new_v4().to_string();
+    
+    // Log the request
+    let request_data = serde_json::json!({
+        "device_id": device_id,
+        "target_version": target_version,
+        "operation": "update_device_bootloader"
+    });
+    
+    if let Err(e) = log_device_request(&device_id, &request_id, "UpdateBootloader", &request_data).await {
+        eprintln!("Failed to log bootloader update request: {}", e);
+    }
+    
+    // Validate target version
+    let _target_semver = Version::parse(&target_version)
+        .map_err(|e| format!("Invalid target bootloader version: {}", e))?;
+    
+    // The frontend must have run the pre-flight and had the user confirm
+    crate::device::preflight::consume_confirm_token(&device_id, &target_version, &confirm_token)?;
+    
+    // Load the bootloader binary from the firmware directory (bundled with app)
+    let bootloader_bytes = match load_bootloader_image(&target_version)
+        .and_then(|bytes| crate::device::preflight::recheck_before_flash(&device_id, &target_version, &bytes).map(|_| bytes))
+    {
+        Ok(bytes) => bytes,
+        Err(error_msg) => {
+            // Log the error response
+            let response_data = serde_json::json!({
+                "error": error_msg,
+                "operation": "update_device_bootloader"
+            });
+            
+            if let Err(e) = log_device_response(&device_id, &request_id, false, &response_data, Some(&error_msg)).await {
+                eprintln!("Failed to log bootloader update error response: {}", e);
+            }
+            
+            return Err(error_msg);
         }
-        
-        return Err(error_msg);
     };
     
     println!("📦 Loaded bootloader binary: {} bytes", bootloader_bytes.len());
@@ -554,6 +580,17 @@ pub async fn update_device_firmware(
                 eprintln!("Failed to log firmware update success response: {}", e);
             }
             
+            // The device runs an official release again
+            if let Err(e) = crate::device::custom_firmware::clear_record(&device_id) {
+                log::warn!("Failed to clear custom firmware record for {}: {}", device_id, e);
+            }
+            crate::activity::record(
+                &device_id,
+                crate::activity::ActivityKind::FirmwareUpdated,
+                format!("Firmware updated to {}", target_version),
+                Some(serde_json::json!({ "version": target_version })),
+            ).await;
+            
             Ok(success)
         }
         Err(e) => {
@@ -573,4 +610,87 @@ pub async fn update_device_firmware(
             Err(format!("Firmware update failed: {}", error_msg))
         }
     }
-} 
\ No newline at end of file
+} 
+
+/// Inspect a custom/dev firmware image and issue the confirmation challenge for flashing it
+#[tauri::command]
+pub async fn inspect_custom_firmware(
+    device_id: String,
+    file_path: String,
+) -> Result<crate::device::custom_firmware::CustomFirmwareChallenge, String> {
+    let path = PathBuf::from(&file_path);
+    let image = fs::read(&path)
+        .map_err(|e| format!("Failed to read firmware file {}: {}", path.display(), e))?;
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(file_path);
+    
+    let challenge = crate::device::custom_firmware::issue_challenge(&device_id, &file_name, &image);
+    log::warn!("⚠️ Custom firmware inspected for {}: {} (sha256 {})", device_id, challenge.file_name, challenge.sha256);
+    Ok(challenge)
+}
+
+/// Flash a custom/dev firmware image after the user typed the confirmation phrase
+#[tauri::command]
+pub async fn flash_custom_firmware(
+    device_id: String,
+    file_path: String,
+    token: String,
+    typed_phrase: String,
+    queue_manager: State<'_, DeviceQueueManager>,
+) -> Result<bool, String> {
+    let path = PathBuf::from(&file_path);
+    let image = fs::read(&path)
+        .map_err(|e| format!("Failed to read firmware file {}: {}", path.display(), e))?;
+    crate::device::custom_firmware::redeem(&device_id, &token, &image, &typed_phrase)?;
+    
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let (sha256, _) = crate::device::custom_firmware::image_hash(&image);
+    let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or(file_path);
+    let request_data = serde_json::json!({
+        "device_id": device_id,
+        "file_name": file_name,
+        "sha256": sha256,
+        "operation": "flash_custom_firmware"
+    });
+    if let Err(e) = log_device_request(&device_id, &request_id, "UpdateFirmware", &request_data).await {
+        eprintln!("Failed to log custom firmware request: {}", e);
+    }
+    
+    let queue_handle = crate::commands::get_or_create_device_queue(&device_id, queue_manager.inner()).await?;
+    let features = queue_handle.get_features().await
+        .map_err(|e| format!("Failed to get device features: {}", e))?;
+    if !features.bootloader_mode.unwrap_or(false) {
+        return Err("Device must be in bootloader mode for firmware update. Please hold the button while reconnecting to enter bootloader mode.".to_string());
+    }
+    
+    log::warn!("⚠️ Flashing NON-OFFICIAL firmware {} onto device {}", file_name, device_id);
+    let result = queue_handle.update_firmware("custom".to_string(), image).await;
+    
+    let response_data = match &result {
+        Ok(success) => serde_json::json!({ "success": success, "sha256": sha256, "operation": "flash_custom_firmware" }),
+        Err(e) => serde_json::json!({ "error": e.to_string(), "operation": "flash_custom_firmware" }),
+    };
+    let error = result.as_ref().err().map(|e| e.to_string());
+    if let Err(e) = log_device_response(&device_id, &request_id, result.is_ok(), &response_data, error.as_deref()).await {
+        eprintln!("Failed to log custom firmware response: {}", e);
+    }
+    
+    let success = result.map_err(|e| format!("Custom firmware flash failed: {}", e))?;
+    
+    // Support bundles carry this so non-official firmware is obvious when triaging
+    let record = crate::device::custom_firmware::CustomFirmwareRecord {
+        sha256,
+        file_name,
+        installed_at: chrono::Utc::now().timestamp(),
+    };
+    crate::activity::record(
+        &device_id,
+        crate::activity::ActivityKind::FirmwareUpdated,
+        format!("Custom firmware {} installed", record.file_name),
+        Some(serde_json::json!({ "custom": true, "sha256": record.sha256 })),
+    ).await;
+    if let Err(e) = crate::device::custom_firmware::save_record(&device_id, record) {
+        log::warn!("Failed to record custom firmware for {}: {}", device_id, e);
+    }
+    
+    Ok(success)
+}
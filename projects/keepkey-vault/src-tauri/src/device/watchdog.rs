@@ -0,0 +1,141 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use crate::commands::DeviceQueueManager;
+
+/// Preference key holding queue worker settings
+pub(crate) const PREFERENCE_KEY: &str = "device_queue";
+
+/// How often queue workers are checked
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How long a worker may go without answering while commands time out
+const WEDGED_THRESHOLD: Duration = Duration::from_secs(120);
+
+/// Queue worker settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct QueueWorkerConfig {
+    /// Stop a connected device's worker after this long without commands; 0 keeps workers forever
+    pub idle_timeout_secs: u64,
+    /// REST signing requests are refused with 429 once this many commands are queued; 0 disables
+    pub max_queue_depth: u32,
+}
+
+impl Default for QueueWorkerConfig {
+    fn default() -> Self {
+        Self { idle_timeout_secs: 600, max_queue_depth: 4 }
+    }
+}
+
+impl QueueWorkerConfig {
+    pub fn from_preferences() -> Self {
+        crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default()
+    }
+}
+
+/// Periodically restart device queue workers that stopped answering, and stop idle ones
+pub fn spawn_queue_watchdog(app: AppHandle, queue_manager: DeviceQueueManager) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+            restart_wedged_workers(&app, &queue_manager).await;
+            stop_idle_workers(&queue_manager).await;
+        }
+    });
+}
+
+/// Shut down workers that have had nothing to do for the idle timeout
+///
+/// The handle is dropped from the manager, so the next request for the device
+/// spawns a fresh worker the same way it would after a reconnect.
+async fn stop_idle_workers(queue_manager: &DeviceQueueManager) {
+    let idle_timeout = QueueWorkerConfig::from_preferences().idle_timeout_secs;
+    if idle_timeout == 0 {
+        return;
+    }
+    let threshold = Duration::from_secs(idle_timeout);
+
+    let idle: Vec<_> = {
+        let mut manager = queue_manager.lock().await;
+        let ids: Vec<String> = manager
+            .iter()
+            .filter(|(device_id, handle)| {
+                handle.is_idle(threshold)
+                    && !crate::commands::is_device_in_pin_flow(device_id)
+                    // Respawning would lose track of the session the passphrase policy has to clear
+                    && !handle.passphrase_state().cached
+            })
+            .map(|(device_id, _)| device_id.clone())
+            .collect();
+        ids.into_iter()
+            .filter_map(|device_id| manager.remove(&device_id).map(|handle| (device_id, handle)))
+            .collect()
+    };
+
+    for (device_id, handle) in idle {
+        log::info!("💤 Stopping idle queue worker for {} (no commands for {}s)", device_id, idle_timeout);
+        if tokio::time::timeout(Duration::from_secs(5), handle.shutdown()).await.is_err() {
+            handle.abort_worker();
+        }
+    }
+}
+
+/// Shut down and respawn every wedged worker, emitting device:queue-restarted for each
+async fn restart_wedged_workers(app: &AppHandle, queue_manager: &DeviceQueueManager) {
+    let wedged: Vec<_> = {
+        let manager = queue_manager.lock().await;
+        manager
+            .iter()
+            // PIN entry legitimately keeps a worker busy waiting on the user
+            .filter(|(device_id, handle)| {
+                handle.is_wedged(WEDGED_THRESHOLD) && !crate::commands::is_device_in_pin_flow(device_id)
+            })
+            .map(|(device_id, handle)| (device_id.clone(), handle.clone()))
+            .collect()
+    };
+
+    for (device_id, handle) in wedged {
+        let health = handle.health();
+        log::warn!(
+            "🐕 Queue worker for {} is wedged ({} timeouts, no response for {}s) - restarting",
+            device_id,
+            health.timeouts_since_response,
+            health.since_last_response.as_secs()
+        );
+
+        // A wedged worker can't process Shutdown, so don't wait on it
+        let _ = tokio::time::timeout(Duration::from_secs(1), handle.shutdown()).await;
+        handle.abort_worker();
+
+        let device_info = tokio::task::spawn_blocking(keepkey_rust::features::list_connected_devices)
+            .await
+            .unwrap_or_default()
+            .into_iter()
+            .find(|d| d.unique_id == device_id);
+
+        let respawned = {
+            let mut manager = queue_manager.lock().await;
+            manager.remove(&device_id);
+            match device_info {
+                Some(info) => {
+                    let new_handle = keepkey_rust::device_queue::DeviceQueueFactory::spawn_worker(device_id.clone(), info);
+                    manager.insert(device_id.clone(), new_handle);
+                    true
+                }
+                None => false,
+            }
+        };
+
+        let payload = serde_json::json!({
+            "deviceId": device_id,
+            "respawned": respawned,
+            "timeouts": health.timeouts_since_response,
+            "stalledSecs": health.since_last_response.as_secs(),
+        });
+        if let Err(e) = crate::commands::emit_or_queue_event(app, "device:queue-restarted", payload).await {
+            log::warn!("Failed to emit device:queue-restarted: {}", e);
+        }
+    }
+}
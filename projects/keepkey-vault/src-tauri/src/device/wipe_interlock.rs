@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+/// Preference key for wipe confirmation settings
+const PREFERENCE_KEY: &str = "wipe_interlock";
+
+/// How long a wipe challenge stays valid
+const CHALLENGE_TTL: Duration = Duration::from_secs(120);
+
+/// Wipe confirmation settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipeInterlockPreferences {
+    /// Require typing the device label to confirm a wipe
+    #[serde(default)]
+    pub require_label: bool,
+    /// Let REST API clients wipe the device without the in-app confirmation
+    #[serde(default)]
+    pub allow_api_wipe: bool,
+}
+
+/// Challenge handed to the frontend; it must be sent back with the wipe
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WipeChallenge {
+    pub token: String,
+    pub device_id: String,
+    pub expires_in_secs: u64,
+    /// Whether the device label has to be typed to confirm
+    pub label_required: bool,
+}
+
+#[derive(Debug)]
+struct PendingWipe {
+    device_id: String,
+    issued_at: Instant,
+    /// Label the user must type, when required
+    expected_label: Option<String>,
+}
+
+lazy_static::lazy_static! {
+    static ref PENDING_WIPES: Mutex<HashMap<String, PendingWipe>> = Mutex::new(HashMap::new());
+}
+
+/// Load wipe confirmation settings from preferences
+pub fn get_preferences() -> WipeInterlockPreferences {
+    crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default()
+}
+
+/// Save wipe confirmation settings
+pub fn set_preferences(preferences: &WipeInterlockPreferences) -> Result<(), String> {
+    crate::preferences::set(PREFERENCE_KEY, serde_json::json!(preferences))
+}
+
+/// Issue a single-use wipe challenge for a device. `label` is the device's
+/// current label; devices without one can't require typing it.
+pub fn issue_challenge(device_id: &str, label: Option<String>) -> WipeChallenge {
+    let expected_label = label
+        .filter(|l| !l.trim().is_empty())
+        .filter(|_| get_preferences().require_label);
+    let token = uuid::Uuid::new_v4().to_string();
+
+    let mut pending = PENDING_WIPES.lock().unwrap();
+    pending.retain(|_, p| p.issued_at.elapsed() < CHALLENGE_TTL);
+    pending.insert(token.clone(), PendingWipe {
+        device_id: device_id.to_string(),
+        issued_at: Instant::now(),
+        expected_label: expected_label.clone(),
+    });
+
+    WipeChallenge {
+        token,
+        device_id: device_id.to_string(),
+        expires_in_secs: CHALLENGE_TTL.as_secs(),
+        label_required: expected_label.is_some(),
+    }
+}
+
+fn check(pending: &PendingWipe, device_id: &str, typed_label: Option<&str>) -> Result<(), String> {
+    if pending.device_id != device_id {
+        return Err("Wipe confirmation was issued for a different device".to_string());
+    }
+    if pending.issued_at.elapsed() >= CHALLENGE_TTL {
+        return Err("Wipe confirmation expired, please start again".to_string());
+    }
+    if let Some(expected) = &pending.expected_label {
+        if typed_label.map(str::trim) != Some(expected.trim()) {
+            return Err("Typed label does not match the device label".to_string());
+        }
+    }
+    Ok(())
+}
+
+/// Consume a wipe challenge; the token can't be reused whether or not it checks out
+pub fn redeem(device_id: &str, token: &str, typed_label: Option<&str>) -> Result<(), String> {
+    let pending = PENDING_WIPES
+        .lock()
+        .unwrap()
+        .remove(token)
+        .ok_or_else(|| "Unknown or already used wipe confirmation".to_string())?;
+
+    check(&pending, device_id, typed_label)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_challenge() {
+        let pending = PendingWipe {
+            device_id: "dev1".to_string(),
+            issued_at: Instant::now(),
+            expected_label: Some("My KeepKey".to_string()),
+        };
+
+        assert!(check(&pending, "dev1", Some("My KeepKey")).is_ok());
+        assert!(check(&pending, "dev1", Some(" My KeepKey ")).is_ok());
+        assert!(check(&pending, "dev1", Some("my keepkey")).is_err());
+        assert!(check(&pending, "dev1", None).is_err());
+        assert!(check(&pending, "dev2", Some("My KeepKey")).is_err());
+    }
+
+    #[test]
+    fn test_redeem_is_single_use() {
+        PENDING_WIPES.lock().unwrap().insert("tok".to_string(), PendingWipe {
+            device_id: "dev1".to_string(),
+            issued_at: Instant::now(),
+            expected_label: None,
+        });
+
+        assert!(redeem("dev1", "tok", None).is_ok());
+        assert!(redeem("dev1", "tok", None).is_err());
+    }
+}
@@ -0,0 +1,91 @@
+//! Persistent store of significant backend failures.
+//!
+//! Frontload failures, provider outages and signing errors are written to the
+//! cache database and announced as `errors:recorded`, so the UI can show an
+//! issues panel instead of the failure only reaching the logs.
+
+use std::sync::Arc;
+use once_cell::sync::OnceCell;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::cache::{CacheManager, ErrorRecord};
+
+/// Oldest entries beyond this are dropped
+const MAX_ERRORS: usize = 500;
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+/// What failed
+#[derive(Debug, Clone, Copy)]
+pub enum ErrorCategory {
+    Frontload,
+    Provider,
+    Signing,
+    Derivation,
+}
+
+impl ErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::Frontload => "frontload",
+            ErrorCategory::Provider => "provider",
+            ErrorCategory::Signing => "signing",
+            ErrorCategory::Derivation => "derivation",
+        }
+    }
+}
+
+/// Remember the app handle used to reach the cache and emit events
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// The cache, if it has been opened; recording an error never opens it
+fn cache() -> Option<Arc<CacheManager>> {
+    let app = APP_HANDLE.get()?;
+    app.try_state::<Arc<once_cell::sync::OnceCell<Arc<CacheManager>>>>()?
+        .get()
+        .cloned()
+}
+
+/// Store a failure and announce it to the frontend
+pub async fn record(category: ErrorCategory, device_id: Option<&str>, message: impl Into<String>) {
+    let mut error = ErrorRecord {
+        id: 0,
+        category: category.as_str().to_string(),
+        message: message.into(),
+        device_id: device_id.map(String::from),
+        correlation_id: crate::server::correlation::current(),
+        created_at: chrono::Utc::now().timestamp(),
+    };
+
+    let Some(cache) = cache() else {
+        log::warn!("Not recording {} error (cache not open): {}", error.category, error.message);
+        return;
+    };
+    match cache.save_error(&error, MAX_ERRORS).await {
+        Ok(id) => error.id = id,
+        Err(e) => {
+            log::warn!("Failed to record {} error: {}", error.category, e);
+            return;
+        }
+    }
+
+    if let Some(app) = APP_HANDLE.get() {
+        let _ = app.emit("errors:recorded", &error);
+    }
+}
+
+pub async fn list(cache: &CacheManager, limit: Option<usize>) -> Result<Vec<ErrorRecord>, String> {
+    cache
+        .list_errors(limit.unwrap_or(100).min(MAX_ERRORS))
+        .await
+        .map_err(|e| format!("Failed to read errors: {}", e))
+}
+
+pub async fn clear(cache: &CacheManager) -> Result<usize, String> {
+    cache
+        .clear_errors()
+        .await
+        .map_err(|e| format!("Failed to clear errors: {}", e))
+}
@@ -0,0 +1,350 @@
+//! Ethereum message hashing and signer recovery.
+//!
+//! EIP-191 personal messages and EIP-712 typed data are hashed here so
+//! signatures produced by the device can be checked on the host without it.
+//! The signing path builds personal message payloads with the same helper.
+
+use std::collections::BTreeSet;
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use sha3::{Digest, Keccak256};
+use utoipa::ToSchema;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Verification {
+    /// Whether the recovered signer is the expected address
+    pub valid: bool,
+    /// EIP-55 checksummed signer address
+    pub recovered_address: String,
+    /// The 32-byte hash that was signed, hex with 0x prefix
+    pub hash: String,
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>, String> {
+    let digits = value.trim().trim_start_matches("0x").trim_start_matches("0X");
+    hex::decode(digits).map_err(|e| format!("Invalid hex {}: {}", value, e))
+}
+
+/// Bytes sent to the device for an EthereumSignMessage request
+pub fn personal_message_bytes(message: &str) -> Vec<u8> {
+    message.as_bytes().to_vec()
+}
+
+/// EIP-191 (version 0x45) hash of a personal message
+pub fn hash_personal_message(message: &[u8]) -> [u8; 32] {
+    let mut data = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    data.extend_from_slice(message);
+    keccak256(&data)
+}
+
+// ============ EIP-712 ============
+
+fn fields<'a>(types: &'a Map<String, Value>, name: &str) -> Result<&'a Vec<Value>, String> {
+    types
+        .get(name)
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("Unknown typed data type {}", name))
+}
+
+fn field_parts(field: &Value) -> Result<(&str, &str), String> {
+    let name = field.get("name").and_then(Value::as_str).ok_or("Typed data field is missing a name")?;
+    let ty = field
+        .get("type")
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("Field {} is missing a type", name))?;
+    Ok((name, ty))
+}
+
+/// Type name without array suffixes
+fn base_type(ty: &str) -> &str {
+    ty.split('[').next().unwrap_or(ty)
+}
+
+fn collect_dependencies(types: &Map<String, Value>, name: &str, found: &mut BTreeSet<String>) -> Result<(), String> {
+    if found.contains(name) || !types.contains_key(name) {
+        return Ok(());
+    }
+    found.insert(name.to_string());
+    for field in fields(types, name)? {
+        let (_, ty) = field_parts(field)?;
+        collect_dependencies(types, base_type(ty), found)?;
+    }
+    Ok(())
+}
+
+/// `encodeType`: the primary type followed by its dependencies in name order
+fn encode_type(types: &Map<String, Value>, primary: &str) -> Result<String, String> {
+    let mut dependencies = BTreeSet::new();
+    collect_dependencies(types, primary, &mut dependencies)?;
+    dependencies.remove(primary);
+
+    let mut encoded = String::new();
+    for name in std::iter::once(primary).chain(dependencies.iter().map(String::as_str)) {
+        let members = fields(types, name)?
+            .iter()
+            .map(|field| field_parts(field).map(|(name, ty)| format!("{} {}", ty, name)))
+            .collect::<Result<Vec<_>, _>>()?;
+        encoded.push_str(&format!("{}({})", name, members.join(",")));
+    }
+    Ok(encoded)
+}
+
+/// Decimal or 0x-hex integer as a 32-byte big-endian (two's complement) word
+fn encode_integer(value: &Value, signed: bool) -> Result<[u8; 32], String> {
+    let text = match value {
+        Value::Number(n) => n.to_string(),
+        Value::String(s) => s.trim().to_string(),
+        other => return Err(format!("Expected an integer, got {}", other)),
+    };
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+    if negative && !signed {
+        return Err(format!("Negative value {} for an unsigned integer", text));
+    }
+
+    let mut word = [0u8; 32];
+    if let Some(hex_digits) = digits.strip_prefix("0x").or_else(|| digits.strip_prefix("0X")) {
+        let padded = if hex_digits.len() % 2 == 1 { format!("0{}", hex_digits) } else { hex_digits.to_string() };
+        let bytes = hex::decode(&padded).map_err(|e| format!("Invalid integer {}: {}", text, e))?;
+        if bytes.len() > 32 {
+            return Err(format!("Integer {} does not fit in 256 bits", text));
+        }
+        word[32 - bytes.len()..].copy_from_slice(&bytes);
+    } else {
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("Invalid integer {}", text));
+        }
+        for digit in digits.bytes() {
+            let mut carry = (digit - b'0') as u16;
+            for byte in word.iter_mut().rev() {
+                let v = *byte as u16 * 10 + carry;
+                *byte = v as u8;
+                carry = v >> 8;
+            }
+            if carry != 0 {
+                return Err(format!("Integer {} does not fit in 256 bits", text));
+            }
+        }
+    }
+
+    if negative {
+        let mut carry = 1u16;
+        for byte in word.iter_mut().rev() {
+            let v = (!*byte) as u16 + carry;
+            *byte = v as u8;
+            carry = v >> 8;
+        }
+    }
+    Ok(word)
+}
+
+/// `encodeData` for one member value
+fn encode_value(types: &Map<String, Value>, ty: &str, value: &Value) -> Result<[u8; 32], String> {
+    if let Some(inner) = ty.strip_suffix(']') {
+        let element = &inner[..inner.rfind('[').ok_or_else(|| format!("Malformed array type {}", ty))?];
+        let items = value.as_array().ok_or_else(|| format!("Expected an array for {}", ty))?;
+        let mut concatenated = Vec::with_capacity(items.len() * 32);
+        for item in items {
+            concatenated.extend(encode_value(types, element, item)?);
+        }
+        return Ok(keccak256(&concatenated));
+    }
+    if types.contains_key(ty) {
+        return hash_struct(types, ty, value);
+    }
+
+    let as_str = || value.as_str().ok_or_else(|| format!("Expected a string for {}", ty));
+    let mut word = [0u8; 32];
+    match ty {
+        "string" => Ok(keccak256(as_str()?.as_bytes())),
+        "bytes" => Ok(keccak256(&decode_hex(as_str()?)?)),
+        "bool" => {
+            word[31] = value.as_bool().ok_or("Expected a boolean")? as u8;
+            Ok(word)
+        }
+        "address" => {
+            let bytes = decode_hex(as_str()?)?;
+            if bytes.len() != 20 {
+                return Err(format!("Invalid address {}", value));
+            }
+            word[12..].copy_from_slice(&bytes);
+            Ok(word)
+        }
+        _ if ty.starts_with("bytes") => {
+            let size: usize = ty[5..].parse().map_err(|_| format!("Unsupported typed data type {}", ty))?;
+            let bytes = decode_hex(as_str()?)?;
+            if size == 0 || size > 32 || bytes.len() > size {
+                return Err(format!("Invalid {} value {}", ty, value));
+            }
+            word[..bytes.len()].copy_from_slice(&bytes);
+            Ok(word)
+        }
+        _ if ty.starts_with("uint") => encode_integer(value, false),
+        _ if ty.starts_with("int") => encode_integer(value, true),
+        _ => Err(format!("Unsupported typed data type {}", ty)),
+    }
+}
+
+/// `hashStruct`: keccak256(typeHash || encodeData)
+fn hash_struct(types: &Map<String, Value>, name: &str, data: &Value) -> Result<[u8; 32], String> {
+    let object = data.as_object().ok_or_else(|| format!("Expected an object for {}", name))?;
+    let mut encoded = keccak256(encode_type(types, name)?.as_bytes()).to_vec();
+    for field in fields(types, name)? {
+        let (field_name, ty) = field_parts(field)?;
+        let value = object
+            .get(field_name)
+            .ok_or_else(|| format!("{} is missing field {}", name, field_name))?;
+        encoded.extend(encode_value(types, ty, value)?);
+    }
+    Ok(keccak256(&encoded))
+}
+
+/// EIP-712 hash of an `eth_signTypedData_v4` payload
+pub fn hash_typed_data(typed_data: &Value) -> Result<[u8; 32], String> {
+    let types = typed_data
+        .get("types")
+        .and_then(Value::as_object)
+        .ok_or("Typed data is missing types")?;
+    if !types.contains_key("EIP712Domain") {
+        return Err("Typed data types must include EIP712Domain".to_string());
+    }
+    let primary = typed_data
+        .get("primaryType")
+        .and_then(Value::as_str)
+        .ok_or("Typed data is missing primaryType")?;
+    let domain = typed_data.get("domain").ok_or("Typed data is missing domain")?;
+
+    let mut data = vec![0x19, 0x01];
+    data.extend(hash_struct(types, "EIP712Domain", domain)?);
+    if primary != "EIP712Domain" {
+        let message = typed_data.get("message").ok_or("Typed data is missing message")?;
+        data.extend(hash_struct(types, primary, message)?);
+    }
+    Ok(keccak256(&data))
+}
+
+// ============ Recovery ============
+
+/// EIP-55 mixed-case address
+fn to_checksum_address(address: &[u8]) -> String {
+    let lower = hex::encode(address);
+    let hash = hex::encode(keccak256(lower.as_bytes()));
+    let checksummed: String = lower
+        .chars()
+        .zip(hash.chars())
+        .map(|(c, h)| if c.is_ascii_alphabetic() && h >= '8' { c.to_ascii_uppercase() } else { c })
+        .collect();
+    format!("0x{}", checksummed)
+}
+
+/// Address that produced a 65-byte r || s || v signature over `hash`
+pub fn recover_address(hash: &[u8; 32], signature: &str) -> Result<String, String> {
+    let bytes = decode_hex(signature)?;
+    if bytes.len() != 65 {
+        return Err(format!("Signature must be 65 bytes, got {}", bytes.len()));
+    }
+    let v = match bytes[64] {
+        27 | 28 => bytes[64] - 27,
+        0 | 1 => bytes[64],
+        other => return Err(format!("Unsupported signature recovery id {}", other)),
+    };
+
+    let mut sig = Signature::from_slice(&bytes[..64]).map_err(|e| format!("Invalid signature: {}", e))?;
+    let mut recovery_id = RecoveryId::from_byte(v).ok_or("Invalid signature recovery id")?;
+    // Recovery expects low-s; the mirrored signature recovers the same key with flipped parity
+    if let Some(normalized) = sig.normalize_s() {
+        sig = normalized;
+        recovery_id = RecoveryId::new(!recovery_id.is_y_odd(), recovery_id.is_x_reduced());
+    }
+
+    let key = VerifyingKey::recover_from_prehash(hash, &sig, recovery_id)
+        .map_err(|e| format!("Failed to recover signer: {}", e))?;
+    let point = key.to_encoded_point(false);
+    Ok(to_checksum_address(&keccak256(&point.as_bytes()[1..])[12..]))
+}
+
+/// Check that `signature` over `hash` was made by `expected_address`
+pub fn verify(hash: [u8; 32], signature: &str, expected_address: &str) -> Result<Verification, String> {
+    let recovered_address = recover_address(&hash, signature)?;
+    Ok(Verification {
+        valid: recovered_address.eq_ignore_ascii_case(expected_address.trim()),
+        recovered_address,
+        hash: format!("0x{}", hex::encode(hash)),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The "Ether Mail" example from EIP-712
+    fn mail() -> Value {
+        serde_json::json!({
+            "types": {
+                "EIP712Domain": [
+                    { "name": "name", "type": "string" },
+                    { "name": "version", "type": "string" },
+                    { "name": "chainId", "type": "uint256" },
+                    { "name": "verifyingContract", "type": "address" }
+                ],
+                "Person": [
+                    { "name": "name", "type": "string" },
+                    { "name": "wallet", "type": "address" }
+                ],
+                "Mail": [
+                    { "name": "from", "type": "Person" },
+                    { "name": "to", "type": "Person" },
+                    { "name": "contents", "type": "string" }
+                ]
+            },
+            "primaryType": "Mail",
+            "domain": {
+                "name": "Ether Mail",
+                "version": "1",
+                "chainId": 1,
+                "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+            },
+            "message": {
+                "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+                "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+                "contents": "Hello, Bob!"
+            }
+        })
+    }
+
+    #[test]
+    fn test_typed_data_hash_and_recovery() {
+        let typed_data = mail();
+        let types = typed_data["types"].as_object().unwrap();
+        assert_eq!(
+            encode_type(types, "Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+
+        let hash = hash_typed_data(&typed_data).unwrap();
+        assert_eq!(hex::encode(hash), "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2");
+
+        let signature = "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d\
+                         07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562\
+                         1c";
+        let result = verify(hash, signature, "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826").unwrap();
+        assert!(result.valid);
+        assert_eq!(result.recovered_address, "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826");
+    }
+
+    #[test]
+    fn test_encode_integer() {
+        assert_eq!(encode_integer(&serde_json::json!(-1), true).unwrap(), [0xff; 32]);
+        assert_eq!(encode_integer(&serde_json::json!("0x0100"), false).unwrap()[30..], [1, 0]);
+        assert_eq!(encode_integer(&serde_json::json!("256"), false).unwrap()[30..], [1, 0]);
+        assert!(encode_integer(&serde_json::json!("-1"), false).is_err());
+    }
+}
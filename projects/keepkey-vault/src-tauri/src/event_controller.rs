@@ -1,68 +1,298 @@
 use keepkey_rust::friendly_usb::FriendlyUsbDevice;
+use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::time::interval;
 use tokio_util::sync::CancellationToken;
+use crate::status_messages::StatusCode;
+use crate::device::errors::{DeviceError, DeviceErrorCode};
+
+/// Preference key holding event controller timings
+const EVENT_CONTROLLER_PREFERENCE_KEY: &str = "event_controller";
+
+/// Where the event controller gets the list of connected devices from
+pub trait DeviceSource: Send + Sync + 'static {
+    fn list_devices(&self) -> Vec<FriendlyUsbDevice>;
+}
+
+/// Real USB enumeration via keepkey-rust
+pub struct UsbDeviceSource;
+
+impl DeviceSource for UsbDeviceSource {
+    fn list_devices(&self) -> Vec<FriendlyUsbDevice> {
+        keepkey_rust::features::list_connected_devices()
+    }
+}
+
+/// Timings for the event controller
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct EventControllerConfig {
+    /// How long a device may be missing from scans before it counts as disconnected
+    pub disconnect_grace_ms: u64,
+    /// Timeout for the feature fetch after a device connects
+    pub feature_fetch_timeout_ms: u64,
+    /// Delay before the first scan so the frontend can attach listeners
+    pub startup_delay_ms: u64,
+    /// Fixed scan interval; unset follows the polling preference and low-power mode
+    pub scan_interval_ms: Option<u64>,
+}
+
+impl Default for EventControllerConfig {
+    fn default() -> Self {
+        Self {
+            disconnect_grace_ms: 0,
+            feature_fetch_timeout_ms: 5000,
+            startup_delay_ms: 500,
+            scan_interval_ms: None,
+        }
+    }
+}
+
+impl EventControllerConfig {
+    /// Load timings from preferences, falling back to defaults
+    pub fn from_preferences() -> Self {
+        crate::preferences::get_as(EVENT_CONTROLLER_PREFERENCE_KEY).unwrap_or_default()
+    }
+
+    fn scan_interval(&self) -> Duration {
+        self.scan_interval_ms.map(Duration::from_millis).unwrap_or_else(crate::power::poll_interval)
+    }
+}
+
+/// Devices that appeared or went away in one scan
+#[derive(Debug, Default)]
+pub struct DeviceChanges {
+    pub connected: Vec<FriendlyUsbDevice>,
+    pub disconnected: Vec<FriendlyUsbDevice>,
+}
+
+/// Outcome of one scan of the device source
+#[derive(Debug)]
+pub struct ScanReport {
+    pub changes: DeviceChanges,
+    /// Everything the scan enumerated
+    pub current: Vec<FriendlyUsbDevice>,
+    /// No devices tracked, including ones within their grace period
+    pub none_tracked: bool,
+}
+
+/// Turns successive device scans into connect/disconnect changes
+///
+/// A device missing from a scan is only reported disconnected once it has
+/// been gone for the grace period, so brief re-enumeration blips are ignored.
+pub struct DeviceTracker {
+    known: Vec<FriendlyUsbDevice>,
+    missing_since: HashMap<String, Instant>,
+    grace: Duration,
+}
+
+impl DeviceTracker {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            known: Vec::new(),
+            missing_since: HashMap::new(),
+            grace,
+        }
+    }
+
+    pub fn set_grace(&mut self, grace: Duration) {
+        self.grace = grace;
+    }
+
+    pub fn update(&mut self, current: &[FriendlyUsbDevice], now: Instant) -> DeviceChanges {
+        let mut changes = DeviceChanges::default();
+
+        for device in current {
+            self.missing_since.remove(&device.unique_id);
+            if !self.known.iter().any(|d| d.unique_id == device.unique_id) {
+                self.known.push(device.clone());
+                changes.connected.push(device.clone());
+            }
+        }
+
+        let missing_since = &mut self.missing_since;
+        let grace = self.grace;
+        self.known.retain(|device| {
+            if current.iter().any(|d| d.unique_id == device.unique_id) {
+                return true;
+            }
+            let since = *missing_since.entry(device.unique_id.clone()).or_insert(now);
+            if now.duration_since(since) >= grace {
+                missing_since.remove(&device.unique_id);
+                changes.disconnected.push(device.clone());
+                false
+            } else {
+                true
+            }
+        });
+
+        changes
+    }
+
+    /// Whether no devices are tracked (including ones within their grace period)
+    pub fn is_empty(&self) -> bool {
+        self.known.is_empty()
+    }
+}
+
+/// First retry delay after a failed feature fetch
+const FETCH_BACKOFF_BASE: Duration = Duration::from_secs(2);
+
+/// Longest delay between feature fetch retries
+const FETCH_BACKOFF_MAX: Duration = Duration::from_secs(300);
+
+/// Consecutive failures before a device is reported unreachable
+const UNREACHABLE_AFTER_FAILURES: u32 = 3;
+
+/// Consecutive feature fetch failures for a device and when to retry
+struct FetchBackoff {
+    failures: u32,
+    next_attempt: Option<Instant>,
+}
+
+lazy_static::lazy_static! {
+    static ref FETCH_BACKOFF: Mutex<HashMap<String, FetchBackoff>> = Mutex::new(HashMap::new());
+}
+
+/// Capped exponential delay before the next retry
+fn backoff_delay(failures: u32) -> Duration {
+    let exponent = failures.saturating_sub(1).min(16);
+    FETCH_BACKOFF_BASE.saturating_mul(1 << exponent).min(FETCH_BACKOFF_MAX)
+}
+
+/// Record a failed fetch and schedule the retry; returns (failures, delay)
+fn record_fetch_failure(device_id: &str) -> (u32, Duration) {
+    let mut backoff = FETCH_BACKOFF.lock().unwrap();
+    let entry = backoff.entry(device_id.to_string()).or_insert(FetchBackoff { failures: 0, next_attempt: None });
+    entry.failures += 1;
+    let delay = backoff_delay(entry.failures);
+    entry.next_attempt = Some(Instant::now() + delay);
+    (entry.failures, delay)
+}
+
+/// Clear a device's backoff; returns true if it had been reported unreachable
+fn record_fetch_success(device_id: &str) -> bool {
+    FETCH_BACKOFF.lock().unwrap()
+        .remove(device_id)
+        .map(|b| b.failures >= UNREACHABLE_AFTER_FAILURES)
+        .unwrap_or(false)
+}
+
+/// Connected devices whose retry is due; each is handed out once per failure
+fn take_due_retries(connected: &[FriendlyUsbDevice]) -> Vec<FriendlyUsbDevice> {
+    let mut backoff = FETCH_BACKOFF.lock().unwrap();
+    let now = Instant::now();
+    connected
+        .iter()
+        .filter(|device| match backoff.get_mut(&device.unique_id) {
+            Some(entry) if entry.next_attempt.map_or(false, |at| at <= now) => {
+                entry.next_attempt = None;
+                true
+            }
+            _ => false,
+        })
+        .cloned()
+        .collect()
+}
 
 pub struct EventController {
     cancellation_token: CancellationToken,
-    task_handle: Option<tauri::async_runtime::JoinHandle<()>>,
+    task_handles: Vec<tauri::async_runtime::JoinHandle<()>>,
     is_running: bool,
+    source: Arc<dyn DeviceSource>,
+    config: EventControllerConfig,
 }
 
 impl EventController {
     pub fn new() -> Self {
+        Self::with_source(Arc::new(UsbDeviceSource), EventControllerConfig::from_preferences())
+    }
+    
+    /// Build a controller over any device source, e.g. a scripted one in tests
+    pub fn with_source(source: Arc<dyn DeviceSource>, config: EventControllerConfig) -> Self {
         Self {
             cancellation_token: CancellationToken::new(),
-            task_handle: None,
+            task_handles: Vec::new(),
             is_running: false,
+            source,
+            config,
         }
     }
     
-    pub fn start(&mut self, app: &AppHandle) {
+    /// Start scanning the device source; every scan is reported on the returned channel.
+    /// Returns None if the controller is already running.
+    pub fn start_scanning(&mut self) -> Option<tokio::sync::mpsc::UnboundedReceiver<ScanReport>> {
         if self.is_running {
             println!("⚠️ Event controller already running");
-            return;
+            return None;
         }
         
+        let (reports, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let cancellation_token = self.cancellation_token.clone();
+        let source = self.source.clone();
+        let config = self.config.clone();
+        let mut preference_changes = crate::preferences::subscribe();
+        
+        let task_handle = tauri::async_runtime::spawn(async move {
+            let mut tracker = DeviceTracker::new(Duration::from_millis(config.disconnect_grace_ms));
+            tokio::time::sleep(Duration::from_millis(config.startup_delay_ms)).await;
+            
+            loop {
+                tokio::select! {
+                    _ = cancellation_token.cancelled() => break,
+                    // Pick up grace period changes without a restart
+                    Ok(change) = preference_changes.recv() => {
+                        if change.key == EVENT_CONTROLLER_PREFERENCE_KEY {
+                            tracker.set_grace(Duration::from_millis(EventControllerConfig::from_preferences().disconnect_grace_ms));
+                        }
+                    }
+                    _ = tokio::time::sleep(config.scan_interval()) => {
+                        let current = source.list_devices();
+                        let changes = tracker.update(&current, Instant::now());
+                        crate::readiness::mark_ready(crate::readiness::Gate::DeviceMonitor);
+                        
+                        let report = ScanReport { changes, current, none_tracked: tracker.is_empty() };
+                        if reports.send(report).is_err() {
+                            break;
+                        }
+                    }
+                }
+            }
+        });
+        
+        self.task_handles.push(task_handle);
+        self.is_running = true;
+        Some(receiver)
+    }
+    
+    pub fn start(&mut self, app: &AppHandle) {
+        let Some(mut reports) = self.start_scanning() else {
+            return;
+        };
+        
         let app_handle = app.clone();
         let cancellation_token = self.cancellation_token.clone();
+        let config = self.config.clone();
+        let mut fetch_timeout = Duration::from_millis(config.feature_fetch_timeout_ms);
+        let mut preference_changes = crate::preferences::subscribe();
         
         let task_handle = tauri::async_runtime::spawn(async move {
-            let mut interval = interval(Duration::from_millis(1000)); // Check every second
-            let mut last_devices: Vec<FriendlyUsbDevice> = Vec::new();
+            let mut low_power = crate::power::is_low_power();
             
             println!("✅ Event controller started - monitoring device connections");
             
             // Wait a moment for frontend to set up listeners, then emit initial scanning status
-            tokio::time::sleep(Duration::from_millis(500)).await;
+            tokio::time::sleep(Duration::from_millis(config.startup_delay_ms)).await;
             println!("📡 Emitting status: Scanning for devices...");
-            let scanning_payload = serde_json::json!({
-                "status": "Scanning for devices..."
-            });
+            let scanning_payload = crate::status_messages::status_payload(StatusCode::Scanning, &[]);
             println!("📡 Scanning payload: {}", scanning_payload);
             if let Err(e) = app_handle.emit("status:update", scanning_payload) {
                 println!("❌ Failed to emit scanning status: {}", e);
             } else {
                 println!("✅ Successfully emitted scanning status");
             }
-
-            // Test emission after longer delay to check if frontend is listening
-//             let app_for_test = app_handle.clone();
-//             tokio::spawn(async move {
-//                 tokio::time::sleep(Duration::from_millis(3000)).await;
-//                 println!("📡 Test: Emitting delayed test status...");
-//                 let test_payload = serde_json::json!({
-//                     "status": "Test message after 3 seconds"
-//                 });
-//                 println!("📡 Test payload: {}", test_payload);
-//                 if let Err(e) = app_for_test.emit("status:update", test_payload) {
-//                     println!("❌ Failed to emit delayed test status: {}", e);
-//                 } else {
-//                     println!("✅ Successfully emitted delayed test status");
-//                 }
-//             });
             
             loop {
                 tokio::select! {
@@ -70,322 +300,174 @@ impl EventController {
                         println!("🛑 Event controller shutting down on cancellation signal");
                         break;
                     }
-                    _ = interval.tick() => {
-                        // Get current devices using high-level API
-                        let current_devices = keepkey_rust::features::list_connected_devices();
+                    // Pick up timing changes without a restart
+                    Ok(change) = preference_changes.recv() => {
+                        if change.key == EVENT_CONTROLLER_PREFERENCE_KEY {
+                            let updated = EventControllerConfig::from_preferences();
+                            fetch_timeout = Duration::from_millis(updated.feature_fetch_timeout_ms);
+                            log::info!("⚙️ Event controller config reloaded: {:?}", updated);
+                        }
+                    }
+                    report = reports.recv() => {
+                        let Some(report) = report else {
+                            break;
+                        };
                         
                         // Check for newly connected devices
-                        for device in &current_devices {
-                            if !last_devices.iter().any(|d| d.unique_id == device.unique_id) {
-                                println!("🔌 Device connected: {} (VID: 0x{:04x}, PID: 0x{:04x})", 
-                                         device.unique_id, device.vid, device.pid);
-                                println!("   Device info: {} - {}", 
-                                         device.manufacturer.as_deref().unwrap_or("Unknown"), 
-                                         device.product.as_deref().unwrap_or("Unknown"));
+                        for device in &report.changes.connected {
+                            println!("🔌 Device connected: {} (VID: 0x{:04x}, PID: 0x{:04x})", 
+                                     device.unique_id, device.vid, device.pid);
+                            println!("   Device info: {} - {}", 
+                                     device.manufacturer.as_deref().unwrap_or("Unknown"), 
+                                     device.product.as_deref().unwrap_or("Unknown"));
+                            
+                            // Check if this might be a recovery device reconnecting with a different ID
+                            if let Some(state) = app_handle.try_state::<crate::commands::DeviceQueueManager>() {
+                                let queue_manager_arc = state.inner().clone();
+                                let manager = queue_manager_arc.lock().await;
                                 
-                                // Check if this might be a recovery device reconnecting with a different ID
-                                if let Some(state) = app_handle.try_state::<crate::commands::DeviceQueueManager>() {
-                                    let queue_manager_arc = state.inner().clone();
-                                    let manager = queue_manager_arc.lock().await;
-                                    
-                                    // Check if any existing device might be the same physical device
-                                    for (existing_id, _) in manager.iter() {
-                                        if crate::commands::are_devices_potentially_same(&device.unique_id, existing_id) &&
-                                           crate::commands::is_device_in_recovery_flow(existing_id) {
-                                            println!("🔄 Device {} appears to be recovery device {} reconnecting", 
-                                                    device.unique_id, existing_id);
-                                            let _ = crate::commands::add_recovery_device_alias(&device.unique_id, existing_id);
-                                            
-                                            // Emit special reconnection event
-                                            let _ = app_handle.emit("device:recovery-reconnected", serde_json::json!({
-                                                "new_id": &device.unique_id,
-                                                "original_id": existing_id,
-                                                "status": "reconnected"
-                                            }));
-                                        }
+                                // Check if any existing device might be the same physical device
+                                for (existing_id, _) in manager.iter() {
+                                    if crate::commands::are_devices_potentially_same(&device.unique_id, existing_id) &&
+                                       crate::commands::is_device_in_recovery_flow(existing_id) {
+                                        println!("🔄 Device {} appears to be recovery device {} reconnecting", 
+                                                device.unique_id, existing_id);
+                                        let _ = crate::commands::add_recovery_device_alias(&device.unique_id, existing_id);
+                                        
+                                        // Keep cached data attributed to the original ID across restarts
+                                        let alias_app = app_handle.clone();
+                                        let alias_id = device.unique_id.clone();
+                                        let canonical_id = existing_id.clone();
+                                        tauri::async_runtime::spawn(async move {
+                                            if let Err(e) = crate::commands::persist_device_alias(&alias_app, &alias_id, &canonical_id, "recovery-reconnect").await {
+                                                println!("⚠️ Failed to persist device alias: {}", e);
+                                            }
+                                        });
+                                        
+                                        // Emit special reconnection event
+                                        let _ = app_handle.emit("device:recovery-reconnected", serde_json::json!({
+                                            "new_id": &device.unique_id,
+                                            "original_id": existing_id,
+                                            "status": "reconnected"
+                                        }));
                                     }
                                 }
-                                
-                                // Emit device found status
-                                let device_short = &device.unique_id[device.unique_id.len().saturating_sub(8)..];
-                                println!("📡 Emitting status: Device found {}", device_short);
-                                let device_found_payload = serde_json::json!({
-                                    "status": format!("Device found {}", device_short)
-                                });
-                                println!("📡 Device found payload: {}", device_found_payload);
-                                if let Err(e) = app_handle.emit("status:update", device_found_payload) {
-                                    println!("❌ Failed to emit device found status: {}", e);
-                                } else {
-                                    println!("✅ Successfully emitted device found status");
-                                }
-                                
-                                // Emit basic device connected event first
-                                let _ = app_handle.emit("device:connected", device);
-                                
-                                // Proactively fetch features and emit device:ready when successful
-                                let app_for_task = app_handle.clone();
-                                let device_for_task = device.clone();
-                                tokio::spawn(async move {
-                                    println!("📡 Fetching device features for: {}", device_for_task.unique_id);
-                                    
-                                    // Emit getting features status
-                                    println!("📡 Emitting status: Getting features...");
-                                    if let Err(e) = app_for_task.emit("status:update", serde_json::json!({
-                                        "status": "Getting features..."
-                                    })) {
-                                        println!("❌ Failed to emit getting features status: {}", e);
-                                    }
-                                    
-                                    match try_get_device_features(&device_for_task, &app_for_task).await {
-                                        Ok(features) => {
-                                            let device_label = features.label.as_deref().unwrap_or("Unlabeled");
-                                            let device_version = &features.version;
-                                            
-                                            println!("📡 Got device features: {} v{} ({})", 
-                                                   device_label,
-                                                   device_version,
-                                                   device_for_task.unique_id);
-                                            
-                                            // Emit device info status
-                                            println!("📡 Emitting status: {} v{}", device_label, device_version);
-                                            if let Err(e) = app_for_task.emit("status:update", serde_json::json!({
-                                                "status": format!("{} v{}", device_label, device_version)
-                                            })) {
-                                                println!("❌ Failed to emit device info status: {}", e);
-                                            }
-                                            
-                                            // Evaluate device status to determine if updates are needed
-                                            let status = crate::commands::evaluate_device_status(
-                                                device_for_task.unique_id.clone(), 
-                                                Some(&features)
-                                            );
-                                            
-                                                                        // Check if device is locked with PIN before determining if it's ready
-                            let has_pin_protection = features.pin_protection;
-                            let pin_cached = features.pin_cached;
-                            let is_pin_locked = features.initialized && has_pin_protection && !pin_cached;
+                            }
                             
-                            // Emit status updates based on what the device needs
-                            // CRITICAL: Device in bootloader mode is NEVER ready
-                            let is_actually_ready = !features.bootloader_mode &&  // Never ready if in bootloader mode
-                                                   !status.needs_bootloader_update && 
-                                                   !status.needs_firmware_update && 
-                                                   !status.needs_initialization &&
-                                                   !is_pin_locked;  // Device is NOT ready if locked with PIN
+                            // Retire workers left behind if this device re-enumerated under a new ID
+                            if let Some(state) = app_handle.try_state::<crate::commands::DeviceQueueManager>() {
+                                crate::device::reenumeration::retire_stale_workers(&app_handle, device, &report.current, state.inner()).await;
+                            }
                             
-                            if is_actually_ready {
-                                                println!("✅ Device is fully ready, emitting device:ready event");
-                                                println!("📡 Emitting status: Device ready");
-                                                if let Err(e) = app_for_task.emit("status:update", serde_json::json!({
-                                                    "status": "Device ready"
-                                                })) {
-                                                    println!("❌ Failed to emit device ready status: {}", e);
-                                                }
-                                                                                let ready_payload = serde_json::json!({
-                                    "device": device_for_task,
-                                    "features": features,
-                                    "status": "ready"
-                                });
-                                
-                                // Queue device:ready event as it's important for wallet initialization
-                                if let Err(e) = crate::commands::emit_or_queue_event(&app_for_task, "device:ready", ready_payload).await {
-                                    println!("❌ Failed to emit/queue device:ready event: {}", e);
-                                } else {
-                                    println!("📡 Successfully emitted/queued device:ready for {}", device_for_task.unique_id);
-                                }
-                                            } else {
-                                                                                println!("⚠️ Device connected but needs updates (bootloader_mode: {}, bootloader: {}, firmware: {}, init: {}, pin_locked: {})", 
-                                        features.bootloader_mode,
-                                        status.needs_bootloader_update, 
-                                        status.needs_firmware_update, 
-                                        status.needs_initialization,
-                                        is_pin_locked);
-                                                
-                                                if is_pin_locked {
-                                                    println!("🔒 Device is initialized but locked with PIN - emitting unlock event");
-                                                    
-                                                    // Emit PIN unlock needed event
-                                                    let pin_unlock_payload = serde_json::json!({
-                                                        "deviceId": device_for_task.unique_id,
-                                                        "features": features,
-                                                        "status": status,
-                                                        "needsPinUnlock": true
-                                                    });
-                                                    
-                                                    if let Err(e) = crate::commands::emit_or_queue_event(&app_for_task, "device:pin-unlock-needed", pin_unlock_payload).await {
-                                                        println!("❌ Failed to emit/queue device:pin-unlock-needed event: {}", e);
-                                                    } else {
-                                                        println!("📡 Successfully emitted/queued device:pin-unlock-needed for {}", device_for_task.unique_id);
-                                                    }
-                                                }
-                                                
-                                                // Emit appropriate status message based on what updates are needed
-                                                let status_message = if features.bootloader_mode {
-                                                    if status.needs_bootloader_update {
-                                                        "Device in bootloader mode - update needed"
-                                                    } else {
-                                                        "Device in bootloader mode - reboot needed"
-                                                    }
-                                                } else if is_pin_locked {
-                                                    "Device locked - enter PIN"
-                                                } else if status.needs_bootloader_update && status.needs_firmware_update && status.needs_initialization {
-                                                    "Device needs updates"
-                                                } else if status.needs_bootloader_update {
-                                                    "Bootloader update needed"
-                                                } else if status.needs_firmware_update {
-                                                    "Firmware update needed"
-                                                } else if status.needs_initialization {
-                                                    "Device setup needed"
-                                                } else {
-                                                    "Device ready"
-                                                };
-                                                
-                                                println!("📡 Emitting status: {}", status_message);
-                                                if let Err(e) = app_for_task.emit("status:update", serde_json::json!({
-                                                    "status": status_message
-                                                })) {
-                                                    println!("❌ Failed to emit update status: {}", e);
-                                                }
-                                            }
-                                            
-                                                                        // Emit device:features-updated event with evaluated status (for DeviceUpdateManager)
-                            // This is a critical event that should be queued if frontend isn't ready
-                            let features_payload = serde_json::json!({
-                                "deviceId": device_for_task.unique_id,
-                                "features": features,
-                                "status": status  // Use evaluated status instead of hardcoded "ready"
-                            });
+                            // Apply the passphrase cache policy before any operation can unlock the device
+                            crate::device::passphrase_policy::apply_to_device(&device.unique_id);
                             
-                            if let Err(e) = crate::commands::emit_or_queue_event(&app_for_task, "device:features-updated", features_payload).await {
-                                println!("❌ Failed to emit/queue device:features-updated event: {}", e);
+                            // Emit device found status
+                            let device_short = &device.unique_id[device.unique_id.len().saturating_sub(8)..];
+                            println!("📡 Emitting status: Device found {}", device_short);
+                            let device_found_payload = crate::status_messages::status_payload(StatusCode::DeviceFound, &[("device", device_short)]);
+                            println!("📡 Device found payload: {}", device_found_payload);
+                            if let Err(e) = app_handle.emit("status:update", device_found_payload) {
+                                println!("❌ Failed to emit device found status: {}", e);
                             } else {
-                                println!("📡 Successfully emitted/queued device:features-updated for {}", device_for_task.unique_id);
+                                println!("✅ Successfully emitted device found status");
                             }
-                                        }
-                                        Err(e) => {
-                                            println!("❌ Failed to get features for {}: {}", device_for_task.unique_id, e);
-                                            
-                                            // Check for timeout errors specifically
-                                            if e.contains("Timeout while fetching device features") {
-                                                println!("⏱️ Device timeout detected - device may be in invalid state");
-                                                println!("❌ OOPS this should never happen - device communication failed!");
-                                                
-                                                // Log detailed error for debugging
-                                                eprintln!("ERROR: Device timeout indicates invalid state - this should be prevented!");
-                                                eprintln!("Device ID: {}", device_for_task.unique_id);
-                                                eprintln!("Error: {}", e);
-                                                
-                                                // Emit device invalid state event for UI to handle
-                                                let invalid_state_payload = serde_json::json!({
-                                                    "deviceId": device_for_task.unique_id,
-                                                    "error": e,
-                                                    "errorType": "DEVICE_TIMEOUT",
-                                                    "status": "invalid_state"
-                                                });
-                                                let _ = app_for_task.emit("device:invalid-state", &invalid_state_payload);
-                                                
-                                                // Also emit status update
-                                                let _ = app_for_task.emit("status:update", serde_json::json!({
-                                                    "status": "Device timeout - please reconnect"
-                                                }));
-                                            }
-                                            // Check if this is a device access error
-                                            else if e.contains("Device Already In Use") || 
-                                               e.contains("already claimed") ||
-                                               e.contains("🔒") {
-                                                
-                                                let user_friendly_error = if e.contains("🔒") {
-                                                    e.clone()
-                                                } else {
-                                                    format!(
-                                                        "🔒 KeepKey Device Already In Use\n\n\
-                                                        Your KeepKey device is currently being used by another application.\n\n\
-                                                        Common causes:\n\
-                                                        • KeepKey Desktop app is running\n\
-                                                        • KeepKey Bridge is running\n\
-                                                        • Another wallet application is connected\n\
-                                                        • Previous connection wasn't properly closed\n\n\
-                                                        Solutions:\n\
-                                                        1. Close KeepKey Desktop app completely\n\
-                                                        2. Close any other wallet applications\n\
-                                                        3. Unplug and reconnect your KeepKey device\n\
-                                                        4. Try again\n\n\
-                                                        Technical details: {}", e
-                                                    )
-                                                };
-                                                
-                                                // Emit device access error event
-                                                let error_payload = serde_json::json!({
-                                                    "deviceId": device_for_task.unique_id,
-                                                    "error": user_friendly_error,
-                                                    "errorType": "DEVICE_CLAIMED",
-                                                    "status": "error"
-                                                });
-                                                let _ = app_for_task.emit("device:access-error", &error_payload);
-                                            }
-                                        }
-                                    }
+                            
+                            // Emit basic device connected event first
+                            let _ = app_handle.emit("device:connected", device);
+                            crate::automation::dispatch_event("device:connected", &serde_json::json!(device));
+                            crate::notifications::notify_event("device:connected", &serde_json::json!(device));
+                            {
+                                let device_id = device.unique_id.clone();
+                                tokio::spawn(async move {
+                                    crate::activity::record(&device_id, crate::activity::ActivityKind::Connected, "Device connected", None).await;
                                 });
                             }
+                            
+                            // Proactively fetch features and emit device:ready when successful
+                            spawn_feature_fetch(app_handle.clone(), device.clone(), fetch_timeout);
                         }
                         
                         // Check for disconnected devices
-                        for device in &last_devices {
-                            if !current_devices.iter().any(|d| d.unique_id == device.unique_id) {
-                                println!("🔌❌ Device disconnected: {}", device.unique_id);
-                                
-                                // Check if device is in recovery flow before cleaning up
-                                let is_in_recovery = crate::commands::is_device_in_recovery_flow(&device.unique_id);
-                                
-                                if is_in_recovery {
-                                    println!("🛡️ Device {} is in recovery flow - preserving queue and state", device.unique_id);
-                                    // Don't emit disconnection or clean up queue - just wait for reconnection
-                                    continue;
-                                }
-                                
-                                // Emit device disconnected status
-                                println!("📡 Emitting status: Device disconnected");
-                                if let Err(e) = app_handle.emit("status:update", serde_json::json!({
-                                    "status": "Device disconnected"
-                                })) {
-                                    println!("❌ Failed to emit disconnect status: {}", e);
-                                }
-                                
-                                // Clean up device queue for disconnected device
-                                if let Some(state) = app_handle.try_state::<crate::commands::DeviceQueueManager>() {
-                                    let device_id = device.unique_id.clone();
-                                    // Clone the underlying Arc so it outlives this scope
-                                    let queue_manager_arc = state.inner().clone();
-                                    tokio::spawn(async move {
-                                        println!("♻️ Cleaning up device queue for disconnected device: {}", device_id);
-                                        let mut manager = queue_manager_arc.lock().await;
-                                        if let Some(handle) = manager.remove(&device_id) {
-                                            let _ = handle.shutdown().await;
-                                            println!("✅ Device queue cleaned up for: {}", device_id);
-                                        }
-                                    });
-                                }
-                                
-                                let _ = app_handle.emit("device:disconnected", &device.unique_id);
+                        for device in &report.changes.disconnected {
+                            println!("🔌❌ Device disconnected: {}", device.unique_id);
+                            
+                            // Check if device is in recovery flow before cleaning up
+                            let is_in_recovery = crate::commands::is_device_in_recovery_flow(&device.unique_id);
+                            
+                            if is_in_recovery {
+                                println!("🛡️ Device {} is in recovery flow - preserving queue and state", device.unique_id);
+                                // Don't emit disconnection or clean up queue - just wait for reconnection
+                                continue;
+                            }
+                            
+                            // Emit device disconnected status
+                            println!("📡 Emitting status: Device disconnected");
+                            if let Err(e) = app_handle.emit("status:update", crate::status_messages::status_payload(StatusCode::DeviceDisconnected, &[])) {
+                                println!("❌ Failed to emit disconnect status: {}", e);
+                            }
+                            
+                            // Clean up device queue for disconnected device
+                            if let Some(state) = app_handle.try_state::<crate::commands::DeviceQueueManager>() {
+                                let device_id = device.unique_id.clone();
+                                // Clone the underlying Arc so it outlives this scope
+                                let queue_manager_arc = state.inner().clone();
+                                tokio::spawn(async move {
+                                    println!("♻️ Cleaning up device queue for disconnected device: {}", device_id);
+                                    let mut manager = queue_manager_arc.lock().await;
+                                    if let Some(handle) = manager.remove(&device_id) {
+                                        let _ = handle.shutdown().await;
+                                        println!("✅ Device queue cleaned up for: {}", device_id);
+                                    }
+                                });
+                            }
+                            
+                            crate::device::preflight::record_disconnect(&device.unique_id);
+                            record_fetch_success(&device.unique_id);
+                            let _ = app_handle.emit("device:disconnected", &device.unique_id);
+                            crate::automation::dispatch_event("device:disconnected", &serde_json::json!({ "deviceId": device.unique_id }));
+                            crate::notifications::notify_event("device:disconnected", &serde_json::json!({ "deviceId": device.unique_id }));
+                            {
+                                let device_id = device.unique_id.clone();
+                                tokio::spawn(async move {
+                                    crate::activity::record(&device_id, crate::activity::ActivityKind::Disconnected, "Device disconnected", None).await;
+                                });
                             }
                         }
                         
                         // If no devices connected after checking disconnections, emit scanning status
-                        if current_devices.is_empty() && !last_devices.is_empty() {
+                        if report.none_tracked && !report.changes.disconnected.is_empty() {
                             // After a short delay, go back to scanning
                             let app_for_scanning = app_handle.clone();
                             tokio::spawn(async move {
                                 tokio::time::sleep(Duration::from_millis(1000)).await;
                                 println!("📡 Emitting status: Scanning for devices... (after disconnect)");
-                                if let Err(e) = app_for_scanning.emit("status:update", serde_json::json!({
-                                    "status": "Scanning for devices..."
-                                })) {
+                                if let Err(e) = app_for_scanning.emit("status:update", crate::status_messages::status_payload(StatusCode::Scanning, &[])) {
                                     println!("❌ Failed to emit scanning status after disconnect: {}", e);
                                 }
                             });
                         }
                         
-                        last_devices = current_devices;
+                        // Run frontloads deferred during low-power mode once normal power is back
+                        let now_low_power = crate::power::is_low_power();
+                        if now_low_power != low_power {
+                            low_power = now_low_power;
+                            println!("🔋 Low-power mode {}", if low_power { "enabled" } else { "disabled" });
+                            let _ = app_handle.emit("power:low-power-changed", serde_json::json!({ "active": low_power }));
+                            
+                            if !low_power {
+                                for device_id in crate::power::take_deferred_frontloads() {
+                                    if report.current.iter().any(|d| d.unique_id == device_id) {
+                                        crate::cache::schedule::maybe_auto_frontload(&app_handle, &device_id).await;
+                                    }
+                                }
+                            }
+                        }
+                        
+                        // Retry feature fetches for connected devices whose backoff has elapsed
+                        for device in take_due_retries(&report.current) {
+                            spawn_feature_fetch(app_handle.clone(), device, fetch_timeout);
+                        }
                     }
                 }
             }
@@ -393,8 +475,7 @@ impl EventController {
             println!("✅ Event controller stopped cleanly");
         });
         
-        self.task_handle = Some(task_handle);
-        self.is_running = true;
+        self.task_handles.push(task_handle);
     }
     
     pub fn stop(&mut self) {
@@ -404,12 +485,12 @@ impl EventController {
         
         println!("🛑 Stopping event controller...");
         
-        // Cancel the background task
+        // Cancel the background tasks
         self.cancellation_token.cancel();
         self.is_running = false;
         
-        // Wait for the task to complete if it exists
-        if let Some(handle) = self.task_handle.take() {
+        // Wait for the scan and event tasks to complete
+        for handle in self.task_handles.drain(..) {
             // Try to wait for completion with a timeout
             tauri::async_runtime::spawn(async move {
                 if let Err(e) = tokio::time::timeout(Duration::from_secs(5), handle).await {
@@ -428,10 +509,233 @@ impl Drop for EventController {
     }
 }
 
+/// Fetch features for a device in the background, emitting device:ready or the
+/// relevant error events
+fn spawn_feature_fetch(app_for_task: AppHandle, device_for_task: FriendlyUsbDevice, fetch_timeout: Duration) {
+    tokio::spawn(async move {
+        println!("📡 Fetching device features for: {}", device_for_task.unique_id);
+        
+        // Emit getting features status
+        println!("📡 Emitting status: Getting features...");
+        if let Err(e) = app_for_task.emit("status:update", crate::status_messages::status_payload(StatusCode::GettingFeatures, &[])) {
+            println!("❌ Failed to emit getting features status: {}", e);
+        }
+        
+        match try_get_device_features(&device_for_task, &app_for_task, fetch_timeout).await {
+            Ok(features) => {
+                let device_label = features.label.as_deref().unwrap_or("Unlabeled");
+                let device_version = &features.version;
+                
+                println!("📡 Got device features: {} v{} ({})", 
+                       device_label,
+                       device_version,
+                       device_for_task.unique_id);
+                
+                crate::device::capabilities::update_cached_capabilities(&device_for_task.unique_id, &features);
+                
+                if record_fetch_success(&device_for_task.unique_id) {
+                    let _ = app_for_task.emit("device:reachable", serde_json::json!({ "deviceId": device_for_task.unique_id }));
+                }
+                
+                // Emit device info status
+                println!("📡 Emitting status: {} v{}", device_label, device_version);
+                if let Err(e) = app_for_task.emit("status:update", crate::status_messages::status_payload(
+                    StatusCode::DeviceInfo,
+                    &[("label", device_label), ("version", device_version.as_str())],
+                )) {
+                    println!("❌ Failed to emit device info status: {}", e);
+                }
+                
+                // Evaluate device status to determine if updates are needed
+                let status = crate::commands::evaluate_device_status(
+                    device_for_task.unique_id.clone(), 
+                    Some(&features)
+                );
+                
+                // Check if device is locked with PIN before determining if it's ready
+                let has_pin_protection = features.pin_protection;
+                let pin_cached = features.pin_cached;
+                let is_pin_locked = features.initialized && has_pin_protection && !pin_cached;
+
+                // Emit status updates based on what the device needs
+                // CRITICAL: Device in bootloader mode is NEVER ready
+                let is_actually_ready = !features.bootloader_mode &&  // Never ready if in bootloader mode
+                    !status.needs_bootloader_update &&
+                    !status.needs_firmware_update &&
+                    !status.needs_initialization &&
+                    !is_pin_locked;  // Device is NOT ready if locked with PIN
+
+                if is_actually_ready {
+                    println!("✅ Device is fully ready, emitting device:ready event");
+                    println!("📡 Emitting status: Device ready");
+                    if let Err(e) = app_for_task.emit("status:update", crate::status_messages::status_payload(StatusCode::DeviceReady, &[])) {
+                        println!("❌ Failed to emit device ready status: {}", e);
+                    }
+                    let ready_payload = serde_json::json!({
+                        "device": device_for_task,
+                        "features": features,
+                        "status": "ready"
+                    });
+                    
+                    // Queue device:ready event as it's important for wallet initialization
+                    if let Err(e) = crate::commands::emit_or_queue_event(&app_for_task, "device:ready", ready_payload).await {
+                        println!("❌ Failed to emit/queue device:ready event: {}", e);
+                    } else {
+                        println!("📡 Successfully emitted/queued device:ready for {}", device_for_task.unique_id);
+                    }
+                    
+                    // Warm the pubkey cache if this device's frontload schedule allows it
+                    crate::cache::schedule::maybe_auto_frontload(&app_for_task, &device_for_task.unique_id).await;
+                } else {
+                    println!("⚠️ Device connected but needs updates (bootloader_mode: {}, bootloader: {}, firmware: {}, init: {}, pin_locked: {})",
+                        features.bootloader_mode,
+                        status.needs_bootloader_update,
+                        status.needs_firmware_update,
+                        status.needs_initialization,
+                        is_pin_locked);
+                    
+                    if is_pin_locked {
+                        println!("🔒 Device is initialized but locked with PIN - emitting unlock event");
+                        
+                        // Emit PIN unlock needed event
+                        let pin_unlock_payload = serde_json::json!({
+                            "deviceId": device_for_task.unique_id,
+                            "features": features,
+                            "status": status,
+                            "needsPinUnlock": true
+                        });
+                        
+                        if let Err(e) = crate::commands::emit_or_queue_event(&app_for_task, "device:pin-unlock-needed", pin_unlock_payload).await {
+                            println!("❌ Failed to emit/queue device:pin-unlock-needed event: {}", e);
+                        } else {
+                            println!("📡 Successfully emitted/queued device:pin-unlock-needed for {}", device_for_task.unique_id);
+                        }
+                    }
+                    
+                    // Emit appropriate status message based on what updates are needed
+                    let status_code = if features.bootloader_mode {
+                        if status.needs_bootloader_update {
+                            StatusCode::BootloaderModeUpdateNeeded
+                        } else {
+                            StatusCode::BootloaderModeRebootNeeded
+                        }
+                    } else if is_pin_locked {
+                        StatusCode::PinLocked
+                    } else if status.needs_bootloader_update && status.needs_firmware_update && status.needs_initialization {
+                        StatusCode::UpdatesNeeded
+                    } else if status.needs_bootloader_update {
+                        StatusCode::BootloaderUpdateNeeded
+                    } else if status.needs_firmware_update {
+                        StatusCode::FirmwareUpdateNeeded
+                    } else if status.needs_initialization {
+                        StatusCode::SetupNeeded
+                    } else {
+                        StatusCode::DeviceReady
+                    };
+                    
+                    println!("📡 Emitting status: {}", status_code.as_str());
+                    if let Err(e) = app_for_task.emit("status:update", crate::status_messages::status_payload(status_code, &[])) {
+                        println!("❌ Failed to emit update status: {}", e);
+                    }
+                }
+                
+                // Emit device:features-updated event with evaluated status (for DeviceUpdateManager)
+                // This is a critical event that should be queued if frontend isn't ready
+                let features_payload = serde_json::json!({
+                    "deviceId": device_for_task.unique_id,
+                    "features": features,
+                    "status": status  // Use evaluated status instead of hardcoded "ready"
+                });
+
+                if let Err(e) = crate::commands::emit_or_queue_event(&app_for_task, "device:features-updated", features_payload).await {
+                    println!("❌ Failed to emit/queue device:features-updated event: {}", e);
+                } else {
+                    println!("📡 Successfully emitted/queued device:features-updated for {}", device_for_task.unique_id);
+                }
+            }
+            Err(e) => {
+                println!("❌ Failed to get features for {}: {}", device_for_task.unique_id, e);
+                
+                // Back off before retrying; PIN flows aren't failures, just a busy device
+                if !e.contains("PIN flow") {
+                    let (failures, retry_in) = record_fetch_failure(&device_for_task.unique_id);
+                    println!("⏳ Retrying feature fetch for {} in {}s (failure {})", device_for_task.unique_id, retry_in.as_secs(), failures);
+                    
+                    if failures >= UNREACHABLE_AFTER_FAILURES {
+                        let _ = app_for_task.emit("device:unreachable", serde_json::json!({
+                            "deviceId": device_for_task.unique_id,
+                            "failures": failures,
+                            "retryInSecs": retry_in.as_secs(),
+                            "error": e,
+                        }));
+                    }
+                }
+                
+                // Check for timeout errors specifically
+                if e.contains("Timeout while fetching device features") {
+                    println!("⏱️ Device timeout detected - device may be in invalid state");
+                    println!("❌ OOPS this should never happen - device communication failed!");
+                    
+                    // Log detailed error for debugging
+                    eprintln!("ERROR: Device timeout indicates invalid state - this should be prevented!");
+                    eprintln!("Device ID: {}", device_for_task.unique_id);
+                    eprintln!("Error: {}", e);
+                    
+                    // Emit device invalid state event for UI to handle
+                    let invalid_state_payload = serde_json::json!({
+                        "deviceId": device_for_task.unique_id,
+                        "error": e,
+                        "errorType": "DEVICE_TIMEOUT",
+                        "code": DeviceErrorCode::Timeout,
+                        "status": "invalid_state"
+                    });
+                    let _ = app_for_task.emit("device:invalid-state", &invalid_state_payload);
+                    
+                    // Also emit status update
+                    let _ = app_for_task.emit("status:update", crate::status_messages::status_payload(StatusCode::DeviceTimeout, &[]));
+                }
+                // Check if this is a device access error
+                else if DeviceError::classify(e.as_str()).code == DeviceErrorCode::DeviceBusy {
+                    
+                    let user_friendly_error = if e.contains("🔒") {
+                        e.clone()
+                    } else {
+                        format!(
+                            "🔒 KeepKey Device Already In Use\n\n\
+                            Your KeepKey device is currently being used by another application.\n\n\
+                            Common causes:\n\
+                            • KeepKey Desktop app is running\n\
+                            • KeepKey Bridge is running\n\
+                            • Another wallet application is connected\n\
+                            • Previous connection wasn't properly closed\n\n\
+                            Solutions:\n\
+                            1. Close KeepKey Desktop app completely\n\
+                            2. Close any other wallet applications\n\
+                            3. Unplug and reconnect your KeepKey device\n\
+                            4. Try again\n\n\
+                            Technical details: {}", e
+                        )
+                    };
+                    
+                    // Emit device access error event
+                    let error_payload = serde_json::json!({
+                        "deviceId": device_for_task.unique_id,
+                        "error": user_friendly_error,
+                        "errorType": "DEVICE_CLAIMED",
+                        "code": DeviceErrorCode::DeviceBusy,
+                        "status": "error"
+                    });
+                    let _ = app_for_task.emit("device:access-error", &error_payload);
+                }
+            }
+        }
+    });
+}
+
 /// Try to get device features without blocking the event loop
 /// Returns features if successful, error message if failed
 /// This function handles OOB bootloader detection by trying Initialize message when GetFeatures fails
-async fn try_get_device_features(device: &FriendlyUsbDevice, app_handle: &AppHandle) -> Result<keepkey_rust::features::DeviceFeatures, String> {
+async fn try_get_device_features(device: &FriendlyUsbDevice, app_handle: &AppHandle, fetch_timeout: Duration) -> Result<keepkey_rust::features::DeviceFeatures, String> {
     // Check if device is in PIN flow - if so, skip automatic feature fetching to avoid interference
     if crate::commands::is_device_in_pin_flow(&device.unique_id) {
         return Err("Device is in PIN flow - skipping automatic feature fetch".to_string());
@@ -465,7 +769,7 @@ async fn try_get_device_features(device: &FriendlyUsbDevice, app_handle: &AppHan
         }
         
         // Try to get features with a timeout using the shared worker
-        match tokio::time::timeout(Duration::from_secs(5), queue_handle.get_features()).await {
+        match tokio::time::timeout(fetch_timeout, queue_handle.get_features()).await {
             Ok(Ok(raw_features)) => {
                 // Convert features to our DeviceFeatures format
                 let device_features = crate::commands::convert_features_to_device_features(raw_features);
@@ -517,7 +821,7 @@ async fn try_get_device_features(device: &FriendlyUsbDevice, app_handle: &AppHan
         );
         
         // Try to get features with a timeout
-        match tokio::time::timeout(Duration::from_secs(5), queue_handle.get_features()).await {
+        match tokio::time::timeout(fetch_timeout, queue_handle.get_features()).await {
             Ok(Ok(raw_features)) => {
                 // Convert features to our DeviceFeatures format
                 let device_features = crate::commands::convert_features_to_device_features(raw_features);
@@ -581,3 +885,103 @@ pub fn spawn_event_controller(app: &AppHandle) -> Arc<Mutex<EventController>> {
     
     controller_arc
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(id: &str) -> FriendlyUsbDevice {
+        FriendlyUsbDevice::new(id.to_string(), 0x2b24, 0x0002, None, None, None)
+    }
+
+    fn ids(devices: &[FriendlyUsbDevice]) -> Vec<&str> {
+        devices.iter().map(|d| d.unique_id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_tracker_reports_connects_and_disconnects() {
+        let mut tracker = DeviceTracker::new(Duration::ZERO);
+        let now = Instant::now();
+
+        let changes = tracker.update(&[device("a"), device("b")], now);
+        assert_eq!(ids(&changes.connected), vec!["a", "b"]);
+        assert!(changes.disconnected.is_empty());
+
+        let changes = tracker.update(&[device("b")], now);
+        assert!(changes.connected.is_empty());
+        assert_eq!(ids(&changes.disconnected), vec!["a"]);
+
+        let changes = tracker.update(&[], now);
+        assert_eq!(ids(&changes.disconnected), vec!["b"]);
+        assert!(tracker.is_empty());
+    }
+
+    #[test]
+    fn test_tracker_grace_period_hides_blips() {
+        let grace = Duration::from_secs(3);
+        let mut tracker = DeviceTracker::new(grace);
+        let start = Instant::now();
+
+        tracker.update(&[device("a")], start);
+
+        // Gone briefly, then back within the grace period: no events
+        assert!(tracker.update(&[], start + Duration::from_secs(1)).disconnected.is_empty());
+        let changes = tracker.update(&[device("a")], start + Duration::from_secs(2));
+        assert!(changes.connected.is_empty() && changes.disconnected.is_empty());
+
+        // Gone for longer than the grace period: one disconnect
+        assert!(tracker.update(&[], start + Duration::from_secs(3)).disconnected.is_empty());
+        let changes = tracker.update(&[], start + Duration::from_secs(6));
+        assert_eq!(ids(&changes.disconnected), vec!["a"]);
+    }
+
+    /// Replays a fixed sequence of scans, then reports no devices
+    struct ScriptedSource {
+        scans: Mutex<std::collections::VecDeque<Vec<FriendlyUsbDevice>>>,
+    }
+
+    impl DeviceSource for ScriptedSource {
+        fn list_devices(&self) -> Vec<FriendlyUsbDevice> {
+            self.scans.lock().unwrap().pop_front().unwrap_or_default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_controller_reports_connect_flap_and_disconnect() {
+        let scans = vec![vec![device("a")], vec![device("a")], vec![], vec![device("a")], vec![device("a")]];
+        let source = ScriptedSource { scans: Mutex::new(scans.into()) };
+        let config = EventControllerConfig {
+            disconnect_grace_ms: 1000,
+            startup_delay_ms: 0,
+            scan_interval_ms: Some(20),
+            ..Default::default()
+        };
+        let mut controller = EventController::with_source(Arc::new(source), config);
+        let mut reports = controller.start_scanning().unwrap();
+        assert!(controller.start_scanning().is_none());
+
+        // Connect, a one-scan flap well within the grace period, then gone for good
+        let mut events = Vec::new();
+        while !events.iter().any(|e: &String| e.starts_with("disconnected")) {
+            let report = tokio::time::timeout(Duration::from_secs(10), reports.recv())
+                .await
+                .expect("no disconnect within 10s")
+                .expect("scan loop stopped");
+            events.extend(report.changes.connected.iter().map(|d| format!("connected:{}", d.unique_id)));
+            events.extend(report.changes.disconnected.iter().map(|d| format!("disconnected:{}", d.unique_id)));
+            if !report.changes.disconnected.is_empty() {
+                assert!(report.none_tracked);
+            }
+        }
+        controller.stop();
+
+        assert_eq!(events, vec!["connected:a", "disconnected:a"]);
+    }
+
+    #[test]
+    fn test_backoff_delay_is_capped() {
+        assert_eq!(backoff_delay(1), Duration::from_secs(2));
+        assert_eq!(backoff_delay(3), Duration::from_secs(8));
+        assert_eq!(backoff_delay(50), FETCH_BACKOFF_MAX);
+    }
+}
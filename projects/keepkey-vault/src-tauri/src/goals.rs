@@ -0,0 +1,192 @@
+//! Portfolio savings goals.
+//!
+//! A goal is a target amount in USD or BTC with an optional deadline. Each
+//! portfolio refresh pushes the new totals here; every goal's progress is
+//! recomputed and stored, and `goals:milestone` is emitted when a goal
+//! crosses 25, 50, 75 or 100 percent for the first time.
+
+use std::sync::Mutex;
+use serde::Deserialize;
+use tauri::AppHandle;
+use utoipa::ToSchema;
+
+use crate::cache::{CacheManager, PortfolioGoal};
+
+/// Percentages announced as they are crossed
+const MILESTONES: [u8; 4] = [25, 50, 75, 100];
+
+const UNITS: [&str; 2] = ["usd", "btc"];
+
+/// Portfolio totals from the latest refresh
+#[derive(Debug, Clone, Copy)]
+struct Totals {
+    usd: f64,
+    btc: Option<f64>,
+}
+
+lazy_static::lazy_static! {
+    static ref LAST_TOTALS: Mutex<Option<Totals>> = Mutex::new(None);
+}
+
+/// A goal as submitted by the user
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct NewGoal {
+    pub name: String,
+    /// "usd" or "btc"
+    pub unit: String,
+    pub target: f64,
+    /// Unix timestamp the goal should be reached by
+    pub deadline: Option<i64>,
+}
+
+/// Amount the totals represent in a goal's unit, if known
+fn amount_in(unit: &str, totals: Totals) -> Option<f64> {
+    match unit {
+        "usd" => Some(totals.usd),
+        "btc" => totals.btc,
+        _ => None,
+    }
+}
+
+/// Highest milestone `progress` (a fraction of the target) has reached
+fn milestone_for(progress: f64) -> u8 {
+    MILESTONES
+        .iter()
+        .copied()
+        .filter(|m| progress * 100.0 >= *m as f64)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Move a goal to `current`, returning the milestone newly crossed, if any.
+/// Milestones never go back down, so a dip and recovery announces nothing.
+fn advance(goal: &mut PortfolioGoal, current: f64, now: i64) -> Option<u8> {
+    goal.current = current;
+    goal.updated_at = now;
+
+    let reached = milestone_for(current / goal.target);
+    if reached <= goal.milestone {
+        return None;
+    }
+    goal.milestone = reached;
+    if reached == 100 && goal.achieved_at.is_none() {
+        goal.achieved_at = Some(now);
+    }
+    Some(reached)
+}
+
+pub async fn list(cache: &CacheManager) -> Result<Vec<PortfolioGoal>, String> {
+    cache
+        .list_portfolio_goals()
+        .await
+        .map_err(|e| format!("Failed to list goals: {}", e))
+}
+
+/// Validate and store a new goal, with progress from the latest refresh
+pub async fn create(cache: &CacheManager, goal: NewGoal) -> Result<PortfolioGoal, String> {
+    let name = goal.name.trim();
+    if name.is_empty() {
+        return Err("Goal name is required".to_string());
+    }
+    let unit = goal.unit.to_lowercase();
+    if !UNITS.contains(&unit.as_str()) {
+        return Err(format!("Unsupported goal unit: {}", goal.unit));
+    }
+    if !goal.target.is_finite() || goal.target <= 0.0 {
+        return Err("Goal target must be a positive number".to_string());
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    let mut created = PortfolioGoal {
+        id: uuid::Uuid::new_v4().to_string(),
+        name: name.to_string(),
+        unit,
+        target: goal.target,
+        deadline: goal.deadline,
+        current: 0.0,
+        milestone: 0,
+        achieved_at: None,
+        created_at: now,
+        updated_at: now,
+    };
+    // Milestones already behind a new goal aren't news
+    let totals = *LAST_TOTALS.lock().unwrap();
+    if let Some(current) = totals.and_then(|totals| amount_in(&created.unit, totals)) {
+        advance(&mut created, current, now);
+    }
+
+    cache
+        .save_portfolio_goal(&created)
+        .await
+        .map_err(|e| format!("Failed to save goal: {}", e))?;
+    Ok(created)
+}
+
+/// Returns whether the goal existed
+pub async fn delete(cache: &CacheManager, id: &str) -> Result<bool, String> {
+    cache
+        .delete_portfolio_goal(id)
+        .await
+        .map_err(|e| format!("Failed to delete goal: {}", e))
+}
+
+/// Recompute every goal from a refreshed portfolio and announce crossed milestones
+pub async fn update_progress(app: &AppHandle, cache: &CacheManager, total_usd: f64, total_btc: Option<f64>) -> Result<(), String> {
+    let totals = Totals { usd: total_usd, btc: total_btc.filter(|btc| btc.is_finite() && *btc >= 0.0) };
+    *LAST_TOTALS.lock().unwrap() = Some(totals);
+
+    let now = chrono::Utc::now().timestamp();
+    for mut goal in list(cache).await? {
+        let Some(current) = amount_in(&goal.unit, totals) else {
+            continue;
+        };
+        let crossed = advance(&mut goal, current, now);
+        cache
+            .save_portfolio_goal(&goal)
+            .await
+            .map_err(|e| format!("Failed to save goal: {}", e))?;
+
+        if let Some(milestone) = crossed {
+            log::info!("🎯 Goal {} reached {}%", goal.name, milestone);
+            let payload = serde_json::json!({ "goal": goal, "milestone": milestone });
+            if let Err(e) = crate::commands::emit_or_queue_event(app, "goals:milestone", payload).await {
+                log::warn!("Failed to emit goals:milestone: {}", e);
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn goal(target: f64) -> PortfolioGoal {
+        PortfolioGoal {
+            id: "g".to_string(),
+            name: "Trip".to_string(),
+            unit: "usd".to_string(),
+            target,
+            deadline: None,
+            current: 0.0,
+            milestone: 0,
+            achieved_at: None,
+            created_at: 0,
+            updated_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_advance() {
+        let mut g = goal(1000.0);
+        assert_eq!(advance(&mut g, 100.0, 1), None);
+        assert_eq!(advance(&mut g, 600.0, 2), Some(50));
+        assert_eq!(advance(&mut g, 300.0, 3), None);
+        assert_eq!(advance(&mut g, 700.0, 4), None);
+        assert_eq!(g.milestone, 50);
+        assert_eq!(advance(&mut g, 1200.0, 5), Some(100));
+        assert_eq!(g.achieved_at, Some(5));
+        assert_eq!(g.current, 1200.0);
+    }
+}
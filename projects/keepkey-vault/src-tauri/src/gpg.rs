@@ -0,0 +1,299 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+use crate::commands::DeviceQueueManager;
+use crate::identity::{Identity, IdentityCurve};
+
+/// Preference holding the enrolled OpenPGP identity
+const GPG_PREFERENCE_KEY: &str = "gpg_identity";
+
+// OpenPGP constants (RFC 4880 / RFC 6637)
+const TAG_SIGNATURE: u8 = 2;
+const TAG_PUBLIC_KEY: u8 = 6;
+const TAG_USER_ID: u8 = 13;
+const SIG_BINARY_DOCUMENT: u8 = 0x00;
+const SIG_POSITIVE_CERTIFICATION: u8 = 0x13;
+const ALGO_ECDSA: u8 = 19;
+const ALGO_EDDSA: u8 = 22;
+const HASH_SHA256: u8 = 8;
+const OID_NIST_P256: &[u8] = &[0x2A, 0x86, 0x48, 0xCE, 0x3D, 0x03, 0x01, 0x07];
+const OID_ED25519: &[u8] = &[0x2B, 0x06, 0x01, 0x04, 0x01, 0xDA, 0x47, 0x0F, 0x01];
+
+/// Key flags: certify and sign
+const KEY_FLAGS_CERTIFY_SIGN: u8 = 0x03;
+
+/// An enrolled device-backed OpenPGP key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpgIdentity {
+    /// e.g. "Alice <alice@example.com>"
+    pub user_id: String,
+    pub curve: IdentityCurve,
+    /// Key creation time; part of the fingerprint, so fixed at enrollment
+    pub created_at: u32,
+    pub fingerprint: String,
+    pub key_id: String,
+    pub armored_public_key: String,
+}
+
+impl GpgIdentity {
+    fn identity(&self) -> Identity {
+        slip13_identity(&self.user_id)
+    }
+}
+
+fn slip13_identity(user_id: &str) -> Identity {
+    Identity {
+        proto: "gpg".to_string(),
+        host: user_id.to_string(),
+        ..Default::default()
+    }
+}
+
+/// The enrolled OpenPGP identity, if any
+pub fn get_identity() -> Option<GpgIdentity> {
+    crate::preferences::get_as(GPG_PREFERENCE_KEY)
+}
+
+fn save_identity(identity: &GpgIdentity) -> Result<(), String> {
+    crate::preferences::set(GPG_PREFERENCE_KEY, serde_json::json!(identity))
+}
+
+/// Derive a key for `user_id`, self-certify it on the device and store it
+pub async fn enroll(user_id: &str, curve: IdentityCurve, queue_manager: &DeviceQueueManager) -> Result<GpgIdentity, String> {
+    let user_id = user_id.trim();
+    if user_id.is_empty() {
+        return Err("User ID is required, e.g. \"Alice <alice@example.com>\"".to_string());
+    }
+
+    let identity = slip13_identity(user_id);
+    let device_key = crate::identity::derive_public_key(&identity, curve.device_curve(), queue_manager).await?;
+    let created_at = chrono::Utc::now().timestamp() as u32;
+    let key_body = public_key_body(curve, &device_key, created_at)?;
+    let fingerprint = fingerprint(&key_body);
+
+    // Self-signature binding the user ID to the key
+    let mut signed_data = key_hash_prefix(&key_body);
+    signed_data.push(0xB4);
+    signed_data.extend_from_slice(&(user_id.len() as u32).to_be_bytes());
+    signed_data.extend_from_slice(user_id.as_bytes());
+    let certification = sign_packet(
+        &identity, curve, SIG_POSITIVE_CERTIFICATION, &signed_data, &fingerprint, created_at, queue_manager,
+    ).await?;
+
+    let mut key_block = packet(TAG_PUBLIC_KEY, &key_body);
+    key_block.extend(packet(TAG_USER_ID, user_id.as_bytes()));
+    key_block.extend(certification);
+
+    let gpg_identity = GpgIdentity {
+        user_id: user_id.to_string(),
+        curve,
+        created_at,
+        fingerprint: hex::encode_upper(fingerprint),
+        key_id: hex::encode_upper(&fingerprint[12..]),
+        armored_public_key: armor("PGP PUBLIC KEY BLOCK", &key_block),
+    };
+    save_identity(&gpg_identity)?;
+
+    log::info!("🔏 Enrolled OpenPGP key {} for {}", gpg_identity.fingerprint, user_id);
+    Ok(gpg_identity)
+}
+
+/// Create an ASCII-armored detached signature over `data` with the enrolled key
+pub async fn sign_detached(data: &[u8], queue_manager: &DeviceQueueManager) -> Result<String, String> {
+    let gpg_identity = get_identity()
+        .ok_or_else(|| "No OpenPGP key enrolled".to_string())?;
+    let identity = gpg_identity.identity();
+
+    // Make sure the connected device holds the enrolled key (same seed and passphrase)
+    let device_key = crate::identity::derive_public_key(&identity, gpg_identity.curve.device_curve(), queue_manager).await?;
+    let key_body = public_key_body(gpg_identity.curve, &device_key, gpg_identity.created_at)?;
+    let fingerprint = fingerprint(&key_body);
+    if hex::encode_upper(fingerprint) != gpg_identity.fingerprint {
+        return Err("The connected device doesn't hold the enrolled OpenPGP key".to_string());
+    }
+
+    let now = chrono::Utc::now().timestamp() as u32;
+    let signature = sign_packet(&identity, gpg_identity.curve, SIG_BINARY_DOCUMENT, data, &fingerprint, now, queue_manager).await?;
+    Ok(armor("PGP SIGNATURE", &signature))
+}
+
+/// Public key packet body (version 4)
+fn public_key_body(curve: IdentityCurve, device_key: &[u8], created_at: u32) -> Result<Vec<u8>, String> {
+    let mut body = vec![4];
+    body.extend_from_slice(&created_at.to_be_bytes());
+    match curve {
+        IdentityCurve::Ed25519 => {
+            body.push(ALGO_EDDSA);
+            body.push(OID_ED25519.len() as u8);
+            body.extend_from_slice(OID_ED25519);
+            let mut point = vec![0x40];
+            point.extend_from_slice(crate::identity::ed25519_key_bytes(device_key)?);
+            put_mpi(&mut body, &point);
+        }
+        IdentityCurve::Nistp256 => {
+            body.push(ALGO_ECDSA);
+            body.push(OID_NIST_P256.len() as u8);
+            body.extend_from_slice(OID_NIST_P256);
+            put_mpi(&mut body, &crate::identity::nistp256_uncompressed(device_key)?);
+        }
+    }
+    Ok(body)
+}
+
+/// Key material as hashed into fingerprints and certifications
+fn key_hash_prefix(key_body: &[u8]) -> Vec<u8> {
+    let mut prefix = vec![0x99];
+    prefix.extend_from_slice(&(key_body.len() as u16).to_be_bytes());
+    prefix.extend_from_slice(key_body);
+    prefix
+}
+
+/// Version 4 key fingerprint
+fn fingerprint(key_body: &[u8]) -> [u8; 20] {
+    Sha1::digest(key_hash_prefix(key_body)).into()
+}
+
+fn algorithm(curve: IdentityCurve) -> u8 {
+    match curve {
+        IdentityCurve::Ed25519 => ALGO_EDDSA,
+        IdentityCurve::Nistp256 => ALGO_ECDSA,
+    }
+}
+
+/// Build a version 4 signature packet, signing its SHA-256 digest on the device
+async fn sign_packet(
+    identity: &Identity,
+    curve: IdentityCurve,
+    sig_type: u8,
+    signed_data: &[u8],
+    fingerprint: &[u8; 20],
+    created_at: u32,
+    queue_manager: &DeviceQueueManager,
+) -> Result<Vec<u8>, String> {
+    let mut subpackets = Vec::new();
+    subpackets.extend_from_slice(&[5, 2]);
+    subpackets.extend_from_slice(&created_at.to_be_bytes());
+    subpackets.extend_from_slice(&[22, 33, 4]);
+    subpackets.extend_from_slice(fingerprint);
+    if sig_type == SIG_POSITIVE_CERTIFICATION {
+        subpackets.extend_from_slice(&[2, 27, KEY_FLAGS_CERTIFY_SIGN]);
+    }
+
+    let mut hashed = vec![4, sig_type, algorithm(curve), HASH_SHA256];
+    hashed.extend_from_slice(&(subpackets.len() as u16).to_be_bytes());
+    hashed.extend_from_slice(&subpackets);
+
+    let mut hasher = Sha256::new();
+    hasher.update(signed_data);
+    hasher.update(&hashed);
+    hasher.update([4, 0xFF]);
+    hasher.update((hashed.len() as u32).to_be_bytes());
+    let digest = hasher.finalize();
+
+    let signature = crate::identity::sign(identity, curve.device_curve(), &digest, queue_manager).await?;
+
+    let mut body = hashed;
+    // Unhashed issuer key ID for older implementations
+    body.extend_from_slice(&10u16.to_be_bytes());
+    body.extend_from_slice(&[9, 16]);
+    body.extend_from_slice(&fingerprint[12..]);
+    body.extend_from_slice(&digest[..2]);
+    put_mpi(&mut body, &signature[..32]);
+    put_mpi(&mut body, &signature[32..]);
+
+    Ok(packet(TAG_SIGNATURE, &body))
+}
+
+/// New-format packet with the given tag
+fn packet(tag: u8, body: &[u8]) -> Vec<u8> {
+    let mut out = vec![0xC0 | tag];
+    let len = body.len();
+    if len < 192 {
+        out.push(len as u8);
+    } else if len < 8384 {
+        let len = len - 192;
+        out.push(((len >> 8) + 192) as u8);
+        out.push((len & 0xFF) as u8);
+    } else {
+        out.push(0xFF);
+        out.extend_from_slice(&(len as u32).to_be_bytes());
+    }
+    out.extend_from_slice(body);
+    out
+}
+
+fn put_mpi(buf: &mut Vec<u8>, value: &[u8]) {
+    let trimmed: Vec<u8> = value.iter().copied().skip_while(|b| *b == 0).collect();
+    let bits = match trimmed.first() {
+        Some(first) => (trimmed.len() - 1) * 8 + (8 - first.leading_zeros() as usize),
+        None => 0,
+    };
+    buf.extend_from_slice(&(bits as u16).to_be_bytes());
+    buf.extend_from_slice(&trimmed);
+}
+
+fn crc24(data: &[u8]) -> u32 {
+    let mut crc: u32 = 0xB704CE;
+    for byte in data {
+        crc ^= (*byte as u32) << 16;
+        for _ in 0..8 {
+            crc <<= 1;
+            if crc & 0x1000000 != 0 {
+                crc ^= 0x1864CFB;
+            }
+        }
+    }
+    crc & 0xFFFFFF
+}
+
+/// ASCII armor (RFC 4880 section 6.2)
+fn armor(label: &str, data: &[u8]) -> String {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let encoded = engine.encode(data);
+    let checksum = engine.encode(&crc24(data).to_be_bytes()[1..]);
+
+    let mut out = format!("-----BEGIN {}-----\n\n", label);
+    for line in encoded.as_bytes().chunks(64) {
+        out.push_str(std::str::from_utf8(line).unwrap_or_default());
+        out.push('\n');
+    }
+    out.push_str(&format!("={}\n-----END {}-----\n", checksum, label));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mpi_and_packet_encoding() {
+        let mut buf = Vec::new();
+        put_mpi(&mut buf, &[0x00, 0x01, 0xFF]);
+        assert_eq!(buf, vec![0x00, 0x09, 0x01, 0xFF]);
+
+        assert_eq!(packet(TAG_USER_ID, b"abc")[..2], [0xCD, 3]);
+        assert_eq!(packet(TAG_SIGNATURE, &[0u8; 200])[..3], [0xC2, 192, 8]);
+    }
+
+    #[test]
+    fn test_armor_checksum() {
+        assert_eq!(crc24(&[]), 0xB704CE);
+        let armored = armor("PGP SIGNATURE", b"hello");
+        assert!(armored.starts_with("-----BEGIN PGP SIGNATURE-----\n\naGVsbG8=\n="));
+        assert!(armored.ends_with("-----END PGP SIGNATURE-----\n"));
+    }
+
+    #[test]
+    fn test_ed25519_key_body() {
+        let mut device_key = vec![0x01];
+        device_key.extend_from_slice(&[0x11; 32]);
+        let body = public_key_body(IdentityCurve::Ed25519, &device_key, 1_700_000_000).unwrap();
+        assert_eq!(body[0], 4);
+        assert_eq!(body[5], ALGO_EDDSA);
+        // 263 bits: 0x40 prefix byte followed by the 32-byte key
+        assert_eq!(&body[16..18], &[0x01, 0x07]);
+        assert_eq!(body.len(), 18 + 33);
+    }
+}
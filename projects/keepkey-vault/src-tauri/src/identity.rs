@@ -0,0 +1,188 @@
+use sha2::{Digest, Sha256};
+use crate::commands::DeviceQueueManager;
+
+const HARDENED: u32 = 0x80000000;
+
+/// Curve an identity key is derived on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IdentityCurve {
+    Ed25519,
+    Nistp256,
+}
+
+impl IdentityCurve {
+    /// Curve name understood by the firmware
+    pub fn device_curve(self) -> &'static str {
+        match self {
+            IdentityCurve::Ed25519 => "ed25519",
+            IdentityCurve::Nistp256 => "nist256p1",
+        }
+    }
+}
+
+/// SLIP-13 identity fields, as serialized by the firmware
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Identity {
+    pub proto: String,
+    pub user: Option<String>,
+    pub host: String,
+    pub port: Option<String>,
+    pub path: Option<String>,
+}
+
+impl Identity {
+    pub fn parse(uri: &str) -> Result<Self, String> {
+        let (proto, rest) = uri.split_once("://").unwrap_or(("ssh", uri));
+        let (user, rest) = match rest.split_once('@') {
+            Some((user, rest)) => (Some(user.to_string()), rest),
+            None => (None, rest),
+        };
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], Some(rest[i..].to_string())),
+            None => (rest, None),
+        };
+        let (host, port) = match authority.split_once(':') {
+            Some((host, port)) => (host, Some(port.to_string())),
+            None => (authority, None),
+        };
+
+        if host.is_empty() {
+            return Err(format!("Invalid SSH identity: {}", uri));
+        }
+
+        Ok(Self { proto: proto.to_string(), user, host: host.to_string(), port, path })
+    }
+
+    pub fn to_uri(&self) -> String {
+        let mut uri = format!("{}://", self.proto);
+        if let Some(user) = &self.user {
+            uri.push_str(user);
+            uri.push('@');
+        }
+        uri.push_str(&self.host);
+        if let Some(port) = &self.port {
+            uri.push(':');
+            uri.push_str(port);
+        }
+        if let Some(path) = &self.path {
+            uri.push_str(path);
+        }
+        uri
+    }
+
+    /// SLIP-13 derivation path: m/13'/a'/b'/c'/d' from sha256(index || uri)
+    pub fn address_n(&self) -> Vec<u32> {
+        let mut hasher = Sha256::new();
+        hasher.update(0u32.to_le_bytes());
+        hasher.update(self.to_uri().as_bytes());
+        let digest = hasher.finalize();
+
+        let mut path = vec![13 | HARDENED];
+        for chunk in digest[..16].chunks(4) {
+            path.push(u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]) | HARDENED);
+        }
+        path
+    }
+
+    pub fn to_message(&self) -> keepkey_rust::messages::IdentityType {
+        keepkey_rust::messages::IdentityType {
+            proto: Some(self.proto.clone()),
+            user: self.user.clone(),
+            host: Some(self.host.clone()),
+            port: self.port.clone(),
+            path: self.path.clone(),
+            index: Some(0),
+        }
+    }
+}
+
+/// Queue handle of the first connected device
+async fn device_queue(queue_manager: &DeviceQueueManager) -> Result<keepkey_rust::device_queue::DeviceQueueHandle, String> {
+    let device = keepkey_rust::features::list_connected_devices()
+        .into_iter()
+        .next()
+        .ok_or_else(|| "No KeepKey device connected".to_string())?;
+    crate::commands::get_or_create_device_queue(&device.unique_id, queue_manager).await
+}
+
+/// Public key of an identity on a curve ("ed25519" or "nist256p1"), as returned by the firmware
+pub async fn derive_public_key(identity: &Identity, curve: &str, queue_manager: &DeviceQueueManager) -> Result<Vec<u8>, String> {
+    let queue_handle = device_queue(queue_manager).await?;
+    let msg = keepkey_rust::messages::GetPublicKey {
+        address_n: identity.address_n(),
+        ecdsa_curve_name: Some(curve.to_string()),
+        show_display: Some(false),
+        ..Default::default()
+    };
+
+    match queue_handle.send_raw(msg.into(), false).await.map_err(|e| e.to_string())? {
+        keepkey_rust::messages::Message::PublicKey(public_key) => public_key.node.public_key
+            .ok_or_else(|| "Device returned no public key".to_string()),
+        keepkey_rust::messages::Message::Failure(failure) => {
+            Err(format!("Device returned error: {}", failure.message.unwrap_or_default()))
+        }
+        _ => Err("Unexpected response from device for public key request".to_string()),
+    }
+}
+
+/// Sign a challenge with an identity key; the user confirms on the KeepKey screen
+///
+/// Returns the 64-byte signature without the firmware's leading zero byte.
+pub async fn sign(identity: &Identity, curve: &str, challenge: &[u8], queue_manager: &DeviceQueueManager) -> Result<Vec<u8>, String> {
+    let queue_handle = device_queue(queue_manager).await?;
+    let msg = keepkey_rust::messages::SignIdentity {
+        identity: Some(identity.to_message()),
+        challenge_hidden: Some(challenge.to_vec()),
+        challenge_visual: Some(String::new()),
+        ecdsa_curve_name: Some(curve.to_string()),
+    };
+
+    log::info!("🔑 Requesting {} identity signature for {}", identity.proto, identity.to_uri());
+    let signature = match queue_handle.send_raw(msg.into(), false).await.map_err(|e| e.to_string())? {
+        keepkey_rust::messages::Message::SignedIdentity(signed) => signed.signature
+            .ok_or_else(|| "Device returned no signature".to_string())?,
+        keepkey_rust::messages::Message::Failure(failure) => {
+            return Err(format!("Device returned error: {}", failure.message.unwrap_or_default()));
+        }
+        _ => return Err("Unexpected response from device for identity signature".to_string()),
+    };
+
+    if signature.len() != 65 {
+        return Err(format!("Unexpected signature length {}", signature.len()));
+    }
+    Ok(signature[1..].to_vec())
+}
+
+/// Strip the firmware's marker byte from an ed25519 public key
+pub fn ed25519_key_bytes(public_key: &[u8]) -> Result<&[u8], String> {
+    match public_key.len() {
+        33 => Ok(&public_key[1..]),
+        32 => Ok(public_key),
+        len => Err(format!("Unexpected ed25519 public key length {}", len)),
+    }
+}
+
+/// Uncompressed SEC1 point (0x04 || x || y) for a nist256p1 public key
+pub fn nistp256_uncompressed(public_key: &[u8]) -> Result<Vec<u8>, String> {
+    use p256::elliptic_curve::sec1::ToEncodedPoint;
+    let point = p256::PublicKey::from_sec1_bytes(public_key)
+        .map_err(|e| format!("Invalid nistp256 public key: {}", e))?;
+    Ok(point.to_encoded_point(false).as_bytes().to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identity_round_trip() {
+        let identity = Identity::parse("ssh://git@github.com:22/repo").unwrap();
+        assert_eq!(identity.user.as_deref(), Some("git"));
+        assert_eq!(identity.host, "github.com");
+        assert_eq!(identity.port.as_deref(), Some("22"));
+        assert_eq!(identity.to_uri(), "ssh://git@github.com:22/repo");
+        assert!(identity.address_n().iter().all(|n| n & HARDENED != 0));
+        assert!(Identity::parse("ssh://").is_err());
+    }
+}
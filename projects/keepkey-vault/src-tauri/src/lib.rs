@@ -7,9 +7,44 @@ mod commands;
 mod device;
 mod event_controller;
 mod logging;
+mod protocol_trace;
 mod slip132;
 mod server;
 mod cache;
+mod automation;
+mod secrets;
+mod screen_protection;
+mod identity;
+mod ssh_agent;
+mod gpg;
+mod notifications;
+mod status_messages;
+mod power;
+mod wallets;
+mod settings_backup;
+mod preferences;
+mod support;
+mod utxos;
+mod payment_uri;
+mod rate_lock;
+mod safe_mode;
+mod window_title;
+mod windows;
+mod error_center;
+mod readiness;
+mod confirmations;
+mod notes;
+mod activity;
+mod eth_signatures;
+mod derivation;
+mod self_check;
+mod provider_usage;
+mod asset_registry;
+mod goals;
+mod accounts;
+mod reconciliation;
+mod portfolio;
+mod reserves;
 
 // Re-export commonly used types
 
@@ -64,20 +99,36 @@ fn vault_change_view(app: tauri::AppHandle, view: String) -> Result<(), String>
     }
 }
 
+/// Open support. Without a request this just shows the help center; with one it
+/// collects metadata (and diagnostics if opted in), submits a ticket to the
+/// configured support backend or opens a prefilled form, and returns the reference.
 #[tauri::command]
-fn vault_open_support(app: tauri::AppHandle) -> Result<(), String> {
+async fn vault_open_support(
+    app: tauri::AppHandle,
+    request: Option<support::SupportRequest>,
+) -> Result<Option<support::SupportTicket>, String> {
     println!("Opening support");
     
-    // Switch to browser view and navigate to support
-    app.emit("vault:change_view", serde_json::json!({
-        "view": "browser"
-    })).map_err(|e| format!("Failed to emit view change event: {}", e))?;
+    let (url, ticket) = match request {
+        Some(request) => {
+            let ticket = support::create_ticket(request).await?;
+            (ticket.form_url.clone(), Some(ticket))
+        }
+        None => (Some("https://support.keepkey.com".to_string()), None),
+    };
     
-    app.emit("browser:navigate", serde_json::json!({
-        "url": "https://support.keepkey.com"
-    })).map_err(|e| format!("Failed to emit navigation event: {}", e))?;
+    // Switch to browser view and navigate to support (or the prefilled form)
+    if let Some(url) = url {
+        app.emit("vault:change_view", serde_json::json!({
+            "view": "browser"
+        })).map_err(|e| format!("Failed to emit view change event: {}", e))?;
+        
+        app.emit("browser:navigate", serde_json::json!({
+            "url": url
+        })).map_err(|e| format!("Failed to emit navigation event: {}", e))?;
+    }
     
-    Ok(())
+    Ok(ticket)
 }
 
 // Add the missing vault_open_app command to open external URLs
@@ -165,6 +216,8 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_sql::Builder::default().build())
         .plugin(tauri_plugin_process::init())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
         .register_uri_scheme_protocol("kkapi", |_app, request| {
             // 1️⃣ Rewrite kkapi://… → http://localhost:1646/…
             let original_url = request.uri().to_string();
@@ -172,6 +225,19 @@ pub fn run() {
             
             log::debug!("🔄 Proxying kkapi request: {} -> {}", original_url, proxied_url);
             
+            // Only echo the request origin back if it's on the CORS allowlist
+            let allowed_origin = request.headers()
+                .get("origin")
+                .and_then(|v| v.to_str().ok())
+                .filter(|origin| server::cors::is_origin_allowed(origin))
+                .map(|origin| origin.to_string());
+            let with_cors = |builder: tauri::http::response::Builder| match &allowed_origin {
+                Some(origin) => builder
+                    .header("Access-Control-Allow-Origin", origin.as_str())
+                    .header("Vary", "Origin"),
+                None => builder,
+            };
+            
             // 2️⃣ Create HTTP client and forward the request
             let client = reqwest::blocking::Client::new();
             let method = match request.method() {
@@ -215,18 +281,16 @@ pub fn run() {
                         Ok(body) => body,
                         Err(e) => {
                             log::error!("❌ Failed to read response body: {}", e);
-                            return Response::builder()
+                            return with_cors(Response::builder())
                                 .status(StatusCode::INTERNAL_SERVER_ERROR)
-                                .header("Access-Control-Allow-Origin", "*")
                                 .body(format!("Failed to read response: {}", e).into_bytes())
                                 .unwrap();
                         }
                     };
                     
                     // Build response with CORS headers
-                    let response_builder = Response::builder()
+                    let response_builder = with_cors(Response::builder())
                         .status(status_code)
-                        .header("Access-Control-Allow-Origin", "*")
                         .header("Access-Control-Allow-Methods", "GET,POST,PUT,DELETE,OPTIONS,PATCH")
                         .header("Access-Control-Allow-Headers", "Content-Type,Authorization,X-Requested-With");
                     
@@ -235,9 +299,8 @@ pub fn run() {
                 }
                 Err(e) => {
                     log::error!("❌ Failed to proxy request to {}: {}", proxied_url, e);
-                    Response::builder()
+                    with_cors(Response::builder())
                         .status(StatusCode::BAD_GATEWAY)
-                        .header("Access-Control-Allow-Origin", "*")
                         .header("Content-Type", "application/json")
                         .body(format!(r#"{{"error": "Proxy request failed", "details": "{}"}}"#, e).into_bytes())
                         .unwrap()
@@ -252,6 +315,18 @@ pub fn run() {
                 println!("✅ Device logging initialized - logs will be written to ~/.keepkey/logs/");
             }
             
+            // Boot in safe mode if the last few startups never got far enough to reset the sentinel
+            let safe_mode = safe_mode::record_startup();
+            safe_mode::spawn_stability_timer();
+            
+            // Allow sensitive flows to toggle screen capture protection on the main window
+            screen_protection::init(app.handle());
+            
+            // Move any plaintext secrets from preferences into the OS keychain
+            if let Err(e) = secrets::migrate_plaintext_secrets() {
+                log::warn!("Failed to migrate secrets to the OS keychain: {}", e);
+            }
+            
             // Initialize real device system using keepkey_rust
             let device_queue_manager = Arc::new(tokio::sync::Mutex::new(
                 std::collections::HashMap::<String, keepkey_rust::device_queue::DeviceQueueHandle>::new()
@@ -269,9 +344,29 @@ pub fn run() {
             app.manage(last_responses);
             app.manage(cache_manager.clone());
             
+            notifications::init(app.handle().clone());
+            preferences::init(app.handle().clone());
+            window_title::init(app.handle().clone());
+            power::init();
+            error_center::init(app.handle().clone());
+            readiness::init(app.handle().clone());
+            confirmations::init(app.handle().clone());
+            activity::init(app.handle().clone());
+            provider_usage::init(app.handle().clone());
+            device::pin_lockout::init(app.handle().clone());
+            
             // Start event controller with proper management
             let _event_controller = event_controller::spawn_event_controller(&app.handle());
             
+            // Catch software derivation regressions before any address is shown
+            self_check::spawn_startup_check(app.handle().clone());
+            
+            // Restart device queue workers that get stuck after transport errors
+            device::watchdog::spawn_queue_watchdog(app.handle().clone(), device_queue_manager.clone());
+            
+            // Expire cached passphrase sessions according to the configured policy
+            device::passphrase_policy::spawn_policy_enforcer(device_queue_manager.clone());
+            
             // Start background log cleanup task
             let _app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
@@ -284,13 +379,63 @@ pub fn run() {
                 }
             });
             
+            // Pick up firmware releases published after this build
+            tauri::async_runtime::spawn(async {
+                if let Err(e) = device::releases::refresh_remote_manifest().await {
+                    log::warn!("Using bundled firmware release manifest: {}", e);
+                }
+            });
+            
+            // Hardware-backed SSH agent, when the user has turned it on
+            let ssh_queue_manager = device_queue_manager.clone();
+            tauri::async_runtime::spawn(async move {
+                ssh_agent::start_if_enabled(ssh_queue_manager);
+            });
+            
+            // Let the frontend offer diagnostics and a cache/preferences reset
+            if safe_mode {
+                let safe_mode_handle = app.handle().clone();
+                tauri::async_runtime::spawn(async move {
+                    let status = safe_mode::status();
+                    if let Err(e) = commands::emit_or_queue_event(&safe_mode_handle, "app:safe-mode", serde_json::json!(status)).await {
+                        log::warn!("Failed to emit app:safe-mode: {}", e);
+                    }
+                });
+            }
+            
+            // Open the cache up front so corruption or a full disk is reported early
+            // (not in safe mode, where the cache may be what keeps the app crashing)
+            if safe_mode {
+                // Opened lazily on first use instead
+                readiness::mark_ready(readiness::Gate::Cache);
+            } else {
+                let cache_check_handle = app.handle().clone();
+                let cache_check_cell = cache_manager.clone();
+                tauri::async_runtime::spawn(async move {
+                    match commands::get_cache_manager(&cache_check_cell).await {
+                        Ok(cache) => {
+                            if let Some(degradation) = cache.degradation() {
+                                log::warn!("⚠️ Cache degraded ({:?}): {}", degradation.mode, degradation.reason);
+                                let _ = cache_check_handle.emit("cache:degraded", degradation);
+                            }
+                        }
+                        Err(e) => log::error!("❌ Cache unavailable: {}", e),
+                    }
+                    // Cache endpoints report their own error if opening failed
+                    readiness::mark_ready(readiness::Gate::Cache);
+                });
+                
+                // Periodically vacuum the cache database so it doesn't grow unbounded
+                cache::maintenance::spawn_vacuum_schedule(cache_manager.clone());
+                
+                // Pick up asset/path registries published after this build
+                asset_registry::spawn_startup_refresh(app.handle().clone(), cache_manager.clone());
+            }
+            
             // Start REST/MCP server in background (ALWAYS ENABLED - no preference check)
             let server_handle = app.handle().clone();
             let server_queue_manager = device_queue_manager.clone();
             tauri::async_runtime::spawn(async move {
-                // Add a small delay to ensure config system is ready
-                tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
-                
                 log::info!("🚀 Starting REST/MCP server (always enabled)...");
                 log::info!("🔧 Debug: About to call server::start_server");
                 
@@ -343,18 +488,30 @@ pub fn run() {
             // Device operations - unified queue interface
             device::queue::add_to_device_queue,
             commands::get_queue_status,
+            commands::get_queue_metrics,
             // Basic device enumeration (non-queue operations)
             commands::get_connected_devices,
             commands::get_blocking_actions,
             // New device commands (all go through queue)
             commands::get_device_status,
             commands::get_device_info_by_id,
+            commands::get_device_capabilities,
+            commands::get_firmware_releases,
+            commands::request_wipe_challenge,
             commands::wipe_device,
+            commands::get_wipe_interlock_preferences,
+            commands::set_wipe_interlock_preferences,
             commands::set_device_label,
+            commands::set_device_language,
+            commands::set_auto_lock_delay,
+            commands::set_passphrase_protection,
             commands::get_connected_devices_with_features,
             // Update commands
+            device::updates::bootloader_update_preflight,
             device::updates::update_device_bootloader,
             device::updates::update_device_firmware,
+            device::updates::inspect_custom_firmware,
+            device::updates::flash_custom_firmware,
             // PIN creation commands
             commands::initialize_device_pin,
             commands::send_pin_matrix_response,
@@ -372,18 +529,87 @@ pub fn run() {
             commands::get_device_log_path,
             commands::get_recent_device_logs,
             commands::cleanup_device_logs,
+            commands::get_protocol_trace_status,
+            commands::set_protocol_trace,
             // Configuration and onboarding commands
             commands::is_first_time_install,
             commands::is_onboarded,
             commands::set_onboarding_completed,
             commands::get_preference,
             commands::set_preference,
+            commands::get_typed_preference,
+            commands::set_typed_preference,
+            commands::get_preference_schema,
             commands::debug_onboarding_state,
             // API control commands
             commands::get_api_enabled,
             commands::set_api_enabled,
             commands::get_api_status,
+            commands::copy_sensitive,
+            commands::get_screen_protection_status,
+            commands::set_screen_capture_protection,
+            commands::get_api_tls_info,
+            commands::set_api_tls_enabled,
+            commands::get_cors_allowed_origins,
+            commands::add_cors_allowed_origin,
+            commands::remove_cors_allowed_origin,
+            commands::reset_cors_allowed_origins,
+            commands::get_disabled_endpoint_groups,
+            commands::set_disabled_endpoint_groups,
+            commands::get_testnet_mode,
+            commands::set_testnet_mode,
+            commands::get_testnet_networks,
+            commands::respond_batch_approval,
+            commands::respond_pairing_approval,
+            commands::list_utxos,
+            commands::tag_utxo,
+            commands::get_frozen_utxos,
+            commands::parse_payment_uri,
+            commands::create_payment_intent,
+            commands::confirm_payment_intent,
+            commands::cancel_payment_intent,
+            commands::get_rate_lock_preferences,
+            commands::set_rate_lock_preferences,
+            commands::get_reconciliation_preferences,
+            commands::set_reconciliation_preferences,
+            commands::reconcile_portfolio,
+            commands::get_confirmation_thresholds,
+            commands::set_confirmation_threshold,
+            // Startup
+            commands::get_startup_readiness,
+            // Safe mode
+            commands::get_safe_mode_status,
+            commands::safe_mode_reset,
+            commands::exit_safe_mode,
+            commands::update_portfolio_summary,
+            commands::list_portfolio_goals,
+            commands::create_portfolio_goal,
+            commands::delete_portfolio_goal,
+            commands::list_account_names,
+            commands::set_account_name,
+            commands::delete_account_name,
+            // Additional windows
+            commands::open_vault_window,
+            commands::set_vault_window_device,
+            commands::list_vault_windows,
+            // Error center
+            commands::list_errors,
+            commands::clear_errors,
             commands::restart_app,
+            // Encrypted notes
+            commands::create_note,
+            commands::list_notes,
+            commands::update_note,
+            commands::delete_note,
+            commands::get_timeline,
+            commands::list_duplicate_seeds,
+            commands::derive_addresses_offline,
+            commands::lookup_address,
+            commands::run_derivation_self_check,
+            commands::record_provider_request,
+            commands::get_provider_usage,
+            commands::get_asset_registry_status,
+            commands::refresh_asset_registry,
             // Test commands
             commands::test_device_queue,
             commands::test_status_emission,
@@ -404,8 +630,50 @@ pub fn run() {
             commands::force_cleanup_seed_verification,
             // Cache commands
             commands::get_cache_status,
+            commands::get_device_aliases,
             commands::trigger_frontload,
-            commands::clear_device_cache
+            commands::plan_frontload,
+            commands::get_frontload_schedule,
+            commands::set_frontload_schedule,
+            commands::get_frontload_chain_priority,
+            commands::set_frontload_chain_priority,
+            commands::get_cosmos_chains,
+            commands::set_cosmos_chains,
+            commands::get_ssh_agent_status,
+            commands::set_ssh_agent_config,
+            commands::get_gpg_identity,
+            commands::gpg_enroll,
+            commands::gpg_sign_detached,
+            commands::get_notification_preferences,
+            commands::set_notification_preferences,
+            commands::get_status_messages,
+            commands::create_mock_device,
+            commands::remove_mock_device,
+            commands::get_cache_health,
+            commands::get_power_status,
+            commands::set_polling_config,
+            commands::set_low_power_mode,
+            commands::get_passphrase_cache_policy,
+            commands::set_passphrase_cache_policy,
+            commands::get_settings_backup,
+            commands::set_settings_backup,
+            commands::backup_settings,
+            commands::restore_settings,
+            commands::get_wallets,
+            commands::save_wallet,
+            commands::remove_wallet,
+            commands::get_automation_hooks,
+            commands::save_automation_hook,
+            commands::remove_automation_hook,
+            commands::test_automation_hook,
+            commands::store_secret,
+            commands::has_stored_secret,
+            commands::get_pioneer_api_key,
+            commands::delete_stored_secret,
+            commands::clear_device_cache,
+            commands::restore_device_cache,
+            commands::compact_cache,
+            commands::get_cache_disk_usage
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,163 @@
+//! Encrypted notes about devices and their accounts.
+//!
+//! Note text is encrypted with AES-256-GCM under a key derived from a user
+//! passphrase (Argon2id, random salt per note), so the cache database only
+//! ever holds ciphertext.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use rand::RngCore;
+use serde::Serialize;
+
+use crate::cache::{CacheManager, EncryptedNote};
+
+/// Longest note accepted, in bytes
+const MAX_NOTE_LEN: usize = 4096;
+
+/// A decrypted note
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Note {
+    pub id: i64,
+    pub device_id: String,
+    pub account_path: Option<String>,
+    pub text: String,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+fn seal(text: &str, passphrase: &str) -> Result<(Vec<u8>, Vec<u8>, Vec<u8>), String> {
+    if passphrase.is_empty() {
+        return Err("A passphrase is required to encrypt notes".to_string());
+    }
+    if text.len() > MAX_NOTE_LEN {
+        return Err(format!("Notes are limited to {} bytes", MAX_NOTE_LEN));
+    }
+
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let key = crate::settings_backup::derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), text.as_bytes())
+        .map_err(|_| "Failed to encrypt note".to_string())?;
+    Ok((salt.to_vec(), nonce.to_vec(), ciphertext))
+}
+
+fn open(note: &EncryptedNote, passphrase: &str) -> Result<Note, String> {
+    if note.nonce.len() != 12 {
+        return Err(format!("Note {} is corrupt", note.id));
+    }
+    let key = crate::settings_backup::derive_key(passphrase, &note.salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&note.nonce), note.ciphertext.as_ref())
+        .map_err(|_| "Wrong passphrase for notes".to_string())?;
+
+    Ok(Note {
+        id: note.id,
+        device_id: note.device_id.clone(),
+        account_path: note.account_path.clone(),
+        text: String::from_utf8(plaintext).map_err(|_| format!("Note {} is corrupt", note.id))?,
+        created_at: note.created_at,
+        updated_at: note.updated_at,
+    })
+}
+
+pub async fn create(
+    cache: &CacheManager,
+    device_id: &str,
+    account_path: Option<String>,
+    text: &str,
+    passphrase: &str,
+) -> Result<Note, String> {
+    let (salt, nonce, ciphertext) = seal(text, passphrase)?;
+    let now = chrono::Utc::now().timestamp();
+    let mut note = EncryptedNote {
+        id: 0,
+        device_id: device_id.to_string(),
+        account_path,
+        salt,
+        nonce,
+        ciphertext,
+        created_at: now,
+        updated_at: now,
+    };
+    note.id = cache
+        .save_note(&note)
+        .await
+        .map_err(|e| format!("Failed to save note: {}", e))?;
+    open(&note, passphrase)
+}
+
+/// Decrypt every note of a device, optionally only those for one account
+pub async fn list(
+    cache: &CacheManager,
+    device_id: &str,
+    account_path: Option<&str>,
+    passphrase: &str,
+) -> Result<Vec<Note>, String> {
+    cache
+        .list_notes(device_id)
+        .await
+        .map_err(|e| format!("Failed to read notes: {}", e))?
+        .iter()
+        .filter(|note| account_path.map_or(true, |path| note.account_path.as_deref() == Some(path)))
+        .map(|note| open(note, passphrase))
+        .collect()
+}
+
+/// Replace a note's text; the passphrase must open the existing note
+pub async fn update(cache: &CacheManager, id: i64, text: &str, passphrase: &str) -> Result<Note, String> {
+    let mut note = cache
+        .get_note(id)
+        .await
+        .map_err(|e| format!("Failed to read note: {}", e))?
+        .ok_or_else(|| format!("Note {} not found", id))?;
+    open(&note, passphrase)?;
+
+    let (salt, nonce, ciphertext) = seal(text, passphrase)?;
+    note.salt = salt;
+    note.nonce = nonce;
+    note.ciphertext = ciphertext;
+    note.updated_at = chrono::Utc::now().timestamp();
+    cache
+        .save_note(&note)
+        .await
+        .map_err(|e| format!("Failed to save note: {}", e))?;
+    open(&note, passphrase)
+}
+
+pub async fn delete(cache: &CacheManager, id: i64) -> Result<(), String> {
+    match cache.delete_note(id).await {
+        Ok(true) => Ok(()),
+        Ok(false) => Err(format!("Note {} not found", id)),
+        Err(e) => Err(format!("Failed to delete note: {}", e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open_roundtrip() {
+        let (salt, nonce, ciphertext) = seal("office backup", "correct horse").unwrap();
+        let note = EncryptedNote {
+            id: 1,
+            device_id: "dev".to_string(),
+            account_path: None,
+            salt,
+            nonce,
+            ciphertext,
+            created_at: 0,
+            updated_at: 0,
+        };
+        assert!(!note.ciphertext.windows(6).any(|w| w == b"office"));
+        assert_eq!(open(&note, "correct horse").unwrap().text, "office backup");
+        assert!(open(&note, "wrong").is_err());
+    }
+}
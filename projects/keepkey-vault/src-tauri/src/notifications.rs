@@ -0,0 +1,149 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use tauri_plugin_notification::NotificationExt;
+
+/// Preference key holding the per-category notification toggles
+const NOTIFICATIONS_PREFERENCE_KEY: &str = "notifications";
+
+static APP_HANDLE: once_cell::sync::OnceCell<AppHandle> = once_cell::sync::OnceCell::new();
+
+lazy_static::lazy_static! {
+    /// Devices already notified about an available update this session
+    static ref UPDATE_NOTIFIED: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+/// Which kinds of native notifications the user wants
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct NotificationPreferences {
+    /// Device connected / disconnected
+    pub device: bool,
+    /// Firmware or bootloader update available
+    pub updates: bool,
+    /// Frontload finished or failed
+    pub frontload: bool,
+    /// Transaction reached its confirmation threshold
+    pub transactions: bool,
+}
+
+impl Default for NotificationPreferences {
+    fn default() -> Self {
+        Self {
+            device: true,
+            updates: true,
+            frontload: false,
+            transactions: true,
+        }
+    }
+}
+
+/// Give the notification service a handle to show notifications with
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Load the notification toggles
+pub fn get_preferences() -> NotificationPreferences {
+    match crate::preferences::get_stored(NOTIFICATIONS_PREFERENCE_KEY) {
+        // Older configs store a single on/off switch
+        Some(serde_json::Value::Bool(false)) => NotificationPreferences {
+            device: false,
+            updates: false,
+            frontload: false,
+            transactions: false,
+        },
+        Some(value) => serde_json::from_value(value).unwrap_or_default(),
+        None => NotificationPreferences::default(),
+    }
+}
+
+/// Persist the notification toggles
+pub fn set_preferences(preferences: &NotificationPreferences) -> Result<(), String> {
+    crate::preferences::set(NOTIFICATIONS_PREFERENCE_KEY, serde_json::json!(preferences))
+}
+
+/// Title and body for an event, if it's one the user wants to hear about
+fn notification_for(event_name: &str, payload: &serde_json::Value, preferences: &NotificationPreferences) -> Option<(String, String)> {
+    match event_name {
+        "device:connected" if preferences.device => Some((
+            "KeepKey connected".to_string(),
+            "Your KeepKey is connected to the vault.".to_string(),
+        )),
+        "device:disconnected" if preferences.device => Some((
+            "KeepKey disconnected".to_string(),
+            "Your KeepKey was disconnected.".to_string(),
+        )),
+        "device:features-updated" if preferences.updates => {
+            let status = payload.get("status")?;
+            let needs_firmware = status.get("needsFirmwareUpdate").and_then(|v| v.as_bool()).unwrap_or(false);
+            let needs_bootloader = status.get("needsBootloaderUpdate").and_then(|v| v.as_bool()).unwrap_or(false);
+            if !needs_firmware && !needs_bootloader {
+                return None;
+            }
+
+            // Only once per device per session
+            let device_id = payload.get("deviceId").and_then(|v| v.as_str()).unwrap_or_default().to_string();
+            if !UPDATE_NOTIFIED.lock().ok()?.insert(device_id) {
+                return None;
+            }
+
+            let body = match status.pointer("/firmwareCheck/latestVersion").and_then(|v| v.as_str()) {
+                Some(latest) if needs_firmware => format!("Firmware {} is available for your KeepKey.", latest),
+                _ if needs_bootloader => "A bootloader update is available for your KeepKey.".to_string(),
+                _ => "A firmware update is available for your KeepKey.".to_string(),
+            };
+            Some(("KeepKey update available".to_string(), body))
+        }
+        "frontload:completed" if preferences.frontload => Some((
+            "Accounts ready".to_string(),
+            "Your KeepKey accounts have been loaded.".to_string(),
+        )),
+        "frontload:failed" if preferences.frontload => Some((
+            "Account loading failed".to_string(),
+            payload.get("error").and_then(|v| v.as_str()).unwrap_or("Some accounts could not be loaded.").to_string(),
+        )),
+        "tx:finalized" if preferences.transactions => {
+            let txid = payload.get("txid").and_then(|v| v.as_str()).unwrap_or_default();
+            Some((
+                "Transaction confirmed".to_string(),
+                format!("Transaction {}… is now final.", &txid[..txid.len().min(12)]),
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// Show a native notification for a vault event when its category is enabled
+pub fn notify_event(event_name: &str, payload: &serde_json::Value) {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+    let Some((title, body)) = notification_for(event_name, payload, &get_preferences()) else {
+        return;
+    };
+
+    if let Err(e) = app.notification().builder().title(&title).body(&body).show() {
+        log::warn!("Failed to show notification for {}: {}", event_name, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_notification_once_per_device() {
+        let preferences = NotificationPreferences::default();
+        let payload = serde_json::json!({
+            "deviceId": "test-device",
+            "status": { "needsFirmwareUpdate": true, "needsBootloaderUpdate": false, "firmwareCheck": { "latestVersion": "7.10.0" } }
+        });
+
+        let (_, body) = notification_for("device:features-updated", &payload, &preferences).unwrap();
+        assert!(body.contains("7.10.0"));
+        assert!(notification_for("device:features-updated", &payload, &preferences).is_none());
+        assert!(notification_for("frontload:completed", &serde_json::json!({}), &preferences).is_none());
+    }
+}
@@ -0,0 +1,288 @@
+//! BIP21 (bitcoin: and friends) and EIP-681 (ethereum:) payment URI parsing.
+//!
+//! The result is a [`PaymentIntent`] the send flow or MCP tools can hand to
+//! the tx builder without re-parsing the URI themselves.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// UTXO URI schemes with their symbol and CAIP-2 id
+const UTXO_SCHEMES: &[(&str, &str, &str)] = &[
+    ("bitcoin", "BTC", "bip122:000000000019d6689c085ae165831e93"),
+    ("bitcoincash", "BCH", "bip122:000000000000000000651ef99cb9fcbe"),
+    ("litecoin", "LTC", "bip122:12a765e31ffd4059bada1e25190f6e98"),
+    ("dogecoin", "DOGE", "bip122:00000000001a91e3dace36e2be3bf030"),
+    ("dash", "DASH", "bip122:000007d91d1254d60e2dd1ae58038307"),
+];
+
+const UTXO_DECIMALS: u32 = 8;
+const ETH_DECIMALS: u32 = 18;
+
+/// Structured payment request decoded from a URI
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PaymentIntent {
+    /// URI scheme, e.g. "bitcoin" or "ethereum"
+    pub scheme: String,
+    /// CAIP-2 network id
+    pub network: String,
+    /// Recipient address
+    pub address: String,
+    /// Asset symbol, or the token contract for ERC-20 transfers
+    pub asset: String,
+    /// Token contract when the URI requests an ERC-20 transfer
+    pub token_contract: Option<String>,
+    /// Amount in display units, when the decimals are known
+    pub amount: Option<String>,
+    /// Amount in base units (satoshis, wei, token units)
+    pub amount_base_units: Option<String>,
+    pub label: Option<String>,
+    pub message: Option<String>,
+    /// EIP-155 chain id for ethereum: URIs
+    pub chain_id: Option<u64>,
+    /// Parameters not mapped above (e.g. lightning, pj, gas)
+    pub extra: BTreeMap<String, String>,
+}
+
+/// Parse a bitcoin:/litecoin:/.../ethereum: payment URI
+pub fn parse(uri: &str) -> Result<PaymentIntent, String> {
+    let uri = uri.trim();
+    let (scheme, rest) = uri
+        .split_once(':')
+        .ok_or_else(|| "Not a payment URI (missing scheme)".to_string())?;
+    let scheme = scheme.to_ascii_lowercase();
+
+    if scheme == "ethereum" {
+        return parse_eip681(rest);
+    }
+
+    let (_, symbol, network) = UTXO_SCHEMES
+        .iter()
+        .find(|(name, _, _)| *name == scheme)
+        .ok_or_else(|| format!("Unsupported payment URI scheme: {}", scheme))?;
+    parse_bip21(&scheme, symbol, network, rest)
+}
+
+fn split_query(rest: &str) -> (&str, Vec<(String, String)>) {
+    match rest.split_once('?') {
+        Some((path, query)) => (
+            path,
+            url::form_urlencoded::parse(query.as_bytes())
+                .map(|(k, v)| (k.into_owned(), v.into_owned()))
+                .collect(),
+        ),
+        None => (rest, Vec::new()),
+    }
+}
+
+fn parse_bip21(scheme: &str, symbol: &str, network: &str, rest: &str) -> Result<PaymentIntent, String> {
+    let (address, params) = split_query(rest.trim_start_matches("//"));
+    if address.is_empty() {
+        return Err("Payment URI has no address".to_string());
+    }
+    // Cash addresses repeat their own prefix
+    let address = if scheme == "bitcoincash" && !address.contains(':') {
+        format!("bitcoincash:{}", address)
+    } else {
+        address.to_string()
+    };
+
+    let mut intent = PaymentIntent {
+        scheme: scheme.to_string(),
+        network: network.to_string(),
+        address,
+        asset: symbol.to_string(),
+        token_contract: None,
+        amount: None,
+        amount_base_units: None,
+        label: None,
+        message: None,
+        chain_id: None,
+        extra: BTreeMap::new(),
+    };
+
+    for (key, value) in params {
+        match key.to_ascii_lowercase().as_str() {
+            "amount" => {
+                let base = decimal_to_base_units(&value, UTXO_DECIMALS)?;
+                intent.amount = Some(value);
+                intent.amount_base_units = Some(base);
+            }
+            "label" => intent.label = Some(value),
+            "message" => intent.message = Some(value),
+            // BIP21: unknown required parameters make the URI invalid
+            other if other.starts_with("req-") => {
+                return Err(format!("Unsupported required parameter: {}", key));
+            }
+            _ => {
+                intent.extra.insert(key, value);
+            }
+        }
+    }
+
+    Ok(intent)
+}
+
+fn parse_eip681(rest: &str) -> Result<PaymentIntent, String> {
+    let (path, params) = split_query(rest);
+    let path = path.strip_prefix("pay-").unwrap_or(path);
+    let (target_part, function) = match path.split_once('/') {
+        Some((target, function)) => (target, Some(function)),
+        None => (path, None),
+    };
+    let (target, chain_id) = match target_part.split_once('@') {
+        Some((target, chain)) => (
+            target,
+            chain.parse::<u64>().map_err(|_| format!("Invalid chain id: {}", chain))?,
+        ),
+        None => (target_part, 1),
+    };
+    if !is_eth_address(target) {
+        return Err(format!("Invalid Ethereum address: {}", target));
+    }
+
+    let mut intent = PaymentIntent {
+        scheme: "ethereum".to_string(),
+        network: format!("eip155:{}", chain_id),
+        address: target.to_string(),
+        asset: "ETH".to_string(),
+        token_contract: None,
+        amount: None,
+        amount_base_units: None,
+        label: None,
+        message: None,
+        chain_id: Some(chain_id),
+        extra: BTreeMap::new(),
+    };
+
+    match function {
+        None => {
+            for (key, value) in params {
+                match key.as_str() {
+                    "value" => {
+                        let wei = scientific_to_integer(&value)?;
+                        intent.amount = Some(base_units_to_decimal(&wei, ETH_DECIMALS));
+                        intent.amount_base_units = Some(wei);
+                    }
+                    "label" => intent.label = Some(value),
+                    "message" => intent.message = Some(value),
+                    _ => {
+                        intent.extra.insert(key, value);
+                    }
+                }
+            }
+        }
+        Some("transfer") => {
+            // ERC-20: the target is the token, the recipient is a parameter
+            intent.token_contract = Some(target.to_string());
+            intent.asset = target.to_string();
+            intent.address = String::new();
+            for (key, value) in params {
+                match key.as_str() {
+                    "address" => {
+                        if !is_eth_address(&value) {
+                            return Err(format!("Invalid recipient address: {}", value));
+                        }
+                        intent.address = value;
+                    }
+                    "uint256" => intent.amount_base_units = Some(scientific_to_integer(&value)?),
+                    "label" => intent.label = Some(value),
+                    "message" => intent.message = Some(value),
+                    _ => {
+                        intent.extra.insert(key, value);
+                    }
+                }
+            }
+            if intent.address.is_empty() {
+                return Err("ERC-20 transfer URI has no recipient address".to_string());
+            }
+        }
+        Some(other) => return Err(format!("Unsupported contract function: {}", other)),
+    }
+
+    Ok(intent)
+}
+
+fn is_eth_address(value: &str) -> bool {
+    value.len() == 42 && value.starts_with("0x") && value[2..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// "0.015" with 8 decimals -> "1500000"
+fn decimal_to_base_units(value: &str, decimals: u32) -> Result<String, String> {
+    let (whole, fraction) = value.split_once('.').unwrap_or((value, ""));
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit())
+    {
+        return Err(format!("Invalid amount: {}", value));
+    }
+    if fraction.len() > decimals as usize {
+        return Err(format!("Amount {} has more than {} decimals", value, decimals));
+    }
+    let digits = format!("{}{:0<width$}", whole, fraction, width = decimals as usize);
+    Ok(trim_leading_zeros(&digits))
+}
+
+/// "1500000" with 8 decimals -> "0.015"
+fn base_units_to_decimal(value: &str, decimals: u32) -> String {
+    let decimals = decimals as usize;
+    let padded = format!("{:0>width$}", value, width = decimals + 1);
+    let (whole, fraction) = padded.split_at(padded.len() - decimals);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{}.{}", whole, fraction)
+    }
+}
+
+/// EIP-681 numbers may use scientific notation ("2.014e18"); the result must be whole
+fn scientific_to_integer(value: &str) -> Result<String, String> {
+    let lower = value.to_ascii_lowercase();
+    let (mantissa, exponent) = match lower.split_once('e') {
+        Some((m, e)) => (m, e.parse::<u32>().map_err(|_| format!("Invalid number: {}", value))?),
+        None => (lower.as_str(), 0),
+    };
+    let fraction = mantissa.split_once('.').map(|(_, f)| f).unwrap_or("");
+    if fraction.len() > exponent as usize {
+        return Err(format!("{} is not a whole number of base units", value));
+    }
+    decimal_to_base_units(mantissa, exponent).map_err(|_| format!("Invalid number: {}", value))
+}
+
+fn trim_leading_zeros(digits: &str) -> String {
+    let trimmed = digits.trim_start_matches('0');
+    if trimmed.is_empty() { "0".to_string() } else { trimmed.to_string() }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bip21() {
+        let intent = parse("bitcoin:bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq?amount=0.015&label=Coffee%20shop&pj=https://example.com/pj").unwrap();
+        assert_eq!(intent.address, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+        assert_eq!(intent.amount.as_deref(), Some("0.015"));
+        assert_eq!(intent.amount_base_units.as_deref(), Some("1500000"));
+        assert_eq!(intent.label.as_deref(), Some("Coffee shop"));
+        assert_eq!(intent.extra.get("pj").map(String::as_str), Some("https://example.com/pj"));
+        assert!(parse("bitcoin:bc1qxyz?req-somethingnew=1").is_err());
+        assert!(parse("bitcoin:bc1qxyz?amount=0.000000001").is_err());
+    }
+
+    #[test]
+    fn test_parse_eip681() {
+        let native = parse("ethereum:0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359@1?value=2.014e18").unwrap();
+        assert_eq!(native.network, "eip155:1");
+        assert_eq!(native.amount_base_units.as_deref(), Some("2014000000000000000"));
+        assert_eq!(native.amount.as_deref(), Some("2.014"));
+
+        let token = parse("ethereum:0x89205a3a3b2a69de6dbf7f01ed13b2108b2c43e7@137/transfer?address=0x8e23ee67d1332ad560396262c48ffbb01f93d052&uint256=1e6").unwrap();
+        assert_eq!(token.token_contract.as_deref(), Some("0x89205a3a3b2a69de6dbf7f01ed13b2108b2c43e7"));
+        assert_eq!(token.address, "0x8e23ee67d1332ad560396262c48ffbb01f93d052");
+        assert_eq!(token.amount_base_units.as_deref(), Some("1000000"));
+        assert_eq!(token.chain_id, Some(137));
+    }
+}
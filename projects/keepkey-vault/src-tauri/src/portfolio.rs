@@ -0,0 +1,306 @@
+//! Portfolio refresh coordination and balance change detection.
+//!
+//! Balances are fetched by the frontend; each refresh reports the per-asset
+//! balances here. They are compared with the previous refresh, and
+//! `portfolio:changed` is emitted with one delta per asset that moved, so
+//! UIs and automation hooks can react to "received 0.1 BTC" instead of
+//! diffing snapshots themselves. API clients that know a single network just
+//! received funds ask for that slice to be refetched through
+//! `portfolio:refresh-requested`. While frontload runs, the per-chain sync
+//! state is tracked here so partial portfolios can say what is still missing.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::AppHandle;
+use utoipa::ToSchema;
+
+/// Balance changes smaller than this are rounding noise
+const MIN_BALANCE_DELTA: f64 = 1e-12;
+
+/// One asset's balance as of a portfolio refresh
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetBalance {
+    pub caip: String,
+    pub symbol: String,
+    /// In whole coins
+    pub balance: f64,
+    pub value_usd: f64,
+}
+
+/// How one asset changed between two refreshes
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceDelta {
+    pub caip: String,
+    pub symbol: String,
+    pub old_balance: f64,
+    pub new_balance: f64,
+    /// New minus old, in whole coins
+    pub delta: f64,
+    pub usd_delta: f64,
+}
+
+/// Part of the portfolio to refetch; nothing selected means everything
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshRequest {
+    /// Only if this device is the one connected
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// CAIP-2 network, e.g. bip122:000000000019d6689c085ae165831e93
+    #[serde(default)]
+    pub network: Option<String>,
+    /// CAIP-19 asset; its whole network is refetched in the same provider call
+    #[serde(default)]
+    pub caip: Option<String>,
+}
+
+/// How far frontload has got with one chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, ToSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum SyncState {
+    Pending,
+    InProgress,
+    Complete,
+    /// Frontload stopped or hit errors before this chain was fully cached
+    Failed,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainSync {
+    pub chain: String,
+    pub state: SyncState,
+}
+
+/// Balances known so far, and which chains are still being synced
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioSnapshot {
+    pub balances: Vec<AssetBalance>,
+    /// Chains in frontload order; empty if the device has not been frontloaded since startup
+    pub sync_state: Vec<ChainSync>,
+    /// Set while any chain is not complete
+    pub partial: bool,
+}
+
+lazy_static::lazy_static! {
+    /// Per-device balances reported by the latest refresh
+    static ref LAST_BALANCES: Mutex<HashMap<String, Vec<AssetBalance>>> = Mutex::new(HashMap::new());
+    /// Per-device chain sync state of the latest frontload
+    static ref SYNC_STATE: Mutex<HashMap<String, Vec<ChainSync>>> = Mutex::new(HashMap::new());
+}
+
+/// When balances or sync state last changed, in unix milliseconds
+static LAST_UPDATED: AtomicI64 = AtomicI64::new(0);
+
+/// Move the last-updated time forward, strictly, so two changes in one millisecond still differ
+fn touch() {
+    let now = chrono::Utc::now().timestamp_millis();
+    let _ = LAST_UPDATED.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |last| Some(now.max(last + 1)));
+}
+
+/// When any device's balances or sync state last changed, in unix milliseconds
+pub fn last_updated() -> i64 {
+    LAST_UPDATED.load(Ordering::SeqCst)
+}
+
+/// Per-asset changes; assets missing on one side count as a zero balance
+fn deltas(previous: &[AssetBalance], current: &[AssetBalance]) -> Vec<BalanceDelta> {
+    let zero = |asset: &AssetBalance| AssetBalance { balance: 0.0, value_usd: 0.0, ..asset.clone() };
+    let mut changes = Vec::new();
+
+    let pairs = current
+        .iter()
+        .map(|new| (previous.iter().find(|old| old.caip == new.caip).cloned().unwrap_or_else(|| zero(new)), new.clone()))
+        .chain(
+            previous
+                .iter()
+                .filter(|old| !current.iter().any(|new| new.caip == old.caip))
+                .map(|old| (old.clone(), zero(old))),
+        );
+    for (old, new) in pairs {
+        let delta = new.balance - old.balance;
+        if delta.abs() < MIN_BALANCE_DELTA {
+            continue;
+        }
+        changes.push(BalanceDelta {
+            caip: new.caip,
+            symbol: new.symbol,
+            old_balance: old.balance,
+            new_balance: new.balance,
+            delta,
+            usd_delta: new.value_usd - old.value_usd,
+        });
+    }
+    changes
+}
+
+/// Fill in the network from the CAIP and reject selections that contradict themselves
+fn normalize(mut request: RefreshRequest) -> Result<RefreshRequest, String> {
+    let trimmed = |value: Option<String>| value.map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    request.device_id = trimmed(request.device_id);
+    request.network = trimmed(request.network);
+    request.caip = trimmed(request.caip);
+
+    if let Some(caip) = &request.caip {
+        let (network, _) = caip
+            .split_once('/')
+            .ok_or_else(|| format!("Not a CAIP-19 asset id: {}", caip))?;
+        match &request.network {
+            Some(requested) if requested != network => {
+                return Err(format!("Asset {} is not on network {}", caip, requested));
+            }
+            _ => request.network = Some(network.to_string()),
+        }
+    }
+    if request.network.as_ref().is_some_and(|network| !network.contains(':')) {
+        return Err("Network must be a CAIP-2 id like bip122:000000000019d6689c085ae165831e93".to_string());
+    }
+    Ok(request)
+}
+
+/// Ask the frontend, which holds the provider client, to refetch a slice of the portfolio
+pub async fn request_refresh(app: &AppHandle, request: RefreshRequest) -> Result<RefreshRequest, String> {
+    let request = normalize(request)?;
+    if let Some(device_id) = &request.device_id {
+        let connected = keepkey_rust::features::list_connected_devices()
+            .iter()
+            .any(|device| device.is_keepkey && &device.unique_id == device_id);
+        if !connected {
+            return Err(format!("Device not found: {}", device_id));
+        }
+    }
+
+    log::info!("🔄 Portfolio refresh requested for {}", request.network.as_deref().unwrap_or("all networks"));
+    crate::commands::emit_or_queue_event(app, "portfolio:refresh-requested", serde_json::json!(request)).await?;
+    Ok(request)
+}
+
+/// Mark every chain of a starting frontload as pending
+pub fn begin_sync<'a>(device_id: &str, chains: impl IntoIterator<Item = &'a str>) {
+    let chains = chains
+        .into_iter()
+        .map(|chain| ChainSync { chain: chain.to_string(), state: SyncState::Pending })
+        .collect();
+    SYNC_STATE.lock().unwrap().insert(device_id.to_string(), chains);
+    touch();
+}
+
+pub fn set_chain_state(device_id: &str, chain: &str, state: SyncState) {
+    if let Some(chains) = SYNC_STATE.lock().unwrap().get_mut(device_id) {
+        if let Some(entry) = chains.iter_mut().find(|entry| entry.chain == chain) {
+            entry.state = state;
+        }
+    }
+    touch();
+}
+
+/// Mark every chain a failed frontload did not finish as failed
+pub fn fail_sync(device_id: &str) {
+    if let Some(chains) = SYNC_STATE.lock().unwrap().get_mut(device_id) {
+        for entry in chains.iter_mut().filter(|entry| entry.state != SyncState::Complete) {
+            entry.state = SyncState::Failed;
+        }
+    }
+    touch();
+}
+
+fn snapshot_from(balances: Vec<AssetBalance>, sync_state: Vec<ChainSync>) -> PortfolioSnapshot {
+    let partial = sync_state.iter().any(|entry| entry.state != SyncState::Complete);
+    PortfolioSnapshot { balances, sync_state, partial }
+}
+
+/// Whatever balances are known for a device, with its sync state; None if the
+/// device has neither been refreshed nor frontloaded since startup
+pub fn snapshot(device_id: &str) -> Option<PortfolioSnapshot> {
+    let sync_state = SYNC_STATE.lock().unwrap().get(device_id).cloned();
+    let balances = last_balances(device_id);
+    if sync_state.is_none() && balances.is_none() {
+        return None;
+    }
+    Some(snapshot_from(balances.unwrap_or_default(), sync_state.unwrap_or_default()))
+}
+
+/// Balances reported by the device's latest refresh
+pub fn last_balances(device_id: &str) -> Option<Vec<AssetBalance>> {
+    LAST_BALANCES.lock().ok()?.get(device_id).cloned()
+}
+
+/// Store a refresh's balances and announce what changed since the same
+/// device's previous one. The first refresh of a device only sets the baseline.
+pub async fn record(app: &AppHandle, device_id: &str, balances: Vec<AssetBalance>) -> Vec<BalanceDelta> {
+    let previous = LAST_BALANCES.lock().unwrap().insert(device_id.to_string(), balances.clone());
+    touch();
+    let Some(previous) = previous else {
+        return Vec::new();
+    };
+
+    let changes = deltas(&previous, &balances);
+    if !changes.is_empty() {
+        log::info!("💹 Portfolio changed for {} asset(s) on device {}", changes.len(), device_id);
+        let payload = serde_json::json!({ "deviceId": device_id, "changes": changes });
+        if let Err(e) = crate::commands::emit_or_queue_event(app, "portfolio:changed", payload).await {
+            log::warn!("Failed to emit portfolio:changed: {}", e);
+        }
+    }
+    changes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn asset(symbol: &str, balance: f64, value_usd: f64) -> AssetBalance {
+        AssetBalance { caip: format!("caip:{}", symbol), symbol: symbol.to_string(), balance, value_usd }
+    }
+
+    #[test]
+    fn test_deltas() {
+        let previous = vec![asset("BTC", 1.0, 60_000.0), asset("ETH", 2.0, 6_000.0)];
+        let current = vec![asset("BTC", 1.1, 66_000.0), asset("ETH", 2.0, 6_100.0), asset("ATOM", 5.0, 50.0)];
+
+        let changes = deltas(&previous, &current);
+        assert_eq!(changes.len(), 2);
+        assert_eq!(changes[0].symbol, "BTC");
+        assert!((changes[0].delta - 0.1).abs() < 1e-9);
+        assert!((changes[0].usd_delta - 6_000.0).abs() < 1e-9);
+        assert_eq!(changes[1].symbol, "ATOM");
+        assert_eq!(changes[1].old_balance, 0.0);
+
+        let emptied = deltas(&current, &[]);
+        assert_eq!(emptied.len(), 3);
+        assert!(emptied.iter().all(|change| change.new_balance == 0.0));
+    }
+
+    #[test]
+    fn test_normalize() {
+        let request = |network: Option<&str>, caip: Option<&str>| RefreshRequest {
+            device_id: None,
+            network: network.map(String::from),
+            caip: caip.map(String::from),
+        };
+        let btc = "bip122:000000000019d6689c085ae165831e93/slip44:0";
+
+        let normalized = normalize(request(None, Some(btc))).unwrap();
+        assert_eq!(normalized.network.as_deref(), Some("bip122:000000000019d6689c085ae165831e93"));
+        assert_eq!(normalize(request(Some(" "), None)).unwrap(), RefreshRequest::default());
+        assert!(normalize(request(Some("eip155:1"), Some(btc))).is_err());
+        assert!(normalize(request(None, Some("bitcoin"))).is_err());
+        assert!(normalize(request(Some("bitcoin"), None)).is_err());
+    }
+
+    #[test]
+    fn test_snapshot_partial() {
+        let chain = |chain: &str, state| ChainSync { chain: chain.to_string(), state };
+
+        let syncing = snapshot_from(vec![asset("BTC", 1.0, 60_000.0)], vec![chain("bitcoin", SyncState::Complete), chain("litecoin", SyncState::InProgress)]);
+        assert!(syncing.partial);
+        assert_eq!(syncing.balances.len(), 1);
+        assert!(!snapshot_from(Vec::new(), vec![chain("bitcoin", SyncState::Complete)]).partial);
+        assert!(!snapshot_from(Vec::new(), Vec::new()).partial);
+    }
+}
@@ -0,0 +1,262 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+/// Preference key holding the device polling configuration
+const POLLING_PREFERENCE_KEY: &str = "device_polling";
+
+/// How long a battery reading is reused before asking the OS again
+const BATTERY_CHECK_TTL: Duration = Duration::from_secs(30);
+
+/// Bounds for configured poll intervals
+const MIN_POLL_INTERVAL_MS: u64 = 250;
+const MAX_POLL_INTERVAL_MS: u64 = 60_000;
+
+/// When low-power mode applies
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum LowPowerMode {
+    /// Follow the OS: low power while running on battery
+    #[default]
+    Auto,
+    On,
+    Off,
+}
+
+/// Device polling preferences
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PollingConfig {
+    /// Device scan interval in normal operation
+    pub interval_ms: u64,
+    /// Device scan interval in low-power mode
+    pub low_power_interval_ms: u64,
+    pub low_power: LowPowerMode,
+}
+
+impl Default for PollingConfig {
+    fn default() -> Self {
+        Self {
+            interval_ms: 1000,
+            low_power_interval_ms: 5000,
+            low_power: LowPowerMode::Auto,
+        }
+    }
+}
+
+/// Current power state as seen by the vault
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PowerStatus {
+    /// None when the OS doesn't report a battery
+    pub on_battery: Option<bool>,
+    pub low_power_active: bool,
+    /// Session-only override set via set_low_power_mode
+    pub runtime_override: Option<bool>,
+    pub poll_interval_ms: u64,
+    /// Devices whose auto frontload is waiting for normal power
+    pub deferred_frontloads: Vec<String>,
+    pub config: PollingConfig,
+}
+
+lazy_static::lazy_static! {
+    static ref RUNTIME_OVERRIDE: Mutex<Option<bool>> = Mutex::new(None);
+    static ref LAST_BATTERY_CHECK: Mutex<Option<(Instant, Option<bool>)>> = Mutex::new(None);
+    static ref DEFERRED_FRONTLOADS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    /// Polling configuration as last read from preferences
+    static ref CONFIG: Mutex<Option<PollingConfig>> = Mutex::new(None);
+}
+
+fn load_config() -> PollingConfig {
+    crate::preferences::get_as(POLLING_PREFERENCE_KEY).unwrap_or_default()
+}
+
+/// The polling configuration; read from preferences once and kept current by [`init`]
+pub fn get_config() -> PollingConfig {
+    let mut config = CONFIG.lock().unwrap();
+    config.get_or_insert_with(load_config).clone()
+}
+
+/// Reload the polling configuration whenever its preference changes
+pub fn init() {
+    let mut changes = crate::preferences::subscribe();
+    tauri::async_runtime::spawn(async move {
+        while let Ok(change) = changes.recv().await {
+            if change.key == POLLING_PREFERENCE_KEY {
+                *CONFIG.lock().unwrap() = Some(load_config());
+            }
+        }
+    });
+}
+
+/// Validate and persist the polling configuration
+pub fn set_config(polling: &PollingConfig) -> Result<(), String> {
+    for (name, value) in [("intervalMs", polling.interval_ms), ("lowPowerIntervalMs", polling.low_power_interval_ms)] {
+        if !(MIN_POLL_INTERVAL_MS..=MAX_POLL_INTERVAL_MS).contains(&value) {
+            return Err(format!("{} must be between {} and {} ms", name, MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS));
+        }
+    }
+
+    crate::preferences::set(POLLING_PREFERENCE_KEY, serde_json::json!(polling))?;
+    *CONFIG.lock().unwrap() = Some(polling.clone());
+    Ok(())
+}
+
+/// Force low-power mode on or off for this session, or None to follow preferences
+pub fn set_runtime_override(enabled: Option<bool>) {
+    if let Ok(mut runtime) = RUNTIME_OVERRIDE.lock() {
+        *runtime = enabled;
+    }
+}
+
+/// Whether the machine is running on battery (cached briefly)
+pub fn on_battery() -> Option<bool> {
+    let mut last = LAST_BATTERY_CHECK.lock().ok()?;
+    if let Some((checked_at, on_battery)) = *last {
+        if checked_at.elapsed() < BATTERY_CHECK_TTL {
+            return on_battery;
+        }
+    }
+
+    let on_battery = query_battery();
+    *last = Some((Instant::now(), on_battery));
+    on_battery
+}
+
+/// Power source according to sysfs; None without a battery
+#[cfg(target_os = "linux")]
+fn query_battery() -> Option<bool> {
+    let mut found = false;
+    for entry in std::fs::read_dir("/sys/class/power_supply").ok()?.flatten() {
+        let read = |name: &str| std::fs::read_to_string(entry.path().join(name)).map(|s| s.trim().to_string());
+        let Ok(kind) = read("type") else { continue };
+        if kind != "Battery" {
+            continue;
+        }
+        found = true;
+        let Ok(status) = read("status") else { continue };
+        if status == "Discharging" {
+            return Some(true);
+        }
+    }
+    found.then_some(false)
+}
+
+/// Power source according to `pmset -g batt`
+#[cfg(target_os = "macos")]
+fn query_battery() -> Option<bool> {
+    let output = std::process::Command::new("pmset").args(["-g", "batt"]).output().ok()?;
+    parse_pmset(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn parse_pmset(output: &str) -> Option<bool> {
+    if !output.contains("InternalBattery") {
+        return None;
+    }
+    Some(output.contains("'Battery Power'"))
+}
+
+/// Power source according to GetSystemPowerStatus
+#[cfg(target_os = "windows")]
+fn query_battery() -> Option<bool> {
+    #[repr(C)]
+    #[derive(Default)]
+    struct SystemPowerStatus {
+        ac_line_status: u8,
+        battery_flag: u8,
+        battery_life_percent: u8,
+        system_status_flag: u8,
+        battery_life_time: u32,
+        battery_full_life_time: u32,
+    }
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetSystemPowerStatus(status: *mut SystemPowerStatus) -> i32;
+    }
+
+    const NO_SYSTEM_BATTERY: u8 = 128;
+    let mut status = SystemPowerStatus::default();
+    // SAFETY: the struct matches SYSTEM_POWER_STATUS and outlives the call
+    if unsafe { GetSystemPowerStatus(&mut status) } == 0 || status.battery_flag & NO_SYSTEM_BATTERY != 0 {
+        return None;
+    }
+    match status.ac_line_status {
+        0 => Some(true),
+        1 => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", target_os = "windows")))]
+fn query_battery() -> Option<bool> {
+    None
+}
+
+/// Whether low-power mode is in effect right now
+pub fn is_low_power() -> bool {
+    low_power_for(&get_config())
+}
+
+fn low_power_for(config: &PollingConfig) -> bool {
+    if let Some(forced) = RUNTIME_OVERRIDE.lock().ok().and_then(|r| *r) {
+        return forced;
+    }
+    match config.low_power {
+        LowPowerMode::On => true,
+        LowPowerMode::Off => false,
+        LowPowerMode::Auto => on_battery().unwrap_or(false),
+    }
+}
+
+/// Interval between device scans for the current power state
+pub fn poll_interval() -> Duration {
+    interval_for(&get_config())
+}
+
+fn interval_for(config: &PollingConfig) -> Duration {
+    let ms = if low_power_for(config) { config.low_power_interval_ms } else { config.interval_ms };
+    Duration::from_millis(ms.clamp(MIN_POLL_INTERVAL_MS, MAX_POLL_INTERVAL_MS))
+}
+
+/// Remember a device whose auto frontload was skipped for low power
+pub fn defer_frontload(device_id: &str) {
+    if let Ok(mut deferred) = DEFERRED_FRONTLOADS.lock() {
+        deferred.insert(device_id.to_string());
+    }
+}
+
+/// Take all deferred frontloads, e.g. once normal power is back
+pub fn take_deferred_frontloads() -> Vec<String> {
+    DEFERRED_FRONTLOADS.lock()
+        .map(|mut deferred| deferred.drain().collect())
+        .unwrap_or_default()
+}
+
+/// Snapshot of the current power state
+pub fn status() -> PowerStatus {
+    let config = get_config();
+    PowerStatus {
+        on_battery: on_battery(),
+        low_power_active: low_power_for(&config),
+        runtime_override: RUNTIME_OVERRIDE.lock().ok().and_then(|r| *r),
+        poll_interval_ms: interval_for(&config).as_millis() as u64,
+        deferred_frontloads: DEFERRED_FRONTLOADS.lock().map(|d| d.iter().cloned().collect()).unwrap_or_default(),
+        config,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pmset() {
+        let on_battery = "Now drawing from 'Battery Power'\n -InternalBattery-0 (id=4653155)\t85%; discharging; 5:12 remaining present: true\n";
+        let on_ac = "Now drawing from 'AC Power'\n -InternalBattery-0 (id=4653155)\t100%; charged; 0:00 remaining present: true\n";
+        assert_eq!(parse_pmset(on_battery), Some(true));
+        assert_eq!(parse_pmset(on_ac), Some(false));
+        assert_eq!(parse_pmset("Now drawing from 'AC Power'\n"), None);
+    }
+}
@@ -0,0 +1,227 @@
+//! Typed registry of the preferences stored in keepkey.json.
+//!
+//! Every known key has a type, a default and a description. Writes through
+//! [`set`] are validated, and every change to the preferences file (from any
+//! subsystem) is announced as a `preferences:changed` event and on an in-process
+//! channel so subsystems can react without a restart.
+
+use once_cell::sync::{Lazy, OnceCell};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::broadcast;
+
+/// Shape a preference value must have
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum PreferenceKind {
+    Bool,
+    Enum { values: &'static [&'static str] },
+    /// An http(s) URL
+    Url,
+    Array,
+    Object,
+    /// A whole number within the given bounds
+    Integer { min: i64, max: i64 },
+    /// Anything JSON, for keys with legacy shapes
+    Any,
+}
+
+/// A registered preference
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreferenceSpec {
+    pub key: &'static str,
+    pub kind: PreferenceKind,
+    /// Default value as JSON text
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+impl PreferenceSpec {
+    pub fn default_value(&self) -> Value {
+        serde_json::from_str(self.default).unwrap_or(Value::Null)
+    }
+}
+
+/// All preferences the backend knows about
+pub const REGISTRY: &[PreferenceSpec] = &[
+    PreferenceSpec { key: "language", kind: PreferenceKind::Enum { values: &["en", "es", "fr", "de"] }, default: "\"en\"", description: "UI and status message language" },
+    PreferenceSpec { key: "theme", kind: PreferenceKind::Enum { values: &["dark", "light"] }, default: "\"dark\"", description: "UI theme" },
+    PreferenceSpec { key: "isOnboarded", kind: PreferenceKind::Bool, default: "false", description: "Whether onboarding was completed" },
+    PreferenceSpec { key: "notifications", kind: PreferenceKind::Any, default: "true", description: "Native notification toggles (or false to disable all)" },
+    PreferenceSpec { key: "api_tls_enabled", kind: PreferenceKind::Bool, default: "false", description: "Also serve the REST API over HTTPS" },
+    PreferenceSpec { key: "cors_allowed_origins", kind: PreferenceKind::Array, default: "null", description: "Origins allowed to call the REST API" },
+    PreferenceSpec { key: "firmware_manifest_url", kind: PreferenceKind::Url, default: "null", description: "Override for the firmware release manifest" },
+    PreferenceSpec { key: "frontload_schedules", kind: PreferenceKind::Object, default: "{}", description: "Scheduled cache refreshes by device id" },
+    PreferenceSpec { key: "frontload_chain_priority", kind: PreferenceKind::Array, default: "[]", description: "Chains to frontload first" },
+    PreferenceSpec { key: "automation_hooks", kind: PreferenceKind::Array, default: "[]", description: "Webhooks and scripts run on vault events" },
+    PreferenceSpec { key: "cosmos_chains", kind: PreferenceKind::Array, default: "[]", description: "Additional Cosmos SDK chains" },
+    PreferenceSpec { key: "wallets", kind: PreferenceKind::Array, default: "[]", description: "Named groups of devices and watch-only keys" },
+    PreferenceSpec { key: "ssh_agent", kind: PreferenceKind::Object, default: "{}", description: "SSH agent identities" },
+    PreferenceSpec { key: "gpg_identity", kind: PreferenceKind::Object, default: "null", description: "Enrolled OpenPGP signing identity" },
+    PreferenceSpec { key: "device_polling", kind: PreferenceKind::Object, default: "{}", description: "Device scan intervals and low-power mode" },
+    PreferenceSpec { key: "event_controller", kind: PreferenceKind::Object, default: "{}", description: "Disconnect grace period and feature fetch timeouts" },
+    PreferenceSpec { key: "endpoint_flags", kind: PreferenceKind::Object, default: "{}", description: "REST endpoint groups switched off" },
+    PreferenceSpec { key: "passphrase_cache_policy", kind: PreferenceKind::Object, default: "{}", description: "How long passphrase sessions stay cached" },
+    PreferenceSpec { key: "wipe_interlock", kind: PreferenceKind::Object, default: "{}", description: "Wipe confirmation requirements" },
+    PreferenceSpec { key: "support_endpoint", kind: PreferenceKind::Url, default: "null", description: "Support backend that accepts tickets directly" },
+    PreferenceSpec { key: "settings_backup", kind: PreferenceKind::Object, default: "{}", description: "Encrypted settings backup destination" },
+    PreferenceSpec { key: "testnet_mode", kind: PreferenceKind::Bool, default: "false", description: "Derive testnet/signet and Sepolia accounts" },
+    PreferenceSpec { key: "coin_selection_strategy", kind: PreferenceKind::Enum { values: &["bnb", "avoid-reuse", "oldest-first", "default"] }, default: "\"bnb\"", description: "How the Bitcoin tx builder picks inputs" },
+    PreferenceSpec { key: "rate_lock", kind: PreferenceKind::Object, default: "{}", description: "Exchange rate lock window and drift warning threshold" },
+    PreferenceSpec { key: "device_queue", kind: PreferenceKind::Object, default: "{}", description: "Idle timeout and maximum depth for device queue workers" },
+    PreferenceSpec { key: "portfolio_in_title", kind: PreferenceKind::Bool, default: "false", description: "Show the portfolio total in the window title" },
+    PreferenceSpec { key: "portfolio_denomination", kind: PreferenceKind::Enum { values: &["usd", "btc", "sats"] }, default: "\"usd\"", description: "Unit portfolio totals are shown in" },
+    PreferenceSpec { key: "custom_firmware", kind: PreferenceKind::Object, default: "{}", description: "Devices flashed with non-official firmware" },
+    PreferenceSpec { key: "confirmation_thresholds", kind: PreferenceKind::Object, default: "{}", description: "Confirmations required per network before a transaction is final" },
+    PreferenceSpec { key: "asset_registry_url", kind: PreferenceKind::Url, default: "null", description: "Signed asset/path registry to use instead of the bundled one" },
+    PreferenceSpec { key: "asset_registry_keys", kind: PreferenceKind::Array, default: "[]", description: "Extra hex secp256k1 public keys trusted to sign the asset registry, on top of the pinned publisher keys" },
+    PreferenceSpec { key: "balance_reconciliation", kind: PreferenceKind::Object, default: "{}", description: "Cross-check Bitcoin balances against a second provider" },
+    PreferenceSpec { key: "cache_tombstone_retention_days", kind: PreferenceKind::Integer { min: 1, max: 365 }, default: "14", description: "Days a cleared device cache can still be restored" },
+];
+
+/// A single preference modification
+#[derive(Debug, Clone, Serialize)]
+pub struct PreferenceChange {
+    pub key: String,
+    /// New value, or null when the key was removed
+    pub value: Value,
+}
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+static CHANGES: Lazy<broadcast::Sender<PreferenceChange>> = Lazy::new(|| broadcast::channel(64).0);
+
+/// Remember the app handle used to emit `preferences:changed`
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// Receive every preference change made from now on
+pub fn subscribe() -> broadcast::Receiver<PreferenceChange> {
+    CHANGES.subscribe()
+}
+
+/// Look up a registered preference
+pub fn spec(key: &str) -> Option<&'static PreferenceSpec> {
+    REGISTRY.iter().find(|spec| spec.key == key)
+}
+
+/// Check a value against the registered type of its key (unregistered keys are accepted)
+pub fn validate(key: &str, value: &Value) -> Result<(), String> {
+    let Some(spec) = spec(key) else {
+        return Ok(());
+    };
+    if value.is_null() {
+        return Ok(());
+    }
+
+    let valid = match spec.kind {
+        PreferenceKind::Bool => value.is_boolean(),
+        PreferenceKind::Enum { values } => value.as_str().map_or(false, |s| values.contains(&s)),
+        PreferenceKind::Url => value
+            .as_str()
+            .and_then(|s| url::Url::parse(s).ok())
+            .map_or(false, |u| matches!(u.scheme(), "http" | "https")),
+        PreferenceKind::Array => value.is_array(),
+        PreferenceKind::Object => value.is_object(),
+        PreferenceKind::Integer { min, max } => value.as_i64().map_or(false, |n| (min..=max).contains(&n)),
+        PreferenceKind::Any => true,
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("Invalid value for preference {}: expected {:?}", key, spec.kind))
+    }
+}
+
+/// The value stored for a preference, without falling back to a default
+pub fn get_stored(key: &str) -> Option<Value> {
+    crate::commands::load_config()
+        .ok()
+        .and_then(|config| config.get(key).cloned())
+}
+
+/// Current value of a preference, falling back to its registered default
+pub fn get(key: &str) -> Value {
+    get_stored(key)
+        .or_else(|| spec(key).map(|spec| spec.default_value()))
+        .unwrap_or(Value::Null)
+}
+
+/// Typed read of a preference; None if unset and without a usable default
+pub fn get_as<T: DeserializeOwned>(key: &str) -> Option<T> {
+    serde_json::from_value(get(key)).ok()
+}
+
+/// Validate and store a preference; null removes it
+pub fn set(key: &str, value: Value) -> Result<(), String> {
+    validate(key, &value)?;
+
+    let mut config = crate::commands::load_config()?;
+    if let Some(obj) = config.as_object_mut() {
+        if value.is_null() {
+            obj.remove(key);
+        } else {
+            obj.insert(key.to_string(), value);
+        }
+    }
+    crate::commands::save_config(&config)
+}
+
+/// Announce the top-level keys that differ between two versions of the preferences file
+pub fn notify_changes(previous: Option<&Value>, current: &Value) {
+    let empty = serde_json::Map::new();
+    let before = previous.and_then(|v| v.as_object()).unwrap_or(&empty);
+    let after = current.as_object().unwrap_or(&empty);
+
+    let changed = after
+        .iter()
+        .filter(|(key, value)| before.get(*key) != Some(*value))
+        .map(|(key, value)| PreferenceChange { key: key.clone(), value: value.clone() })
+        .chain(
+            before
+                .keys()
+                .filter(|key| !after.contains_key(*key))
+                .map(|key| PreferenceChange { key: key.clone(), value: Value::Null }),
+        );
+
+    for change in changed {
+        log::debug!("⚙️ Preference changed: {}", change.key);
+        if let Some(app) = APP_HANDLE.get() {
+            if let Err(e) = app.emit("preferences:changed", &change) {
+                log::warn!("Failed to emit preferences:changed: {}", e);
+            }
+        }
+        // No receivers is fine
+        let _ = CHANGES.send(change);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate() {
+        assert!(validate("language", &serde_json::json!("fr")).is_ok());
+        assert!(validate("language", &serde_json::json!("xx")).is_err());
+        assert!(validate("api_tls_enabled", &serde_json::json!("yes")).is_err());
+        assert!(validate("firmware_manifest_url", &serde_json::json!("ftp://example.com")).is_err());
+        assert!(validate("firmware_manifest_url", &serde_json::json!("https://example.com/m.json")).is_ok());
+        assert!(validate("some_frontend_key", &serde_json::json!(42)).is_ok());
+        assert!(validate("cache_tombstone_retention_days", &serde_json::json!(30)).is_ok());
+        assert!(validate("cache_tombstone_retention_days", &serde_json::json!(0)).is_err());
+        assert!(validate("cache_tombstone_retention_days", &serde_json::json!("30")).is_err());
+    }
+
+    #[test]
+    fn test_registry_defaults_parse() {
+        for spec in REGISTRY {
+            assert!(serde_json::from_str::<Value>(spec.default).is_ok(), "bad default for {}", spec.key);
+            assert!(validate(spec.key, &spec.default_value()).is_ok(), "default fails validation for {}", spec.key);
+        }
+    }
+}
@@ -0,0 +1,171 @@
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use chrono::Utc;
+use serde::Serialize;
+use keepkey_rust::messages::Message;
+use keepkey_rust::transport::trace::{set_trace_sink, TraceDirection};
+
+/// Messages whose contents may be written when sensitive output is allowed;
+/// everything else (PIN, passphrase, recovery words, entropy, decrypted data,
+/// debug link state, and any message type added later) is always redacted
+const LOGGABLE: &[&str] = &[
+    "Initialize",
+    "GetFeatures",
+    "Features",
+    "Ping",
+    "Success",
+    "Failure",
+    "Cancel",
+    "ClearSession",
+    "ButtonRequest",
+    "ButtonAck",
+    "PinMatrixRequest",
+    "PassphraseRequest",
+    "GetAddress",
+    "Address",
+    "GetPublicKey",
+    "PublicKey",
+    "EthereumGetAddress",
+    "EthereumAddress",
+    "CosmosGetAddress",
+    "CosmosAddress",
+    "ThorchainGetAddress",
+    "ThorchainAddress",
+    "OsmosisGetAddress",
+    "OsmosisAddress",
+    "BinanceGetAddress",
+    "BinanceAddress",
+    "RippleGetAddress",
+    "RippleAddress",
+    "SignTx",
+    "TxRequest",
+    "TxAck",
+    "EthereumSignTx",
+    "EthereumTxRequest",
+    "EthereumTxAck",
+    "SignMessage",
+    "MessageSignature",
+    "VerifyMessage",
+];
+
+fn contents_loggable(message_type: &str) -> bool {
+    LOGGABLE.contains(&message_type)
+}
+
+/// Current protocol trace settings
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolTraceStatus {
+    pub enabled: bool,
+    /// Whether message contents (addresses, public keys) are written
+    pub include_sensitive: bool,
+    pub trace_file: String,
+}
+
+lazy_static::lazy_static! {
+    static ref TRACE_STATE: Mutex<(bool, bool)> = Mutex::new((false, false));
+}
+
+/// Path of today's protocol trace file, separate from the device communication log
+fn trace_file_path() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?;
+    let logs_dir = home_dir.join(".keepkey").join("logs");
+    std::fs::create_dir_all(&logs_dir)
+        .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+    Ok(logs_dir.join(format!("protocol-trace-{}.log", Utc::now().format("%Y-%m-%d"))))
+}
+
+/// Message type name without the protobuf enum prefix, e.g. "GetAddress"
+fn message_type_name(msg: &Message) -> String {
+    let name = format!("{:?}", msg.message_type());
+    name.strip_prefix("MessageType").map(String::from).unwrap_or(name)
+}
+
+/// Build one trace line; contents are only included when allowed and never for secrets
+fn trace_entry(direction: TraceDirection, msg: &Message, size: usize, include_sensitive: bool) -> serde_json::Value {
+    let message_type = message_type_name(msg);
+    let data = if include_sensitive && contents_loggable(&message_type) {
+        serde_json::Value::String(format!("{:?}", msg))
+    } else {
+        serde_json::Value::String("[redacted]".to_string())
+    };
+
+    serde_json::json!({
+        "timestamp": Utc::now().to_rfc3339(),
+        "direction": match direction {
+            TraceDirection::Send => "SEND",
+            TraceDirection::Receive => "RECEIVE",
+        },
+        "message_type": message_type,
+        "size": size,
+        "data": data,
+    })
+}
+
+fn write_trace_entry(entry: &serde_json::Value) -> Result<(), String> {
+    let mut file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(trace_file_path()?)
+        .map_err(|e| format!("Failed to open trace file: {}", e))?;
+    writeln!(file, "{}", entry)
+        .map_err(|e| format!("Failed to write trace entry: {}", e))
+}
+
+/// Current protocol trace settings
+pub fn status() -> Result<ProtocolTraceStatus, String> {
+    let (enabled, include_sensitive) = *TRACE_STATE.lock()
+        .map_err(|_| "Failed to lock trace state".to_string())?;
+    Ok(ProtocolTraceStatus {
+        enabled,
+        include_sensitive,
+        trace_file: trace_file_path()?.to_string_lossy().to_string(),
+    })
+}
+
+/// Turn protocol tracing on or off
+pub fn set_enabled(enabled: bool, include_sensitive: bool) -> Result<ProtocolTraceStatus, String> {
+    {
+        let mut state = TRACE_STATE.lock()
+            .map_err(|_| "Failed to lock trace state".to_string())?;
+        *state = (enabled, enabled && include_sensitive);
+    }
+
+    if enabled {
+        set_trace_sink(Some(Arc::new(move |direction, msg: &Message, size| {
+            let entry = trace_entry(direction, msg, size, include_sensitive);
+            if let Err(e) = write_trace_entry(&entry) {
+                log::warn!("Failed to write protocol trace: {}", e);
+            }
+        })));
+        log::info!("🔬 Protocol trace enabled (sensitive contents: {})", include_sensitive);
+    } else {
+        set_trace_sink(None);
+        log::info!("🔬 Protocol trace disabled");
+    }
+
+    status()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_entry_redaction() {
+        let ack: Message = keepkey_rust::messages::PinMatrixAck { pin: "1234".to_string() }.into();
+        let entry = trace_entry(TraceDirection::Send, &ack, 8, true);
+        assert_eq!(entry["message_type"], "PinMatrixAck");
+        assert_eq!(entry["data"], "[redacted]");
+
+        let ping: Message = keepkey_rust::messages::Ping::default().into();
+        assert_eq!(trace_entry(TraceDirection::Send, &ping, 2, false)["data"], "[redacted]");
+        assert_ne!(trace_entry(TraceDirection::Send, &ping, 2, true)["data"], "[redacted]");
+
+        for secret in ["DecryptedMessage", "DebugLinkState", "EntropyAck", "WordAck", "SomeFutureMessage"] {
+            assert!(!contents_loggable(secret), "{} must be redacted", secret);
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! Usage tracking for external data providers.
+//!
+//! Every outbound Pioneer request, from the backend or reported by the
+//! frontend, is written to the cache with its status, size and latency. The
+//! summary answers "is Pioneer down or is it my vault", and a
+//! `provider:degraded` event is emitted when the recent error rate spikes.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+use utoipa::ToSchema;
+
+use crate::cache::{CacheManager, ProviderRequest};
+
+pub const PIONEER: &str = "pioneer";
+
+/// Second opinion for Bitcoin balances, see [`crate::reconciliation`]
+pub const BLOCKBOOK: &str = "blockbook";
+
+/// Oldest requests beyond this are dropped, per provider
+const MAX_REQUESTS_PER_PROVIDER: usize = 5000;
+
+/// Window the error rate is watched over
+const SPIKE_WINDOW_SECS: i64 = 300;
+
+/// Fewer requests than this in the window never count as a spike
+const SPIKE_MIN_REQUESTS: usize = 10;
+
+const SPIKE_ERROR_RATE: f64 = 0.5;
+
+/// Minimum time between two warnings for the same provider
+const SPIKE_COOLDOWN_SECS: i64 = 900;
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+lazy_static::lazy_static! {
+    /// Provider -> when the last spike warning went out
+    static ref LAST_WARNING: Mutex<HashMap<String, i64>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EndpointUsage {
+    pub endpoint: String,
+    pub requests: usize,
+    pub errors: usize,
+}
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsage {
+    pub provider: String,
+    pub window_secs: i64,
+    pub requests: usize,
+    pub errors: usize,
+    pub error_rate: f64,
+    /// Response bytes received
+    pub bytes: u64,
+    pub avg_latency_ms: u64,
+    pub p95_latency_ms: u64,
+    pub last_error: Option<String>,
+    pub last_error_at: Option<i64>,
+    /// Busiest endpoints first
+    pub endpoints: Vec<EndpointUsage>,
+}
+
+pub fn init(app: AppHandle) {
+    let _ = APP_HANDLE.set(app);
+}
+
+/// The cache, if it has been opened; recording never opens it
+fn cache() -> Option<Arc<CacheManager>> {
+    let app = APP_HANDLE.get()?;
+    app.try_state::<Arc<once_cell::sync::OnceCell<Arc<CacheManager>>>>()?
+        .get()
+        .cloned()
+}
+
+fn is_error(request: &ProviderRequest) -> bool {
+    request.error.is_some() || request.status.map_or(true, |status| status >= 400)
+}
+
+/// Aggregate requests made within the window
+fn summarize(provider: &str, window_secs: i64, requests: &[ProviderRequest]) -> ProviderUsage {
+    let errors: Vec<&ProviderRequest> = requests.iter().filter(|r| is_error(r)).collect();
+    let last_error = errors.iter().max_by_key(|r| r.created_at);
+
+    let mut latencies: Vec<u64> = requests.iter().map(|r| r.latency_ms).collect();
+    latencies.sort_unstable();
+    let p95_latency_ms = match latencies.len() {
+        0 => 0,
+        n => latencies[((n * 95).div_ceil(100)).saturating_sub(1)],
+    };
+
+    let mut endpoints: HashMap<&str, EndpointUsage> = HashMap::new();
+    for request in requests {
+        let usage = endpoints.entry(&request.endpoint).or_insert_with(|| EndpointUsage {
+            endpoint: request.endpoint.clone(),
+            requests: 0,
+            errors: 0,
+        });
+        usage.requests += 1;
+        if is_error(request) {
+            usage.errors += 1;
+        }
+    }
+    let mut endpoints: Vec<EndpointUsage> = endpoints.into_values().collect();
+    endpoints.sort_by(|a, b| b.requests.cmp(&a.requests).then_with(|| a.endpoint.cmp(&b.endpoint)));
+
+    ProviderUsage {
+        provider: provider.to_string(),
+        window_secs,
+        requests: requests.len(),
+        errors: errors.len(),
+        error_rate: if requests.is_empty() { 0.0 } else { errors.len() as f64 / requests.len() as f64 },
+        bytes: requests.iter().map(|r| r.bytes).sum(),
+        avg_latency_ms: if latencies.is_empty() { 0 } else { latencies.iter().sum::<u64>() / latencies.len() as u64 },
+        p95_latency_ms,
+        last_error: last_error.map(|r| {
+            r.error.clone().unwrap_or_else(|| format!("HTTP {}", r.status.unwrap_or_default()))
+        }),
+        last_error_at: last_error.map(|r| r.created_at),
+        endpoints,
+    }
+}
+
+/// Usage of a provider over the last `window_secs` (default one day)
+pub async fn usage(cache: &CacheManager, provider: &str, window_secs: Option<i64>) -> Result<ProviderUsage, String> {
+    let window_secs = window_secs.unwrap_or(86_400).max(1);
+    let since = chrono::Utc::now().timestamp() - window_secs;
+    let requests = cache
+        .list_provider_requests(provider, since)
+        .await
+        .map_err(|e| format!("Failed to read provider usage: {}", e))?;
+    Ok(summarize(provider, window_secs, &requests))
+}
+
+/// Warn once per cooldown when the recent error rate is high
+async fn check_spike(cache: &CacheManager, provider: &str) {
+    let Ok(recent) = usage(cache, provider, Some(SPIKE_WINDOW_SECS)).await else {
+        return;
+    };
+    if recent.requests < SPIKE_MIN_REQUESTS || recent.error_rate < SPIKE_ERROR_RATE {
+        return;
+    }
+
+    let now = chrono::Utc::now().timestamp();
+    {
+        let mut last_warning = LAST_WARNING.lock().unwrap();
+        if last_warning.get(provider).is_some_and(|at| now - at < SPIKE_COOLDOWN_SECS) {
+            return;
+        }
+        last_warning.insert(provider.to_string(), now);
+    }
+
+    log::warn!(
+        "⚠️ {} error rate {:.0}% over the last {}s ({} requests)",
+        provider, recent.error_rate * 100.0, SPIKE_WINDOW_SECS, recent.requests,
+    );
+    if let Some(app) = APP_HANDLE.get() {
+        if let Err(e) = crate::commands::emit_or_queue_event(app, "provider:degraded", serde_json::json!(recent)).await {
+            log::warn!("Failed to emit provider:degraded: {}", e);
+        }
+    }
+}
+
+/// Record one outbound request
+pub async fn record(
+    provider: &str,
+    endpoint: &str,
+    status: Option<u16>,
+    bytes: u64,
+    latency_ms: u64,
+    error: Option<String>,
+) {
+    let Some(cache) = cache() else {
+        return;
+    };
+    let request = ProviderRequest {
+        id: 0,
+        provider: provider.to_string(),
+        endpoint: endpoint.to_string(),
+        status,
+        bytes,
+        latency_ms,
+        error,
+        created_at: chrono::Utc::now().timestamp(),
+    };
+    if let Err(e) = cache.save_provider_request(&request, MAX_REQUESTS_PER_PROVIDER).await {
+        log::warn!("Failed to record {} request: {}", provider, e);
+        return;
+    }
+    if is_error(&request) {
+        check_spike(&cache, provider).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(endpoint: &str, status: Option<u16>, latency_ms: u64, created_at: i64) -> ProviderRequest {
+        ProviderRequest {
+            id: 0,
+            provider: PIONEER.to_string(),
+            endpoint: endpoint.to_string(),
+            status,
+            bytes: 100,
+            latency_ms,
+            error: None,
+            created_at,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let requests = vec![
+            request("/api/v1/portfolio", Some(200), 100, 1),
+            request("/api/v1/portfolio", Some(502), 300, 3),
+            request("/api/v1/GetFeeRate", None, 200, 2),
+        ];
+        let usage = summarize(PIONEER, 60, &requests);
+        assert_eq!(usage.requests, 3);
+        assert_eq!(usage.errors, 2);
+        assert_eq!(usage.bytes, 300);
+        assert_eq!(usage.avg_latency_ms, 200);
+        assert_eq!(usage.p95_latency_ms, 300);
+        assert_eq!(usage.last_error.as_deref(), Some("HTTP 502"));
+        assert_eq!(usage.endpoints[0].endpoint, "/api/v1/portfolio");
+        assert_eq!(usage.endpoints[0].errors, 1);
+
+        assert_eq!(summarize(PIONEER, 60, &[]).error_rate, 0.0);
+    }
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::cache::{CacheManager, PendingPaymentIntent};
+
+/// Preference key for rate lock settings
+const PREFERENCE_KEY: &str = "rate_lock";
+
+/// Rate lock settings for fiat-denominated sends
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLockPreferences {
+    /// How long a quoted rate stays locked
+    #[serde(default = "default_window_secs")]
+    pub window_secs: u64,
+    /// Rate movement (percent) that triggers a warning before signing
+    #[serde(default = "default_max_drift_percent")]
+    pub max_drift_percent: f64,
+}
+
+fn default_window_secs() -> u64 {
+    120
+}
+
+fn default_max_drift_percent() -> f64 {
+    1.0
+}
+
+impl Default for RateLockPreferences {
+    fn default() -> Self {
+        Self {
+            window_secs: default_window_secs(),
+            max_drift_percent: default_max_drift_percent(),
+        }
+    }
+}
+
+/// Start a fiat-denominated send at the rate the user was quoted
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatePaymentIntent {
+    pub caip: String,
+    pub recipient: String,
+    pub fiat_currency: Option<String>,
+    pub fiat_amount: String,
+    /// Fiat per unit of the asset at quote time
+    pub rate: f64,
+    /// Decimals used to round the crypto amount (default 8)
+    pub decimals: Option<u32>,
+}
+
+/// Result of re-checking a locked rate just before signing
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RateCheck {
+    pub intent: PendingPaymentIntent,
+    pub current_rate: f64,
+    /// Signed movement from the locked rate, in percent
+    pub drift_percent: f64,
+    pub expired: bool,
+    /// Whether the user should be warned before signing
+    pub warning: Option<String>,
+}
+
+pub fn get_preferences() -> RateLockPreferences {
+    crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default()
+}
+
+pub fn set_preferences(preferences: &RateLockPreferences) -> Result<(), String> {
+    if preferences.window_secs == 0 || preferences.max_drift_percent < 0.0 {
+        return Err("Rate lock window must be positive and drift threshold non-negative".to_string());
+    }
+    crate::preferences::set(PREFERENCE_KEY, serde_json::json!(preferences))
+}
+
+/// Lock the quoted rate and record the pending payment
+pub async fn create_intent(cache: &CacheManager, request: CreatePaymentIntent) -> Result<PendingPaymentIntent, String> {
+    let fiat: f64 = request
+        .fiat_amount
+        .parse()
+        .map_err(|_| format!("Invalid fiat amount: {}", request.fiat_amount))?;
+    if fiat <= 0.0 || !request.rate.is_finite() || request.rate <= 0.0 {
+        return Err("Fiat amount and rate must be positive".to_string());
+    }
+
+    let preferences = get_preferences();
+    let decimals = request.decimals.unwrap_or(8) as usize;
+    let now = chrono::Utc::now().timestamp();
+
+    // Old unconfirmed intents are useless once their window has passed
+    if let Err(e) = cache.prune_payment_intents(now - 24 * 60 * 60).await {
+        log::warn!("Failed to prune payment intents: {}", e);
+    }
+
+    let intent = PendingPaymentIntent {
+        id: uuid::Uuid::new_v4().to_string(),
+        caip: request.caip,
+        recipient: request.recipient,
+        fiat_currency: request.fiat_currency.unwrap_or_else(|| "USD".to_string()).to_uppercase(),
+        fiat_amount: request.fiat_amount,
+        crypto_amount: format!("{:.*}", decimals, fiat / request.rate),
+        locked_rate: request.rate,
+        status: "pending".to_string(),
+        created_at: now,
+        expires_at: now + preferences.window_secs as i64,
+        confirmed_at: None,
+    };
+
+    cache
+        .save_payment_intent(&intent)
+        .await
+        .map_err(|e| format!("Failed to save payment intent: {}", e))?;
+    Ok(intent)
+}
+
+/// Percent change from the locked rate to the current one
+fn drift_percent(locked: f64, current: f64) -> f64 {
+    (current - locked) / locked * 100.0
+}
+
+/// Compare the current rate against the lock; confirms the intent unless a warning applies
+///
+/// Pass `accept` once the user has acknowledged the warning to confirm anyway.
+pub async fn confirm_intent(cache: &CacheManager, id: &str, current_rate: f64, accept: bool) -> Result<RateCheck, String> {
+    let mut intent = cache
+        .get_payment_intent(id)
+        .await
+        .map_err(|e| format!("Failed to read payment intent: {}", e))?
+        .ok_or_else(|| format!("Payment intent {} not found", id))?;
+    if intent.status != "pending" {
+        return Err(format!("Payment intent {} is already {}", id, intent.status));
+    }
+    if !current_rate.is_finite() || current_rate <= 0.0 {
+        return Err("Current rate must be positive".to_string());
+    }
+
+    let preferences = get_preferences();
+    let now = chrono::Utc::now().timestamp();
+    let expired = now > intent.expires_at;
+    let drift = drift_percent(intent.locked_rate, current_rate);
+
+    let warning = if expired {
+        Some(format!(
+            "The locked rate expired {}s ago; the rate has moved {:+.2}% since",
+            now - intent.expires_at,
+            drift
+        ))
+    } else if drift.abs() > preferences.max_drift_percent {
+        Some(format!(
+            "The {} rate moved {:+.2}% since the quote (limit {:.2}%)",
+            intent.fiat_currency, drift, preferences.max_drift_percent
+        ))
+    } else {
+        None
+    };
+
+    if warning.is_none() || accept {
+        intent.status = "confirmed".to_string();
+        intent.confirmed_at = Some(now);
+        cache
+            .save_payment_intent(&intent)
+            .await
+            .map_err(|e| format!("Failed to save payment intent: {}", e))?;
+    }
+
+    Ok(RateCheck {
+        intent,
+        current_rate,
+        drift_percent: drift,
+        expired,
+        warning,
+    })
+}
+
+/// Abandon a pending intent (e.g. the user went back to edit the amount)
+pub async fn cancel_intent(cache: &CacheManager, id: &str) -> Result<(), String> {
+    let mut intent = cache
+        .get_payment_intent(id)
+        .await
+        .map_err(|e| format!("Failed to read payment intent: {}", e))?
+        .ok_or_else(|| format!("Payment intent {} not found", id))?;
+    intent.status = "cancelled".to_string();
+    cache
+        .save_payment_intent(&intent)
+        .await
+        .map_err(|e| format!("Failed to save payment intent: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drift_percent() {
+        assert!((drift_percent(50_000.0, 50_500.0) - 1.0).abs() < 1e-9);
+        assert!((drift_percent(50_000.0, 49_000.0) + 2.0).abs() < 1e-9);
+    }
+}
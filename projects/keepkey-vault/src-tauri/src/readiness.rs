@@ -0,0 +1,96 @@
+//! Startup readiness gates.
+//!
+//! The cache, the REST server and the device monitor come up independently.
+//! Each marks its gate once usable; when all of them are, `app:ready` is
+//! emitted once. The server answers requests that need a gate which isn't
+//! ready yet with 503 + Retry-After instead of racing it.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tauri::AppHandle;
+use tokio::sync::watch;
+
+/// A subsystem other parts of startup wait on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Gate {
+    /// Cache database opened (or known to be unavailable)
+    Cache,
+    /// REST server bound and accepting connections
+    Server,
+    /// First device scan completed
+    DeviceMonitor,
+}
+
+impl Gate {
+    pub const ALL: [Gate; 3] = [Gate::Cache, Gate::Server, Gate::DeviceMonitor];
+
+    fn bit(self) -> u8 {
+        match self {
+            Gate::Cache => 1,
+            Gate::Server => 1 << 1,
+            Gate::DeviceMonitor => 1 << 2,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Gate::Cache => "cache",
+            Gate::Server => "server",
+            Gate::DeviceMonitor => "device-monitor",
+        }
+    }
+}
+
+const ALL_READY: u8 = 0b111;
+
+/// Bitmask of ready gates
+static READY: Lazy<watch::Sender<u8>> = Lazy::new(|| watch::channel(0).0);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadinessStatus {
+    pub cache: bool,
+    pub server: bool,
+    pub device_monitor: bool,
+    pub ready: bool,
+}
+
+/// Mark a gate ready; repeated calls are harmless
+pub fn mark_ready(gate: Gate) {
+    let newly_ready = READY.send_if_modified(|mask| {
+        let before = *mask;
+        *mask |= gate.bit();
+        *mask != before
+    });
+    if newly_ready {
+        log::info!("🚦 Startup gate ready: {}", gate.as_str());
+    }
+}
+
+pub fn is_ready(gate: Gate) -> bool {
+    *READY.borrow() & gate.bit() != 0
+}
+
+pub fn status() -> ReadinessStatus {
+    let mask = *READY.borrow();
+    ReadinessStatus {
+        cache: mask & Gate::Cache.bit() != 0,
+        server: mask & Gate::Server.bit() != 0,
+        device_monitor: mask & Gate::DeviceMonitor.bit() != 0,
+        ready: mask == ALL_READY,
+    }
+}
+
+/// Emit `app:ready` once every gate is ready
+pub fn init(app: AppHandle) {
+    let mut ready = READY.subscribe();
+    tauri::async_runtime::spawn(async move {
+        if ready.wait_for(|mask| *mask == ALL_READY).await.is_err() {
+            return;
+        }
+        log::info!("✅ All startup gates ready");
+        if let Err(e) = crate::commands::emit_or_queue_event(&app, "app:ready", serde_json::json!(status())).await {
+            log::warn!("Failed to emit app:ready: {}", e);
+        }
+    });
+}
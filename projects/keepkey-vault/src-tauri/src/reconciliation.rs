@@ -0,0 +1,263 @@
+//! Balance reconciliation against a second provider.
+//!
+//! Balances normally come from Pioneer alone. With reconciliation turned on,
+//! each Bitcoin account is also looked up on a Blockbook instance and the two
+//! totals are compared, so a stale or wrong indexer shows up as a flagged
+//! discrepancy instead of being trusted silently.
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Preference key for reconciliation settings
+const PREFERENCE_KEY: &str = "balance_reconciliation";
+
+pub(crate) const DEFAULT_BLOCKBOOK_URL: &str = "https://btc1.trezor.io";
+
+/// The only asset the second provider covers
+const BITCOIN_CAIP: &str = "bip122:000000000019d6689c085ae165831e93/slip44:0";
+
+/// Reconciliation settings
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReconciliationPreferences {
+    /// Check every balance lookup against the second provider
+    #[serde(default)]
+    pub enabled: bool,
+    /// Differences up to this many satoshis are not flagged
+    #[serde(default)]
+    pub tolerance_sats: u64,
+    /// Blockbook instance to compare with
+    #[serde(default)]
+    pub blockbook_url: Option<String>,
+}
+
+/// Outcome of comparing one account's balance between providers
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceReconciliation {
+    pub provider: String,
+    /// Total in satoshis according to the second provider
+    pub balance: Option<u64>,
+    /// Second provider minus primary, in satoshis
+    pub difference_sats: Option<i64>,
+    pub tolerance_sats: u64,
+    /// Set when the difference exceeds the tolerance
+    pub mismatch: bool,
+    pub error: Option<String>,
+}
+
+/// One account balance from the primary provider's portfolio response
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PortfolioBalance {
+    pub caip: String,
+    pub pubkey: String,
+    /// Balance in whole coins, e.g. "0.00123456"
+    pub balance: String,
+}
+
+/// Comparison of one portfolio asset, summed over its accounts
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetReconciliation {
+    pub caip: String,
+    pub accounts: usize,
+    /// Total in satoshis according to the primary provider
+    pub primary_sats: u64,
+    #[serde(flatten)]
+    pub reconciliation: BalanceReconciliation,
+}
+
+/// Blockbook's xpub summary; amounts are decimal strings
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockbookXpub {
+    balance: String,
+    #[serde(default)]
+    unconfirmed_balance: Option<String>,
+}
+
+pub fn get_preferences() -> ReconciliationPreferences {
+    crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default()
+}
+
+pub fn set_preferences(preferences: &ReconciliationPreferences) -> Result<(), String> {
+    if let Some(url) = &preferences.blockbook_url {
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err("Blockbook URL must be an http(s) URL".to_string());
+        }
+    }
+    crate::preferences::set(PREFERENCE_KEY, serde_json::json!(preferences))
+}
+
+/// Whole coins ("0.00123456") to satoshis
+fn to_sats(balance: &str) -> Option<u64> {
+    let (whole, fraction) = balance.trim().split_once('.').unwrap_or((balance.trim(), ""));
+    if fraction.len() > 8 || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let whole: u64 = if whole.is_empty() { 0 } else { whole.parse().ok()? };
+    let fraction: u64 = format!("{:0<8}", fraction).parse().ok()?;
+    whole.checked_mul(100_000_000)?.checked_add(fraction)
+}
+
+/// Signed difference and whether it is beyond the tolerance
+fn compare(primary: u64, secondary: u64, tolerance_sats: u64) -> (i64, bool) {
+    let difference = secondary as i64 - primary as i64;
+    (difference, difference.unsigned_abs() > tolerance_sats)
+}
+
+/// Confirmed plus (signed) unconfirmed balance
+fn total_sats(summary: &BlockbookXpub) -> Result<u64, String> {
+    let confirmed: i64 = summary.balance.parse().map_err(|_| format!("Invalid balance {}", summary.balance))?;
+    let unconfirmed: i64 = match &summary.unconfirmed_balance {
+        Some(value) => value.parse().map_err(|_| format!("Invalid unconfirmed balance {}", value))?,
+        None => 0,
+    };
+    Ok((confirmed + unconfirmed).max(0) as u64)
+}
+
+async fn fetch_blockbook_balance(base_url: &str, xpub: &str) -> Result<u64, String> {
+    const ENDPOINT: &str = "/api/v2/xpub";
+    let started = std::time::Instant::now();
+    let record = |status: Option<u16>, bytes: usize, error: Option<String>| {
+        crate::provider_usage::record(
+            crate::provider_usage::BLOCKBOOK,
+            ENDPOINT,
+            status,
+            bytes as u64,
+            started.elapsed().as_millis() as u64,
+            error,
+        )
+    };
+
+    let url = format!("{}{}/{}?details=basic", base_url.trim_end_matches('/'), ENDPOINT, xpub);
+    let response = match reqwest::Client::new()
+        .get(&url)
+        .header("accept", "application/json")
+        .timeout(std::time::Duration::from_secs(30))
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            let error = format!("Failed to query Blockbook: {}", e);
+            record(None, 0, Some(error.clone())).await;
+            return Err(error);
+        }
+    };
+
+    let status = response.status();
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(e) => {
+            let error = format!("Failed to read Blockbook response: {}", e);
+            record(Some(status.as_u16()), 0, Some(error.clone())).await;
+            return Err(error);
+        }
+    };
+    if !status.is_success() {
+        record(Some(status.as_u16()), body.len(), None).await;
+        return Err(format!("Blockbook returned {}", status));
+    }
+
+    let parsed = serde_json::from_slice::<BlockbookXpub>(&body)
+        .map_err(|e| format!("Invalid Blockbook response: {}", e))
+        .and_then(|summary| total_sats(&summary));
+    record(Some(status.as_u16()), body.len(), parsed.as_ref().err().cloned()).await;
+    parsed
+}
+
+/// Total balance of an account according to the second provider
+pub async fn fetch_balance(preferences: &ReconciliationPreferences, xpub: &str) -> Result<u64, String> {
+    let base_url = preferences.blockbook_url.as_deref().unwrap_or(DEFAULT_BLOCKBOOK_URL);
+    fetch_blockbook_balance(base_url, xpub).await
+}
+
+/// Compare a balance of `subject` (an xpub or asset) from the primary provider
+/// with the second provider's
+pub fn reconcile(preferences: &ReconciliationPreferences, subject: &str, primary_sats: u64, secondary: Result<u64, String>) -> BalanceReconciliation {
+    let mut reconciliation = BalanceReconciliation {
+        provider: crate::provider_usage::BLOCKBOOK.to_string(),
+        balance: None,
+        difference_sats: None,
+        tolerance_sats: preferences.tolerance_sats,
+        mismatch: false,
+        error: None,
+    };
+
+    match secondary {
+        Ok(balance) => {
+            let (difference, mismatch) = compare(primary_sats, balance, preferences.tolerance_sats);
+            if mismatch {
+                log::warn!("⚖️ Balance mismatch for {}…: Pioneer {} sats, Blockbook {} sats", &subject[..12.min(subject.len())], primary_sats, balance);
+            }
+            reconciliation.balance = Some(balance);
+            reconciliation.difference_sats = Some(difference);
+            reconciliation.mismatch = mismatch;
+        }
+        Err(e) => reconciliation.error = Some(e),
+    }
+    reconciliation
+}
+
+/// Check the Bitcoin balances of a portfolio response against the second
+/// provider; empty unless reconciliation is enabled
+pub async fn reconcile_portfolio(balances: Vec<PortfolioBalance>) -> Result<Vec<AssetReconciliation>, String> {
+    let preferences = get_preferences();
+    if !preferences.enabled {
+        return Ok(Vec::new());
+    }
+
+    let mut primary_sats = 0u64;
+    let mut lookups = tokio::task::JoinSet::new();
+    for entry in balances.into_iter().filter(|b| b.caip == BITCOIN_CAIP) {
+        primary_sats += to_sats(&entry.balance).ok_or_else(|| format!("Invalid balance {}", entry.balance))?;
+        let preferences = preferences.clone();
+        lookups.spawn(async move { fetch_balance(&preferences, &entry.pubkey).await });
+    }
+    let accounts = lookups.len();
+    if accounts == 0 {
+        return Ok(Vec::new());
+    }
+
+    let mut secondary: Result<u64, String> = Ok(0);
+    while let Some(result) = lookups.join_next().await {
+        let balance = result.map_err(|e| format!("Blockbook lookup failed: {}", e)).and_then(|b| b);
+        secondary = match (secondary, balance) {
+            (Ok(total), Ok(balance)) => Ok(total + balance),
+            (Err(e), _) | (_, Err(e)) => Err(e),
+        };
+    }
+
+    Ok(vec![AssetReconciliation {
+        caip: BITCOIN_CAIP.to_string(),
+        accounts,
+        primary_sats,
+        reconciliation: reconcile(&preferences, BITCOIN_CAIP, primary_sats, secondary),
+    }])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compare() {
+        assert_eq!(compare(100_000, 100_000, 0), (0, false));
+        assert_eq!(compare(100_000, 100_500, 1_000), (500, false));
+        assert_eq!(compare(100_000, 98_000, 1_000), (-2_000, true));
+
+        let summary = BlockbookXpub { balance: "150000".to_string(), unconfirmed_balance: Some("-50000".to_string()) };
+        assert_eq!(total_sats(&summary), Ok(100_000));
+    }
+
+    #[test]
+    fn test_to_sats() {
+        assert_eq!(to_sats("0.00123456"), Some(123_456));
+        assert_eq!(to_sats("1.5"), Some(150_000_000));
+        assert_eq!(to_sats("2"), Some(200_000_000));
+        assert_eq!(to_sats("0.000000001"), None);
+        assert_eq!(to_sats("-1"), None);
+    }
+}
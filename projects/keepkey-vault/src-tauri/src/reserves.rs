@@ -0,0 +1,246 @@
+//! Proof-of-reserves report.
+//!
+//! Builds on the ownership proof bundle: every Bitcoin address of a device
+//! that holds confirmed funds (according to the UTXO indexer) is listed with
+//! its balance, and signs the report message on the device. The block height
+//! the balances refer to comes from the Blockbook instance used for balance
+//! reconciliation.
+
+use std::collections::BTreeMap;
+use keepkey_rust::device_queue::DeviceQueueHandle;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::cache::CacheManager;
+use crate::device::ownership_proof::{ProofChain, ProofTarget};
+use crate::utxos::Utxo;
+
+/// Report format version, bumped on incompatible changes
+pub const RESERVES_REPORT_VERSION: u32 = 1;
+
+/// Confirmed funds held by one address
+#[derive(Debug, Clone, PartialEq, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressReserve {
+    pub address: String,
+    pub derivation_path: String,
+    pub script_type: Option<String>,
+    /// Satoshis in unspent outputs with at least the required confirmations
+    pub balance_sats: u64,
+    pub utxo_count: usize,
+    /// Signature over the report message by this address
+    pub signature: Option<String>,
+}
+
+/// Signed list of a device's Bitcoin addresses and their balances at a block height
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservesReport {
+    pub version: u32,
+    pub message: String,
+    pub device_id: String,
+    /// Master key fingerprint (hex), identifies the seed without revealing keys
+    pub master_fingerprint: String,
+    /// Balances count outputs confirmed at or below this height that are still unspent
+    pub block_height: u64,
+    pub min_confirmations: u64,
+    pub total_sats: u64,
+    pub created_at: i64,
+    pub addresses: Vec<AddressReserve>,
+}
+
+/// Options for a reserves report
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ReservesReportRequest {
+    /// Statement every address signs, typically including the auditor's challenge
+    pub message: String,
+    /// Outputs with fewer confirmations are left out; defaults to 1
+    #[serde(default)]
+    pub min_confirmations: Option<u64>,
+}
+
+/// Group confirmed UTXOs by address; outputs without an address or path can't be proven and are skipped
+fn summarize(utxos: &[Utxo], min_confirmations: u64) -> Vec<AddressReserve> {
+    let mut by_address: BTreeMap<&str, AddressReserve> = BTreeMap::new();
+    for utxo in utxos {
+        if utxo.confirmations.unwrap_or(0) < min_confirmations {
+            continue;
+        }
+        let (Some(address), Some(path)) = (utxo.address.as_deref(), utxo.path.as_deref()) else {
+            log::warn!("Leaving {}:{} out of the reserves report: no address or path", utxo.txid, utxo.vout);
+            continue;
+        };
+        let entry = by_address.entry(address).or_insert_with(|| AddressReserve {
+            address: address.to_string(),
+            derivation_path: path.to_string(),
+            script_type: utxo.script_type.clone(),
+            balance_sats: 0,
+            utxo_count: 0,
+            signature: None,
+        });
+        entry.balance_sats += utxo.value;
+        entry.utxo_count += 1;
+    }
+    by_address.into_values().collect()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockbookStatus {
+    blockbook: BlockbookInfo,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockbookInfo {
+    best_height: u64,
+}
+
+/// Current chain tip according to the reconciliation Blockbook instance
+async fn fetch_best_height() -> Result<u64, String> {
+    const ENDPOINT: &str = "/api/v2";
+    let started = std::time::Instant::now();
+    let record = |status: Option<u16>, bytes: usize, error: Option<String>| {
+        crate::provider_usage::record(
+            crate::provider_usage::BLOCKBOOK,
+            ENDPOINT,
+            status,
+            bytes as u64,
+            started.elapsed().as_millis() as u64,
+            error,
+        )
+    };
+
+    let base_url = crate::reconciliation::get_preferences()
+        .blockbook_url
+        .unwrap_or_else(|| crate::reconciliation::DEFAULT_BLOCKBOOK_URL.to_string());
+    let url = format!("{}{}", base_url.trim_end_matches('/'), ENDPOINT);
+    let response = match reqwest::Client::new()
+        .get(&url)
+        .header("accept", "application/json")
+        .timeout(std::time::Duration::from_secs(30))
+        .send()
+        .await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            let error = format!("Failed to query Blockbook: {}", e);
+            record(None, 0, Some(error.clone())).await;
+            return Err(error);
+        }
+    };
+
+    let status = response.status();
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(e) => {
+            let error = format!("Failed to read Blockbook response: {}", e);
+            record(Some(status.as_u16()), 0, Some(error.clone())).await;
+            return Err(error);
+        }
+    };
+    if !status.is_success() {
+        record(Some(status.as_u16()), body.len(), None).await;
+        return Err(format!("Blockbook returned {}", status));
+    }
+
+    let parsed = serde_json::from_slice::<BlockbookStatus>(&body)
+        .map(|status| status.blockbook.best_height)
+        .map_err(|e| format!("Invalid Blockbook status: {}", e));
+    record(Some(status.as_u16()), body.len(), parsed.as_ref().err().cloned()).await;
+    parsed
+}
+
+/// Look up the device's confirmed Bitcoin balances and sign the message with every
+/// funded address. Each signature needs confirmation on the device.
+pub async fn build_report(
+    cache: &CacheManager,
+    queue_handle: &DeviceQueueHandle,
+    request: &ReservesReportRequest,
+) -> Result<ReservesReport, String> {
+    let device_id = queue_handle.device_id().to_string();
+    let min_confirmations = request.min_confirmations.unwrap_or(1).max(1);
+
+    let tip = fetch_best_height().await?;
+    let utxos = crate::utxos::list_utxos(cache, &device_id).await?;
+    let mut addresses = summarize(&utxos, min_confirmations);
+    if addresses.is_empty() {
+        return Err(format!("No confirmed Bitcoin funds found for device {}", device_id));
+    }
+
+    let targets: Vec<ProofTarget> = addresses
+        .iter()
+        .map(|reserve| ProofTarget {
+            chain: ProofChain::Bitcoin,
+            derivation_path: reserve.derivation_path.clone(),
+            script_type: reserve.script_type.clone(),
+        })
+        .collect();
+    let bundle = crate::device::ownership_proof::build_bundle(queue_handle, &request.message, &targets).await?;
+
+    for (reserve, proof) in addresses.iter_mut().zip(bundle.proofs) {
+        if proof.address != reserve.address {
+            return Err(format!(
+                "Device derived {} for {}, but the indexer reported {}",
+                proof.address, reserve.derivation_path, reserve.address
+            ));
+        }
+        reserve.signature = Some(proof.signature);
+    }
+
+    let total_sats = addresses.iter().map(|reserve| reserve.balance_sats).sum();
+    log::info!("Built reserves report for {} with {} address(es)", device_id, addresses.len());
+
+    Ok(ReservesReport {
+        version: RESERVES_REPORT_VERSION,
+        message: bundle.message,
+        device_id,
+        master_fingerprint: bundle.master_fingerprint,
+        block_height: tip.saturating_sub(min_confirmations - 1),
+        min_confirmations,
+        total_sats,
+        created_at: bundle.created_at,
+        addresses,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utxo(vout: u32, address: Option<&str>, value: u64, confirmations: u64) -> Utxo {
+        Utxo {
+            txid: "00".repeat(32),
+            vout,
+            value,
+            address: address.map(String::from),
+            confirmations: Some(confirmations),
+            path: address.map(|_| "m/84'/0'/0'/0/0".to_string()),
+            account_path: "m/84'/0'/0'".to_string(),
+            script_type: Some("p2wpkh".to_string()),
+            label: None,
+            frozen: false,
+            finalized: false,
+        }
+    }
+
+    #[test]
+    fn test_summarize() {
+        let utxos = vec![
+            utxo(0, Some("bc1qa"), 1000, 3),
+            utxo(1, Some("bc1qa"), 2000, 1),
+            utxo(2, Some("bc1qb"), 5000, 0),
+            utxo(3, None, 7000, 6),
+        ];
+
+        let reserves = summarize(&utxos, 1);
+        assert_eq!(reserves.len(), 1);
+        assert_eq!(reserves[0].address, "bc1qa");
+        assert_eq!(reserves[0].balance_sats, 3000);
+        assert_eq!(reserves[0].utxo_count, 2);
+
+        let deep = summarize(&utxos, 2);
+        assert_eq!(deep[0].balance_sats, 1000);
+    }
+}
@@ -0,0 +1,150 @@
+//! Crash-loop protection.
+//!
+//! Every startup bumps a counter in a sentinel file, and the counter is reset
+//! once the app has run for a while. If it reaches [`CRASH_THRESHOLD`], the
+//! previous startups never got that far, so the vault boots in safe mode:
+//! auto-frontload, the early cache check and the vault proxy are skipped, and
+//! the user can reset the cache or preferences from the UI.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+/// Unfinished startups in a row that trigger safe mode
+const CRASH_THRESHOLD: u32 = 3;
+
+/// How long the app must stay up for a startup to count as successful
+const STABLE_AFTER: Duration = Duration::from_secs(60);
+
+static SAFE_MODE: AtomicBool = AtomicBool::new(false);
+
+/// Contents of the startup sentinel file
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+struct StartupSentinel {
+    /// Startups that have not yet reached [`STABLE_AFTER`]
+    unfinished_startups: u32,
+    last_started_at: Option<i64>,
+}
+
+/// Safe mode state reported to the frontend
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SafeModeStatus {
+    pub active: bool,
+    /// Startups in a row that ended before the app was stable
+    pub failed_startups: u32,
+    /// Device, firmware and app details for troubleshooting
+    pub diagnostics: serde_json::Value,
+}
+
+fn sentinel_path() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?;
+
+    let dir = home_dir.join(".keepkey");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create config directory: {}", e))?;
+
+    Ok(dir.join("startup.json"))
+}
+
+fn read_sentinel() -> StartupSentinel {
+    sentinel_path()
+        .ok()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn write_sentinel(sentinel: &StartupSentinel) -> Result<(), String> {
+    let contents = serde_json::to_string_pretty(sentinel).map_err(|e| e.to_string())?;
+    std::fs::write(sentinel_path()?, contents)
+        .map_err(|e| format!("Failed to write startup sentinel: {}", e))
+}
+
+/// Whether this run is in safe mode
+pub fn is_active() -> bool {
+    SAFE_MODE.load(Ordering::SeqCst)
+}
+
+/// Record a startup and decide whether to boot in safe mode; call before anything else starts
+pub fn record_startup() -> bool {
+    let mut sentinel = read_sentinel();
+    let active = should_enter_safe_mode(sentinel.unfinished_startups);
+
+    sentinel.unfinished_startups += 1;
+    sentinel.last_started_at = Some(chrono::Utc::now().timestamp());
+    if let Err(e) = write_sentinel(&sentinel) {
+        log::warn!("{}", e);
+    }
+
+    if active {
+        log::warn!(
+            "🛟 {} startups in a row did not finish - booting in safe mode",
+            sentinel.unfinished_startups - 1
+        );
+    }
+    SAFE_MODE.store(active, Ordering::SeqCst);
+    active
+}
+
+fn should_enter_safe_mode(unfinished_startups: u32) -> bool {
+    unfinished_startups >= CRASH_THRESHOLD
+}
+
+/// Reset the crash counter once the app has stayed up long enough
+///
+/// In safe mode the counter is kept so the next launch is safe too, until the
+/// user leaves safe mode explicitly.
+pub fn spawn_stability_timer() {
+    tauri::async_runtime::spawn(async {
+        tokio::time::sleep(STABLE_AFTER).await;
+        if is_active() {
+            return;
+        }
+        if let Err(e) = write_sentinel(&StartupSentinel::default()) {
+            log::warn!("{}", e);
+        }
+    });
+}
+
+pub fn status() -> SafeModeStatus {
+    SafeModeStatus {
+        active: is_active(),
+        // The current startup is counted too
+        failed_startups: read_sentinel().unfinished_startups.saturating_sub(1),
+        diagnostics: crate::support::collect_metadata(),
+    }
+}
+
+/// Boot normally on the next launch
+pub fn exit() -> Result<(), String> {
+    write_sentinel(&StartupSentinel::default())
+}
+
+/// Move the preferences file aside so the next launch starts from defaults
+pub fn reset_preferences() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?;
+    let config_path = home_dir.join(".keepkey").join("keepkey.json");
+    let backup_path = config_path.with_extension(format!("json.safe-mode-{}", chrono::Utc::now().format("%Y%m%d%H%M%S")));
+
+    std::fs::rename(&config_path, &backup_path)
+        .map_err(|e| format!("Failed to move preferences aside: {}", e))?;
+    log::warn!("📦 Moved preferences to {}", backup_path.display());
+    Ok(backup_path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_should_enter_safe_mode() {
+        assert!(!should_enter_safe_mode(0));
+        assert!(!should_enter_safe_mode(CRASH_THRESHOLD - 1));
+        assert!(should_enter_safe_mode(CRASH_THRESHOLD));
+    }
+}
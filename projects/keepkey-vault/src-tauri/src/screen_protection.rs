@@ -0,0 +1,96 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Label of the window that gets content protection
+const MAIN_WINDOW: &str = "main";
+
+static APP_HANDLE: OnceCell<AppHandle> = OnceCell::new();
+
+lazy_static::lazy_static! {
+    /// Sensitive flows currently on screen (e.g. "recovery:<device_id>")
+    static ref SENSITIVE_FLOWS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    /// Protection explicitly requested by the frontend, independent of flows
+    static ref MANUAL_PROTECTION: Mutex<bool> = Mutex::new(false);
+}
+
+/// Current screen-capture protection state
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScreenProtectionStatus {
+    pub enabled: bool,
+    pub manual: bool,
+    pub active_flows: Vec<String>,
+}
+
+/// Store the app handle so flow tracking can reach the window from anywhere
+pub fn init(app: &AppHandle) {
+    let _ = APP_HANDLE.set(app.clone());
+}
+
+/// Get the current protection state
+pub fn get_status() -> ScreenProtectionStatus {
+    let active_flows: Vec<String> = SENSITIVE_FLOWS.lock()
+        .map(|flows| flows.iter().cloned().collect())
+        .unwrap_or_default();
+    let manual = MANUAL_PROTECTION.lock().map(|m| *m).unwrap_or(false);
+
+    ScreenProtectionStatus {
+        enabled: manual || !active_flows.is_empty(),
+        manual,
+        active_flows,
+    }
+}
+
+/// Apply the current state to the main window
+fn apply() {
+    let Some(app) = APP_HANDLE.get() else {
+        return;
+    };
+
+    let status = get_status();
+    match app.get_webview_window(MAIN_WINDOW) {
+        Some(window) => {
+            if let Err(e) = window.set_content_protected(status.enabled) {
+                log::warn!("Failed to set screen capture protection: {}", e);
+                return;
+            }
+            log::info!("🛡️ Screen capture protection {}", if status.enabled { "enabled" } else { "disabled" });
+            let _ = app.emit("screen-protection:changed", &status);
+        }
+        None => log::warn!("Main window not found, cannot apply screen capture protection"),
+    }
+}
+
+/// Mark a sensitive flow as started, protecting the window while it's active
+pub fn enter_sensitive_flow(flow: &str) {
+    let inserted = SENSITIVE_FLOWS.lock()
+        .map(|mut flows| flows.insert(flow.to_string()))
+        .unwrap_or(false);
+
+    if inserted {
+        apply();
+    }
+}
+
+/// Mark a sensitive flow as finished
+pub fn exit_sensitive_flow(flow: &str) {
+    let removed = SENSITIVE_FLOWS.lock()
+        .map(|mut flows| flows.remove(flow))
+        .unwrap_or(false);
+
+    if removed {
+        apply();
+    }
+}
+
+/// Turn protection on or off regardless of active flows
+pub fn set_manual_protection(enabled: bool) -> ScreenProtectionStatus {
+    if let Ok(mut manual) = MANUAL_PROTECTION.lock() {
+        *manual = enabled;
+    }
+    apply();
+    get_status()
+}
@@ -0,0 +1,115 @@
+use keyring::Entry;
+
+/// Keychain service name all vault secrets are stored under
+const KEYRING_SERVICE: &str = "com.keepkey.vault";
+
+/// Secrets the frontend is allowed to manage directly
+pub const MANAGED_SECRETS: &[&str] = &[
+    "pioneer_api_key",
+    "rpc_credentials",
+    crate::settings_backup::WEBDAV_CREDENTIALS_SECRET,
+];
+
+/// Plaintext preference keys migrated into the keychain on startup
+const LEGACY_PREFERENCE_KEYS: &[(&str, &str)] = &[
+    ("pioneerApiKey", "pioneer_api_key"),
+    ("pioneer_api_key", "pioneer_api_key"),
+];
+
+/// Environment variables imported into the keychain the first time they're read
+const LEGACY_ENV_VARS: &[(&str, &str)] = &[
+    ("PIONEER_API_KEY", "pioneer_api_key"),
+];
+
+fn entry(name: &str) -> Result<Entry, String> {
+    Entry::new(KEYRING_SERVICE, name)
+        .map_err(|e| format!("Failed to open keychain entry {}: {}", name, e))
+}
+
+/// Store a secret in the OS keychain
+pub fn set_secret(name: &str, value: &str) -> Result<(), String> {
+    entry(name)?
+        .set_password(value)
+        .map_err(|e| format!("Failed to store secret {}: {}", name, e))
+}
+
+/// Read a secret from the OS keychain, falling back to (and importing) legacy env vars
+pub fn get_secret(name: &str) -> Result<Option<String>, String> {
+    match entry(name)?.get_password() {
+        Ok(value) => return Ok(Some(value)),
+        Err(keyring::Error::NoEntry) => {}
+        Err(e) => return Err(format!("Failed to read secret {}: {}", name, e)),
+    }
+
+    for (env_var, secret_name) in LEGACY_ENV_VARS {
+        if *secret_name != name {
+            continue;
+        }
+        if let Ok(value) = std::env::var(env_var) {
+            if !value.is_empty() {
+                log::info!("🔑 Importing {} from environment into the keychain", env_var);
+                set_secret(name, &value)?;
+                return Ok(Some(value));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+/// The Pioneer API key, if one has been stored; read errors count as no key
+pub fn pioneer_api_key() -> Option<String> {
+    match get_secret("pioneer_api_key") {
+        Ok(key) => key.filter(|key| !key.is_empty()),
+        Err(e) => {
+            log::warn!("Failed to read Pioneer API key: {}", e);
+            None
+        }
+    }
+}
+
+/// Check whether a secret is present without exposing it
+pub fn has_secret(name: &str) -> bool {
+    matches!(get_secret(name), Ok(Some(_)))
+}
+
+/// Remove a secret from the OS keychain (missing secrets are not an error)
+pub fn delete_secret(name: &str) -> Result<(), String> {
+    match entry(name)?.delete_password() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(format!("Failed to delete secret {}: {}", name, e)),
+    }
+}
+
+/// Check that a secret name may be managed from the frontend
+pub fn validate_managed_secret(name: &str) -> Result<(), String> {
+    if MANAGED_SECRETS.contains(&name) {
+        Ok(())
+    } else {
+        Err(format!("Unknown secret: {}", name))
+    }
+}
+
+/// Move plaintext secrets out of keepkey.json and into the keychain
+pub fn migrate_plaintext_secrets() -> Result<usize, String> {
+    let mut migrated = 0;
+
+    for (pref_key, secret_name) in LEGACY_PREFERENCE_KEYS {
+        if let Some(value) = crate::preferences::get_stored(pref_key) {
+            if let Some(value) = value.as_str().filter(|s| !s.is_empty()) {
+                set_secret(secret_name, value)?;
+            }
+            crate::preferences::set(pref_key, serde_json::Value::Null)?;
+            migrated += 1;
+        }
+    }
+
+    if migrated > 0 {
+        log::info!("🔑 Migrated {} plaintext secret(s) into the OS keychain", migrated);
+    }
+
+    // Hook secrets were stored inline in preferences before the keychain existed
+    migrated += crate::automation::migrate_hook_secrets()?;
+
+    Ok(migrated)
+}
@@ -0,0 +1,192 @@
+//! Derivation self-check against known test vectors.
+//!
+//! Runs at startup and on demand. Software derivation is always checked
+//! against published BIP84 vectors. Debug builds also ask connected devices
+//! for account xpubs and first addresses and compare both paths; mock devices
+//! hold the `all all ...` test seed, so their addresses are checked against
+//! known values too. Real devices are only queried on demand, since the
+//! request may prompt for a PIN.
+
+use keepkey_rust::device_queue::{DeviceQueueFactory, DeviceQueueHandle};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::commands::DeviceQueueManager;
+
+/// (account xpub, coin, script type, change, index, address) from BIP84
+const SOFTWARE_VECTORS: &[(&str, &str, &str, u32, u32, &str)] = &[
+    (
+        "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs",
+        "bitcoin", "p2wpkh", 0, 0, "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+    ),
+    (
+        "zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs",
+        "bitcoin", "p2wpkh", 1, 0, "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el",
+    ),
+];
+
+/// (account path, script type, first receive address) for the mock device seed
+const DEVICE_VECTORS: &[(&[u32], &str, &str)] = &[
+    (&[0x8000_002c, 0x8000_0000, 0x8000_0000], "p2pkh", "1JAd7XCBzGudGpJQSDSfpmJhiygtLQWaGL"),
+    (&[0x8000_0031, 0x8000_0000, 0x8000_0000], "p2sh-p2wpkh", "3L6TyTisPBmrDAj6RoKmDzNnj4eQi54gD2"),
+    (&[0x8000_0054, 0x8000_0000, 0x8000_0000], "p2wpkh", "bc1qannfxke2tfd4l7vhepehpvt05y83v3qsf6nfkk"),
+];
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SelfCheckReport {
+    pub checked: usize,
+    pub failures: Vec<String>,
+}
+
+impl SelfCheckReport {
+    fn expect(&mut self, what: String, expected: &str, actual: Result<String, String>) {
+        self.checked += 1;
+        match actual {
+            Ok(actual) if actual == expected => {}
+            Ok(actual) => self.failures.push(format!("{}: expected {}, got {}", what, expected, actual)),
+            Err(e) => self.failures.push(format!("{}: {}", what, e)),
+        }
+    }
+}
+
+/// Check the software derivation module against the published vectors
+pub fn run_software(report: &mut SelfCheckReport) {
+    for (xpub, coin, script_type, change, index, expected) in SOFTWARE_VECTORS {
+        report.expect(
+            format!("software {} {}/{}", script_type, change, index),
+            expected,
+            crate::derivation::derive_address(xpub, coin, script_type, *change, *index),
+        );
+    }
+}
+
+fn is_mock(device_id: &str) -> bool {
+    #[cfg(feature = "mock-device")]
+    {
+        keepkey_rust::mock_device::is_mock_device(device_id)
+    }
+    #[cfg(not(feature = "mock-device"))]
+    {
+        let _ = device_id;
+        false
+    }
+}
+
+fn script_type_to_proto(script_type: &str) -> i32 {
+    use keepkey_rust::messages::InputScriptType;
+    match script_type {
+        "p2sh-p2wpkh" => InputScriptType::Spendp2shwitness as i32,
+        "p2wpkh" => InputScriptType::Spendwitness as i32,
+        _ => InputScriptType::Spendaddress as i32,
+    }
+}
+
+async fn device_xpub(queue_handle: &DeviceQueueHandle, address_n: &[u32]) -> Result<String, String> {
+    let msg = keepkey_rust::messages::GetPublicKey {
+        address_n: address_n.to_vec(),
+        coin_name: Some("Bitcoin".to_string()),
+        ecdsa_curve_name: Some("secp256k1".to_string()),
+        show_display: Some(false),
+        ..Default::default()
+    };
+    match queue_handle.send_raw(msg.into(), false).await.map_err(|e| e.to_string())? {
+        keepkey_rust::messages::Message::PublicKey(public_key) => {
+            public_key.xpub.ok_or_else(|| "Device returned no xpub".to_string())
+        }
+        other => Err(format!("Unexpected response: {:?}", other.message_type())),
+    }
+}
+
+/// Compare the device's first addresses with software derivation from its xpubs
+async fn run_device(queue_handle: &DeviceQueueHandle, device_id: &str, report: &mut SelfCheckReport) {
+    let mock = is_mock(device_id);
+    for (account, script_type, mock_address) in DEVICE_VECTORS {
+        let what = format!("{} {} first address", device_id, script_type);
+        let mut address_n = account.to_vec();
+        address_n.extend([0, 0]);
+
+        let device_address = queue_handle
+            .get_address(address_n, "Bitcoin".to_string(), Some(script_type_to_proto(script_type)), Some(false))
+            .await
+            .map_err(|e| e.to_string());
+        let device_address = match device_address {
+            Ok(address) => address,
+            Err(e) => {
+                report.expect(what, "", Err(e));
+                continue;
+            }
+        };
+
+        let software_address = match device_xpub(queue_handle, account).await {
+            Ok(xpub) => crate::derivation::derive_address(&xpub, "bitcoin", script_type, 0, 0),
+            Err(e) => Err(e),
+        };
+        report.expect(format!("{} (software vs device)", what), &device_address, software_address);
+        if mock {
+            report.expect(format!("{} (test vector)", what), mock_address, Ok(device_address));
+        }
+    }
+}
+
+/// Run the self-check; devices are included in debug builds only, and real
+/// devices only when `include_real_devices` is set
+pub async fn run(queue_manager: &DeviceQueueManager, include_real_devices: bool) -> SelfCheckReport {
+    let mut report = SelfCheckReport::default();
+    run_software(&mut report);
+
+    if cfg!(debug_assertions) {
+        for device in keepkey_rust::features::list_connected_devices() {
+            if !include_real_devices && !is_mock(&device.unique_id) {
+                continue;
+            }
+            let queue_handle = {
+                let mut manager = queue_manager.lock().await;
+                manager
+                    .entry(device.unique_id.clone())
+                    .or_insert_with(|| DeviceQueueFactory::spawn_worker(device.unique_id.clone(), device.clone()))
+                    .clone()
+            };
+            run_device(&queue_handle, &device.unique_id, &mut report).await;
+        }
+    }
+
+    for failure in &report.failures {
+        log::error!("❌ Derivation self-check: {}", failure);
+    }
+    report
+}
+
+/// Startup run; failures are recorded and announced as `derivation:self-check-failed`
+pub fn spawn_startup_check(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let Some(queue_manager) = app.try_state::<DeviceQueueManager>().map(|s| s.inner().clone()) else {
+            return;
+        };
+        let report = run(&queue_manager, false).await;
+        if report.failures.is_empty() {
+            log::info!("✅ Derivation self-check passed ({} checks)", report.checked);
+            return;
+        }
+
+        for failure in &report.failures {
+            crate::error_center::record(crate::error_center::ErrorCategory::Derivation, None, failure.clone()).await;
+        }
+        if let Err(e) = crate::commands::emit_or_queue_event(&app, "derivation:self-check-failed", serde_json::json!(report)).await {
+            log::warn!("Failed to emit derivation:self-check-failed: {}", e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_software_vectors_pass() {
+        let mut report = SelfCheckReport::default();
+        run_software(&mut report);
+        assert_eq!(report.checked, SOFTWARE_VECTORS.len());
+        assert!(report.failures.is_empty(), "{:?}", report.failures);
+    }
+}
@@ -0,0 +1,122 @@
+use axum::{
+    extract::{Query, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::accounts::AccountNameRequest;
+use crate::cache::{AccountMetadata, CacheManager};
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+
+// ============ Account names ============
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AccountNamesQuery {
+    /// Only names for this device
+    pub device_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct AccountNameKey {
+    pub device_id: String,
+    /// Account derivation path, e.g. m/84'/0'/0'
+    pub account_path: String,
+}
+
+async fn cache(state: &ServerState) -> Result<Arc<CacheManager>, Response> {
+    crate::commands::get_cache_manager(&state.cache_manager).await.map_err(|e| (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+    ).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/accounts/names",
+    params(AccountNamesQuery),
+    responses(
+        (status = 200, description = "Named accounts", body = Vec<AccountMetadata>),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "accounts"
+)]
+pub async fn list_account_names(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<AccountNamesQuery>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::accounts::list(&cache, query.device_id.as_deref()).await {
+        Ok(accounts) => Json(accounts).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/accounts/names",
+    request_body = AccountNameRequest,
+    responses(
+        (status = 200, description = "Account named or renamed", body = AccountMetadata),
+        (status = 400, description = "Invalid name, color or path"),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "accounts"
+)]
+pub async fn set_account_name(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<AccountNameRequest>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::accounts::set(&cache, request).await {
+        Ok(account) => Json(account).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "INVALID_ACCOUNT_NAME")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/accounts/names",
+    params(AccountNameKey),
+    responses(
+        (status = 204, description = "Name removed"),
+        (status = 404, description = "Account has no name"),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "accounts"
+)]
+pub async fn delete_account_name(
+    State(state): State<Arc<ServerState>>,
+    Query(key): Query<AccountNameKey>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::accounts::remove(&cache, &key.device_id, &key.account_path).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Account {} has no name", key.account_path), "ACCOUNT_NOT_FOUND")),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{State, Json},
+    extract::{Query, State, Json},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
@@ -16,6 +16,9 @@ use crate::commands::DeviceResponse;
 pub struct ErrorResponse {
     pub error: String,
     pub code: String,
+    /// Correlation ID to look up in the device logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl ErrorResponse {
@@ -23,6 +26,7 @@ impl ErrorResponse {
         Self {
             error: error.into(),
             code: code.into(),
+            request_id: crate::server::correlation::current(),
         }
     }
 }
@@ -88,7 +92,9 @@ pub async fn utxo_get_address(
         })?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
+    
+    ensure_chain_supported(&device_id, &utxo_chain(&request.coin))?;
     
     // Create device request using the same pattern as other endpoints
     let device_request = DeviceRequest::GetAddress {
@@ -333,6 +339,253 @@ pub async fn thorchain_get_address(
     ).await
 }
 
+// ============ Registry Cosmos Chain Address ============
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct CosmosChainAddressRequest {
+    /// Chain name from the Cosmos chain registry, e.g. "akash"
+    pub chain: String,
+    /// Defaults to m/44'/slip44'/0'/0/0 for the chain
+    #[serde(default, alias = "addressNList")]
+    pub address_n: Option<Vec<u32>>,
+    #[serde(alias = "showDisplay")]
+    pub show_display: Option<bool>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/addresses/cosmos-chain",
+    request_body = CosmosChainAddressRequest,
+    responses(
+        (status = 200, description = "Address generated successfully", body = AddressResponse),
+        (status = 400, description = "Unknown chain"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Address"
+)]
+pub async fn cosmos_chain_get_address(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<CosmosChainAddressRequest>,
+) -> Result<Json<AddressResponse>, Response> {
+    let chain = crate::device::cosmos_chains::find_chain(&request.chain).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(format!("Unknown Cosmos chain: {}", request.chain), "UNKNOWN_CHAIN"))
+        ).into_response()
+    })?;
+    let address_n = request.address_n.unwrap_or_else(|| chain.default_address_n());
+    
+    handle_address_request(
+        state,
+        address_n,
+        request.show_display,
+        |path, show_display| match chain.name.as_str() {
+            "osmosis" => DeviceRequest::OsmosisGetAddress { path, show_display },
+            "thorchain" => DeviceRequest::ThorchainGetAddress { path, testnet: false, show_display },
+            "mayachain" => DeviceRequest::MayachainGetAddress { path, show_display },
+            _ => DeviceRequest::CosmosGetAddress { path, hrp: chain.hrp.clone(), show_display },
+        }
+    ).await
+}
+
+// ============ Ownership Proofs ============
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnershipProofRequest {
+    /// Statement to sign, typically including the requester's challenge or nonce
+    pub message: String,
+    pub addresses: Vec<crate::device::ownership_proof::ProofTarget>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/addresses/ownership-proof",
+    request_body = OwnershipProofRequest,
+    responses(
+        (status = 200, description = "Signed ownership proof bundle", body = crate::device::ownership_proof::OwnershipProofBundle),
+        (status = 400, description = "Invalid request or signing rejected"),
+        (status = 503, description = "No device connected")
+    ),
+    tag = "Address"
+)]
+pub async fn ownership_proof(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<OwnershipProofRequest>,
+) -> Result<Json<crate::device::ownership_proof::OwnershipProofBundle>, Response> {
+    let devices = keepkey_rust::features::list_connected_devices();
+    let device = devices.first()
+        .ok_or_else(|| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse::new("No KeepKey device connected", "DEVICE_NOT_FOUND"))
+            ).into_response()
+        })?;
+    let device_id = device.unique_id.clone();
+
+    let queue_handle = {
+        let mut manager = state.device_queue_manager.lock().await;
+
+        if let Some(handle) = manager.get(&device_id) {
+            handle.clone()
+        } else {
+            let handle = keepkey_rust::device_queue::DeviceQueueFactory::spawn_worker(device_id.clone(), device.clone());
+            manager.insert(device_id.clone(), handle.clone());
+            handle
+        }
+    };
+
+    crate::device::ownership_proof::build_bundle(&queue_handle, &request.message, &request.addresses)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(e, "OWNERSHIP_PROOF_FAILED"))
+            ).into_response()
+        })
+}
+
+// ============ Testnet Address ============
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct TestnetAddressRequest {
+    pub network: crate::device::testnet::TestnetNetwork,
+    /// p2pkh, p2sh-p2wpkh or p2wpkh (Bitcoin only, defaults to p2wpkh)
+    pub script_type: Option<String>,
+    pub account: Option<u32>,
+    pub index: Option<u32>,
+    pub show_display: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TestnetAddressResponse {
+    pub address: String,
+    pub network: crate::device::testnet::TestnetNetwork,
+    pub caip: String,
+    pub path: String,
+    pub address_n: Vec<u32>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/addresses/testnet",
+    request_body = TestnetAddressRequest,
+    responses(
+        (status = 200, description = "Testnet receive address, ready to paste into a faucet", body = TestnetAddressResponse),
+        (status = 400, description = "Bad request"),
+        (status = 403, description = "Testnet mode is disabled"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "Address"
+)]
+pub async fn testnet_get_address(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<TestnetAddressRequest>,
+) -> Result<Json<TestnetAddressResponse>, Response> {
+    crate::device::testnet::ensure_enabled().map_err(|e| {
+        (
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::new(e, "TESTNET_DISABLED"))
+        ).into_response()
+    })?;
+
+    let script_type = request.script_type.unwrap_or_else(|| "p2wpkh".to_string());
+    let address_n = crate::device::testnet::address_path(
+        request.network,
+        &script_type,
+        request.account.unwrap_or(0),
+        request.index.unwrap_or(0),
+    ).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "INVALID_REQUEST"))
+        ).into_response()
+    })?;
+
+    let path = format!("m/{}", address_n.iter()
+        .map(|&n| if n & 0x80000000 != 0 { format!("{}'", n & 0x7FFFFFFF) } else { n.to_string() })
+        .collect::<Vec<_>>()
+        .join("/"));
+
+    let network = request.network;
+    let show_display = request.show_display;
+    let Json(AddressResponse { address }) = if network.is_bitcoin() {
+        handle_address_request(state, address_n.clone(), show_display, |path, show_display| DeviceRequest::GetAddress {
+            path,
+            coin_name: crate::device::testnet::BITCOIN_TESTNET_COIN.to_string(),
+            script_type: Some(script_type),
+            show_display,
+        }).await?
+    } else {
+        handle_address_request(state, address_n.clone(), show_display, |path, show_display| {
+            DeviceRequest::EthereumGetAddress { path, show_display }
+        }).await?
+    };
+
+    Ok(Json(TestnetAddressResponse {
+        address,
+        network,
+        caip: network.caip().to_string(),
+        path,
+        address_n,
+    }))
+}
+
+#[utoipa::path(
+    post,
+    path = "/addresses/reserves-report",
+    request_body = crate::reserves::ReservesReportRequest,
+    responses(
+        (status = 200, description = "Confirmed Bitcoin balances per address at a block height, each address signing the message", body = crate::reserves::ReservesReport),
+        (status = 400, description = "Invalid request, no confirmed funds, or signing rejected"),
+        (status = 503, description = "No device connected or cache unavailable")
+    ),
+    tag = "Address"
+)]
+pub async fn reserves_report(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<crate::reserves::ReservesReportRequest>,
+) -> Result<Json<crate::reserves::ReservesReport>, Response> {
+    let devices = keepkey_rust::features::list_connected_devices();
+    let device = devices.first()
+        .ok_or_else(|| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse::new("No KeepKey device connected", "DEVICE_NOT_FOUND"))
+            ).into_response()
+        })?;
+    let device_id = device.unique_id.clone();
+
+    let cache = crate::commands::get_cache_manager(&state.cache_manager).await
+        .map_err(|e| (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response())?;
+
+    let queue_handle = {
+        let mut manager = state.device_queue_manager.lock().await;
+
+        if let Some(handle) = manager.get(&device_id) {
+            handle.clone()
+        } else {
+            let handle = keepkey_rust::device_queue::DeviceQueueFactory::spawn_worker(device_id.clone(), device.clone());
+            manager.insert(device_id.clone(), handle.clone());
+            handle
+        }
+    };
+
+    crate::reserves::build_report(&cache, &queue_handle, &request)
+        .await
+        .map(Json)
+        .map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(e, "RESERVES_REPORT_FAILED"))
+            ).into_response()
+        })
+}
+
 // ============ Helper Function ============
 
 async fn handle_address_request<F>(
@@ -361,11 +614,16 @@ where
         })?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     // Create device request
     let device_request = create_request(path.clone(), show_display);
     
+    // Refuse chains the connected firmware can't handle
+    if let Some(chain) = chain_for_request(&device_request) {
+        ensure_chain_supported(&device_id, chain)?;
+    }
+    
     // Process through the queue
     let address = process_address_through_queue(
         state,
@@ -384,6 +642,35 @@ where
     Ok(Json(AddressResponse { address }))
 }
 
+/// Chain name used by the capability map for an address request
+fn chain_for_request(request: &DeviceRequest) -> Option<&'static str> {
+    match request {
+        DeviceRequest::BinanceGetAddress { .. } => Some("binance"),
+        DeviceRequest::CosmosGetAddress { .. } => Some("cosmos"),
+        DeviceRequest::OsmosisGetAddress { .. } => Some("osmosis"),
+        DeviceRequest::EthereumGetAddress { .. } => Some("ethereum"),
+        DeviceRequest::MayachainGetAddress { .. } => Some("mayachain"),
+        DeviceRequest::XrpGetAddress { .. } => Some("ripple"),
+        DeviceRequest::ThorchainGetAddress { .. } => Some("thorchain"),
+        _ => None,
+    }
+}
+
+/// Capability map chain name for a UTXO coin name (e.g. "Bitcoin Cash" -> "bitcoincash")
+fn utxo_chain(coin: &str) -> String {
+    coin.to_lowercase().replace(' ', "")
+}
+
+/// Map a capability check failure to an error response
+fn ensure_chain_supported(device_id: &str, chain: &str) -> Result<(), Response> {
+    crate::device::capabilities::ensure_chain_supported(device_id, chain).map_err(|e| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "UNSUPPORTED_BY_FIRMWARE"))
+        ).into_response()
+    })
+}
+
 async fn process_address_through_queue(
     state: Arc<ServerState>,
     device_id: String,
@@ -408,19 +695,21 @@ async fn process_address_through_queue(
     let cache = crate::commands::get_cache_manager(&state.cache_manager).await
         .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     
-    // Process the request through the cache-aware handler
-    let response = match crate::device::address_operations::process_address_request_with_cache(
-        &cache,
-        &queue_handle,
-        &device_request,
-        &request_id,
-        &device_id,
-    ).await {
+    // Process the request through the cache-aware handler, sharing identical in-flight requests
+    let response = match crate::device::inflight::coalesce(&device_id, &device_request, &request_id, || {
+        crate::device::address_operations::process_address_request_with_cache(
+            &cache,
+            &queue_handle,
+            &device_request,
+            &request_id,
+            &device_id,
+        )
+    }).await {
         Ok(response) => response,
         Err(error) => {
             // Log the actual error for debugging
-            eprintln!("❌ Address request failed for device {}: {}", device_id, error);
-            log::error!("Address request failed for device {}: {}", device_id, error);
+            eprintln!("❌ [{}] Address request failed for device {}: {}", request_id, device_id, error);
+            log::error!("[{}] Address request failed for device {}: {}", request_id, device_id, error);
             return Err(StatusCode::INTERNAL_SERVER_ERROR);
         }
     };
@@ -448,4 +737,47 @@ async fn process_address_through_queue(
             Err(StatusCode::INTERNAL_SERVER_ERROR)
         }
     }
-} 
\ No newline at end of file
+} 
+
+// ============ Address Lookup ============
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct AddressLookupQuery {
+    /// Address to look for
+    pub address: String,
+    /// Receive and change addresses scanned per cached account (default 100, at most 1000)
+    pub depth: Option<u32>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/addresses/lookup",
+    params(AddressLookupQuery),
+    responses(
+        (status = 200, description = "Devices and paths the address belongs to; empty when it isn't ours", body = Vec<crate::derivation::AddressMatch>),
+        (status = 400, description = "Missing address"),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "Address"
+)]
+pub async fn lookup_address(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<AddressLookupQuery>,
+) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match crate::derivation::lookup_address(&cache, &query.address, query.depth).await {
+        Ok(matches) => Json(matches).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "LOOKUP_FAILED")),
+        ).into_response(),
+    }
+}
@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::cache::CacheStatus;
+use crate::cache::types::FrontloadStatus;
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+
+// ============ Frontload ============
+
+/// Optional frontload settings; the device's schedule applies when omitted
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct FrontloadRequest {
+    /// "full" or "incremental"
+    pub mode: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FrontloadStarted {
+    pub device_id: String,
+    pub mode: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/cache/frontload/{device_id}",
+    params(("device_id" = String, Path, description = "Device ID")),
+    request_body = FrontloadRequest,
+    responses(
+        (status = 202, description = "Frontload started; poll the status endpoint for progress", body = FrontloadStarted),
+        (status = 400, description = "Invalid frontload mode"),
+        (status = 409, description = "A frontload is already running for the device")
+    ),
+    tag = "cache"
+)]
+pub async fn trigger_frontload(
+    State(state): State<Arc<ServerState>>,
+    Path(device_id): Path<String>,
+    request: Option<Json<FrontloadRequest>>,
+) -> Response {
+    let request = request.map(|Json(r)| r).unwrap_or_default();
+    let mode = match request.mode.as_deref() {
+        Some(m) => match m.parse::<crate::cache::schedule::FrontloadMode>() {
+            Ok(mode) => mode,
+            Err(e) => return (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(e, "INVALID_MODE")),
+            ).into_response(),
+        },
+        None => crate::cache::schedule::get_schedule(&device_id).mode,
+    };
+
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    if let Ok(status) = cache.get_cache_status(&device_id).await {
+        if matches!(status.frontload_status, FrontloadStatus::InProgress) {
+            return (
+                StatusCode::CONFLICT,
+                Json(ErrorResponse::new(format!("Frontload already running for {}", device_id), "FRONTLOAD_IN_PROGRESS")),
+            ).into_response();
+        }
+    }
+
+    crate::cache::schedule::spawn_frontload(&state.app_handle, cache, state.device_queue_manager.clone(), &device_id, mode);
+
+    let mode = serde_json::to_value(mode).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default();
+    (StatusCode::ACCEPTED, Json(FrontloadStarted { device_id, mode })).into_response()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/cache/frontload/{device_id}/status",
+    params(("device_id" = String, Path, description = "Device ID")),
+    responses(
+        (status = 200, description = "Frontload status, progress and cache statistics", body = CacheStatus),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "cache"
+)]
+pub async fn frontload_status(
+    State(state): State<Arc<ServerState>>,
+    Path(device_id): Path<String>,
+) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match cache.get_cache_status(&device_id).await {
+        Ok(status) => Json(status).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(format!("Failed to read cache status: {}", e), "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
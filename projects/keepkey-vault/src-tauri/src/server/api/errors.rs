@@ -0,0 +1,83 @@
+use axum::{
+    extract::{Query, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::cache::ErrorRecord;
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+
+// ============ Error center ============
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ErrorListQuery {
+    /// Maximum number of errors to return, newest first (default 100)
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ClearErrorsResponse {
+    pub cleared: usize,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/errors",
+    params(ErrorListQuery),
+    responses(
+        (status = 200, description = "Recorded backend failures, newest first", body = Vec<ErrorRecord>),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "system"
+)]
+pub async fn list_errors(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<ErrorListQuery>,
+) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match crate::error_center::list(&cache, query.limit).await {
+        Ok(errors) => Json(errors).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/errors",
+    responses(
+        (status = 200, description = "All recorded errors removed", body = ClearErrorsResponse),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "system"
+)]
+pub async fn clear_errors(State(state): State<Arc<ServerState>>) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match crate::error_center::clear(&cache).await {
+        Ok(cleared) => Json(ClearErrorsResponse { cleared }).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
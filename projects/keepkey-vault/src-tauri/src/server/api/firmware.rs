@@ -0,0 +1,27 @@
+use axum::extract::{Json, Query};
+use serde::Deserialize;
+
+use crate::device::releases::FirmwareCatalog;
+
+// ============ Release Catalog ============
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+pub struct ReleasesQuery {
+    /// Re-fetch the published manifest instead of using the cached one
+    pub refresh: Option<bool>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/firmware/releases",
+    params(ReleasesQuery),
+    responses(
+        (status = 200, description = "Firmware and bootloader release catalog", body = FirmwareCatalog)
+    ),
+    tag = "system"
+)]
+pub async fn get_firmware_releases(
+    Query(query): Query<ReleasesQuery>,
+) -> Json<FirmwareCatalog> {
+    Json(crate::device::releases::get_catalog_refreshed(query.refresh.unwrap_or(false)).await)
+}
@@ -0,0 +1,103 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::cache::{CacheManager, PortfolioGoal};
+use crate::goals::NewGoal;
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+
+// ============ Portfolio goals ============
+
+async fn cache(state: &ServerState) -> Result<Arc<CacheManager>, Response> {
+    crate::commands::get_cache_manager(&state.cache_manager).await.map_err(|e| (
+        StatusCode::SERVICE_UNAVAILABLE,
+        Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+    ).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/goals",
+    responses(
+        (status = 200, description = "Savings goals with progress as of the last portfolio refresh", body = Vec<PortfolioGoal>),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "goals"
+)]
+pub async fn list_goals(State(state): State<Arc<ServerState>>) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::goals::list(&cache).await {
+        Ok(goals) => Json(goals).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/goals",
+    request_body = NewGoal,
+    responses(
+        (status = 200, description = "Goal created", body = PortfolioGoal),
+        (status = 400, description = "Invalid goal"),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "goals"
+)]
+pub async fn create_goal(
+    State(state): State<Arc<ServerState>>,
+    Json(goal): Json<NewGoal>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::goals::create(&cache, goal).await {
+        Ok(goal) => Json(goal).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "INVALID_GOAL")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/goals/{id}",
+    params(("id" = String, Path, description = "Goal ID")),
+    responses(
+        (status = 204, description = "Goal removed"),
+        (status = 404, description = "Goal not found"),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "goals"
+)]
+pub async fn delete_goal(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::goals::delete(&cache, &id).await {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Goal {} not found", id), "GOAL_NOT_FOUND")),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
@@ -1,4 +1,15 @@
 pub mod thorchain;
 pub mod addresses;
 pub mod system;
-pub mod transactions; 
\ No newline at end of file
+pub mod transactions;
+pub mod firmware;
+pub mod wallets;
+pub mod utxos;
+pub mod payments;
+pub mod cache;
+pub mod errors;
+pub mod timeline;
+pub mod providers;
+pub mod goals;
+pub mod accounts;
+pub mod portfolio;
@@ -0,0 +1,169 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::ToSchema;
+
+use crate::cache::PendingPaymentIntent;
+use crate::payment_uri::PaymentIntent;
+use crate::rate_lock::{CreatePaymentIntent, RateCheck};
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+
+// ============ Payment URIs ============
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct ParsePaymentUriRequest {
+    /// bitcoin:, litecoin:, dogecoin:, dash:, bitcoincash: or ethereum: URI
+    pub uri: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/parse-payment-uri",
+    request_body = ParsePaymentUriRequest,
+    responses(
+        (status = 200, description = "Decoded payment intent", body = PaymentIntent),
+        (status = 400, description = "Invalid or unsupported URI")
+    ),
+    tag = "Transaction"
+)]
+pub async fn parse_payment_uri(
+    Json(request): Json<ParsePaymentUriRequest>,
+) -> Response {
+    match crate::payment_uri::parse(&request.uri) {
+        Ok(intent) => Json(intent).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "INVALID_PAYMENT_URI")),
+        ).into_response(),
+    }
+}
+
+// ============ Payment Intents ============
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmPaymentIntentRequest {
+    /// Fiat per unit of the asset right now
+    pub current_rate: f64,
+    /// Confirm even if the rate moved past the threshold or the lock expired
+    pub accept: Option<bool>,
+}
+
+async fn cache(state: &ServerState) -> Result<Arc<crate::cache::CacheManager>, Response> {
+    crate::commands::get_cache_manager(&state.cache_manager).await.map_err(|e| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response()
+    })
+}
+
+fn intent_error(e: String) -> Response {
+    let status = if e.ends_with("not found") { StatusCode::NOT_FOUND } else { StatusCode::BAD_REQUEST };
+    (status, Json(ErrorResponse::new(e, "PAYMENT_INTENT_ERROR"))).into_response()
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/payment-intents",
+    request_body = CreatePaymentIntent,
+    responses(
+        (status = 200, description = "Payment intent with the locked rate", body = PendingPaymentIntent),
+        (status = 400, description = "Invalid amount or rate")
+    ),
+    tag = "Transaction"
+)]
+pub async fn create_payment_intent(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<CreatePaymentIntent>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::rate_lock::create_intent(&cache, request).await {
+        Ok(intent) => Json(intent).into_response(),
+        Err(e) => intent_error(e),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/payment-intents/{id}",
+    params(("id" = String, Path, description = "Payment intent ID")),
+    responses(
+        (status = 200, description = "Payment intent", body = PendingPaymentIntent),
+        (status = 404, description = "Payment intent not found")
+    ),
+    tag = "Transaction"
+)]
+pub async fn get_payment_intent(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match cache.get_payment_intent(&id).await {
+        Ok(Some(intent)) => Json(intent).into_response(),
+        Ok(None) => intent_error(format!("Payment intent {} not found", id)),
+        Err(e) => intent_error(e.to_string()),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/payment-intents/{id}/confirm",
+    params(("id" = String, Path, description = "Payment intent ID")),
+    request_body = ConfirmPaymentIntentRequest,
+    responses(
+        (status = 200, description = "Rate check; the intent is confirmed unless a warning is returned", body = RateCheck),
+        (status = 400, description = "Intent is not pending or the rate is invalid"),
+        (status = 404, description = "Payment intent not found")
+    ),
+    tag = "Transaction"
+)]
+pub async fn confirm_payment_intent(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+    Json(request): Json<ConfirmPaymentIntentRequest>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::rate_lock::confirm_intent(&cache, &id, request.current_rate, request.accept.unwrap_or(false)).await {
+        Ok(check) => Json(check).into_response(),
+        Err(e) => intent_error(e),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/payment-intents/{id}",
+    params(("id" = String, Path, description = "Payment intent ID")),
+    responses(
+        (status = 204, description = "Payment intent cancelled"),
+        (status = 404, description = "Payment intent not found")
+    ),
+    tag = "Transaction"
+)]
+pub async fn cancel_payment_intent(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Response {
+    let cache = match cache(&state).await {
+        Ok(cache) => cache,
+        Err(response) => return response,
+    };
+    match crate::rate_lock::cancel_intent(&cache, &id).await {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => intent_error(e),
+    }
+}
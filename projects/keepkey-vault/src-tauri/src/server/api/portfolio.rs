@@ -0,0 +1,61 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::portfolio::{PortfolioSnapshot, RefreshRequest};
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+
+// ============ Portfolio ============
+
+#[utoipa::path(
+    post,
+    path = "/api/portfolio/refresh",
+    request_body = RefreshRequest,
+    responses(
+        (status = 202, description = "Refresh of the selected slice requested; balances follow on portfolio:changed", body = RefreshRequest),
+        (status = 400, description = "Invalid network or CAIP"),
+        (status = 404, description = "Device not connected")
+    ),
+    tag = "portfolio"
+)]
+pub async fn refresh_portfolio(
+    State(state): State<Arc<ServerState>>,
+    request: Option<Json<RefreshRequest>>,
+) -> Response {
+    let request = request.map(|Json(request)| request).unwrap_or_default();
+    match crate::portfolio::request_refresh(&state.app_handle, request).await {
+        Ok(request) => (StatusCode::ACCEPTED, Json(request)).into_response(),
+        Err(e) if e.starts_with("Device not found") => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(e, "DEVICE_NOT_FOUND")),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "INVALID_REQUEST")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/portfolio/{device_id}",
+    params(("device_id" = String, Path, description = "Device ID")),
+    responses(
+        (status = 200, description = "Balances known so far with per-chain sync state; partial while frontload runs", body = PortfolioSnapshot),
+        (status = 404, description = "No balances or frontload for this device since startup")
+    ),
+    tag = "portfolio"
+)]
+pub async fn get_portfolio(Path(device_id): Path<String>) -> Response {
+    match crate::portfolio::snapshot(&device_id) {
+        Some(snapshot) => Json(snapshot).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("No portfolio for device {}", device_id), "PORTFOLIO_NOT_FOUND")),
+        ).into_response(),
+    }
+}
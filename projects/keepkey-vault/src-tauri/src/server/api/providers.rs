@@ -0,0 +1,53 @@
+use axum::{
+    extract::{Path, Query, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::provider_usage::ProviderUsage;
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+
+// ============ Provider usage ============
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderUsageQuery {
+    /// Seconds to look back (default one day)
+    pub window_secs: Option<i64>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/providers/{provider}/usage",
+    params(("provider" = String, Path, description = "Provider name, e.g. pioneer"), ProviderUsageQuery),
+    responses(
+        (status = 200, description = "Request counts, bytes, latency and errors for the provider", body = ProviderUsage),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "system"
+)]
+pub async fn get_provider_usage(
+    State(state): State<Arc<ServerState>>,
+    Path(provider): Path<String>,
+    Query(query): Query<ProviderUsageQuery>,
+) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match crate::provider_usage::usage(&cache, &provider, query.window_secs).await {
+        Ok(usage) => Json(usage).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    extract::{State, Json},
+    extract::{Query, State, Json},
     http::StatusCode,
     response::{IntoResponse, Response},
 };
@@ -9,6 +9,51 @@ use utoipa::ToSchema;
 
 use crate::server::ServerState;
 use crate::commands::{DeviceRequest, DeviceResponse};
+use crate::device::errors::DeviceError;
+use crate::device::settings_verify::SettingsSnapshot;
+
+// ============ Capabilities ============
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+#[serde(rename_all = "camelCase")]
+pub struct CapabilitiesQuery {
+    /// Device to inspect (defaults to the first connected device)
+    pub device_id: Option<String>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/system/info/capabilities",
+    params(CapabilitiesQuery),
+    responses(
+        (status = 200, description = "Device capability map", body = crate::device::capabilities::DeviceCapabilities),
+        (status = 503, description = "No device connected"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "System"
+)]
+pub async fn get_capabilities(
+    State(state): State<Arc<ServerState>>,
+    Query(query): Query<CapabilitiesQuery>,
+) -> Result<Json<crate::device::capabilities::DeviceCapabilities>, Response> {
+    let device_id = match query.device_id {
+        Some(device_id) => device_id,
+        None => keepkey_rust::features::list_connected_devices()
+            .first()
+            .map(|d| d.unique_id.clone())
+            .ok_or_else(|| {
+                (
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    Json(ErrorResponse::new("No KeepKey device connected", "DEVICE_NOT_FOUND"))
+                ).into_response()
+            })?,
+    };
+
+    crate::device::capabilities::fetch_capabilities(&device_id, &state.device_queue_manager)
+        .await
+        .map(Json)
+        .map_err(|e| DeviceError::classify(e).into_response())
+}
 
 // ============ Ping ============
 
@@ -32,6 +77,9 @@ pub struct PingResponse {
 pub struct ErrorResponse {
     pub error: String,
     pub code: String,
+    /// Correlation ID to look up in the device logs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
 }
 
 impl ErrorResponse {
@@ -39,6 +87,7 @@ impl ErrorResponse {
         Self {
             error: error.into(),
             code: code.into(),
+            request_id: crate::server::correlation::current(),
         }
     }
 }
@@ -68,7 +117,7 @@ pub async fn system_ping(
         })?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     // Create device request
     let device_request = DeviceRequest::Ping {
@@ -84,12 +133,7 @@ pub async fn system_ping(
         device_request,
         device.clone(),
     ).await
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Device operation failed: {}", e), "DEVICE_ERROR"))
-        ).into_response()
-    })?;
+    .map_err(|e| DeviceError::classify(format!("Device operation failed: {}", e)).into_response())?;
     
     match response {
         DeviceResponse::PingResponse { message, .. } => Ok(Json(PingResponse { message })),
@@ -138,7 +182,7 @@ pub async fn get_entropy(
         })?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     let device_request = DeviceRequest::GetEntropy { size: request.size };
     
@@ -149,12 +193,7 @@ pub async fn get_entropy(
         device_request,
         device.clone(),
     ).await
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Device operation failed: {}", e), "DEVICE_ERROR"))
-        ).into_response()
-    })?;
+    .map_err(|e| DeviceError::classify(format!("Device operation failed: {}", e)).into_response())?;
     
     match response {
         DeviceResponse::Entropy { entropy, .. } => Ok(Json(GetEntropyResponse { entropy })),
@@ -167,26 +206,69 @@ pub async fn get_entropy(
 
 // ============ Get Public Key ============
 
+/// Curves the firmware can derive public keys on
+const SUPPORTED_CURVES: &[&str] = &["secp256k1", "nist256p1", "ed25519"];
+
+/// Output format for get-public-key
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PublicKeyFormat {
+    /// Serialized extended public key (secp256k1 only)
+    #[default]
+    Xpub,
+    /// Hex-encoded public key bytes
+    Raw,
+}
+
 #[derive(Debug, Deserialize, ToSchema)]
 pub struct GetPublicKeyRequest {
     pub address_n: Vec<u32>,
+    /// secp256k1 (default), nist256p1 or ed25519
+    #[serde(alias = "curve")]
     pub ecdsa_curve_name: Option<String>,
     pub show_display: Option<bool>,
+    #[serde(default)]
+    pub format: PublicKeyFormat,
 }
 
 #[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GetPublicKeyResponse {
-    pub xpub: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub xpub: Option<String>,
+    /// Hex-encoded public key
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    pub curve: String,
     pub node: serde_json::Value,
 }
 
+/// Check the curve and path of a public key request, returning the normalized curve name
+fn validate_public_key_request(request: &GetPublicKeyRequest) -> Result<String, (String, &'static str)> {
+    let curve = request.ecdsa_curve_name.as_deref().unwrap_or("secp256k1").to_lowercase();
+    if !SUPPORTED_CURVES.contains(&curve.as_str()) {
+        return Err((format!("Unsupported curve {}. Supported: {}", curve, SUPPORTED_CURVES.join(", ")), "UNSUPPORTED_CURVE"));
+    }
+
+    // SLIP-10 ed25519 only defines hardened derivation
+    if curve == "ed25519" && request.address_n.iter().any(|n| n & 0x80000000 == 0) {
+        return Err(("ed25519 paths must be fully hardened".to_string(), "INVALID_PATH"));
+    }
+
+    if curve != "secp256k1" && request.format == PublicKeyFormat::Xpub {
+        return Err((format!("xpub output is only available for secp256k1; use format \"raw\" for {}", curve), "UNSUPPORTED_FORMAT"));
+    }
+
+    Ok(curve)
+}
+
 #[utoipa::path(
     post,
     path = "/system/info/get-public-key",
     request_body = GetPublicKeyRequest,
     responses(
         (status = 200, description = "Public key retrieved", body = GetPublicKeyResponse),
+        (status = 400, description = "Unsupported curve, path or format"),
         (status = 500, description = "Internal server error")
     ),
     tag = "System"
@@ -204,8 +286,12 @@ pub async fn get_public_key(
             ).into_response()
         })?;
     
+    let curve = validate_public_key_request(&request).map_err(|(error, code)| {
+        (StatusCode::BAD_REQUEST, Json(ErrorResponse::new(error, code))).into_response()
+    })?;
+    
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     let path = format!("m/{}", request.address_n.iter()
         .map(|n| n.to_string())
@@ -218,7 +304,7 @@ pub async fn get_public_key(
         path,
         coin_name: None, // Will be defaulted to Bitcoin in system operations
         script_type: None, // Will be set to None in system operations (let firmware decide)
-        ecdsa_curve_name: request.ecdsa_curve_name,
+        ecdsa_curve_name: Some(curve.clone()),
         show_display: request.show_display,
     };
     
@@ -229,16 +315,17 @@ pub async fn get_public_key(
         device_request,
         device.clone(),
     ).await
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Device operation failed: {}", e), "DEVICE_ERROR"))
-        ).into_response()
-    })?;
+    .map_err(|e| DeviceError::classify(format!("Device operation failed: {}", e)).into_response())?;
     
     match response {
         DeviceResponse::PublicKey { xpub, node, .. } => {
-            Ok(Json(GetPublicKeyResponse { xpub, node: node.unwrap_or(serde_json::Value::Null) }))
+            let node = node.unwrap_or(serde_json::Value::Null);
+            let public_key = node.get("public_key").and_then(|v| v.as_str()).map(String::from);
+            let xpub = match request.format {
+                PublicKeyFormat::Xpub => Some(xpub),
+                PublicKeyFormat::Raw => None,
+            };
+            Ok(Json(GetPublicKeyResponse { xpub, public_key, curve, node }))
         },
         _ => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
@@ -270,7 +357,8 @@ pub struct ApplySettingsResponse {
     path = "/system/settings/apply",
     request_body = ApplySettingsRequest,
     responses(
-        (status = 200, description = "Settings applied", body = ApplySettingsResponse),
+        (status = 200, description = "Settings applied and read back from the device", body = ApplySettingsResponse),
+        (status = 409, description = "Device acknowledged but did not apply the settings"),
         (status = 500, description = "Internal server error")
     ),
     tag = "System"
@@ -288,8 +376,17 @@ pub async fn apply_settings(
             ).into_response()
         })?;
     
+    if let Some(label) = &request.label {
+        crate::device::capabilities::ensure_label_fits(label).map_err(|e| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse::new(e, "INVALID_LABEL"))
+            ).into_response()
+        })?;
+    }
+    
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     let device_request = DeviceRequest::ApplySettings {
         label: request.label,
@@ -306,15 +403,14 @@ pub async fn apply_settings(
         device_request,
         device.clone(),
     ).await
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Device operation failed: {}", e), "DEVICE_ERROR"))
-        ).into_response()
-    })?;
+    .map_err(|e| DeviceError::classify(format!("Device operation failed: {}", e)).into_response())?;
     
     match response {
-        DeviceResponse::Success { .. } => Ok(Json(ApplySettingsResponse { success: true })),
+        DeviceResponse::Success { success: true, .. } => Ok(Json(ApplySettingsResponse { success: true })),
+        DeviceResponse::Success { error, .. } => Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse::new(error.unwrap_or_else(|| "Device did not apply the settings".to_string()), "SETTINGS_NOT_APPLIED"))
+        ).into_response()),
         _ => Err((
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(ErrorResponse::new("Unexpected response from device", "INVALID_RESPONSE"))
@@ -322,6 +418,97 @@ pub async fn apply_settings(
     }
 }
 
+// ============ Individual Settings ============
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct LanguageSettingRequest {
+    pub language: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoLockSettingRequest {
+    pub auto_lock_delay_ms: u32,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct PassphraseSettingRequest {
+    pub enabled: bool,
+}
+
+async fn apply_setting(
+    state: Arc<ServerState>,
+    setting: crate::device::settings::DeviceSetting,
+) -> Result<Json<SettingsSnapshot>, Response> {
+    let devices = keepkey_rust::features::list_connected_devices();
+    let device = devices.first()
+        .ok_or_else(|| {
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse::new("No KeepKey device connected", "DEVICE_NOT_FOUND"))
+            ).into_response()
+        })?;
+
+    crate::device::settings::apply(&device.unique_id, &state.device_queue_manager, setting)
+        .await
+        .map(Json)
+        .map_err(|e| DeviceError::classify(e).into_response())
+}
+
+#[utoipa::path(
+    post,
+    path = "/system/settings/language",
+    request_body = LanguageSettingRequest,
+    responses(
+        (status = 200, description = "Language applied; settings read back from the device", body = SettingsSnapshot),
+        (status = 412, description = "Language not supported by the firmware"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "System"
+)]
+pub async fn set_language(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<LanguageSettingRequest>,
+) -> Result<Json<SettingsSnapshot>, Response> {
+    apply_setting(state, crate::device::settings::DeviceSetting::Language(request.language)).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/system/settings/auto-lock",
+    request_body = AutoLockSettingRequest,
+    responses(
+        (status = 200, description = "Auto-lock delay applied; settings read back from the device", body = SettingsSnapshot),
+        (status = 412, description = "Delay out of range or not supported by the firmware"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "System"
+)]
+pub async fn set_auto_lock_delay(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<AutoLockSettingRequest>,
+) -> Result<Json<SettingsSnapshot>, Response> {
+    apply_setting(state, crate::device::settings::DeviceSetting::AutoLockDelayMs(request.auto_lock_delay_ms)).await
+}
+
+#[utoipa::path(
+    post,
+    path = "/system/settings/passphrase",
+    request_body = PassphraseSettingRequest,
+    responses(
+        (status = 200, description = "Passphrase protection toggled; settings read back from the device", body = SettingsSnapshot),
+        (status = 412, description = "Passphrase protection not supported by the firmware"),
+        (status = 500, description = "Internal server error")
+    ),
+    tag = "System"
+)]
+pub async fn set_passphrase_protection(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<PassphraseSettingRequest>,
+) -> Result<Json<SettingsSnapshot>, Response> {
+    apply_setting(state, crate::device::settings::DeviceSetting::Passphrase(request.enabled)).await
+}
+
 // ============ Clear Session ============
 
 #[derive(Debug, Serialize, ToSchema)]
@@ -352,7 +539,7 @@ pub async fn clear_session(
         })?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     let device_request = DeviceRequest::ClearSession;
     
@@ -363,12 +550,7 @@ pub async fn clear_session(
         device_request,
         device.clone(),
     ).await
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Device operation failed: {}", e), "DEVICE_ERROR"))
-        ).into_response()
-    })?;
+    .map_err(|e| DeviceError::classify(format!("Device operation failed: {}", e)).into_response())?;
     
     match response {
         DeviceResponse::Success { .. } => Ok(Json(ClearSessionResponse { success: true })),
@@ -392,6 +574,7 @@ pub struct WipeDeviceResponse {
     path = "/system/wipe-device",
     responses(
         (status = 200, description = "Device wiped", body = WipeDeviceResponse),
+        (status = 403, description = "Wiping from API clients is disabled"),
         (status = 500, description = "Internal server error")
     ),
     tag = "System"
@@ -399,6 +582,17 @@ pub struct WipeDeviceResponse {
 pub async fn wipe_device(
     State(state): State<Arc<ServerState>>,
 ) -> Result<Json<WipeDeviceResponse>, Response> {
+    // API clients can't go through the in-app confirmation, so they need an explicit opt-in
+    if !crate::device::wipe_interlock::get_preferences().allow_api_wipe {
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse::new(
+                "Wiping from API clients is disabled; wipe from the KeepKey Vault app or enable it in preferences",
+                "WIPE_DISABLED",
+            ))
+        ).into_response());
+    }
+    
     let devices = keepkey_rust::features::list_connected_devices();
     let device = devices.first()
         .ok_or_else(|| {
@@ -409,7 +603,7 @@ pub async fn wipe_device(
         })?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     let device_request = DeviceRequest::WipeDevice;
     
@@ -420,12 +614,7 @@ pub async fn wipe_device(
         device_request,
         device.clone(),
     ).await
-    .map_err(|e| {
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ErrorResponse::new(format!("Device operation failed: {}", e), "DEVICE_ERROR"))
-        ).into_response()
-    })?;
+    .map_err(|e| DeviceError::classify(format!("Device operation failed: {}", e)).into_response())?;
     
     match response {
         DeviceResponse::Success { .. } => Ok(Json(WipeDeviceResponse { success: true })),
@@ -462,14 +651,16 @@ async fn process_system_request_with_cache(
     let cache = crate::commands::get_cache_manager(&state.cache_manager).await
         .map_err(|e| format!("Failed to get cache manager: {}", e))?;
     
-    // Process the request through the cache-aware handler
-    let response = match crate::device::system_operations::process_system_request_with_cache(
-        &cache,
-        &queue_handle,
-        &device_request,
-        &request_id,
-        &device_id,
-    ).await {
+    // Process the request through the cache-aware handler, sharing identical in-flight requests
+    let response = match crate::device::inflight::coalesce(&device_id, &device_request, &request_id, || {
+        crate::device::system_operations::process_system_request_with_cache(
+            &cache,
+            &queue_handle,
+            &device_request,
+            &request_id,
+            &device_id,
+        )
+    }).await {
         Ok(response) => response,
         Err(e) => return Err(e),
     };
@@ -565,4 +756,22 @@ pub async fn exit_application(
         success: true,
         message: "Application shutdown initiated".to_string(),
     }))
-} 
\ No newline at end of file
+} 
+
+// ============ Metrics ============
+
+#[utoipa::path(
+    get,
+    path = "/metrics",
+    responses(
+        (status = 200, description = "Device queue metrics in the Prometheus text format", content_type = "text/plain")
+    ),
+    tag = "system"
+)]
+pub async fn prometheus_metrics(State(state): State<Arc<ServerState>>) -> Response {
+    let metrics = crate::device::queue_metrics::snapshot(&state.device_queue_manager, None).await;
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        crate::device::queue_metrics::render_prometheus(&metrics),
+    ).into_response()
+}
@@ -0,0 +1,52 @@
+use axum::{
+    extract::{Path, Query, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use utoipa::IntoParams;
+
+use crate::activity::TimelineItem;
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+
+// ============ Activity timeline ============
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct TimelineQuery {
+    /// Maximum number of items to return, newest first (default 200)
+    pub limit: Option<usize>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/timeline/{device_id}",
+    params(("device_id" = String, Path, description = "Device ID"), TimelineQuery),
+    responses(
+        (status = 200, description = "Device lifecycle events, signatures and errors, newest first", body = Vec<TimelineItem>),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "device"
+)]
+pub async fn get_timeline(
+    State(state): State<Arc<ServerState>>,
+    Path(device_id): Path<String>,
+    Query(query): Query<TimelineQuery>,
+) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match crate::activity::timeline(&cache, &device_id, query.limit).await {
+        Ok(items) => Json(items).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
@@ -1,6 +1,7 @@
 use axum::{
     extract::{State, Json},
     http::StatusCode,
+    response::{IntoResponse, Response},
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
@@ -8,6 +9,8 @@ use utoipa::ToSchema;
 
 use crate::server::ServerState;
 use crate::commands::{DeviceRequest, DeviceResponse, BitcoinUtxoInput, BitcoinUtxoOutput};
+use crate::eth_signatures::Verification;
+use crate::server::api::addresses::ErrorResponse;
 
 // ============ UTXO Transaction Signing ============
 
@@ -47,15 +50,9 @@ pub async fn utxo_sign_transaction(
         .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
-    let device_request = DeviceRequest::SignTransaction {
-        coin: request.coin,
-        inputs: request.inputs,
-        outputs: request.outputs,
-        version: request.version.unwrap_or(1),
-        lock_time: request.lock_time.unwrap_or(0),
-    };
+    let device_request = utxo_device_request(request);
     
     let response = process_transaction_request(
         state,
@@ -76,6 +73,16 @@ pub async fn utxo_sign_transaction(
     }
 }
 
+fn utxo_device_request(request: UtxoSignTransactionRequest) -> DeviceRequest {
+    DeviceRequest::SignTransaction {
+        coin: request.coin,
+        inputs: request.inputs,
+        outputs: request.outputs,
+        version: request.version.unwrap_or(1),
+        lock_time: request.lock_time.unwrap_or(0),
+    }
+}
+
 // ============ Ethereum Transaction Signing ============
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -121,20 +128,9 @@ pub async fn eth_sign_transaction(
         .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
-    let device_request = DeviceRequest::EthereumSignTransaction {
-        nonce: request.nonce,
-        gas_price: request.gas_price,
-        gas_limit: request.gas_limit,
-        to: request.to,
-        value: request.value,
-        data: request.data,
-        chain_id: request.chain_id,
-        max_fee_per_gas: request.max_fee_per_gas,
-        max_priority_fee_per_gas: request.max_priority_fee_per_gas,
-        access_list: request.access_list,
-    };
+    let device_request = eth_device_request(request);
     
     let response = process_transaction_request(
         state,
@@ -152,6 +148,21 @@ pub async fn eth_sign_transaction(
     }
 }
 
+fn eth_device_request(request: EthSignTransactionRequest) -> DeviceRequest {
+    DeviceRequest::EthereumSignTransaction {
+        nonce: request.nonce,
+        gas_price: request.gas_price,
+        gas_limit: request.gas_limit,
+        to: request.to,
+        value: request.value,
+        data: request.data,
+        chain_id: request.chain_id,
+        max_fee_per_gas: request.max_fee_per_gas,
+        max_priority_fee_per_gas: request.max_priority_fee_per_gas,
+        access_list: request.access_list,
+    }
+}
+
 // ============ Ethereum Message Signing ============
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -186,7 +197,7 @@ pub async fn eth_sign_message(
         .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     // First get the address for this derivation path
     
@@ -237,6 +248,53 @@ pub async fn eth_sign_message(
     }
 }
 
+// ============ Ethereum Signature Verification ============
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct EthVerifyRequest {
+    /// Personal message (EIP-191), as passed to /eth/sign
+    pub message: Option<String>,
+    /// EIP-712 payload as used by eth_signTypedData_v4
+    #[schema(value_type = Object)]
+    pub typed_data: Option<serde_json::Value>,
+    /// 65-byte r || s || v signature, hex
+    pub signature: String,
+    /// Expected signer address
+    pub address: String,
+}
+
+#[utoipa::path(
+    post,
+    path = "/eth/verify",
+    request_body = EthVerifyRequest,
+    responses(
+        (status = 200, description = "Signer recovered; valid tells whether it matches the address", body = Verification),
+        (status = 400, description = "Malformed payload or signature")
+    ),
+    tag = "Transaction"
+)]
+pub async fn eth_verify_signature(
+    Json(request): Json<EthVerifyRequest>,
+) -> Result<Json<Verification>, Response> {
+    let bad_request = |e: String| (
+        StatusCode::BAD_REQUEST,
+        Json(ErrorResponse::new(e, "INVALID_SIGNATURE_PAYLOAD")),
+    ).into_response();
+
+    let hash = match (&request.message, &request.typed_data) {
+        (Some(message), None) => crate::eth_signatures::hash_personal_message(
+            &crate::eth_signatures::personal_message_bytes(message),
+        ),
+        (None, Some(typed_data)) => crate::eth_signatures::hash_typed_data(typed_data).map_err(bad_request)?,
+        _ => return Err(bad_request("Provide exactly one of message or typedData".to_string())),
+    };
+
+    crate::eth_signatures::verify(hash, &request.signature, &request.address)
+        .map(Json)
+        .map_err(bad_request)
+}
+
 // ============ Cosmos/Amino Signing ============
 
 #[derive(Debug, Deserialize, ToSchema)]
@@ -273,7 +331,7 @@ pub async fn cosmos_sign_amino(
         .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
     
     let device_id = device.unique_id.clone();
-    let request_id = uuid::Uuid::new_v4().to_string();
+    let request_id = crate::server::correlation::request_id();
     
     let device_request = DeviceRequest::CosmosSignAmino {
         sign_doc: request.sign_doc,
@@ -300,6 +358,177 @@ pub async fn cosmos_sign_amino(
     }
 }
 
+// ============ Batch Signing ============
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum BatchTransaction {
+    Utxo(UtxoSignTransactionRequest),
+    Ethereum(EthSignTransactionRequest),
+}
+
+impl BatchTransaction {
+    fn summary(&self, index: usize) -> crate::device::batch_signing::BatchItemSummary {
+        match self {
+            BatchTransaction::Utxo(tx) => {
+                let spends: Vec<&BitcoinUtxoOutput> = tx.outputs.iter()
+                    .filter(|o| !o.is_change.unwrap_or(false) && o.address_type != "change")
+                    .collect();
+                crate::device::batch_signing::BatchItemSummary {
+                    index,
+                    kind: "utxo".to_string(),
+                    network: tx.coin.clone(),
+                    recipients: spends.iter().map(|o| o.address.clone()).collect(),
+                    amount: spends.iter().map(|o| o.amount).sum::<u64>().to_string(),
+                }
+            }
+            BatchTransaction::Ethereum(tx) => crate::device::batch_signing::BatchItemSummary {
+                index,
+                kind: "ethereum".to_string(),
+                network: tx.chain_id.to_string(),
+                recipients: vec![tx.to.clone()],
+                amount: tx.value.clone(),
+            },
+        }
+    }
+
+    fn into_device_request(self) -> DeviceRequest {
+        match self {
+            BatchTransaction::Utxo(tx) => utxo_device_request(tx),
+            BatchTransaction::Ethereum(tx) => eth_device_request(tx),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSignRequest {
+    /// Transactions in the order they should be signed
+    pub transactions: Vec<BatchTransaction>,
+    /// Skip the remaining transactions after the first failure (default true)
+    pub stop_on_error: Option<bool>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchItemResult {
+    pub index: usize,
+    pub success: bool,
+    pub serialized: Option<String>,
+    pub txid: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchSignResponse {
+    pub batch_id: String,
+    pub results: Vec<BatchItemResult>,
+}
+
+#[utoipa::path(
+    post,
+    path = "/transactions/batch-sign",
+    request_body = BatchSignRequest,
+    responses(
+        (status = 200, description = "Per-transaction signing results", body = BatchSignResponse),
+        (status = 400, description = "Empty batch"),
+        (status = 403, description = "Batch rejected or approval timed out"),
+        (status = 503, description = "No device connected")
+    ),
+    tag = "Transaction"
+)]
+pub async fn batch_sign_transactions(
+    State(state): State<Arc<ServerState>>,
+    Json(request): Json<BatchSignRequest>,
+) -> Result<Json<BatchSignResponse>, StatusCode> {
+    if request.transactions.is_empty() {
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let devices = keepkey_rust::features::list_connected_devices();
+    let device = devices.first()
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    let device_id = device.unique_id.clone();
+
+    let batch_id = uuid::Uuid::new_v4().to_string();
+    let summaries: Vec<_> = request.transactions.iter()
+        .enumerate()
+        .map(|(index, tx)| tx.summary(index))
+        .collect();
+
+    // One vault approval covering the whole batch instead of a prompt per transaction
+    if let Err(e) = crate::device::batch_signing::request_approval(&state.app_handle, &batch_id, &summaries).await {
+        log::warn!("Batch {} not signed: {}", batch_id, e);
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let queue_handle = {
+        let mut manager = state.device_queue_manager.lock().await;
+
+        if let Some(handle) = manager.get(&device_id) {
+            handle.clone()
+        } else {
+            let handle = keepkey_rust::device_queue::DeviceQueueFactory::spawn_worker(device_id.clone(), device.clone());
+            manager.insert(device_id.clone(), handle.clone());
+            handle
+        }
+    };
+
+    let stop_on_error = request.stop_on_error.unwrap_or(true);
+    let total = request.transactions.len();
+    let mut results = Vec::with_capacity(total);
+    let mut failed = false;
+
+    for (index, tx) in request.transactions.into_iter().enumerate() {
+        if failed && stop_on_error {
+            results.push(BatchItemResult {
+                index,
+                success: false,
+                serialized: None,
+                txid: None,
+                error: Some("Skipped after an earlier failure".to_string()),
+            });
+            continue;
+        }
+
+        let request_id = crate::server::correlation::request_id();
+        let response = crate::device::transaction_operations::process_transaction_request(
+            &queue_handle,
+            &tx.into_device_request(),
+            &request_id,
+            &device_id,
+        ).await;
+
+        let result = match response {
+            Ok(DeviceResponse::SignedTransaction { signed_tx, txid, success: true, .. }) => BatchItemResult {
+                index, success: true, serialized: Some(signed_tx), txid, error: None,
+            },
+            Ok(DeviceResponse::EthereumSignedTransaction { serialized, success: true, .. }) => BatchItemResult {
+                index, success: true, serialized: Some(serialized), txid: None, error: None,
+            },
+            Ok(DeviceResponse::SignedTransaction { error, .. })
+            | Ok(DeviceResponse::EthereumSignedTransaction { error, .. }) => BatchItemResult {
+                index, success: false, serialized: None, txid: None,
+                error: Some(error.unwrap_or_else(|| "Signing failed".to_string())),
+            },
+            Ok(_) => BatchItemResult {
+                index, success: false, serialized: None, txid: None,
+                error: Some("Unexpected device response".to_string()),
+            },
+            Err(e) => BatchItemResult {
+                index, success: false, serialized: None, txid: None, error: Some(e),
+            },
+        };
+
+        failed |= !result.success;
+        crate::device::batch_signing::emit_progress(&state.app_handle, &batch_id, index, total, result.success).await;
+        results.push(result);
+    }
+
+    Ok(Json(BatchSignResponse { batch_id, results }))
+}
+
 // ============ Helper Function ============
 
 async fn process_transaction_request(
@@ -0,0 +1,108 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::cache::UtxoTag;
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+use crate::utxos::{Utxo, UtxoTagUpdate, XpubBalance, XpubBalanceRequest};
+
+// ============ UTXOs ============
+
+#[utoipa::path(
+    get,
+    path = "/api/utxos/{device_id}",
+    params(("device_id" = String, Path, description = "Device ID")),
+    responses(
+        (status = 200, description = "UTXOs of the device's Bitcoin accounts with labels and freeze flags", body = Vec<Utxo>),
+        (status = 404, description = "No cached Bitcoin accounts for the device"),
+        (status = 502, description = "Indexer unavailable")
+    ),
+    tag = "utxos"
+)]
+pub async fn list_utxos(
+    State(state): State<Arc<ServerState>>,
+    Path(device_id): Path<String>,
+) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match crate::utxos::list_utxos(&cache, &device_id).await {
+        Ok(utxos) => Json(utxos).into_response(),
+        Err(e) if e.starts_with("No cached") => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(e, "NO_ACCOUNTS")),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::BAD_GATEWAY,
+            Json(ErrorResponse::new(e, "INDEXER_ERROR")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/utxos/{device_id}/{txid}/{vout}",
+    params(
+        ("device_id" = String, Path, description = "Device ID"),
+        ("txid" = String, Path, description = "Transaction ID"),
+        ("vout" = u32, Path, description = "Output index")
+    ),
+    request_body = UtxoTagUpdate,
+    responses(
+        (status = 200, description = "Label and freeze flag saved", body = UtxoTag),
+        (status = 400, description = "Invalid outpoint")
+    ),
+    tag = "utxos"
+)]
+pub async fn tag_utxo(
+    State(state): State<Arc<ServerState>>,
+    Path((device_id, txid, vout)): Path<(String, String, u32)>,
+    Json(update): Json<UtxoTagUpdate>,
+) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match crate::utxos::tag_utxo(&cache, &device_id, &txid, vout, update).await {
+        Ok(tag) => Json(tag).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "INVALID_UTXO")),
+        ).into_response(),
+    }
+}
+
+// ============ Balances for keys held elsewhere ============
+
+#[utoipa::path(
+    post,
+    path = "/api/balances/xpubs",
+    request_body = XpubBalanceRequest,
+    responses(
+        (status = 200, description = "Balance per key; keys that failed carry an error", body = Vec<XpubBalance>),
+        (status = 400, description = "No keys or too many keys")
+    ),
+    tag = "utxos"
+)]
+pub async fn xpub_balances(Json(request): Json<XpubBalanceRequest>) -> Response {
+    match crate::utxos::xpub_balances(request).await {
+        Ok(balances) => Json(balances).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "INVALID_REQUEST")),
+        ).into_response(),
+    }
+}
@@ -0,0 +1,137 @@
+use axum::{
+    extract::{Path, State, Json},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::server::ServerState;
+use crate::server::api::addresses::ErrorResponse;
+use crate::device::duplicate_seeds::DuplicateSeedGroup;
+use crate::wallets::{Wallet, WalletSummary};
+
+// ============ Wallets ============
+
+async fn summarize(state: &ServerState, wallet: Wallet) -> WalletSummary {
+    let cache = crate::commands::get_cache_manager(&state.cache_manager).await.ok();
+    crate::wallets::summarize(wallet, cache.as_deref()).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/wallets",
+    responses(
+        (status = 200, description = "All wallets with their devices and accounts", body = Vec<WalletSummary>)
+    ),
+    tag = "wallets"
+)]
+pub async fn list_wallets(
+    State(state): State<Arc<ServerState>>,
+) -> Json<Vec<WalletSummary>> {
+    let mut summaries = Vec::new();
+    for wallet in crate::wallets::get_wallets() {
+        summaries.push(summarize(&state, wallet).await);
+    }
+    Json(summaries)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/wallets/{id}",
+    params(("id" = String, Path, description = "Wallet ID")),
+    responses(
+        (status = 200, description = "Wallet with its devices and accounts", body = WalletSummary),
+        (status = 404, description = "Wallet not found")
+    ),
+    tag = "wallets"
+)]
+pub async fn get_wallet(
+    State(state): State<Arc<ServerState>>,
+    Path(id): Path<String>,
+) -> Response {
+    match crate::wallets::find_wallet(&id) {
+        Some(wallet) => Json(summarize(&state, wallet).await).into_response(),
+        None => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Wallet {} not found", id), "WALLET_NOT_FOUND")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/wallets",
+    request_body = Wallet,
+    responses(
+        (status = 200, description = "Wallet created or updated", body = WalletSummary),
+        (status = 400, description = "Invalid wallet")
+    ),
+    tag = "wallets"
+)]
+pub async fn save_wallet(
+    State(state): State<Arc<ServerState>>,
+    Json(wallet): Json<Wallet>,
+) -> Response {
+    match crate::wallets::save_wallet(wallet) {
+        Ok(wallet) => Json(summarize(&state, wallet).await).into_response(),
+        Err(e) => (
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse::new(e, "INVALID_WALLET")),
+        ).into_response(),
+    }
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/wallets/{id}",
+    params(("id" = String, Path, description = "Wallet ID")),
+    responses(
+        (status = 204, description = "Wallet removed"),
+        (status = 404, description = "Wallet not found")
+    ),
+    tag = "wallets"
+)]
+pub async fn delete_wallet(
+    Path(id): Path<String>,
+) -> Response {
+    match crate::wallets::remove_wallet(&id) {
+        Ok(true) => StatusCode::NO_CONTENT.into_response(),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse::new(format!("Wallet {} not found", id), "WALLET_NOT_FOUND")),
+        ).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "WALLET_SAVE_FAILED")),
+        ).into_response(),
+    }
+}
+
+// ============ Duplicate seeds ============
+
+#[utoipa::path(
+    get,
+    path = "/api/devices/duplicate-seeds",
+    responses(
+        (status = 200, description = "Groups of cached devices that hold the same seed", body = Vec<DuplicateSeedGroup>),
+        (status = 503, description = "Cache unavailable")
+    ),
+    tag = "device"
+)]
+pub async fn list_duplicate_seeds(State(state): State<Arc<ServerState>>) -> Response {
+    let cache = match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache,
+        Err(e) => return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse::new(e, "CACHE_UNAVAILABLE")),
+        ).into_response(),
+    };
+
+    match crate::device::duplicate_seeds::find_duplicates(&cache).await {
+        Ok(groups) => Json(groups).into_response(),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse::new(e, "CACHE_ERROR")),
+        ).into_response(),
+    }
+}
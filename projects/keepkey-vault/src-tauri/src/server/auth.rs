@@ -1,14 +1,28 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{Request, State},
+    http::{header, HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
     Json,
 };
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 use serde::{Serialize, Deserialize};
+use tokio::sync::oneshot;
 use utoipa::ToSchema;
 
 use super::ServerState;
 
+/// How long a pairing request waits for the user to approve it in the vault
+const APPROVAL_TIMEOUT: Duration = Duration::from_secs(120);
+
+lazy_static::lazy_static! {
+    static ref PENDING_PAIRINGS: Mutex<HashMap<String, oneshot::Sender<bool>>> = Mutex::new(HashMap::new());
+    /// Pairing keys already read from the keychain, by origin
+    static ref PAIRING_KEYS: Mutex<HashMap<String, String>> = Mutex::new(HashMap::new());
+}
+
 #[derive(Deserialize, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PairingInfo {
@@ -28,24 +42,104 @@ pub struct AuthResponse {
     pub api_key: String,
 }
 
+fn secret_name(origin: &str) -> String {
+    format!("pairing:{}", origin)
+}
+
+/// The key issued to an origin when it was paired
+fn pairing_key(origin: &str) -> Result<Option<String>, String> {
+    if let Some(key) = PAIRING_KEYS.lock().unwrap().get(origin) {
+        return Ok(Some(key.clone()));
+    }
+    let key = crate::secrets::get_secret(&secret_name(origin))?;
+    if let Some(key) = &key {
+        PAIRING_KEYS.lock().unwrap().insert(origin.to_string(), key.clone());
+    }
+    Ok(key)
+}
+
+/// Compare without returning early on the first differing byte
+fn keys_match(expected: &str, given: &str) -> bool {
+    expected.len() == given.len()
+        && expected.bytes().zip(given.bytes()).fold(0u8, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+/// Normalized Origin header and bearer token of a request
+fn credentials(headers: &HeaderMap) -> (Option<String>, Option<String>) {
+    let origin = headers
+        .get(header::ORIGIN)
+        .and_then(|value| value.to_str().ok())
+        .and_then(super::cors::normalize_origin);
+    let token = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(|token| token.trim().to_string());
+    (origin, token)
+}
+
+/// Check a bearer key against the key stored for the origin
+fn verify(origin: &str, token: Option<&str>) -> Result<(), StatusCode> {
+    let expected = match pairing_key(origin) {
+        Ok(Some(key)) => key,
+        Ok(None) => return Err(StatusCode::NOT_FOUND),
+        Err(e) => {
+            log::error!("Failed to read pairing key for {}: {}", origin, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    match token {
+        Some(token) if keys_match(&expected, token) => Ok(()),
+        _ => Err(StatusCode::UNAUTHORIZED),
+    }
+}
+
 #[utoipa::path(
     get,
     path = "/auth/pair",
     responses(
-        (status = 200, description = "Auth verification successful", body = AuthResponse),
+        (status = 200, description = "The bearer key is valid for the calling origin", body = AuthResponse),
+        (status = 400, description = "Missing Origin header"),
+        (status = 401, description = "Missing or wrong bearer key"),
         (status = 404, description = "No pairing found"),
     ),
     tag = "auth"
 )]
-pub async fn auth_verify(
-    State(_state): State<Arc<ServerState>>,
-) -> Result<Json<AuthResponse>, StatusCode> {
-    // For now, return a dummy API key
+pub async fn auth_verify(headers: HeaderMap) -> Result<Json<AuthResponse>, StatusCode> {
+    let (origin, token) = credentials(&headers);
+    let origin = origin.ok_or(StatusCode::BAD_REQUEST)?;
+    verify(&origin, token.as_deref())?;
+
     Ok(Json(AuthResponse {
-        api_key: "keepkey-vault-api-key".to_string(),
+        api_key: token.unwrap_or_default(),
     }))
 }
 
+/// Browser callers from origins other than the built-in KeepKey ones must
+/// present the key issued to their origin at pairing
+pub async fn auth_middleware(request: Request, next: Next) -> Response {
+    if request.uri().path() == "/auth/pair" {
+        return next.run(request).await;
+    }
+
+    let (origin, token) = credentials(request.headers());
+    if let Some(origin) = origin.filter(|origin| !super::cors::is_builtin_origin(origin)) {
+        if let Err(status) = verify(&origin, token.as_deref()) {
+            let status = if status == StatusCode::NOT_FOUND { StatusCode::UNAUTHORIZED } else { status };
+            log::warn!("🔒 Rejected {} {} from unpaired or unauthenticated origin {}", request.method(), request.uri().path(), origin);
+            return (
+                status,
+                Json(super::api::addresses::ErrorResponse::new(
+                    format!("Origin {} must pair and send its key as a bearer token", origin),
+                    "UNAUTHORIZED",
+                )),
+            ).into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
 #[utoipa::path(
     post,
     path = "/auth/pair",
@@ -53,15 +147,101 @@ pub async fn auth_verify(
     responses(
         (status = 200, description = "Pairing successful", body = AuthResponse),
         (status = 400, description = "Invalid pairing information"),
+        (status = 403, description = "Pairing rejected in the vault or not answered in time"),
     ),
     tag = "auth"
 )]
 pub async fn auth_pair(
-    State(_state): State<Arc<ServerState>>,
-    Json(_pairing_info): Json<PairingInfo>,
+    State(state): State<Arc<ServerState>>,
+    Json(pairing_info): Json<PairingInfo>,
 ) -> Result<Json<AuthResponse>, StatusCode> {
-    // For now, return a dummy API key
-    Ok(Json(AuthResponse {
-        api_key: "keepkey-vault-api-key".to_string(),
-    }))
-} 
\ No newline at end of file
+    let origin = super::cors::normalize_origin(&pairing_info.url)
+        .ok_or(StatusCode::BAD_REQUEST)?;
+
+    // Any local process can ask; only the user can say yes
+    if let Err(e) = request_approval(&state.app_handle, &pairing_info, &origin).await {
+        log::warn!("Pairing for {} ({}) not approved: {}", pairing_info.name, origin, e);
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // Paired apps are allowed to call the API from their origin
+    if let Err(e) = super::cors::add_allowed_origin(&pairing_info.url) {
+        log::warn!("Pairing for {} did not update CORS allowlist: {}", pairing_info.name, e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    // Each paired origin gets its own key, kept in the OS keychain
+    let api_key = match pairing_key(&origin) {
+        Ok(Some(key)) => key,
+        Ok(None) => {
+            let key = uuid::Uuid::new_v4().simple().to_string();
+            crate::secrets::set_secret(&secret_name(&origin), &key).map_err(|e| {
+                log::error!("Failed to store pairing key for {}: {}", origin, e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+            PAIRING_KEYS.lock().unwrap().insert(origin.clone(), key.clone());
+            log::info!("🔑 Paired {} ({})", pairing_info.name, origin);
+            key
+        }
+        Err(e) => {
+            log::error!("Failed to read pairing key for {}: {}", origin, e);
+            return Err(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+
+    Ok(Json(AuthResponse { api_key }))
+}
+
+/// Ask the user to approve a pairing in the vault and wait for the answer
+async fn request_approval(app: &tauri::AppHandle, pairing_info: &PairingInfo, origin: &str) -> Result<(), String> {
+    let request_id = uuid::Uuid::new_v4().to_string();
+    let (tx, rx) = oneshot::channel();
+    PENDING_PAIRINGS.lock().unwrap().insert(request_id.clone(), tx);
+
+    let payload = serde_json::json!({
+        "requestId": request_id,
+        "name": pairing_info.name,
+        "origin": origin,
+        "imageUrl": pairing_info.image_url,
+        "expiresInSecs": APPROVAL_TIMEOUT.as_secs(),
+    });
+    if let Err(e) = crate::commands::emit_or_queue_event(app, "pairing:approval-requested", payload).await {
+        PENDING_PAIRINGS.lock().unwrap().remove(&request_id);
+        return Err(format!("Failed to request pairing approval: {}", e));
+    }
+
+    let result = tokio::time::timeout(APPROVAL_TIMEOUT, rx).await;
+    PENDING_PAIRINGS.lock().unwrap().remove(&request_id);
+
+    match result {
+        Ok(Ok(true)) => Ok(()),
+        Ok(Ok(false)) => Err("Pairing was rejected".to_string()),
+        Ok(Err(_)) => Err("Pairing approval was cancelled".to_string()),
+        Err(_) => Err("Pairing approval timed out".to_string()),
+    }
+}
+
+/// Deliver the user's decision for a pending pairing request
+pub fn respond(request_id: &str, approved: bool) -> Result<(), String> {
+    let sender = PENDING_PAIRINGS
+        .lock()
+        .unwrap()
+        .remove(request_id)
+        .ok_or_else(|| format!("No pending pairing request {}", request_id))?;
+    sender
+        .send(approved)
+        .map_err(|_| format!("Pairing request {} is no longer waiting for approval", request_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keys_match() {
+        assert!(keys_match("0123abcd", "0123abcd"));
+        assert!(!keys_match("0123abcd", "0123abce"));
+        assert!(!keys_match("0123abcd", "0123abc"));
+        assert!(!keys_match("0123abcd", ""));
+    }
+}
@@ -0,0 +1,108 @@
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::Arc;
+use axum::{
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::device::watchdog::QueueWorkerConfig;
+use crate::server::ServerState;
+
+/// Signing endpoints that queue work on the device
+const SIGNING_PREFIXES: &[&str] = &[
+    "/utxo/sign-transaction",
+    "/eth/sign",
+    "/cosmos/sign-amino",
+    "/transactions/batch-sign",
+];
+
+/// Rough time a queued signing request holds the device, for Retry-After
+const SECS_PER_QUEUED_COMMAND: u64 = 5;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct QueueFullResponse {
+    error: String,
+    code: String,
+    device_id: String,
+    /// Position the request would have taken in the queue
+    queue_position: u32,
+    max_queue_depth: u32,
+}
+
+/// Configured queue depth limit, kept current by [`init`]
+static MAX_QUEUE_DEPTH: AtomicU32 = AtomicU32::new(0);
+
+fn is_signing_path(path: &str) -> bool {
+    SIGNING_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+fn reload_max_depth() {
+    MAX_QUEUE_DEPTH.store(QueueWorkerConfig::from_preferences().max_queue_depth, Ordering::Relaxed);
+}
+
+/// Load the queue depth limit and reload it whenever the preference changes
+pub fn init() {
+    reload_max_depth();
+    let mut changes = crate::preferences::subscribe();
+    tokio::spawn(async move {
+        loop {
+            match changes.recv().await {
+                Ok(change) if change.key == crate::device::watchdog::PREFERENCE_KEY => reload_max_depth(),
+                Ok(_) => {}
+                Err(RecvError::Lagged(_)) => reload_max_depth(),
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Refuse signing requests with 429 while the device queue is at its configured depth
+pub async fn backpressure_middleware(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if !is_signing_path(request.uri().path()) {
+        return next.run(request).await;
+    }
+    let max_depth = MAX_QUEUE_DEPTH.load(Ordering::Relaxed);
+    if max_depth == 0 {
+        return next.run(request).await;
+    }
+
+    // Signing goes to a device that already has a queue worker; judge by the
+    // busiest one rather than enumerating USB devices on every request
+    let busiest = state
+        .device_queue_manager
+        .lock()
+        .await
+        .iter()
+        .map(|(device_id, handle)| (device_id.clone(), handle.health().in_flight))
+        .max_by_key(|(_, in_flight)| *in_flight);
+    let Some((device_id, depth)) = busiest else {
+        return next.run(request).await;
+    };
+
+    if depth >= max_depth {
+        log::warn!("🚧 Refusing signing request for {}: {} commands queued", device_id, depth);
+        let retry_after = (depth as u64 * SECS_PER_QUEUED_COMMAND).max(1);
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            [(header::RETRY_AFTER, retry_after.to_string())],
+            Json(QueueFullResponse {
+                error: format!("Device queue is full ({} requests waiting)", depth),
+                code: "QUEUE_FULL".to_string(),
+                device_id,
+                queue_position: depth + 1,
+                max_queue_depth: max_depth,
+            }),
+        ).into_response();
+    }
+
+    next.run(request).await
+}
@@ -0,0 +1,195 @@
+//! Batched read-only operations for MCP agents.
+//!
+//! The `run_plan` tool takes a list of steps and answers with every result
+//! at once, saving a chat round trip per step. The whole plan is validated
+//! before anything runs, and identical steps are answered once so a plan
+//! doesn't queue the same device request twice.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use axum::extract::State;
+use axum::Json;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::server::ServerState;
+
+/// Most steps accepted in one plan
+const MAX_STEPS: usize = 20;
+
+const OPERATIONS: &[&str] = &[
+    "list_devices",
+    "get_device_features",
+    "fetch_portfolio",
+    "derive_addresses",
+    "lookup_address",
+    "list_goals",
+];
+
+#[derive(Debug, Clone, Deserialize)]
+struct Step {
+    op: String,
+    #[serde(default)]
+    args: Value,
+}
+
+/// Tool definition listed by tools/list
+pub fn tool_definition() -> Value {
+    json!({
+        "name": "run_plan",
+        "description": "Run several read-only operations in one call and return all results in order",
+        "inputSchema": {
+            "type": "object",
+            "properties": {
+                "steps": {
+                    "type": "array",
+                    "maxItems": MAX_STEPS,
+                    "items": {
+                        "type": "object",
+                        "properties": {
+                            "op": {
+                                "type": "string",
+                                "enum": OPERATIONS
+                            },
+                            "args": {
+                                "type": "object",
+                                "description": "fetch_portfolio: {device_id}; derive_addresses: {device_id, accountPath, coinName, scriptType, change, start, count}; lookup_address: {address, depth}"
+                            }
+                        },
+                        "required": ["op"]
+                    }
+                }
+            },
+            "required": ["steps"]
+        }
+    })
+}
+
+/// Parse and check every step before any of them runs
+fn parse_plan(arguments: &Value) -> Result<Vec<Step>, String> {
+    let steps: Vec<Step> = serde_json::from_value(arguments.get("steps").cloned().unwrap_or(Value::Null))
+        .map_err(|e| format!("Invalid plan: {}", e))?;
+    if steps.is_empty() {
+        return Err("The plan has no steps".to_string());
+    }
+    if steps.len() > MAX_STEPS {
+        return Err(format!("At most {} steps per plan", MAX_STEPS));
+    }
+    if let Some(step) = steps.iter().find(|step| !OPERATIONS.contains(&step.op.as_str())) {
+        return Err(format!("Unsupported operation in plan: {}", step.op));
+    }
+    Ok(steps)
+}
+
+fn device_id_argument(args: &Value) -> Result<String, String> {
+    crate::server::prompts::resolve_device_id(args).ok_or_else(|| "No KeepKey device connected".to_string())
+}
+
+/// Balances of every cached Bitcoin account of a device
+async fn fetch_portfolio(state: &ServerState, args: &Value) -> Result<Value, String> {
+    let device_id = device_id_argument(args)?;
+    let cache = crate::commands::get_cache_manager(&state.cache_manager).await?;
+    let names = crate::accounts::names(&cache, &device_id).await;
+    let accounts: Vec<(String, String)> = cache
+        .list_cached_pubkeys(&device_id)
+        .await
+        .map_err(|e| format!("Failed to read cached accounts: {}", e))?
+        .into_iter()
+        .filter(|pubkey| pubkey.coin_name.eq_ignore_ascii_case("bitcoin"))
+        .filter_map(|pubkey| pubkey.xpub.map(|xpub| (xpub, pubkey.derivation_path)))
+        .collect();
+    let keys: Vec<String> = accounts.iter().map(|(xpub, _)| xpub.clone()).collect();
+    if keys.is_empty() {
+        return Err(format!("No cached Bitcoin accounts for device {}", device_id));
+    }
+
+    let balances = crate::utxos::xpub_balances(crate::utxos::XpubBalanceRequest { keys, verify: None }).await?;
+    let total: u64 = balances.iter().map(|b| b.balance).sum();
+    let balances: Vec<Value> = balances
+        .into_iter()
+        .map(|balance| {
+            let name = accounts
+                .iter()
+                .find(|(xpub, _)| *xpub == balance.key)
+                .and_then(|(_, path)| names.get(path));
+            let mut entry = json!(balance);
+            entry["name"] = json!(name);
+            entry
+        })
+        .collect();
+    let mismatches = balances
+        .iter()
+        .filter(|b| b["reconciliation"]["mismatch"] == json!(true))
+        .count();
+    Ok(json!({ "deviceId": device_id, "totalSats": total, "mismatches": mismatches, "accounts": balances }))
+}
+
+async fn run_step(state: &Arc<ServerState>, step: &Step) -> Result<Value, String> {
+    match step.op.as_str() {
+        "list_devices" => crate::server::routes::api_list_devices(State(state.clone()))
+            .await
+            .map(|Json(devices)| json!(devices))
+            .map_err(|status| format!("Failed to list devices ({})", status)),
+        "get_device_features" => crate::server::routes::api_get_features(State(state.clone()))
+            .await
+            .map(|Json(features)| json!(features))
+            .map_err(|status| format!("Failed to get device features ({})", status)),
+        "fetch_portfolio" => fetch_portfolio(state, &step.args).await,
+        "derive_addresses" => {
+            let device_id = device_id_argument(&step.args)?;
+            let request: crate::derivation::DeriveAddressesRequest = serde_json::from_value(step.args.clone())
+                .map_err(|e| format!("Invalid derive_addresses arguments: {}", e))?;
+            let cache = crate::commands::get_cache_manager(&state.cache_manager).await?;
+            crate::derivation::derive_addresses(&cache, &device_id, &request).await.map(|addresses| json!(addresses))
+        }
+        "lookup_address" => {
+            let address = step.args.get("address").and_then(|v| v.as_str()).unwrap_or_default();
+            let depth = step.args.get("depth").and_then(|v| v.as_u64()).map(|d| d as u32);
+            let cache = crate::commands::get_cache_manager(&state.cache_manager).await?;
+            crate::derivation::lookup_address(&cache, address, depth).await.map(|matches| json!(matches))
+        }
+        "list_goals" => {
+            let cache = crate::commands::get_cache_manager(&state.cache_manager).await?;
+            crate::goals::list(&cache).await.map(|goals| json!(goals))
+        }
+        other => Err(format!("Unsupported operation in plan: {}", other)),
+    }
+}
+
+/// Run a plan; a failing step is reported in its slot and doesn't stop the others
+pub async fn run_plan(state: &Arc<ServerState>, arguments: &Value) -> Result<Value, String> {
+    let steps = parse_plan(arguments)?;
+
+    let mut answered: HashMap<String, Value> = HashMap::new();
+    let mut results = Vec::with_capacity(steps.len());
+    for step in &steps {
+        let key = format!("{}:{}", step.op, step.args);
+        let outcome = match answered.get(&key) {
+            Some(outcome) => outcome.clone(),
+            None => {
+                let outcome = match run_step(state, step).await {
+                    Ok(result) => json!({ "op": step.op, "ok": true, "result": result }),
+                    Err(error) => json!({ "op": step.op, "ok": false, "error": error }),
+                };
+                answered.insert(key, outcome.clone());
+                outcome
+            }
+        };
+        results.push(outcome);
+    }
+    Ok(json!({ "results": results }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plan() {
+        let plan = json!({ "steps": [{ "op": "list_devices" }, { "op": "lookup_address", "args": { "address": "bc1q" } }] });
+        assert_eq!(parse_plan(&plan).unwrap().len(), 2);
+        assert!(parse_plan(&json!({ "steps": [] })).is_err());
+        assert!(parse_plan(&json!({ "steps": [{ "op": "list_devices" }, { "op": "wipe_device" }] })).is_err());
+        assert!(parse_plan(&json!({})).is_err());
+    }
+}
@@ -0,0 +1,75 @@
+use axum::{
+    extract::Request,
+    http::HeaderValue,
+    middleware::Next,
+    response::Response,
+};
+
+/// Header carrying the correlation ID in both directions
+pub const CORRELATION_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied correlation ID we accept
+const MAX_CORRELATION_ID_LEN: usize = 64;
+
+tokio::task_local! {
+    static CORRELATION_ID: String;
+}
+
+/// Correlation ID of the REST request being handled, if any
+pub fn current() -> Option<String> {
+    CORRELATION_ID.try_with(|id| id.clone()).ok()
+}
+
+/// Request ID for device queue, cache and log calls: the correlation ID when
+/// called from a REST handler, otherwise a fresh one
+pub fn request_id() -> String {
+    current().unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+/// Accept a client-supplied ID only if it's short and log-safe
+fn sanitize(incoming: &str) -> Option<String> {
+    let valid = !incoming.is_empty()
+        && incoming.len() <= MAX_CORRELATION_ID_LEN
+        && incoming.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    valid.then(|| incoming.to_string())
+}
+
+/// Assign every request a correlation ID, run the handler inside its scope,
+/// and echo it back in the response
+pub async fn correlation_middleware(request: Request, next: Next) -> Response {
+    let id = request.headers()
+        .get(CORRELATION_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(sanitize)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+
+    let mut response = CORRELATION_ID.scope(id.clone(), next.run(request)).await;
+
+    if response.status().is_server_error() {
+        log::warn!("[{}] {} {} -> {}", id, method, path, response.status());
+    } else {
+        log::debug!("[{}] {} {} -> {}", id, method, path, response.status());
+    }
+
+    if let Ok(value) = HeaderValue::from_str(&id) {
+        response.headers_mut().insert(CORRELATION_HEADER, value);
+    }
+
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize("abc-123_x.y"), Some("abc-123_x.y".to_string()));
+        assert!(sanitize("").is_none());
+        assert!(sanitize("bad id\n").is_none());
+        assert!(sanitize(&"a".repeat(65)).is_none());
+    }
+}
@@ -0,0 +1,159 @@
+use std::sync::RwLock;
+use once_cell::sync::Lazy;
+use axum::http::{HeaderValue, request::Parts};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+
+/// Preference key holding the origin allowlist (JSON array of strings)
+const CORS_PREFERENCE_KEY: &str = "cors_allowed_origins";
+
+/// Known KeepKey origins allowed when no allowlist has been configured
+pub const DEFAULT_ALLOWED_ORIGINS: &[&str] = &[
+    "https://keepkey.com",
+    "https://*.keepkey.com",
+    "http://localhost:1420",
+    "http://localhost:8080",
+    "http://127.0.0.1:8080",
+    "tauri://localhost",
+    "http://tauri.localhost",
+    "https://tauri.localhost",
+];
+
+/// In-memory copy of the allowlist so the CORS check doesn't hit the disk per request
+static ALLOWED_ORIGINS: Lazy<RwLock<Vec<String>>> = Lazy::new(|| RwLock::new(load_allowed_origins()));
+
+/// Read the allowlist from preferences, falling back to the defaults
+fn load_allowed_origins() -> Vec<String> {
+    crate::preferences::get_as::<Vec<String>>(CORS_PREFERENCE_KEY)
+        .unwrap_or_else(|| DEFAULT_ALLOWED_ORIGINS.iter().map(|s| s.to_string()).collect())
+}
+
+/// Persist the allowlist to preferences and refresh the in-memory copy
+fn save_allowed_origins(origins: Vec<String>) -> Result<Vec<String>, String> {
+    crate::preferences::set(CORS_PREFERENCE_KEY, serde_json::json!(origins))?;
+
+    let mut allowed = ALLOWED_ORIGINS.write()
+        .map_err(|_| "Failed to lock CORS allowlist".to_string())?;
+    *allowed = origins.clone();
+
+    Ok(origins)
+}
+
+/// Normalize an origin or URL into `scheme://host[:port]` form
+pub fn normalize_origin(value: &str) -> Option<String> {
+    let value = value.trim().trim_end_matches('/');
+
+    // Wildcard entries are kept verbatim (url can't parse "*." hosts)
+    if value.contains("://*.") {
+        return Some(value.to_lowercase());
+    }
+
+    let parsed = url::Url::parse(value).ok()?;
+    match parsed.origin() {
+        url::Origin::Tuple(..) => Some(parsed.origin().ascii_serialization()),
+        // Custom schemes like tauri://localhost have opaque origins
+        url::Origin::Opaque(_) => parsed.host_str()
+            .map(|host| format!("{}://{}", parsed.scheme(), host)),
+    }
+}
+
+/// Check a single allowlist entry against a request origin
+fn origin_matches(entry: &str, origin: &str) -> bool {
+    if let Some((scheme, host_pattern)) = entry.split_once("://*.") {
+        // "https://*.keepkey.com" matches any subdomain over the same scheme
+        match origin.split_once("://") {
+            Some((origin_scheme, origin_host)) => {
+                origin_scheme == scheme && origin_host.ends_with(&format!(".{}", host_pattern))
+            }
+            None => false,
+        }
+    } else {
+        entry == origin
+    }
+}
+
+/// Check whether an origin is on the allowlist
+pub fn is_origin_allowed(origin: &str) -> bool {
+    let origin = origin.trim_end_matches('/').to_lowercase();
+    ALLOWED_ORIGINS.read()
+        .map(|allowed| allowed.iter().any(|entry| origin_matches(entry, &origin)))
+        .unwrap_or(false)
+}
+
+/// Whether an origin is one of the built-in KeepKey origins, which need no pairing
+pub fn is_builtin_origin(origin: &str) -> bool {
+    let origin = origin.trim_end_matches('/').to_lowercase();
+    DEFAULT_ALLOWED_ORIGINS.iter().any(|entry| origin_matches(entry, &origin))
+}
+
+/// Get the current allowlist
+pub fn get_allowed_origins() -> Vec<String> {
+    ALLOWED_ORIGINS.read()
+        .map(|allowed| allowed.clone())
+        .unwrap_or_default()
+}
+
+/// Add an origin (or URL, from which the origin is extracted) to the allowlist
+pub fn add_allowed_origin(value: &str) -> Result<Vec<String>, String> {
+    let origin = normalize_origin(value)
+        .ok_or_else(|| format!("Invalid origin: {}", value))?;
+
+    let mut origins = get_allowed_origins();
+    if !origins.contains(&origin) {
+        log::info!("🌐 Adding CORS origin: {}", origin);
+        origins.push(origin);
+    }
+
+    save_allowed_origins(origins)
+}
+
+/// Remove an origin from the allowlist
+pub fn remove_allowed_origin(value: &str) -> Result<Vec<String>, String> {
+    let origin = normalize_origin(value).unwrap_or_else(|| value.to_string());
+
+    let origins = get_allowed_origins()
+        .into_iter()
+        .filter(|o| o != &origin)
+        .collect();
+
+    save_allowed_origins(origins)
+}
+
+/// Restore the default KeepKey origins
+pub fn reset_allowed_origins() -> Result<Vec<String>, String> {
+    save_allowed_origins(DEFAULT_ALLOWED_ORIGINS.iter().map(|s| s.to_string()).collect())
+}
+
+/// Build the CORS layer for the REST server from the allowlist
+pub fn cors_layer() -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::predicate(|origin: &HeaderValue, _parts: &Parts| {
+            origin.to_str().map(is_origin_allowed).unwrap_or(false)
+        }))
+        .allow_methods(tower_http::cors::Any)
+        .allow_headers(tower_http::cors::Any)
+        .expose_headers([axum::http::HeaderName::from_static(super::correlation::CORRELATION_HEADER)])
+        .allow_credentials(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_matches() {
+        assert!(origin_matches("https://keepkey.com", "https://keepkey.com"));
+        assert!(origin_matches("https://*.keepkey.com", "https://vault.keepkey.com"));
+        assert!(!origin_matches("https://*.keepkey.com", "http://vault.keepkey.com"));
+        assert!(!origin_matches("https://*.keepkey.com", "https://evilkeepkey.com"));
+        assert!(!origin_matches("https://keepkey.com", "https://keepkey.com.evil.io"));
+    }
+
+    #[test]
+    fn test_normalize_origin() {
+        assert_eq!(normalize_origin("https://vault.keepkey.com/some/path").as_deref(), Some("https://vault.keepkey.com"));
+        assert_eq!(normalize_origin("http://localhost:1420/").as_deref(), Some("http://localhost:1420"));
+        assert_eq!(normalize_origin("tauri://localhost").as_deref(), Some("tauri://localhost"));
+        assert_eq!(normalize_origin("https://*.keepkey.com").as_deref(), Some("https://*.keepkey.com"));
+        assert_eq!(normalize_origin("not a url"), None);
+    }
+}
@@ -0,0 +1,115 @@
+use axum::{
+    extract::Request,
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+    routing::get,
+    Router,
+};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tower_http::cors::{AllowOrigin, CorsLayer};
+use utoipa_swagger_ui::{Config, SwaggerUi};
+
+use crate::server::api::addresses::ErrorResponse;
+
+/// Requests the docs scope serves per window, across all clients
+const DOCS_RATE_LIMIT: u32 = 120;
+
+/// Length of the docs rate limit window
+const DOCS_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// Fixed-window request counter
+struct RateWindow {
+    started: Instant,
+    count: u32,
+}
+
+impl RateWindow {
+    /// Count a request at `now`; false once the window's budget is spent
+    fn admit(&mut self, now: Instant, limit: u32, window: Duration) -> bool {
+        if now.duration_since(self.started) >= window {
+            self.started = now;
+            self.count = 0;
+        }
+        if self.count >= limit {
+            return false;
+        }
+        self.count += 1;
+        true
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref DOCS_WINDOW: Mutex<RateWindow> = Mutex::new(RateWindow {
+        started: Instant::now(),
+        count: 0,
+    });
+}
+
+/// Reject anything but reads, and throttle reads, on the docs scope
+async fn read_only_rate_limited(request: Request, next: Next) -> Response {
+    if !matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return (
+            StatusCode::METHOD_NOT_ALLOWED,
+            Json(ErrorResponse::new("API documentation is read-only", "METHOD_NOT_ALLOWED")),
+        ).into_response();
+    }
+
+    let admitted = DOCS_WINDOW
+        .lock()
+        .map(|mut w| w.admit(Instant::now(), DOCS_RATE_LIMIT, DOCS_RATE_WINDOW))
+        .unwrap_or(true);
+    if !admitted {
+        return (
+            StatusCode::TOO_MANY_REQUESTS,
+            Json(ErrorResponse::new("Too many documentation requests, try again shortly", "RATE_LIMITED")),
+        ).into_response();
+    }
+
+    next.run(request).await
+}
+
+/// Docs are public: any origin may read them, nobody may write
+fn docs_cors_layer() -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(AllowOrigin::any())
+        .allow_methods([Method::GET, Method::HEAD])
+        .allow_headers(tower_http::cors::Any)
+        .allow_credentials(false)
+}
+
+/// Swagger UI and OpenAPI JSON, served outside the API's CORS allowlist and
+/// auth so the API surface is always discoverable
+pub fn docs_router(openapi: utoipa::openapi::OpenApi) -> Router {
+    // Serve the spec ourselves so endpoints disabled at runtime drop out of it
+    let spec = move || {
+        let openapi = openapi.clone();
+        async move { Json(super::endpoint_flags::filter_openapi(openapi)) }
+    };
+
+    Router::new()
+        .merge(SwaggerUi::new("/docs").config(Config::new(["/api-docs/openapi.json"])))
+        .route("/api-docs/openapi.json", get(spec.clone()))
+        // Compatibility route for Pioneer SDK kkapi detection
+        .route("/spec/swagger.json", get(spec))
+        .layer(axum::middleware::from_fn(read_only_rate_limited))
+        .layer(axum::middleware::from_fn(super::correlation::correlation_middleware))
+        .layer(docs_cors_layer())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rate_window_resets() {
+        let start = Instant::now();
+        let mut window = RateWindow { started: start, count: 0 };
+
+        assert!(window.admit(start, 2, Duration::from_secs(60)));
+        assert!(window.admit(start, 2, Duration::from_secs(60)));
+        assert!(!window.admit(start + Duration::from_secs(30), 2, Duration::from_secs(60)));
+        assert!(window.admit(start + Duration::from_secs(61), 2, Duration::from_secs(60)));
+    }
+}
@@ -0,0 +1,135 @@
+use axum::{
+    extract::Request,
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::server::api::addresses::ErrorResponse;
+
+/// Preference key holding the disabled endpoint groups
+const PREFERENCE_KEY: &str = "endpoint_flags";
+
+/// Groups of REST endpoints that can be switched off at runtime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EndpointGroup {
+    Bitcoin,
+    Ethereum,
+    Cosmos,
+    Binance,
+    Ripple,
+    Mcp,
+}
+
+impl EndpointGroup {
+    pub const ALL: [EndpointGroup; 6] = [
+        EndpointGroup::Bitcoin,
+        EndpointGroup::Ethereum,
+        EndpointGroup::Cosmos,
+        EndpointGroup::Binance,
+        EndpointGroup::Ripple,
+        EndpointGroup::Mcp,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EndpointGroup::Bitcoin => "bitcoin",
+            EndpointGroup::Ethereum => "ethereum",
+            EndpointGroup::Cosmos => "cosmos",
+            EndpointGroup::Binance => "binance",
+            EndpointGroup::Ripple => "ripple",
+            EndpointGroup::Mcp => "mcp",
+        }
+    }
+}
+
+/// Path prefixes belonging to each group
+const GROUP_PREFIXES: &[(&str, EndpointGroup)] = &[
+    ("/addresses/utxo", EndpointGroup::Bitcoin),
+    ("/utxo/", EndpointGroup::Bitcoin),
+    ("/api/utxos", EndpointGroup::Bitcoin),
+    ("/api/balances", EndpointGroup::Bitcoin),
+    ("/addresses/eth", EndpointGroup::Ethereum),
+    ("/eth/", EndpointGroup::Ethereum),
+    ("/addresses/cosmos", EndpointGroup::Cosmos),
+    ("/addresses/osmosis", EndpointGroup::Cosmos),
+    ("/addresses/tendermint", EndpointGroup::Cosmos),
+    ("/addresses/thorchain", EndpointGroup::Cosmos),
+    ("/addresses/mayachain", EndpointGroup::Cosmos),
+    ("/cosmos/", EndpointGroup::Cosmos),
+    ("/addresses/bnb", EndpointGroup::Binance),
+    ("/addresses/xrp", EndpointGroup::Ripple),
+    ("/mcp", EndpointGroup::Mcp),
+];
+
+/// Group a request path belongs to, if any
+pub fn group_for_path(path: &str) -> Option<EndpointGroup> {
+    GROUP_PREFIXES
+        .iter()
+        .find(|(prefix, _)| path.starts_with(prefix))
+        .map(|(_, group)| *group)
+}
+
+/// Endpoint groups currently disabled in preferences
+pub fn disabled_groups() -> Vec<EndpointGroup> {
+    crate::preferences::get(PREFERENCE_KEY)
+        .get("disabled")
+        .cloned()
+        .and_then(|value| serde_json::from_value(value).ok())
+        .unwrap_or_default()
+}
+
+/// Replace the set of disabled endpoint groups
+pub fn set_disabled_groups(mut groups: Vec<EndpointGroup>) -> Result<Vec<EndpointGroup>, String> {
+    groups.sort_by_key(|g| g.as_str());
+    groups.dedup();
+
+    crate::preferences::set(PREFERENCE_KEY, serde_json::json!({ "disabled": groups }))?;
+
+    log::info!("🚦 Disabled endpoint groups: {:?}", groups);
+    Ok(groups)
+}
+
+/// Answer requests to disabled endpoint groups with a structured 404
+pub async fn endpoint_flags_middleware(request: Request, next: Next) -> Response {
+    if let Some(group) = group_for_path(request.uri().path()) {
+        if disabled_groups().contains(&group) {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ErrorResponse::new(
+                    format!("{} endpoints are disabled on this vault", group.as_str()),
+                    "ENDPOINT_DISABLED",
+                )),
+            ).into_response();
+        }
+    }
+
+    next.run(request).await
+}
+
+/// Drop disabled endpoints from an OpenAPI document
+pub fn filter_openapi(mut openapi: utoipa::openapi::OpenApi) -> utoipa::openapi::OpenApi {
+    let disabled = disabled_groups();
+    if !disabled.is_empty() {
+        openapi.paths.paths.retain(|path, _| {
+            group_for_path(path).map_or(true, |group| !disabled.contains(&group))
+        });
+    }
+    openapi
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_group_for_path() {
+        assert_eq!(group_for_path("/addresses/utxo"), Some(EndpointGroup::Bitcoin));
+        assert_eq!(group_for_path("/eth/signTransaction"), Some(EndpointGroup::Ethereum));
+        assert_eq!(group_for_path("/addresses/cosmos-chain"), Some(EndpointGroup::Cosmos));
+        assert_eq!(group_for_path("/system/ping"), None);
+        assert_eq!(group_for_path("/api/health"), None);
+    }
+}
@@ -0,0 +1,141 @@
+use std::sync::Arc;
+use axum::{
+    body::{to_bytes, Body, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use sha2::{Digest, Sha256};
+
+use crate::server::ServerState;
+
+/// Polled endpoints whose responses get an ETag
+const ETAG_PREFIXES: &[&str] = &[
+    "/api/devices",
+    "/api/wallets",
+    "/api/utxos",
+    "/api/timeline",
+    "/api/errors",
+    "/api/cache",
+    "/api/portfolio",
+];
+
+/// Bodies larger than this are passed through untagged
+const MAX_TAGGED_BODY: usize = 8 * 1024 * 1024;
+
+fn is_tagged_path(path: &str) -> bool {
+    ETAG_PREFIXES.iter().any(|prefix| path.starts_with(prefix))
+}
+
+/// Weak validator from the body; identical JSON yields the same tag
+fn etag_for(body: &[u8]) -> String {
+    format!("W/\"{}\"", &hex::encode(Sha256::digest(body))[..32])
+}
+
+/// When the data behind a path last changed, for endpoints served purely from
+/// the cache or the portfolio store; None means the body has to be hashed
+async fn last_updated(state: &ServerState, path: &str) -> Option<i64> {
+    if path.starts_with("/api/portfolio") {
+        return Some(crate::portfolio::last_updated());
+    }
+    if path.starts_with("/api/timeline") || path.starts_with("/api/errors") {
+        let cache = crate::commands::get_cache_manager(&state.cache_manager).await.ok()?;
+        return cache.last_write().await.ok();
+    }
+    None
+}
+
+/// Whether an If-None-Match header value covers the tag
+fn matches(if_none_match: &str, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    if_none_match.trim() == "*" || if_none_match.split(',').any(|tag| opaque(tag) == opaque(etag))
+}
+
+fn not_modified(etag: &str) -> Response {
+    let mut response = StatusCode::NOT_MODIFIED.into_response();
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    response
+}
+
+/// Tag successful GET responses and answer 304 when the client's copy is current
+pub async fn etag_middleware(
+    State(state): State<Arc<ServerState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.method() != Method::GET || !is_tagged_path(request.uri().path()) {
+        return next.run(request).await;
+    }
+    let if_none_match = request
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+
+    // Timestamp-backed endpoints are tagged without running the handler
+    if let Some(updated) = last_updated(&state, request.uri().path()).await {
+        let etag = etag_for(format!("{}@{}", request.uri(), updated).as_bytes());
+        if if_none_match.as_deref().is_some_and(|inm| matches(inm, &etag)) {
+            return not_modified(&etag);
+        }
+        let mut response = next.run(request).await;
+        if response.status() == StatusCode::OK {
+            if let Ok(value) = HeaderValue::from_str(&etag) {
+                response.headers_mut().insert(header::ETAG, value);
+            }
+        }
+        return response;
+    }
+
+    let response = next.run(request).await;
+    if response.status() != StatusCode::OK {
+        return response;
+    }
+
+    // Streamed or oversized bodies are passed through untagged
+    let within_limit = response
+        .body()
+        .size_hint()
+        .upper()
+        .is_some_and(|size| size <= MAX_TAGGED_BODY as u64);
+    if !within_limit {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let bytes = match to_bytes(body, MAX_TAGGED_BODY).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            log::warn!("Failed to buffer response for ETag: {}", e);
+            return (StatusCode::INTERNAL_SERVER_ERROR, "Failed to read response").into_response();
+        }
+    };
+
+    let etag = etag_for(&bytes);
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        parts.headers.insert(header::ETAG, value);
+    }
+
+    if if_none_match.as_deref().is_some_and(|inm| matches(inm, &etag)) {
+        return not_modified(&etag);
+    }
+
+    Response::from_parts(parts, Body::from(bytes))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches() {
+        let etag = etag_for(b"[]");
+        assert!(matches(&etag, &etag));
+        assert!(matches(&format!("\"other\", {}", etag.trim_start_matches("W/")), &etag));
+        assert!(matches("*", &etag));
+        assert!(!matches(&etag_for(b"[1]"), &etag));
+    }
+}
@@ -3,20 +3,27 @@ pub mod context;
 pub mod auth;
 pub mod api;
 pub mod proxy;
+pub mod tls;
+pub mod cors;
+pub mod prompts;
+pub mod batch;
+pub mod correlation;
+pub mod docs;
+pub mod endpoint_flags;
+pub mod readiness;
+pub mod etag;
+pub mod backpressure;
 
 use axum::{
     Router,
     serve,
-    routing::{get, post},
-    response::Json,
+    routing::{get, post, put, delete},
 };
 
 use tokio::net::TcpListener;
-use tower_http::cors::CorsLayer;
 use tracing::info;
 use std::sync::Arc;
 use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
 use tauri::Emitter;
 
 pub struct ServerState {
@@ -34,6 +41,7 @@ pub struct ServerState {
         // routes::api_set_context,
         // routes::api_clear_context,
         routes::api_list_devices,
+        routes::api_get_device_state,
         routes::api_get_features,
         routes::mcp_handle,
         auth::auth_verify,
@@ -47,22 +55,63 @@ pub struct ServerState {
         api::addresses::tendermint_get_address,
         api::addresses::mayachain_get_address,
         api::addresses::xrp_get_address,
+        api::addresses::cosmos_chain_get_address,
+        api::addresses::ownership_proof,
+        api::addresses::reserves_report,
+        api::addresses::testnet_get_address,
+        api::addresses::lookup_address,
         api::system::system_ping,
+        api::system::get_capabilities,
+        api::firmware::get_firmware_releases,
         api::system::get_entropy,
         api::system::get_public_key,
         api::system::apply_settings,
+        api::system::set_language,
+        api::system::set_auto_lock_delay,
+        api::system::set_passphrase_protection,
         api::system::clear_session,
         api::system::wipe_device,
         api::system::exit_application,
+        api::system::prometheus_metrics,
         api::transactions::utxo_sign_transaction,
         api::transactions::eth_sign_transaction,
         api::transactions::eth_sign_message,
+        api::transactions::eth_verify_signature,
         api::transactions::cosmos_sign_amino,
+        api::transactions::batch_sign_transactions,
+        api::payments::parse_payment_uri,
+        api::payments::create_payment_intent,
+        api::payments::get_payment_intent,
+        api::payments::confirm_payment_intent,
+        api::payments::cancel_payment_intent,
+        api::wallets::list_wallets,
+        api::wallets::get_wallet,
+        api::wallets::save_wallet,
+        api::wallets::delete_wallet,
+        api::wallets::list_duplicate_seeds,
+        api::utxos::list_utxos,
+        api::utxos::tag_utxo,
+        api::utxos::xpub_balances,
+        api::cache::trigger_frontload,
+        api::cache::frontload_status,
+        api::errors::list_errors,
+        api::errors::clear_errors,
+        api::timeline::get_timeline,
+        api::providers::get_provider_usage,
+        api::goals::list_goals,
+        api::goals::create_goal,
+        api::goals::delete_goal,
+        api::accounts::list_account_names,
+        api::accounts::set_account_name,
+        api::accounts::delete_account_name,
+        api::portfolio::refresh_portfolio,
+        api::portfolio::get_portfolio,
     ),
     components(
         schemas(
             routes::HealthResponse,
             routes::DeviceInfo,
+            routes::DeviceState,
             routes::KeepKeyInfo,
             routes::Features,
             // Context schemas - commented out until needed
@@ -75,26 +124,92 @@ pub struct ServerState {
             api::addresses::AddressRequest,
             api::addresses::AddressResponse,
             api::addresses::UtxoAddressRequest,
+            api::addresses::CosmosChainAddressRequest,
+            crate::device::cosmos_chains::CosmosChain,
+            api::addresses::OwnershipProofRequest,
+            api::addresses::TestnetAddressRequest,
+            api::addresses::TestnetAddressResponse,
+            crate::device::testnet::TestnetNetwork,
+            crate::device::ownership_proof::ProofChain,
+            crate::device::ownership_proof::ProofTarget,
+            crate::device::ownership_proof::AddressProof,
+            crate::device::ownership_proof::OwnershipProofBundle,
+            crate::reserves::ReservesReportRequest,
+            crate::reserves::ReservesReport,
+            crate::reserves::AddressReserve,
             api::system::PingRequest,
             api::system::PingResponse,
             api::system::GetEntropyRequest,
             api::system::GetEntropyResponse,
             api::system::GetPublicKeyRequest,
             api::system::GetPublicKeyResponse,
+            api::system::PublicKeyFormat,
             api::system::ApplySettingsRequest,
             api::system::ApplySettingsResponse,
+            api::system::LanguageSettingRequest,
+            api::system::AutoLockSettingRequest,
+            api::system::PassphraseSettingRequest,
+            crate::device::settings_verify::SettingsSnapshot,
             api::system::ClearSessionResponse,
             api::system::WipeDeviceResponse,
+            crate::device::capabilities::DeviceCapabilities,
+            crate::device::releases::FirmwareCatalog,
+            crate::device::releases::FirmwareRelease,
+            crate::device::releases::BootloaderRelease,
             api::transactions::UtxoSignTransactionRequest,
             api::transactions::UtxoSignTransactionResponse,
             api::transactions::EthSignTransactionRequest,
             api::transactions::EthSignTransactionResponse,
             api::transactions::EthSignMessageRequest,
             api::transactions::EthSignMessageResponse,
+            api::transactions::EthVerifyRequest,
+            crate::eth_signatures::Verification,
             api::transactions::CosmosSignAminoRequest,
             api::transactions::CosmosSignAminoResponse,
+            api::transactions::BatchTransaction,
+            api::transactions::BatchSignRequest,
+            api::transactions::BatchItemResult,
+            api::transactions::BatchSignResponse,
+            crate::device::batch_signing::BatchItemSummary,
+            api::payments::ParsePaymentUriRequest,
+            crate::payment_uri::PaymentIntent,
+            api::payments::ConfirmPaymentIntentRequest,
+            crate::rate_lock::CreatePaymentIntent,
+            crate::rate_lock::RateCheck,
+            crate::cache::PendingPaymentIntent,
             crate::commands::BitcoinUtxoInput,
             crate::commands::BitcoinUtxoOutput,
+            crate::wallets::Wallet,
+            crate::wallets::WatchOnlyXpub,
+            crate::wallets::WalletSummary,
+            crate::wallets::WalletDevice,
+            crate::wallets::WalletAccount,
+            crate::device::duplicate_seeds::DuplicateSeedGroup,
+            crate::utxos::Utxo,
+            crate::utxos::UtxoTagUpdate,
+            crate::utxos::XpubBalanceRequest,
+            crate::utxos::XpubBalance,
+            crate::reconciliation::BalanceReconciliation,
+            crate::cache::UtxoTag,
+            api::cache::FrontloadRequest,
+            api::cache::FrontloadStarted,
+            crate::cache::CacheStatus,
+            crate::cache::types::FrontloadStatus,
+            crate::cache::ErrorRecord,
+            api::errors::ClearErrorsResponse,
+            crate::activity::TimelineItem,
+            crate::provider_usage::ProviderUsage,
+            crate::provider_usage::EndpointUsage,
+            crate::derivation::AddressMatch,
+            crate::cache::PortfolioGoal,
+            crate::goals::NewGoal,
+            crate::cache::AccountMetadata,
+            crate::accounts::AccountNameRequest,
+            crate::portfolio::RefreshRequest,
+            crate::portfolio::AssetBalance,
+            crate::portfolio::SyncState,
+            crate::portfolio::ChainSync,
+            crate::portfolio::PortfolioSnapshot,
         )
     ),
     tags(
@@ -103,7 +218,13 @@ pub struct ServerState {
         (name = "mcp", description = "Model Context Protocol endpoints"),
         (name = "auth", description = "Authentication and pairing endpoints"),
         (name = "addresses", description = "Address generation endpoints"),
-        (name = "Transaction", description = "Transaction signing endpoints")
+        (name = "Transaction", description = "Transaction signing endpoints"),
+        (name = "wallets", description = "Multi-device wallet grouping endpoints"),
+        (name = "utxos", description = "UTXO listing, labels and coin control"),
+        (name = "cache", description = "Pubkey cache frontload control"),
+        (name = "goals", description = "Portfolio savings goals"),
+        (name = "accounts", description = "Account names"),
+        (name = "portfolio", description = "Portfolio balances, sync state and refresh")
     ),
     info(
         title = "KeepKey Vault API",
@@ -122,6 +243,8 @@ pub async fn start_server(device_queue_manager: crate::commands::DeviceQueueMana
     // Try to initialize tracing, ignore if already initialized
     let _ = tracing_subscriber::fmt::try_init();
     
+    backpressure::init();
+
     // Create server state
     let server_state = Arc::new(ServerState {
         device_queue_manager,
@@ -129,23 +252,11 @@ pub async fn start_server(device_queue_manager: crate::commands::DeviceQueueMana
         cache_manager,
     });
     
-    // Create Swagger UI
-    let swagger_ui = SwaggerUi::new("/docs")
-        .url("/api-docs/openapi.json", ApiDoc::openapi());
-    
-    // Create a handler for the OpenAPI spec that returns the same JSON
-    let _openapi_spec = ApiDoc::openapi();
-    
     // Build the router
-    let app = Router::new()
+    let api = Router::new()
         // System endpoints
         .route("/api/health", get(routes::health_check))
         
-        // Add compatibility route for Pioneer SDK kkapi detection
-        .route("/spec/swagger.json", get(|| async move {
-            Json(ApiDoc::openapi())
-        }))
-        
         // Context endpoints - commented out until full device interaction is implemented
         // .route("/api/context", get(routes::api_get_context))
         // .route("/api/context", post(routes::api_set_context))
@@ -153,6 +264,9 @@ pub async fn start_server(device_queue_manager: crate::commands::DeviceQueueMana
         
         // Device management endpoints
         .route("/api/devices", get(routes::api_list_devices))
+        .route("/api/devices/duplicate-seeds", get(api::wallets::list_duplicate_seeds))
+        .route("/api/devices/:id/state", get(routes::api_get_device_state))
+        .route("/api/firmware/releases", get(api::firmware::get_firmware_releases))
         .route("/system/info/get-features", post(routes::api_get_features))
         
         // MCP endpoint - Model Context Protocol
@@ -172,46 +286,85 @@ pub async fn start_server(device_queue_manager: crate::commands::DeviceQueueMana
         .route("/addresses/tendermint", post(api::addresses::tendermint_get_address))
         .route("/addresses/mayachain", post(api::addresses::mayachain_get_address))
         .route("/addresses/xrp", post(api::addresses::xrp_get_address))
+        .route("/addresses/cosmos-chain", post(api::addresses::cosmos_chain_get_address))
+        .route("/addresses/ownership-proof", post(api::addresses::ownership_proof))
+        .route("/addresses/reserves-report", post(api::addresses::reserves_report))
+        .route("/addresses/testnet", post(api::addresses::testnet_get_address))
+        .route("/api/addresses/lookup", get(api::addresses::lookup_address))
         
         // System operation endpoints
         .route("/system/ping", post(api::system::system_ping))
+        .route("/system/info/capabilities", get(api::system::get_capabilities))
         .route("/system/info/get-entropy", post(api::system::get_entropy))
         .route("/system/info/get-public-key", post(api::system::get_public_key))
         .route("/system/settings/apply", post(api::system::apply_settings))
+        .route("/system/settings/language", post(api::system::set_language))
+        .route("/system/settings/auto-lock", post(api::system::set_auto_lock_delay))
+        .route("/system/settings/passphrase", post(api::system::set_passphrase_protection))
         .route("/system/clear-session", post(api::system::clear_session))
         .route("/system/wipe-device", post(api::system::wipe_device))
         .route("/system/exit", post(api::system::exit_application))
+        .route("/metrics", get(api::system::prometheus_metrics))
         
         // Transaction signing endpoints
         .route("/utxo/sign-transaction", post(api::transactions::utxo_sign_transaction))
         .route("/eth/signTransaction", post(api::transactions::eth_sign_transaction))
         .route("/eth/sign", post(api::transactions::eth_sign_message))
+        .route("/eth/verify", post(api::transactions::eth_verify_signature))
         .route("/cosmos/sign-amino", post(api::transactions::cosmos_sign_amino))
+        .route("/transactions/batch-sign", post(api::transactions::batch_sign_transactions))
+        .route("/api/parse-payment-uri", post(api::payments::parse_payment_uri))
+        .route("/api/payment-intents", post(api::payments::create_payment_intent))
+        .route("/api/payment-intents/:id", get(api::payments::get_payment_intent).delete(api::payments::cancel_payment_intent))
+        .route("/api/payment-intents/:id/confirm", post(api::payments::confirm_payment_intent))
+
+        // Wallet grouping endpoints
+        .route("/api/wallets", get(api::wallets::list_wallets).post(api::wallets::save_wallet))
+        .route("/api/wallets/:id", get(api::wallets::get_wallet).delete(api::wallets::delete_wallet))
+        .route("/api/utxos/:device_id", get(api::utxos::list_utxos))
+        .route("/api/utxos/:device_id/:txid/:vout", put(api::utxos::tag_utxo))
+        .route("/api/balances/xpubs", post(api::utxos::xpub_balances))
+        .route("/api/cache/frontload/:device_id", post(api::cache::trigger_frontload))
+        .route("/api/cache/frontload/:device_id/status", get(api::cache::frontload_status))
+        .route("/api/errors", get(api::errors::list_errors).delete(api::errors::clear_errors))
+        .route("/api/timeline/:device_id", get(api::timeline::get_timeline))
+        .route("/api/providers/:provider/usage", get(api::providers::get_provider_usage))
+        .route("/api/goals", get(api::goals::list_goals).post(api::goals::create_goal))
+        .route("/api/goals/:id", delete(api::goals::delete_goal))
+        .route("/api/accounts/names", get(api::accounts::list_account_names)
+            .put(api::accounts::set_account_name)
+            .delete(api::accounts::delete_account_name))
+        .route("/api/portfolio/refresh", post(api::portfolio::refresh_portfolio))
+        .route("/api/portfolio/:device_id", get(api::portfolio::get_portfolio))
         
-        // Merge swagger UI first
-        .merge(swagger_ui)
-        // Then add state and middleware
-        .with_state(server_state)
-        .layer(
-            CorsLayer::new()
-                // Allow any origin with wildcard
-                .allow_origin(axum::http::header::HeaderValue::from_static("*"))
-                // Allow all methods
-                .allow_methods(tower_http::cors::Any)
-                // Allow all headers
-                .allow_headers(tower_http::cors::Any)
-                // Note: credentials cannot be used with wildcard origin
-                .allow_credentials(false)
-        );
+        // Add state and middleware
+        .with_state(server_state.clone())
+        // Signing requests beyond the configured queue depth answer 429 + Retry-After
+        .layer(axum::middleware::from_fn_with_state(server_state.clone(), backpressure::backpressure_middleware))
+        // Polled endpoints answer 304 when the client's copy is current
+        .layer(axum::middleware::from_fn_with_state(server_state, etag::etag_middleware))
+        // Endpoint groups disabled in preferences answer 404
+        .layer(axum::middleware::from_fn(endpoint_flags::endpoint_flags_middleware))
+        // Endpoints whose dependencies are still starting answer 503 + Retry-After
+        .layer(axum::middleware::from_fn(readiness::readiness_middleware))
+        // Browser origins other than KeepKey's must send their pairing key
+        .layer(axum::middleware::from_fn(auth::auth_middleware))
+        // Tag every request with a correlation ID for end-to-end tracing
+        .layer(axum::middleware::from_fn(correlation::correlation_middleware))
+        // Only origins on the configurable allowlist get CORS headers
+        .layer(cors::cors_layer())
+        // Large JSON responses are compressed when the client accepts it
+        .layer(tower_http::compression::CompressionLayer::new().gzip(true).deflate(true));
+    
+    // Swagger UI and the OpenAPI JSON live in their own read-only, rate-limited
+    // scope so they stay reachable regardless of API CORS and auth
+    let app = Router::new()
+        .merge(api)
+        .merge(docs::docs_router(ApiDoc::openapi()));
     
     let addr = "127.0.0.1:1646";
     let listener = TcpListener::bind(addr).await?;
     
-    // Start the proxy server on port 8080 - ensure it's ready before continuing
-    let proxy_addr = "127.0.0.1:8080";
-    let proxy_app = proxy::create_proxy_router();
-    let proxy_listener = TcpListener::bind(proxy_addr).await?;
-    
     info!("🚀 Starting servers:");
     info!("  📋 REST API: http://{}/api", addr);
     info!("  📚 API Documentation: http://{}/docs", addr);
@@ -219,85 +372,131 @@ pub async fn start_server(device_queue_manager: crate::commands::DeviceQueueMana
     info!("  🤖 MCP Endpoint: http://{}/mcp", addr);
     info!("  🔐 Authentication: http://{}/auth/pair", addr);
     info!("  🌐 Address Generation: http://{}/address/*", addr);
-    info!("  🌍 Vault Proxy: http://{} -> vault.keepkey.com", proxy_addr);
     
-    // Test proxy server readiness by making a quick health check
-    let proxy_health_check = async {
-        let client = reqwest::Client::new();
-        let mut retries = 0;
-        let max_retries = 10;
+    if crate::safe_mode::is_active() {
+        // Safe mode keeps the app surface minimal; the proxy is only needed for the hosted vault
+        info!("🛟 Safe mode - not starting the vault proxy");
+        match app_handle.emit("server:ready", serde_json::json!({
+            "status": "ready",
+            "rest_url": format!("http://{}/docs", addr),
+            "mcp_url": format!("http://{}/mcp", addr),
+            "proxy_url": null,
+            "proxy_ready": false,
+            "safe_mode": true
+        })) {
+            Ok(_) => log::info!("✅ server:ready event emitted successfully"),
+            Err(e) => log::error!("❌ Failed to emit server:ready event: {}", e),
+        }
+    } else {
+        // Start the proxy server on port 8080 - ensure it's ready before continuing
+        let proxy_addr = "127.0.0.1:8080";
+        let proxy_app = proxy::create_proxy_router();
+        let proxy_listener = TcpListener::bind(proxy_addr).await?;
+    
+        info!("  🌍 Vault Proxy: http://{} -> vault.keepkey.com", proxy_addr);
+    
+        // Test proxy server readiness by making a quick health check
+        let proxy_health_check = async {
+            let client = reqwest::Client::new();
+            let mut retries = 0;
+            let max_retries = 10;
         
-        loop {
-            if retries >= max_retries {
-                return Err("Proxy server failed to start within timeout".to_string());
-            }
+            loop {
+                if retries >= max_retries {
+                    return Err("Proxy server failed to start within timeout".to_string());
+                }
             
-            match client.get(format!("http://{}/", proxy_addr)).send().await {
-                Ok(response) => {
-                    if response.status().is_success() {
-                        log::info!("✅ Proxy server health check passed");
-                        return Ok(());
+                match client.get(format!("http://{}/", proxy_addr)).send().await {
+                    Ok(response) => {
+                        if response.status().is_success() {
+                            log::info!("✅ Proxy server health check passed");
+                            return Ok(());
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("⚠️ Proxy server not ready (attempt {}/{}): {}", retries + 1, max_retries, e);
                     }
                 }
-                Err(e) => {
-                    log::warn!("⚠️ Proxy server not ready (attempt {}/{}): {}", retries + 1, max_retries, e);
-                }
-            }
             
-            tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-            retries += 1;
-        }
-    };
+                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                retries += 1;
+            }
+        };
     
-    // Start the proxy server and wait for it to be ready
-    let proxy_handle = tokio::spawn(async move {
-        serve(proxy_listener, proxy_app).await
-    });
+        // Start the proxy server and wait for it to be ready
+        let proxy_handle = tokio::spawn(async move {
+            serve(proxy_listener, proxy_app).await
+        });
     
-    // Small delay to let proxy server start
-    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        // Small delay to let proxy server start
+        tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
     
-    // Check if proxy server is ready
-    match proxy_health_check.await {
-        Ok(()) => {
-            info!("✅ Both servers started successfully and are ready");
+        // Check if proxy server is ready
+        match proxy_health_check.await {
+            Ok(()) => {
+                info!("✅ Both servers started successfully and are ready");
             
-            // Emit success event to frontend only after both servers are confirmed ready
-            match app_handle.emit("server:ready", serde_json::json!({
-                "status": "ready",
-                "rest_url": format!("http://{}/docs", addr),
-                "mcp_url": format!("http://{}/mcp", addr),
-                "proxy_url": format!("http://{}", proxy_addr),
-                "proxy_ready": true
-            })) {
-                Ok(_) => log::info!("✅ server:ready event emitted successfully"),
-                Err(e) => log::error!("❌ Failed to emit server:ready event: {}", e),
+                // Emit success event to frontend only after both servers are confirmed ready
+                match app_handle.emit("server:ready", serde_json::json!({
+                    "status": "ready",
+                    "rest_url": format!("http://{}/docs", addr),
+                    "mcp_url": format!("http://{}/mcp", addr),
+                    "proxy_url": format!("http://{}", proxy_addr),
+                    "proxy_ready": true
+                })) {
+                    Ok(_) => log::info!("✅ server:ready event emitted successfully"),
+                    Err(e) => log::error!("❌ Failed to emit server:ready event: {}", e),
+                }
             }
-        }
-        Err(e) => {
-            log::error!("❌ CRITICAL: Proxy server failed to start: {}", e);
+            Err(e) => {
+                log::error!("❌ CRITICAL: Proxy server failed to start: {}", e);
             
-            // Emit error event to frontend
-            match app_handle.emit("server:error", serde_json::json!({
-                "error": format!("Proxy server failed to start: {}", e),
-                "critical": true
-            })) {
-                Ok(_) => log::info!("✅ server:error event emitted successfully"),
-                Err(emit_err) => log::error!("❌ Failed to emit server:error event: {}", emit_err),
-            }
+                // Emit error event to frontend
+                match app_handle.emit("server:error", serde_json::json!({
+                    "error": format!("Proxy server failed to start: {}", e),
+                    "critical": true
+                })) {
+                    Ok(_) => log::info!("✅ server:error event emitted successfully"),
+                    Err(emit_err) => log::error!("❌ Failed to emit server:error event: {}", emit_err),
+                }
             
-            return Err(e.into());
+                return Err(e.into());
+            }
         }
+    
+        // Monitor proxy server in the background
+        tokio::spawn(async move {
+            if let Err(e) = proxy_handle.await {
+                log::error!("❌ Proxy server task failed: {}", e);
+            }
+        });
     }
     
-    // Monitor proxy server in the background
-    tokio::spawn(async move {
-        if let Err(e) = proxy_handle.await {
-            log::error!("❌ Proxy server task failed: {}", e);
+    // Optionally serve the same router over HTTPS for clients that refuse plain http
+    if tls::is_tls_enabled() {
+        match tls::load_rustls_config().await {
+            Ok(tls_config) => {
+                let tls_app = app.clone();
+                let tls_addr: std::net::SocketAddr = tls::TLS_ADDR.parse()?;
+                info!("  🔐 HTTPS API: https://{}", tls_addr);
+                tokio::spawn(async move {
+                    if let Err(e) = axum_server::bind_rustls(tls_addr, tls_config)
+                        .serve(tls_app.into_make_service())
+                        .await
+                    {
+                        log::error!("❌ HTTPS server failed: {}", e);
+                    }
+                });
+            }
+            Err(e) => {
+                // HTTPS is optional - keep serving plain HTTP
+                log::error!("❌ Failed to start HTTPS listener: {}", e);
+            }
         }
-    });
+    }
     
-    // Run the main API server
+    // Run the main API server; the listener is bound, so connections queue from here
+    crate::readiness::mark_ready(crate::readiness::Gate::Server);
     serve(listener, app).await?;
     
     Ok(())
@@ -0,0 +1,201 @@
+use serde_json::{json, Value};
+
+use crate::cache::CachedPubkey;
+use crate::server::ServerState;
+use crate::server::context;
+
+/// Maximum cached accounts listed in a prompt
+const MAX_LISTED_ACCOUNTS: usize = 40;
+
+/// Prompt templates offered to MCP clients
+pub fn list_prompts() -> Value {
+    json!({
+        "prompts": [
+            {
+                "name": "summarize_portfolio",
+                "description": "Summarize the accounts and chains set up on the current KeepKey",
+                "arguments": [
+                    {
+                        "name": "device_id",
+                        "description": "Device to summarize (defaults to the current device)",
+                        "required": false
+                    }
+                ]
+            },
+            {
+                "name": "prepare_bitcoin_payment",
+                "description": "Prepare a Bitcoin payment for signing on the KeepKey",
+                "arguments": [
+                    {
+                        "name": "recipient",
+                        "description": "Destination Bitcoin address",
+                        "required": true
+                    },
+                    {
+                        "name": "amount",
+                        "description": "Amount to send in BTC",
+                        "required": true
+                    },
+                    {
+                        "name": "script_type",
+                        "description": "Account type to spend from (p2pkh, p2sh-p2wpkh, p2wpkh)",
+                        "required": false
+                    }
+                ]
+            },
+            {
+                "name": "check_device_health",
+                "description": "Check firmware, bootloader and security settings of the current KeepKey",
+                "arguments": []
+            }
+        ]
+    })
+}
+
+/// The device a prompt is about: explicit argument, then current context, then first connected
+pub(crate) fn resolve_device_id(arguments: &Value) -> Option<String> {
+    arguments.get("device_id")
+        .and_then(|v| v.as_str())
+        .map(String::from)
+        .or_else(|| context::get_current_context_info().map(|(device_id, _)| device_id))
+        .or_else(|| keepkey_rust::features::list_connected_devices()
+            .first()
+            .map(|d| d.unique_id.clone()))
+}
+
+fn required_argument<'a>(arguments: &'a Value, name: &str) -> Result<&'a str, String> {
+    arguments.get(name)
+        .and_then(|v| v.as_str())
+        .filter(|v| !v.trim().is_empty())
+        .ok_or_else(|| format!("Missing required argument: {}", name))
+}
+
+/// One line per cached account, e.g. "Bitcoin m/84'/0'/0' (p2wpkh): xpub..."
+fn describe_accounts(pubkeys: &[CachedPubkey]) -> String {
+    if pubkeys.is_empty() {
+        return "No accounts are cached yet for this device.".to_string();
+    }
+
+    let mut lines: Vec<String> = pubkeys.iter()
+        .take(MAX_LISTED_ACCOUNTS)
+        .map(|pk| {
+            let script = pk.script_type.as_deref().map(|s| format!(" ({})", s)).unwrap_or_default();
+            let value = pk.xpub.as_deref().or(pk.address.as_deref()).unwrap_or("-");
+            format!("- {} {}{}: {}", pk.coin_name, pk.derivation_path, script, value)
+        })
+        .collect();
+
+    if pubkeys.len() > MAX_LISTED_ACCOUNTS {
+        lines.push(format!("- ... and {} more", pubkeys.len() - MAX_LISTED_ACCOUNTS));
+    }
+
+    lines.join("\n")
+}
+
+async fn cached_accounts(state: &ServerState, device_id: &str) -> Vec<CachedPubkey> {
+    match crate::commands::get_cache_manager(&state.cache_manager).await {
+        Ok(cache) => cache.list_cached_pubkeys(device_id).await.unwrap_or_default(),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn user_message(description: &str, text: String) -> Value {
+    json!({
+        "description": description,
+        "messages": [
+            {
+                "role": "user",
+                "content": {
+                    "type": "text",
+                    "text": text
+                }
+            }
+        ]
+    })
+}
+
+/// Render a prompt template with live cache data
+pub async fn get_prompt(state: &ServerState, name: &str, arguments: &Value) -> Result<Value, String> {
+    match name {
+        "summarize_portfolio" => {
+            let device_id = resolve_device_id(arguments)
+                .ok_or_else(|| "No KeepKey device connected".to_string())?;
+            let accounts = cached_accounts(state, &device_id).await;
+
+            let label = match crate::commands::get_cache_manager(&state.cache_manager).await {
+                Ok(cache) => cache.get_cache_metadata(&device_id).await.and_then(|m| m.label),
+                Err(_) => None,
+            };
+
+            let text = format!(
+                "Summarize my KeepKey portfolio.\n\n\
+                 Device: {} ({})\n\
+                 Cached accounts:\n{}\n\n\
+                 Steps:\n\
+                 1. Call `list_devices` to confirm the device is connected.\n\
+                 2. Group the accounts above by chain and account type.\n\
+                 3. Point out chains that have no cached accounts yet.\n\
+                 Never ask me for my recovery phrase or PIN.",
+                label.as_deref().unwrap_or("Unlabeled"),
+                device_id,
+                describe_accounts(&accounts),
+            );
+
+            Ok(user_message("Portfolio summary for the current KeepKey", text))
+        }
+
+        "prepare_bitcoin_payment" => {
+            let recipient = required_argument(arguments, "recipient")?;
+            let amount = required_argument(arguments, "amount")?;
+            let script_type = arguments.get("script_type").and_then(|v| v.as_str()).unwrap_or("p2wpkh");
+
+            let device_id = resolve_device_id(arguments)
+                .ok_or_else(|| "No KeepKey device connected".to_string())?;
+            let bitcoin_accounts: Vec<CachedPubkey> = cached_accounts(state, &device_id).await
+                .into_iter()
+                .filter(|pk| pk.coin_name.eq_ignore_ascii_case("bitcoin"))
+                .filter(|pk| pk.script_type.as_deref().map(|s| s == script_type).unwrap_or(true))
+                .collect();
+
+            let text = format!(
+                "Prepare a Bitcoin payment of {} BTC to {}.\n\n\
+                 Spend from the {} account on device {}.\n\
+                 Cached Bitcoin accounts:\n{}\n\n\
+                 Steps:\n\
+                 1. Call `get_device_status` to confirm the device is connected and unlocked.\n\
+                 2. Validate that {} is a well-formed Bitcoin address.\n\
+                 3. Call `get_bitcoin_address` with script_type {} for a change address.\n\
+                 4. Show me the recipient, amount and change address and wait for my confirmation.\n\
+                 The transaction is only signed after I confirm it on the KeepKey screen.",
+                amount, recipient, script_type, device_id,
+                describe_accounts(&bitcoin_accounts),
+                recipient, script_type,
+            );
+
+            Ok(user_message("Bitcoin payment preparation", text))
+        }
+
+        "check_device_health" => {
+            let device_id = resolve_device_id(arguments)
+                .ok_or_else(|| "No KeepKey device connected".to_string())?;
+
+            let text = format!(
+                "Check the health of my KeepKey ({}).\n\n\
+                 Latest firmware: {}\n\
+                 Latest bootloader: {}\n\n\
+                 Steps:\n\
+                 1. Call `get_device_features`.\n\
+                 2. Compare its firmware and bootloader versions with the latest above.\n\
+                 3. Report whether PIN and passphrase protection are enabled and whether a backup exists.\n\
+                 4. Recommend updates or settings changes, most important first.",
+                device_id,
+                crate::device::releases::latest_firmware_version(),
+                crate::device::releases::latest_bootloader_version(),
+            );
+
+            Ok(user_message("KeepKey health check", text))
+        }
+
+        _ => Err(format!("Unknown prompt: {}", name)),
+    }
+}
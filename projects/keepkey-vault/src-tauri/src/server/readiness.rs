@@ -0,0 +1,80 @@
+use axum::{
+    extract::Request,
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+
+use crate::readiness::Gate;
+use crate::server::api::addresses::ErrorResponse;
+
+/// Seconds clients are asked to wait before retrying
+const RETRY_AFTER_SECS: u64 = 2;
+
+/// Path prefixes and the startup gates they depend on
+const GATE_PREFIXES: &[(&str, &[Gate])] = &[
+    ("/api/cache", &[Gate::Cache]),
+    ("/api/errors", &[Gate::Cache]),
+    ("/api/timeline", &[Gate::Cache]),
+    ("/api/providers", &[Gate::Cache]),
+    ("/api/goals", &[Gate::Cache]),
+    ("/api/accounts", &[Gate::Cache]),
+    ("/api/addresses/lookup", &[Gate::Cache]),
+    ("/api/utxos", &[Gate::Cache, Gate::DeviceMonitor]),
+    ("/api/payment-intents", &[Gate::Cache, Gate::DeviceMonitor]),
+    ("/api/devices/duplicate-seeds", &[Gate::Cache]),
+    ("/api/devices", &[Gate::DeviceMonitor]),
+    ("/system/ping", &[]),
+    ("/addresses", &[Gate::DeviceMonitor]),
+    ("/system", &[Gate::DeviceMonitor]),
+    ("/utxo/", &[Gate::DeviceMonitor]),
+    ("/eth/verify", &[]),
+    ("/eth/", &[Gate::DeviceMonitor]),
+    ("/cosmos/", &[Gate::DeviceMonitor]),
+    ("/transactions", &[Gate::DeviceMonitor]),
+];
+
+/// Gates a request path waits on
+pub fn gates_for_path(path: &str) -> &'static [Gate] {
+    GATE_PREFIXES
+        .iter()
+        .find(|(prefix, _)| path.starts_with(prefix))
+        .map_or(&[], |(_, gates)| *gates)
+}
+
+/// Answer requests whose dependencies are still starting with 503 + Retry-After
+pub async fn readiness_middleware(request: Request, next: Next) -> Response {
+    let pending: Vec<&str> = gates_for_path(request.uri().path())
+        .iter()
+        .filter(|gate| !crate::readiness::is_ready(**gate))
+        .map(|gate| gate.as_str())
+        .collect();
+
+    if !pending.is_empty() {
+        return (
+            StatusCode::SERVICE_UNAVAILABLE,
+            [(header::RETRY_AFTER, RETRY_AFTER_SECS.to_string())],
+            Json(ErrorResponse::new(
+                format!("Vault is still starting (waiting for {})", pending.join(", ")),
+                "NOT_READY",
+            )),
+        ).into_response();
+    }
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gates_for_path() {
+        assert_eq!(gates_for_path("/api/cache/frontload/abc"), &[Gate::Cache]);
+        assert_eq!(gates_for_path("/api/utxos/abc"), &[Gate::Cache, Gate::DeviceMonitor]);
+        assert_eq!(gates_for_path("/eth/signTransaction"), &[Gate::DeviceMonitor]);
+        assert!(gates_for_path("/api/health").is_empty());
+        assert!(gates_for_path("/system/ping").is_empty());
+        assert!(gates_for_path("/docs").is_empty());
+    }
+}
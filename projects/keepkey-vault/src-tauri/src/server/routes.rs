@@ -12,6 +12,8 @@ use utoipa::ToSchema;
 
 use crate::server::ServerState;
 use crate::server::context::{self};
+use crate::server::prompts;
+use crate::server::batch;
 
 #[derive(Debug, Serialize, ToSchema)]
 pub struct HealthResponse {
@@ -210,6 +212,54 @@ pub async fn api_list_devices(State(state): State<Arc<ServerState>>) -> Result<J
     Ok(Json(device_infos))
 }
 
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceState {
+    pub device_id: String,
+    pub connected: bool,
+    /// Whether a queue worker is running for the device
+    pub queue_active: bool,
+    #[schema(value_type = Object)]
+    pub passphrase: keepkey_rust::device_queue::PassphraseSessionState,
+}
+
+/// Get session state for a device
+#[utoipa::path(
+    get,
+    path = "/api/devices/{id}/state",
+    params(("id" = String, Path, description = "Device ID")),
+    responses(
+        (status = 200, description = "Device session state", body = DeviceState)
+    ),
+    tag = "device"
+)]
+pub async fn api_get_device_state(
+    State(state): State<Arc<ServerState>>,
+    axum::extract::Path(device_id): axum::extract::Path<String>,
+) -> Json<DeviceState> {
+    let connected = keepkey_rust::features::list_connected_devices()
+        .iter()
+        .any(|d| d.unique_id == device_id);
+    let handle = state.device_queue_manager.lock().await.get(&device_id).cloned();
+
+    let passphrase = match &handle {
+        Some(handle) => handle.passphrase_state(),
+        None => keepkey_rust::device_queue::PassphraseSessionState {
+            policy: crate::device::passphrase_policy::get_preferences().policy_for(&device_id),
+            cached: false,
+            cached_for_secs: None,
+            expires_in_secs: None,
+        },
+    };
+
+    Json(DeviceState {
+        device_id,
+        connected,
+        queue_active: handle.is_some(),
+        passphrase,
+    })
+}
+
 /// Get device features (SDK compatible format)
 #[utoipa::path(
     post,
@@ -479,6 +529,52 @@ pub async fn mcp_handle(
             }
         }
         
+        "prompts/list" => {
+            McpResponse {
+                jsonrpc: "2.0".to_string(),
+                result: Some(prompts::list_prompts()),
+                error: None,
+                id: mcp_request.id,
+            }
+        }
+        
+        "prompts/get" => {
+            let params = mcp_request.params.unwrap_or(json!({}));
+            match params.get("name").and_then(|n| n.as_str()) {
+                Some(name) => {
+                    let arguments = params.get("arguments").cloned().unwrap_or(json!({}));
+                    match prompts::get_prompt(&state, name, &arguments).await {
+                        Ok(result) => McpResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: Some(result),
+                            error: None,
+                            id: mcp_request.id,
+                        },
+                        Err(message) => McpResponse {
+                            jsonrpc: "2.0".to_string(),
+                            result: None,
+                            error: Some(McpError {
+                                code: -32602,
+                                message,
+                                data: None,
+                            }),
+                            id: mcp_request.id,
+                        },
+                    }
+                }
+                None => McpResponse {
+                    jsonrpc: "2.0".to_string(),
+                    result: None,
+                    error: Some(McpError {
+                        code: -32602,
+                        message: "Missing 'name' parameter".to_string(),
+                        data: None,
+                    }),
+                    id: mcp_request.id,
+                },
+            }
+        }
+        
         "tools/list" => {
             // List available tools
             McpResponse {
@@ -509,6 +605,20 @@ pub async fn mcp_handle(
                                 "properties": {}
                             }
                         },
+                        {
+                            "name": "parse_payment_uri",
+                            "description": "Decode a bitcoin: or ethereum: payment URI into address, amount and asset",
+                            "inputSchema": {
+                                "type": "object",
+                                "properties": {
+                                    "uri": {
+                                        "type": "string",
+                                        "description": "BIP21 or EIP-681 payment URI"
+                                    }
+                                },
+                                "required": ["uri"]
+                            }
+                        },
                         {
                             "name": "get_bitcoin_address",
                             "description": "Get a Bitcoin address for the current device",
@@ -526,7 +636,8 @@ pub async fn mcp_handle(
                                     }
                                 }
                             }
-                        }
+                        },
+                        batch::tool_definition()
                     ]
                 })),
                 error: None,
@@ -619,6 +730,65 @@ pub async fn mcp_handle(
                                 }
                             }
                         }
+                        "parse_payment_uri" => {
+                            let uri = params.get("arguments")
+                                .and_then(|args| args.get("uri"))
+                                .and_then(|uri| uri.as_str())
+                                .unwrap_or_default();
+                            match crate::payment_uri::parse(uri) {
+                                Ok(intent) => McpResponse {
+                                    jsonrpc: "2.0".to_string(),
+                                    result: Some(json!({
+                                        "content": [
+                                            {
+                                                "type": "text",
+                                                "text": serde_json::to_string_pretty(&intent).unwrap_or_else(|_| "Failed to serialize payment intent".to_string())
+                                            }
+                                        ]
+                                    })),
+                                    error: None,
+                                    id: mcp_request.id,
+                                },
+                                Err(e) => McpResponse {
+                                    jsonrpc: "2.0".to_string(),
+                                    result: None,
+                                    error: Some(McpError {
+                                        code: -32602,
+                                        message: e,
+                                        data: None,
+                                    }),
+                                    id: mcp_request.id,
+                                },
+                            }
+                        }
+                        "run_plan" => {
+                            let arguments = params.get("arguments").cloned().unwrap_or(Value::Null);
+                            match batch::run_plan(&state, &arguments).await {
+                                Ok(results) => McpResponse {
+                                    jsonrpc: "2.0".to_string(),
+                                    result: Some(json!({
+                                        "content": [
+                                            {
+                                                "type": "text",
+                                                "text": serde_json::to_string_pretty(&results).unwrap_or_else(|_| "Failed to serialize plan results".to_string())
+                                            }
+                                        ]
+                                    })),
+                                    error: None,
+                                    id: mcp_request.id,
+                                },
+                                Err(e) => McpResponse {
+                                    jsonrpc: "2.0".to_string(),
+                                    result: None,
+                                    error: Some(McpError {
+                                        code: -32602,
+                                        message: e,
+                                        data: None,
+                                    }),
+                                    id: mcp_request.id,
+                                },
+                            }
+                        }
                         "get_bitcoin_address" => {
                             // TODO: Implement actual address generation
                             McpResponse {
@@ -0,0 +1,136 @@
+use std::path::PathBuf;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use axum_server::tls_rustls::RustlsConfig;
+
+/// Address the optional HTTPS listener binds to (plain HTTP stays on 1646)
+pub const TLS_ADDR: &str = "127.0.0.1:1647";
+
+/// Preference key that turns the HTTPS listener on
+const TLS_PREFERENCE_KEY: &str = "api_tls_enabled";
+
+/// Information about the local API certificate, used by clients for pinning
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TlsInfo {
+    pub enabled: bool,
+    pub https_url: String,
+    pub cert_path: String,
+    /// SHA-256 fingerprint of the DER certificate (colon separated, uppercase hex)
+    pub fingerprint: Option<String>,
+}
+
+/// Get the directory holding the generated certificate (~/.keepkey/tls)
+fn get_tls_dir() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?;
+
+    let tls_dir = home_dir.join(".keepkey").join("tls");
+    std::fs::create_dir_all(&tls_dir)
+        .map_err(|e| format!("Failed to create TLS directory: {}", e))?;
+
+    Ok(tls_dir)
+}
+
+fn cert_pem_path() -> Result<PathBuf, String> {
+    Ok(get_tls_dir()?.join("cert.pem"))
+}
+
+fn key_pem_path() -> Result<PathBuf, String> {
+    Ok(get_tls_dir()?.join("key.pem"))
+}
+
+fn cert_der_path() -> Result<PathBuf, String> {
+    Ok(get_tls_dir()?.join("cert.der"))
+}
+
+/// Check whether the HTTPS listener is enabled in preferences (off by default)
+pub fn is_tls_enabled() -> bool {
+    crate::preferences::get_as(TLS_PREFERENCE_KEY).unwrap_or(false)
+}
+
+/// Generate a self-signed certificate for localhost if one doesn't exist yet
+pub fn ensure_certificate() -> Result<(), String> {
+    let cert_path = cert_pem_path()?;
+    let key_path = key_pem_path()?;
+    let der_path = cert_der_path()?;
+
+    if cert_path.exists() && key_path.exists() && der_path.exists() {
+        return Ok(());
+    }
+
+    log::info!("🔐 Generating self-signed certificate for the local API server");
+
+    let cert = rcgen::generate_simple_self_signed(vec![
+        "localhost".to_string(),
+        "127.0.0.1".to_string(),
+    ])
+    .map_err(|e| format!("Failed to generate certificate: {}", e))?;
+
+    let cert_pem = cert.serialize_pem()
+        .map_err(|e| format!("Failed to serialize certificate: {}", e))?;
+    let cert_der = cert.serialize_der()
+        .map_err(|e| format!("Failed to serialize certificate: {}", e))?;
+    let key_pem = cert.serialize_private_key_pem();
+
+    std::fs::write(&cert_path, cert_pem)
+        .map_err(|e| format!("Failed to write certificate: {}", e))?;
+    std::fs::write(&der_path, cert_der)
+        .map_err(|e| format!("Failed to write certificate: {}", e))?;
+    write_private_key(&key_path, key_pem.as_bytes())
+        .map_err(|e| format!("Failed to write private key: {}", e))?;
+
+    log::info!("✅ Certificate written to {}", cert_path.display());
+    Ok(())
+}
+
+/// Write the key to a fresh file that is readable by the current user only from
+/// the moment it exists
+fn write_private_key(path: &std::path::Path, key_pem: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    // A leftover key may have looser permissions; never reuse its file
+    match std::fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(path)?.write_all(key_pem)
+}
+
+/// SHA-256 fingerprint of the current certificate, if one has been generated
+pub fn certificate_fingerprint() -> Option<String> {
+    let der = std::fs::read(cert_der_path().ok()?).ok()?;
+    let digest = Sha256::digest(&der);
+
+    Some(digest.iter()
+        .map(|b| format!("{:02X}", b))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+/// Load the rustls configuration, generating the certificate on first use
+pub async fn load_rustls_config() -> Result<RustlsConfig, String> {
+    ensure_certificate()?;
+
+    RustlsConfig::from_pem_file(cert_pem_path()?, key_pem_path()?)
+        .await
+        .map_err(|e| format!("Failed to load TLS certificate: {}", e))
+}
+
+/// Current TLS status for the frontend and pinning clients
+pub fn get_tls_info() -> Result<TlsInfo, String> {
+    Ok(TlsInfo {
+        enabled: is_tls_enabled(),
+        https_url: format!("https://{}", TLS_ADDR),
+        cert_path: cert_pem_path()?.display().to_string(),
+        fingerprint: certificate_fingerprint(),
+    })
+}
@@ -0,0 +1,386 @@
+//! Opt-in encrypted backup of vault preferences and labels.
+//!
+//! Preferences are exported without anything secret-looking, together with the
+//! account names, UTXO labels and (still encrypted) notes kept in the cache
+//! database. The payload is encrypted with a key derived from a user passphrase
+//! (Argon2id + AES-256-GCM) and stored at a user-chosen destination. Keys and
+//! keychain secrets are never included.
+
+use aes_gcm::aead::{Aead, KeyInit};
+use aes_gcm::{Aes256Gcm, Nonce};
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+
+use crate::cache::{AccountMetadata, CacheManager, EncryptedNote, UtxoTag};
+
+/// Preference key holding the backup destination
+const PREFERENCE_KEY: &str = "settings_backup";
+
+/// Keychain entry with "user:password" for WebDAV destinations
+pub const WEBDAV_CREDENTIALS_SECRET: &str = "settings_backup_webdav";
+
+/// Identifies backup files
+const BACKUP_FORMAT: &str = "keepkey-vault-settings";
+/// Version 1 held only the preference map
+const BACKUP_VERSION: u32 = 2;
+
+/// Minimum passphrase length accepted for new backups
+const MIN_PASSPHRASE_LEN: usize = 8;
+
+/// Preference keys never exported, in addition to anything secret-looking
+const EXCLUDED_KEYS: &[&str] = &[PREFERENCE_KEY, "isOnboarded"];
+
+/// Substrings marking a preference key as sensitive
+const SENSITIVE_KEY_MARKERS: &[&str] = &["secret", "password", "token", "apikey", "api_key", "private", "mnemonic", "seed"];
+
+/// Where encrypted backups are written to and read from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum BackupDestination {
+    /// A local (or synced-folder) file path
+    File { path: String },
+    /// A WebDAV file URL; credentials live in the keychain
+    WebDav { url: String },
+    /// S3-compatible storage through presigned PUT and GET URLs
+    S3 { upload_url: String, download_url: String },
+}
+
+/// Settings backup configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub destination: Option<BackupDestination>,
+    /// Unix timestamp of the last successful backup
+    #[serde(default)]
+    pub last_backup_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupEnvelope {
+    format: String,
+    version: u32,
+    created_at: i64,
+    kdf: String,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// Labels and names kept in the cache database rather than in keepkey.json
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheData {
+    #[serde(default)]
+    account_names: Vec<AccountMetadata>,
+    #[serde(default)]
+    utxo_tags: Vec<UtxoTag>,
+    /// Notes stay encrypted under their own passphrase
+    #[serde(default)]
+    notes: Vec<EncryptedNote>,
+}
+
+impl CacheData {
+    fn len(&self) -> usize {
+        self.account_names.len() + self.utxo_tags.len() + self.notes.len()
+    }
+
+    async fn export(cache: &CacheManager) -> Result<Self, String> {
+        let read_error = |e: anyhow::Error| format!("Failed to read labels for backup: {}", e);
+        Ok(Self {
+            account_names: cache.list_account_metadata(None).await.map_err(read_error)?,
+            utxo_tags: cache.list_all_utxo_tags().await.map_err(read_error)?,
+            notes: cache.list_all_notes().await.map_err(read_error)?,
+        })
+    }
+
+    /// Write everything back; existing names and labels for the same account or
+    /// UTXO are replaced, notes already present are skipped
+    async fn import(&self, cache: &CacheManager) -> Result<usize, String> {
+        let write_error = |e: anyhow::Error| format!("Failed to restore labels: {}", e);
+        for account in &self.account_names {
+            cache.save_account_metadata(account).await.map_err(write_error)?;
+        }
+        for tag in &self.utxo_tags {
+            cache.save_utxo_tag(tag).await.map_err(write_error)?;
+        }
+        let mut notes = 0;
+        for note in &self.notes {
+            if cache.restore_note(note).await.map_err(write_error)? {
+                notes += 1;
+            }
+        }
+        Ok(self.account_names.len() + self.utxo_tags.len() + notes)
+    }
+}
+
+/// Decrypted backup contents
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BackupPayload {
+    preferences: serde_json::Map<String, serde_json::Value>,
+    #[serde(default)]
+    cache: CacheData,
+}
+
+/// Load backup configuration from preferences
+pub fn get_settings() -> BackupSettings {
+    crate::preferences::get_as(PREFERENCE_KEY).unwrap_or_default()
+}
+
+/// Save backup configuration
+pub fn set_settings(settings: &BackupSettings) -> Result<(), String> {
+    crate::preferences::set(PREFERENCE_KEY, serde_json::json!(settings))
+}
+
+fn is_exportable(key: &str) -> bool {
+    let lower = key.to_ascii_lowercase();
+    !EXCLUDED_KEYS.contains(&key) && !SENSITIVE_KEY_MARKERS.iter().any(|m| lower.contains(m))
+}
+
+/// The subset of preferences that may leave the machine
+pub(crate) fn exportable_preferences(config: &serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+    config
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .filter(|(key, _)| is_exportable(key))
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+pub(crate) fn derive_key(passphrase: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive encryption key: {}", e))?;
+    Ok(key)
+}
+
+/// Encrypt a JSON document into a backup envelope
+fn encrypt(plaintext: &serde_json::Value, passphrase: &str) -> Result<Vec<u8>, String> {
+    let mut salt = [0u8; 16];
+    let mut nonce = [0u8; 12];
+    rand::thread_rng().fill_bytes(&mut salt);
+    rand::thread_rng().fill_bytes(&mut nonce);
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let data = serde_json::to_vec(plaintext).map_err(|e| e.to_string())?;
+    let ciphertext = cipher
+        .encrypt(Nonce::from_slice(&nonce), data.as_ref())
+        .map_err(|_| "Failed to encrypt settings".to_string())?;
+
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let envelope = BackupEnvelope {
+        format: BACKUP_FORMAT.to_string(),
+        version: BACKUP_VERSION,
+        created_at: chrono::Utc::now().timestamp(),
+        kdf: "argon2id".to_string(),
+        salt: b64.encode(salt),
+        nonce: b64.encode(nonce),
+        ciphertext: b64.encode(ciphertext),
+    };
+    serde_json::to_vec_pretty(&envelope).map_err(|e| e.to_string())
+}
+
+/// Decrypt a backup envelope
+fn decrypt(bytes: &[u8], passphrase: &str) -> Result<serde_json::Value, String> {
+    let envelope: BackupEnvelope = serde_json::from_slice(bytes)
+        .map_err(|e| format!("Not a settings backup: {}", e))?;
+    if envelope.format != BACKUP_FORMAT || !(1..=BACKUP_VERSION).contains(&envelope.version) {
+        return Err(format!("Unsupported backup format {} v{}", envelope.format, envelope.version));
+    }
+
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let decode = |field: &str, value: &str| b64.decode(value).map_err(|_| format!("Corrupt backup {}", field));
+    let salt = decode("salt", &envelope.salt)?;
+    let nonce = decode("nonce", &envelope.nonce)?;
+    let ciphertext = decode("ciphertext", &envelope.ciphertext)?;
+    if nonce.len() != 12 {
+        return Err("Corrupt backup nonce".to_string());
+    }
+
+    let key = derive_key(passphrase, &salt)?;
+    let cipher = Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())?;
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| "Wrong passphrase or corrupted backup".to_string())?;
+
+    let contents: serde_json::Value = serde_json::from_slice(&plaintext).map_err(|e| format!("Corrupt backup contents: {}", e))?;
+    if envelope.version == 1 {
+        return Ok(serde_json::json!({ "preferences": contents }));
+    }
+    Ok(contents)
+}
+
+fn webdav_credentials() -> Result<Option<(String, String)>, String> {
+    Ok(crate::secrets::get_secret(WEBDAV_CREDENTIALS_SECRET)?.map(|creds| {
+        match creds.split_once(':') {
+            Some((user, pass)) => (user.to_string(), pass.to_string()),
+            None => (creds, String::new()),
+        }
+    }))
+}
+
+async fn upload(destination: &BackupDestination, bytes: Vec<u8>) -> Result<(), String> {
+    let client = reqwest::Client::new();
+    let request = match destination {
+        BackupDestination::File { path } => {
+            return tokio::fs::write(path, bytes)
+                .await
+                .map_err(|e| format!("Failed to write backup to {}: {}", path, e));
+        }
+        BackupDestination::WebDav { url } => {
+            let mut request = client.put(url);
+            if let Some((user, pass)) = webdav_credentials()? {
+                request = request.basic_auth(user, Some(pass));
+            }
+            request
+        }
+        BackupDestination::S3 { upload_url, .. } => client.put(upload_url),
+    };
+
+    let response = request
+        .body(bytes)
+        .send()
+        .await
+        .map_err(|e| format!("Failed to upload backup: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Backup upload failed with HTTP {}", response.status()));
+    }
+    Ok(())
+}
+
+async fn download(destination: &BackupDestination) -> Result<Vec<u8>, String> {
+    let client = reqwest::Client::new();
+    let request = match destination {
+        BackupDestination::File { path } => {
+            return tokio::fs::read(path)
+                .await
+                .map_err(|e| format!("Failed to read backup from {}: {}", path, e));
+        }
+        BackupDestination::WebDav { url } => {
+            let mut request = client.get(url);
+            if let Some((user, pass)) = webdav_credentials()? {
+                request = request.basic_auth(user, Some(pass));
+            }
+            request
+        }
+        BackupDestination::S3 { download_url, .. } => client.get(download_url),
+    };
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("Failed to download backup: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Backup download failed with HTTP {}", response.status()));
+    }
+    response
+        .bytes()
+        .await
+        .map(|b| b.to_vec())
+        .map_err(|e| format!("Failed to read backup: {}", e))
+}
+
+fn configured_destination() -> Result<BackupDestination, String> {
+    let settings = get_settings();
+    if !settings.enabled {
+        return Err("Settings backup is not enabled".to_string());
+    }
+    settings.destination.ok_or_else(|| "No backup destination configured".to_string())
+}
+
+/// Encrypt the current preferences and labels and write them to the configured
+/// destination. Returns the number of preferences and labels backed up.
+pub async fn backup(passphrase: &str, cache: &CacheManager) -> Result<usize, String> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LEN {
+        return Err(format!("Backup passphrase must be at least {} characters", MIN_PASSPHRASE_LEN));
+    }
+    let destination = configured_destination()?;
+
+    let payload = BackupPayload {
+        preferences: exportable_preferences(&crate::commands::load_config()?),
+        cache: CacheData::export(cache).await?,
+    };
+    let count = payload.preferences.len() + payload.cache.len();
+    let bytes = encrypt(&serde_json::json!(payload), passphrase)?;
+    upload(&destination, bytes).await?;
+
+    let mut settings = get_settings();
+    settings.last_backup_at = Some(chrono::Utc::now().timestamp());
+    set_settings(&settings)?;
+
+    log::info!("☁️ Backed up {} preference(s) and {} label(s)", payload.preferences.len(), payload.cache.len());
+    Ok(count)
+}
+
+/// Fetch and decrypt a backup, merging its preferences and labels over the
+/// local ones. Returns the number of preferences and labels restored.
+pub async fn restore(passphrase: &str, destination: Option<BackupDestination>, cache: &CacheManager) -> Result<usize, String> {
+    let destination = match destination {
+        Some(destination) => destination,
+        None => configured_destination()?,
+    };
+
+    let payload: BackupPayload = serde_json::from_value(decrypt(&download(&destination).await?, passphrase)?)
+        .map_err(|e| format!("Corrupt backup contents: {}", e))?;
+    let restored = exportable_preferences(&serde_json::Value::Object(payload.preferences));
+
+    // Check every value first so a bad entry doesn't leave a half-applied restore
+    for (key, value) in &restored {
+        crate::preferences::validate(key, value)?;
+    }
+    for (key, value) in &restored {
+        crate::preferences::set(key, value.clone())?;
+    }
+    let labels = payload.cache.import(cache).await?;
+
+    log::info!("☁️ Restored {} preference(s) and {} label(s) from backup", restored.len(), labels);
+    Ok(restored.len() + labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exportable_preferences_drop_secrets() {
+        let config = serde_json::json!({
+            "language": "fr",
+            "pioneerApiKey": "abc",
+            "rpc_password": "hunter2",
+            "settings_backup": { "enabled": true },
+            "cosmos_chains": [],
+        });
+        let exported = exportable_preferences(&config);
+
+        assert!(exported.contains_key("language"));
+        assert!(exported.contains_key("cosmos_chains"));
+        assert!(!exported.contains_key("pioneerApiKey"));
+        assert!(!exported.contains_key("rpc_password"));
+        assert!(!exported.contains_key("settings_backup"));
+    }
+
+    #[test]
+    fn test_payload_defaults_cache_data() {
+        let payload: BackupPayload = serde_json::from_value(serde_json::json!({ "preferences": { "language": "de" } })).unwrap();
+        assert_eq!(payload.preferences.len(), 1);
+        assert_eq!(payload.cache.len(), 0);
+    }
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let value = serde_json::json!({ "language": "de" });
+        let bytes = encrypt(&value, "correct horse").unwrap();
+
+        assert_eq!(decrypt(&bytes, "correct horse").unwrap(), value);
+        assert!(decrypt(&bytes, "wrong horse").is_err());
+    }
+}
@@ -58,7 +58,7 @@ pub fn convert_xpub_prefix(xpub: &str, script_type: &str) -> Result<String, Stri
 }
 
 /// Double SHA256 for base58check
-fn sha256d(data: &[u8]) -> [u8; 32] {
+pub(crate) fn sha256d(data: &[u8]) -> [u8; 32] {
     use sha2::{Digest, Sha256};
     let hash1 = Sha256::digest(data);
     let hash2 = Sha256::digest(&hash1);
@@ -0,0 +1,371 @@
+use std::path::PathBuf;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio_util::sync::CancellationToken;
+use crate::commands::DeviceQueueManager;
+use crate::identity::{Identity, IdentityCurve};
+
+/// Preference holding the SSH agent settings
+const SSH_AGENT_PREFERENCE_KEY: &str = "ssh_agent";
+
+/// Identity used when none is configured
+const DEFAULT_IDENTITY: &str = "ssh://keepkey-vault";
+
+// SSH agent protocol message numbers (draft-miller-ssh-agent)
+const SSH_AGENT_FAILURE: u8 = 5;
+const SSH_AGENTC_REQUEST_IDENTITIES: u8 = 11;
+const SSH_AGENT_IDENTITIES_ANSWER: u8 = 12;
+const SSH_AGENTC_SIGN_REQUEST: u8 = 13;
+const SSH_AGENT_SIGN_RESPONSE: u8 = 14;
+
+/// Largest agent message accepted from a client
+const MAX_MESSAGE_LEN: usize = 256 * 1024;
+
+#[cfg(windows)]
+const PIPE_NAME: &str = r"\\.\pipe\keepkey-vault-ssh-agent";
+
+lazy_static::lazy_static! {
+    /// Cancellation token of the running agent listener
+    static ref AGENT: Mutex<Option<CancellationToken>> = Mutex::new(None);
+}
+
+/// SSH key type name for an identity curve
+fn key_type(curve: IdentityCurve) -> &'static str {
+    match curve {
+        IdentityCurve::Ed25519 => "ssh-ed25519",
+        IdentityCurve::Nistp256 => "ecdsa-sha2-nistp256",
+    }
+}
+
+/// SSH agent settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshAgentConfig {
+    pub enabled: bool,
+    /// SLIP-13 identity the key is derived from, e.g. "ssh://git@github.com"
+    pub identity: String,
+    pub curve: IdentityCurve,
+}
+
+impl Default for SshAgentConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            identity: DEFAULT_IDENTITY.to_string(),
+            curve: IdentityCurve::Ed25519,
+        }
+    }
+}
+
+/// Agent state reported to the UI
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SshAgentStatus {
+    pub running: bool,
+    /// Value for SSH_AUTH_SOCK
+    pub socket_path: String,
+    pub config: SshAgentConfig,
+}
+
+/// Load the SSH agent settings
+pub fn get_config() -> SshAgentConfig {
+    crate::preferences::get_as(SSH_AGENT_PREFERENCE_KEY).unwrap_or_default()
+}
+
+fn save_config(agent_config: &SshAgentConfig) -> Result<(), String> {
+    crate::preferences::set(SSH_AGENT_PREFERENCE_KEY, serde_json::json!(agent_config))
+}
+
+/// Where clients connect (SSH_AUTH_SOCK)
+pub fn socket_path() -> Result<PathBuf, String> {
+    #[cfg(windows)]
+    {
+        Ok(PathBuf::from(PIPE_NAME))
+    }
+    #[cfg(not(windows))]
+    {
+        let home_dir = dirs::home_dir()
+            .ok_or_else(|| "Could not find home directory".to_string())?;
+        Ok(home_dir.join(".keepkey").join("ssh-agent.sock"))
+    }
+}
+
+fn is_running() -> bool {
+    AGENT.lock().map(|agent| agent.is_some()).unwrap_or(false)
+}
+
+/// Current agent state
+pub fn status() -> Result<SshAgentStatus, String> {
+    Ok(SshAgentStatus {
+        running: is_running(),
+        socket_path: socket_path()?.to_string_lossy().to_string(),
+        config: get_config(),
+    })
+}
+
+/// Save new settings and start or stop the agent to match
+pub fn apply_config(agent_config: SshAgentConfig, queue_manager: DeviceQueueManager) -> Result<SshAgentStatus, String> {
+    Identity::parse(&agent_config.identity)?;
+    save_config(&agent_config)?;
+
+    // Restart so identity or curve changes take effect
+    stop();
+    if agent_config.enabled {
+        start(queue_manager)?;
+    }
+
+    status()
+}
+
+/// Start the agent if it's enabled in the settings
+pub fn start_if_enabled(queue_manager: DeviceQueueManager) {
+    if get_config().enabled {
+        if let Err(e) = start(queue_manager) {
+            log::error!("Failed to start SSH agent: {}", e);
+        }
+    }
+}
+
+fn start(queue_manager: DeviceQueueManager) -> Result<(), String> {
+    let mut agent = AGENT.lock().map_err(|_| "Failed to lock SSH agent state".to_string())?;
+    if agent.is_some() {
+        return Ok(());
+    }
+
+    let token = CancellationToken::new();
+    let path = socket_path()?;
+
+    #[cfg(unix)]
+    let listener = {
+        // A socket left over from a previous run would make bind fail
+        let _ = std::fs::remove_file(&path);
+        let listener = tokio::net::UnixListener::bind(&path)
+            .map_err(|e| format!("Failed to bind SSH agent socket {}: {}", path.display(), e))?;
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict SSH agent socket permissions: {}", e))?;
+        listener
+    };
+
+    let listen_token = token.clone();
+    tauri::async_runtime::spawn(async move {
+        #[cfg(unix)]
+        loop {
+            tokio::select! {
+                _ = listen_token.cancelled() => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => {
+                        let queue_manager = queue_manager.clone();
+                        tokio::spawn(async move { serve_client(stream, queue_manager).await });
+                    }
+                    Err(e) => log::warn!("SSH agent accept failed: {}", e),
+                },
+            }
+        }
+
+        #[cfg(windows)]
+        {
+            use tokio::net::windows::named_pipe::ServerOptions;
+            let mut server = match ServerOptions::new().first_pipe_instance(true).create(PIPE_NAME) {
+                Ok(server) => server,
+                Err(e) => {
+                    log::error!("Failed to create SSH agent pipe: {}", e);
+                    return;
+                }
+            };
+            loop {
+                tokio::select! {
+                    _ = listen_token.cancelled() => break,
+                    connected = server.connect() => {
+                        if let Err(e) = connected {
+                            log::warn!("SSH agent pipe connect failed: {}", e);
+                            continue;
+                        }
+                        let next = match ServerOptions::new().create(PIPE_NAME) {
+                            Ok(next) => next,
+                            Err(e) => {
+                                log::error!("Failed to create SSH agent pipe: {}", e);
+                                break;
+                            }
+                        };
+                        let client = std::mem::replace(&mut server, next);
+                        let queue_manager = queue_manager.clone();
+                        tokio::spawn(async move { serve_client(client, queue_manager).await });
+                    }
+                }
+            }
+        }
+
+        log::info!("🔑 SSH agent stopped");
+    });
+
+    log::info!("🔑 SSH agent listening on {}", path.display());
+    *agent = Some(token);
+    Ok(())
+}
+
+/// Stop the agent if it's running
+pub fn stop() {
+    let token = AGENT.lock().ok().and_then(|mut agent| agent.take());
+    if let Some(token) = token {
+        token.cancel();
+        #[cfg(unix)]
+        if let Ok(path) = socket_path() {
+            let _ = std::fs::remove_file(path);
+        }
+    }
+}
+
+/// Answer agent requests on one client connection until it closes
+async fn serve_client<S>(mut stream: S, queue_manager: DeviceQueueManager)
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    loop {
+        let mut len_buf = [0u8; 4];
+        if stream.read_exact(&mut len_buf).await.is_err() {
+            return;
+        }
+        let len = u32::from_be_bytes(len_buf) as usize;
+        if len == 0 || len > MAX_MESSAGE_LEN {
+            log::warn!("SSH agent: rejecting message of {} bytes", len);
+            return;
+        }
+
+        let mut message = vec![0u8; len];
+        if stream.read_exact(&mut message).await.is_err() {
+            return;
+        }
+
+        let reply = match handle_message(&message, &queue_manager).await {
+            Ok(reply) => reply,
+            Err(e) => {
+                log::warn!("SSH agent request failed: {}", e);
+                vec![SSH_AGENT_FAILURE]
+            }
+        };
+
+        let mut framed = (reply.len() as u32).to_be_bytes().to_vec();
+        framed.extend_from_slice(&reply);
+        if stream.write_all(&framed).await.is_err() {
+            return;
+        }
+    }
+}
+
+async fn handle_message(message: &[u8], queue_manager: &DeviceQueueManager) -> Result<Vec<u8>, String> {
+    let config = get_config();
+    let identity = Identity::parse(&config.identity)?;
+
+    match message[0] {
+        SSH_AGENTC_REQUEST_IDENTITIES => {
+            let key_blob = fetch_key_blob(&identity, config.curve, queue_manager).await?;
+            let mut reply = vec![SSH_AGENT_IDENTITIES_ANSWER];
+            reply.extend_from_slice(&1u32.to_be_bytes());
+            put_string(&mut reply, &key_blob);
+            put_string(&mut reply, identity.to_uri().as_bytes());
+            Ok(reply)
+        }
+        SSH_AGENTC_SIGN_REQUEST => {
+            let mut reader = &message[1..];
+            let requested_key = get_string(&mut reader)?;
+            let data = get_string(&mut reader)?;
+
+            let key_blob = fetch_key_blob(&identity, config.curve, queue_manager).await?;
+            if requested_key != key_blob.as_slice() {
+                return Err("Sign request for a key this agent doesn't hold".to_string());
+            }
+
+            let signature = sign_on_device(&identity, config.curve, data, queue_manager).await?;
+            let mut reply = vec![SSH_AGENT_SIGN_RESPONSE];
+            put_string(&mut reply, &signature);
+            Ok(reply)
+        }
+        other => {
+            log::debug!("SSH agent: unsupported message type {}", other);
+            Ok(vec![SSH_AGENT_FAILURE])
+        }
+    }
+}
+
+/// Derive the identity's public key and encode it as an SSH key blob
+async fn fetch_key_blob(identity: &Identity, curve: IdentityCurve, queue_manager: &DeviceQueueManager) -> Result<Vec<u8>, String> {
+    let public_key = crate::identity::derive_public_key(identity, curve.device_curve(), queue_manager).await?;
+    encode_key_blob(curve, &public_key)
+}
+
+fn encode_key_blob(curve: IdentityCurve, public_key: &[u8]) -> Result<Vec<u8>, String> {
+    let mut blob = Vec::new();
+    put_string(&mut blob, key_type(curve).as_bytes());
+    match curve {
+        IdentityCurve::Ed25519 => put_string(&mut blob, crate::identity::ed25519_key_bytes(public_key)?),
+        IdentityCurve::Nistp256 => {
+            put_string(&mut blob, b"nistp256");
+            put_string(&mut blob, &crate::identity::nistp256_uncompressed(public_key)?);
+        }
+    }
+    Ok(blob)
+}
+
+/// Sign the agent challenge on the device and encode it as an SSH signature blob
+async fn sign_on_device(identity: &Identity, curve: IdentityCurve, data: &[u8], queue_manager: &DeviceQueueManager) -> Result<Vec<u8>, String> {
+    let signature = crate::identity::sign(identity, curve.device_curve(), data, queue_manager).await?;
+
+    let mut blob = Vec::new();
+    put_string(&mut blob, key_type(curve).as_bytes());
+    match curve {
+        IdentityCurve::Ed25519 => put_string(&mut blob, &signature),
+        IdentityCurve::Nistp256 => {
+            let mut inner = Vec::new();
+            put_mpint(&mut inner, &signature[..32]);
+            put_mpint(&mut inner, &signature[32..]);
+            put_string(&mut blob, &inner);
+        }
+    }
+    Ok(blob)
+}
+
+fn put_string(buf: &mut Vec<u8>, value: &[u8]) {
+    buf.extend_from_slice(&(value.len() as u32).to_be_bytes());
+    buf.extend_from_slice(value);
+}
+
+fn put_mpint(buf: &mut Vec<u8>, value: &[u8]) {
+    let trimmed: Vec<u8> = value.iter().copied().skip_while(|b| *b == 0).collect();
+    let mut encoded = Vec::with_capacity(trimmed.len() + 1);
+    if trimmed.first().map(|b| b & 0x80 != 0).unwrap_or(false) {
+        encoded.push(0);
+    }
+    encoded.extend_from_slice(&trimmed);
+    put_string(buf, &encoded);
+}
+
+fn get_string<'a>(reader: &mut &'a [u8]) -> Result<&'a [u8], String> {
+    if reader.len() < 4 {
+        return Err("Truncated agent message".to_string());
+    }
+    let len = u32::from_be_bytes([reader[0], reader[1], reader[2], reader[3]]) as usize;
+    if reader.len() < 4 + len {
+        return Err("Truncated agent message".to_string());
+    }
+    let value = &reader[4..4 + len];
+    *reader = &reader[4 + len..];
+    Ok(value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mpint_encoding() {
+        let mut buf = Vec::new();
+        put_mpint(&mut buf, &[0x00, 0x80, 0x01]);
+        assert_eq!(buf, vec![0, 0, 0, 3, 0x00, 0x80, 0x01]);
+
+        let mut reader: &[u8] = &buf;
+        assert_eq!(get_string(&mut reader).unwrap(), &[0x00, 0x80, 0x01]);
+        assert!(reader.is_empty());
+    }
+}
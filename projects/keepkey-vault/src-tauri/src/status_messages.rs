@@ -0,0 +1,124 @@
+use std::collections::HashMap;
+use serde::Serialize;
+
+/// Locale used when the preferred one has no translation
+const FALLBACK_LOCALE: &str = "en";
+
+/// Locale -> message code -> template with `{param}` placeholders
+type Catalog = HashMap<String, HashMap<String, String>>;
+
+lazy_static::lazy_static! {
+    static ref CATALOG: Catalog = serde_json::from_str(include_str!("../status-messages.json"))
+        .unwrap_or_else(|e| {
+            log::error!("Failed to parse bundled status-messages.json: {}", e);
+            HashMap::new()
+        });
+}
+
+/// Stable codes for backend status messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StatusCode {
+    Scanning,
+    DeviceFound,
+    GettingFeatures,
+    DeviceInfo,
+    DeviceReady,
+    BootloaderModeUpdateNeeded,
+    BootloaderModeRebootNeeded,
+    PinLocked,
+    UpdatesNeeded,
+    BootloaderUpdateNeeded,
+    FirmwareUpdateNeeded,
+    SetupNeeded,
+    DeviceTimeout,
+    DeviceDisconnected,
+}
+
+impl StatusCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatusCode::Scanning => "scanning",
+            StatusCode::DeviceFound => "device_found",
+            StatusCode::GettingFeatures => "getting_features",
+            StatusCode::DeviceInfo => "device_info",
+            StatusCode::DeviceReady => "device_ready",
+            StatusCode::BootloaderModeUpdateNeeded => "bootloader_mode_update_needed",
+            StatusCode::BootloaderModeRebootNeeded => "bootloader_mode_reboot_needed",
+            StatusCode::PinLocked => "pin_locked",
+            StatusCode::UpdatesNeeded => "updates_needed",
+            StatusCode::BootloaderUpdateNeeded => "bootloader_update_needed",
+            StatusCode::FirmwareUpdateNeeded => "firmware_update_needed",
+            StatusCode::SetupNeeded => "setup_needed",
+            StatusCode::DeviceTimeout => "device_timeout",
+            StatusCode::DeviceDisconnected => "device_disconnected",
+        }
+    }
+}
+
+/// The user's locale from the "language" preference
+pub fn current_locale() -> String {
+    crate::preferences::get_as::<String>("language")
+        .unwrap_or_else(|| FALLBACK_LOCALE.to_string())
+}
+
+/// Render a message in the given locale, falling back to English
+pub fn translate(code: StatusCode, locale: &str, params: &[(&str, &str)]) -> String {
+    let template = CATALOG
+        .get(locale)
+        .and_then(|messages| messages.get(code.as_str()))
+        .or_else(|| CATALOG.get(FALLBACK_LOCALE).and_then(|messages| messages.get(code.as_str())))
+        .map(|s| s.as_str())
+        .unwrap_or_else(|| code.as_str());
+
+    params.iter().fold(template.to_string(), |message, (name, value)| {
+        message.replace(&format!("{{{}}}", name), value)
+    })
+}
+
+/// Build a status:update payload carrying the code, its params and the localized text
+pub fn status_payload(code: StatusCode, params: &[(&str, &str)]) -> serde_json::Value {
+    let locale = current_locale();
+    let params_map: HashMap<&str, &str> = params.iter().copied().collect();
+
+    serde_json::json!({
+        "status": translate(code, &locale, params),
+        "code": code,
+        "params": params_map,
+        "locale": locale,
+    })
+}
+
+/// Templates for a locale, with English filling any gaps
+pub fn catalog_for(locale: &str) -> HashMap<String, String> {
+    let mut messages = CATALOG.get(FALLBACK_LOCALE).cloned().unwrap_or_default();
+    if let Some(localized) = CATALOG.get(locale) {
+        messages.extend(localized.clone());
+    }
+    messages
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_translate_with_fallback() {
+        assert_eq!(translate(StatusCode::DeviceReady, "de", &[]), "Gerät bereit");
+        assert_eq!(translate(StatusCode::DeviceReady, "ko", &[]), "Device ready");
+        assert_eq!(
+            translate(StatusCode::DeviceInfo, "es", &[("label", "KeepKey"), ("version", "7.10.0")]),
+            "KeepKey v7.10.0"
+        );
+    }
+
+    #[test]
+    fn test_catalog_covers_every_code_in_english() {
+        let english = catalog_for(FALLBACK_LOCALE);
+        for locale in CATALOG.keys() {
+            for code in catalog_for(locale).keys() {
+                assert!(english.contains_key(code), "{} has unknown code {}", locale, code);
+            }
+        }
+    }
+}
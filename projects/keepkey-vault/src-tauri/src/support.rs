@@ -0,0 +1,222 @@
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+
+/// Help center form opened when no support backend is configured
+const SUPPORT_FORM_URL: &str = "https://support.keepkey.com/hc/en-us/requests/new";
+
+/// Preference key with the URL of a support backend accepting JSON tickets
+const SUPPORT_ENDPOINT_KEY: &str = "support_endpoint";
+
+/// Lines of today's device log included in a diagnostic bundle
+const LOG_TAIL_LINES: usize = 500;
+
+/// What the user wants help with
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportRequest {
+    pub subject: String,
+    pub description: String,
+    #[serde(default)]
+    pub email: Option<String>,
+    /// Opt-in: attach device logs and non-sensitive preferences
+    #[serde(default)]
+    pub include_diagnostics: bool,
+}
+
+/// Result of a support request, shown to the user
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SupportTicket {
+    /// Ticket id from the support backend, or a local reference to quote in the form
+    pub reference: String,
+    /// Whether the ticket was submitted directly (otherwise a prefilled form was opened)
+    pub submitted: bool,
+    pub form_url: Option<String>,
+    /// Where the diagnostic bundle was saved, if one was collected
+    pub diagnostics_path: Option<String>,
+}
+
+/// Device, firmware and app details attached to every request
+pub fn collect_metadata() -> serde_json::Value {
+    let devices: Vec<_> = keepkey_rust::features::list_connected_devices()
+        .into_iter()
+        .map(|device| {
+            let capabilities = crate::device::capabilities::cached_capabilities(&device.unique_id);
+            serde_json::json!({
+                "deviceId": device.unique_id,
+                "product": device.product,
+                "model": capabilities.as_ref().and_then(|c| c.model.clone()),
+                "firmwareVersion": capabilities.as_ref().map(|c| c.firmware_version.clone()),
+                "bootloaderMode": capabilities.as_ref().map(|c| c.bootloader_mode),
+                "customFirmware": crate::device::custom_firmware::record_for(&device.unique_id),
+            })
+        })
+        .collect();
+
+    serde_json::json!({
+        "appVersion": env!("CARGO_PKG_VERSION"),
+        "os": std::env::consts::OS,
+        "arch": std::env::consts::ARCH,
+        "locale": crate::status_messages::current_locale(),
+        "devices": devices,
+        "power": crate::power::status(),
+    })
+}
+
+fn support_dir() -> Result<PathBuf, String> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| "Could not find home directory".to_string())?;
+
+    let dir = home_dir.join(".keepkey").join("support");
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create support directory: {}", e))?;
+
+    Ok(dir)
+}
+
+/// Write a diagnostic bundle (metadata, recent device log, non-sensitive preferences)
+fn write_diagnostics(reference: &str, metadata: &serde_json::Value) -> Result<PathBuf, String> {
+    let log_path = crate::logging::get_device_logger().get_todays_log_path();
+    let log_tail: Vec<String> = std::fs::read_to_string(&log_path)
+        .unwrap_or_default()
+        .lines()
+        .rev()
+        .take(LOG_TAIL_LINES)
+        .map(String::from)
+        .collect::<Vec<_>>()
+        .into_iter()
+        .rev()
+        .collect();
+
+    let bundle = serde_json::json!({
+        "reference": reference,
+        "createdAt": chrono::Utc::now().to_rfc3339(),
+        "metadata": metadata,
+        "preferences": crate::settings_backup::exportable_preferences(&crate::commands::load_config()?),
+        "deviceLog": log_tail,
+    });
+
+    let path = support_dir()?.join(format!("diagnostics-{}.json", reference));
+    let contents = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    std::fs::write(&path, contents)
+        .map_err(|e| format!("Failed to write diagnostic bundle: {}", e))?;
+    Ok(path)
+}
+
+fn describe(request: &SupportRequest, reference: &str, metadata: &serde_json::Value) -> String {
+    format!(
+        "{}\n\n---\nReference: {}\nApp: {} ({}/{})\nDevices: {}",
+        request.description,
+        reference,
+        metadata["appVersion"].as_str().unwrap_or("unknown"),
+        metadata["os"].as_str().unwrap_or("unknown"),
+        metadata["arch"].as_str().unwrap_or("unknown"),
+        metadata["devices"],
+    )
+}
+
+/// Prefilled help center form for a request
+fn form_url(request: &SupportRequest, description: &str) -> String {
+    let mut url = url::Url::parse(SUPPORT_FORM_URL).expect("support form URL is valid");
+    {
+        let mut query = url.query_pairs_mut();
+        query.append_pair("tf_subject", &request.subject);
+        query.append_pair("tf_description", description);
+        if let Some(email) = &request.email {
+            query.append_pair("tf_anonymous_requester_email", email);
+        }
+    }
+    url.to_string()
+}
+
+async fn submit(endpoint: &str, request: &SupportRequest, description: &str, metadata: &serde_json::Value, diagnostics: Option<&PathBuf>) -> Result<String, String> {
+    let diagnostics = match diagnostics {
+        Some(path) => Some(serde_json::from_str::<serde_json::Value>(&std::fs::read_to_string(path).map_err(|e| e.to_string())?)
+            .map_err(|e| e.to_string())?),
+        None => None,
+    };
+
+    let response = reqwest::Client::new()
+        .post(endpoint)
+        .timeout(std::time::Duration::from_secs(30))
+        .json(&serde_json::json!({
+            "subject": request.subject,
+            "description": description,
+            "email": request.email,
+            "metadata": metadata,
+            "diagnostics": diagnostics,
+        }))
+        .send()
+        .await
+        .map_err(|e| format!("Failed to reach support backend: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Support backend returned HTTP {}", response.status()));
+    }
+
+    let body: serde_json::Value = response.json().await.unwrap_or_default();
+    body.get("ticketId")
+        .or_else(|| body.get("id"))
+        .map(|id| id.as_str().map(String::from).unwrap_or_else(|| id.to_string()))
+        .ok_or_else(|| "Support backend did not return a ticket id".to_string())
+}
+
+/// Collect metadata (and diagnostics if opted in), then submit to the support
+/// backend if one is configured or fall back to a prefilled form
+pub async fn create_ticket(request: SupportRequest) -> Result<SupportTicket, String> {
+    if request.subject.trim().is_empty() || request.description.trim().is_empty() {
+        return Err("Subject and description are required".to_string());
+    }
+
+    let reference = format!("KKV-{}", &uuid::Uuid::new_v4().simple().to_string()[..8].to_uppercase());
+    let metadata = collect_metadata();
+    let diagnostics_path = if request.include_diagnostics {
+        Some(write_diagnostics(&reference, &metadata)?)
+    } else {
+        None
+    };
+    let description = describe(&request, &reference, &metadata);
+
+    let endpoint = crate::preferences::get_as::<String>(SUPPORT_ENDPOINT_KEY);
+    if let Some(endpoint) = endpoint {
+        match submit(&endpoint, &request, &description, &metadata, diagnostics_path.as_ref()).await {
+            Ok(ticket_id) => {
+                log::info!("🎫 Support ticket {} created (ref {})", ticket_id, reference);
+                return Ok(SupportTicket {
+                    reference: ticket_id,
+                    submitted: true,
+                    form_url: None,
+                    diagnostics_path: diagnostics_path.map(|p| p.display().to_string()),
+                });
+            }
+            // Fall back to the form so the user can still reach support
+            Err(e) => log::warn!("Support submission failed, opening form instead: {}", e),
+        }
+    }
+
+    Ok(SupportTicket {
+        reference,
+        submitted: false,
+        form_url: Some(form_url(&request, &description)),
+        diagnostics_path: diagnostics_path.map(|p| p.display().to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_form_url_is_prefilled() {
+        let request = SupportRequest {
+            subject: "Stuck on update".to_string(),
+            description: "It hangs".to_string(),
+            email: Some("a@b.c".to_string()),
+            include_diagnostics: false,
+        };
+        let url = form_url(&request, "It hangs\n\nReference: KKV-1");
+
+        assert!(url.starts_with(SUPPORT_FORM_URL));
+        assert!(url.contains("tf_subject=Stuck+on+update"));
+        assert!(url.contains("tf_anonymous_requester_email=a%40b.c"));
+    }
+}
@@ -0,0 +1,327 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::cache::{CacheManager, UtxoTag};
+use crate::reconciliation::ReconciliationPreferences;
+
+/// Indexer used by the frontend tx builder; the vault lists the same UTXO set
+const PIONEER_BASE_URL: &str = "https://pioneers.dev";
+
+/// Most keys accepted by one balance query
+const MAX_BALANCE_KEYS: usize = 20;
+
+/// Unspent output as returned by the indexer
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexerUtxo {
+    txid: String,
+    vout: u32,
+    value: serde_json::Value,
+    address: Option<String>,
+    confirmations: Option<u64>,
+    path: Option<String>,
+}
+
+/// One UTXO of a device account, with its label and freeze flag
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Utxo {
+    pub txid: String,
+    pub vout: u32,
+    /// Value in satoshis
+    pub value: u64,
+    pub address: Option<String>,
+    pub confirmations: Option<u64>,
+    pub path: Option<String>,
+    /// Account-level derivation path the UTXO was found under
+    pub account_path: String,
+    pub script_type: Option<String>,
+    pub label: Option<String>,
+    pub frozen: bool,
+    /// Whether the confirmation threshold for Bitcoin has been reached
+    pub finalized: bool,
+}
+
+/// Label and/or freeze flag for a UTXO; omitted fields are left unchanged
+#[derive(Debug, Clone, Default, Deserialize, ToSchema)]
+pub struct UtxoTagUpdate {
+    /// New label; an empty string clears it
+    pub label: Option<String>,
+    pub frozen: Option<bool>,
+}
+
+/// Keys to look up balances for, without a device
+#[derive(Debug, Clone, Deserialize, ToSchema)]
+pub struct XpubBalanceRequest {
+    /// Extended public keys (xpub/ypub/zpub) or output descriptors containing one
+    pub keys: Vec<String>,
+    /// Also check each balance against a second provider; defaults to the
+    /// `balance_reconciliation` preference
+    #[serde(default)]
+    pub verify: Option<bool>,
+}
+
+/// Balance of one key; `error` is set when it couldn't be looked up
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct XpubBalance {
+    /// The key or descriptor as given
+    pub key: String,
+    pub xpub: Option<String>,
+    /// Total in satoshis
+    pub balance: u64,
+    /// Satoshis in outputs with at least one confirmation
+    pub confirmed_balance: u64,
+    pub utxo_count: usize,
+    pub error: Option<String>,
+    /// Comparison with the second provider, when verification is on
+    pub reconciliation: Option<crate::reconciliation::BalanceReconciliation>,
+}
+
+async fn fetch_unspent(xpub: &str) -> Result<Vec<IndexerUtxo>, String> {
+    const ENDPOINT: &str = "/api/v1/listUnspent/BTC";
+    let started = std::time::Instant::now();
+    let record = |status: Option<u16>, bytes: usize, error: Option<String>| {
+        crate::provider_usage::record(
+            crate::provider_usage::PIONEER,
+            ENDPOINT,
+            status,
+            bytes as u64,
+            started.elapsed().as_millis() as u64,
+            error,
+        )
+    };
+
+    let url = format!("{}{}/{}", PIONEER_BASE_URL, ENDPOINT, xpub);
+    let mut request = reqwest::Client::new()
+        .get(&url)
+        .header("accept", "application/json")
+        .timeout(std::time::Duration::from_secs(30));
+    if let Some(key) = crate::secrets::pioneer_api_key() {
+        request = request.header("authorization", key);
+    }
+    let response = match request.send().await
+    {
+        Ok(response) => response,
+        Err(e) => {
+            let error = format!("Failed to list unspent outputs: {}", e);
+            record(None, 0, Some(error.clone())).await;
+            return Err(error);
+        }
+    };
+
+    let status = response.status();
+    let body = match response.bytes().await {
+        Ok(body) => body,
+        Err(e) => {
+            let error = format!("Failed to read unspent outputs: {}", e);
+            record(Some(status.as_u16()), 0, Some(error.clone())).await;
+            return Err(error);
+        }
+    };
+    if !status.is_success() {
+        record(Some(status.as_u16()), body.len(), None).await;
+        return Err(format!("Indexer returned {}", status));
+    }
+
+    let parsed = serde_json::from_slice(&body).map_err(|e| format!("Invalid unspent output list: {}", e));
+    record(Some(status.as_u16()), body.len(), parsed.as_ref().err().cloned()).await;
+    parsed
+}
+
+/// The indexer sends values as strings or numbers
+fn parse_value(value: &serde_json::Value) -> u64 {
+    match value {
+        serde_json::Value::Number(n) => n.as_u64().unwrap_or(0),
+        serde_json::Value::String(s) => s.parse().unwrap_or(0),
+        _ => 0,
+    }
+}
+
+/// The extended key inside a bare key or an output descriptor like `wpkh([fp/84'/0'/0']xpub.../0/*)`
+fn extract_xpub(key: &str) -> Option<&str> {
+    key.split(|c: char| !c.is_ascii_alphanumeric())
+        .find(|token| token.len() > 100 && token.get(1..4) == Some("pub"))
+}
+
+async fn xpub_balance(key: &str, verify: Option<&ReconciliationPreferences>) -> XpubBalance {
+    let mut balance = XpubBalance {
+        key: key.to_string(),
+        xpub: None,
+        balance: 0,
+        confirmed_balance: 0,
+        utxo_count: 0,
+        error: None,
+        reconciliation: None,
+    };
+
+    let xpub = match extract_xpub(key) {
+        Some(xpub) => xpub,
+        None => {
+            balance.error = Some("No extended public key found".to_string());
+            return balance;
+        }
+    };
+    balance.xpub = Some(xpub.to_string());
+    if let Err(e) = crate::wallets::validate_xpub(xpub) {
+        balance.error = Some(e);
+        return balance;
+    }
+
+    let (unspent, secondary) = match verify {
+        Some(preferences) => {
+            let (unspent, secondary) = tokio::join!(fetch_unspent(xpub), crate::reconciliation::fetch_balance(preferences, xpub));
+            (unspent, Some(secondary))
+        }
+        None => (fetch_unspent(xpub).await, None),
+    };
+
+    match unspent {
+        Ok(outputs) => {
+            for output in &outputs {
+                let value = parse_value(&output.value);
+                balance.balance += value;
+                if output.confirmations.unwrap_or(0) > 0 {
+                    balance.confirmed_balance += value;
+                }
+            }
+            balance.utxo_count = outputs.len();
+            if let (Some(preferences), Some(secondary)) = (verify, secondary) {
+                balance.reconciliation = Some(crate::reconciliation::reconcile(preferences, xpub, balance.balance, secondary));
+            }
+        }
+        Err(e) => balance.error = Some(e),
+    }
+    balance
+}
+
+/// Look up balances of keys held elsewhere, e.g. to check a backup without restoring it
+pub async fn xpub_balances(request: XpubBalanceRequest) -> Result<Vec<XpubBalance>, String> {
+    if request.keys.is_empty() {
+        return Err("No keys given".to_string());
+    }
+    if request.keys.len() > MAX_BALANCE_KEYS {
+        return Err(format!("At most {} keys per request", MAX_BALANCE_KEYS));
+    }
+
+    let preferences = crate::reconciliation::get_preferences();
+    let verify = request.verify.unwrap_or(preferences.enabled).then_some(&preferences);
+
+    let mut balances = Vec::with_capacity(request.keys.len());
+    for key in &request.keys {
+        balances.push(xpub_balance(key.trim(), verify).await);
+    }
+    Ok(balances)
+}
+
+/// List the UTXOs of every cached Bitcoin account of a device
+pub async fn list_utxos(cache: &CacheManager, device_id: &str) -> Result<Vec<Utxo>, String> {
+    let accounts: Vec<_> = cache
+        .list_cached_pubkeys(device_id)
+        .await
+        .map_err(|e| format!("Failed to read cached accounts: {}", e))?
+        .into_iter()
+        .filter(|pubkey| pubkey.coin_name.eq_ignore_ascii_case("bitcoin") && pubkey.xpub.is_some())
+        .collect();
+
+    if accounts.is_empty() {
+        return Err(format!("No cached Bitcoin accounts for device {}", device_id));
+    }
+
+    let tags = cache
+        .list_utxo_tags(device_id)
+        .await
+        .map_err(|e| format!("Failed to read UTXO tags: {}", e))?;
+
+    let mut utxos = Vec::new();
+    for account in accounts {
+        let xpub = account.xpub.as_deref().unwrap_or_default();
+        let unspent_outputs = match fetch_unspent(xpub).await {
+            Ok(outputs) => outputs,
+            Err(e) => {
+                crate::error_center::record(crate::error_center::ErrorCategory::Provider, Some(device_id), e.clone()).await;
+                return Err(e);
+            }
+        };
+        for unspent in unspent_outputs {
+            let finalized = crate::confirmations::observe(
+                device_id,
+                crate::confirmations::BITCOIN_NETWORK,
+                &unspent.txid,
+                unspent.confirmations.unwrap_or(0),
+            ).await;
+            let tag = tags.iter().find(|t| t.txid.eq_ignore_ascii_case(&unspent.txid) && t.vout == unspent.vout);
+            utxos.push(Utxo {
+                value: parse_value(&unspent.value),
+                txid: unspent.txid,
+                vout: unspent.vout,
+                address: unspent.address,
+                confirmations: unspent.confirmations,
+                path: unspent.path,
+                account_path: account.derivation_path.clone(),
+                script_type: account.script_type.clone(),
+                label: tag.and_then(|t| t.label.clone()),
+                frozen: tag.map(|t| t.frozen).unwrap_or(false),
+                finalized,
+            });
+        }
+    }
+
+    Ok(utxos)
+}
+
+/// Apply a label/freeze update to one UTXO
+pub async fn tag_utxo(cache: &CacheManager, device_id: &str, txid: &str, vout: u32, update: UtxoTagUpdate) -> Result<UtxoTag, String> {
+    if txid.len() != 64 || !txid.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(format!("Invalid txid: {}", txid));
+    }
+    let txid = txid.to_lowercase();
+
+    let existing = cache
+        .list_utxo_tags(device_id)
+        .await
+        .map_err(|e| format!("Failed to read UTXO tags: {}", e))?
+        .into_iter()
+        .find(|t| t.txid == txid && t.vout == vout);
+
+    let label = match update.label {
+        Some(label) if label.trim().is_empty() => None,
+        Some(label) => Some(label.trim().to_string()),
+        None => existing.as_ref().and_then(|t| t.label.clone()),
+    };
+
+    let tag = UtxoTag {
+        device_id: device_id.to_string(),
+        txid,
+        vout,
+        label,
+        frozen: update.frozen.unwrap_or_else(|| existing.map(|t| t.frozen).unwrap_or(false)),
+        updated_at: chrono::Utc::now().timestamp(),
+    };
+
+    cache
+        .save_utxo_tag(&tag)
+        .await
+        .map_err(|e| format!("Failed to save UTXO tag: {}", e))?;
+    Ok(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(parse_value(&serde_json::json!("12345")), 12345);
+        assert_eq!(parse_value(&serde_json::json!(678)), 678);
+        assert_eq!(parse_value(&serde_json::json!(null)), 0);
+    }
+
+    #[test]
+    fn test_extract_xpub() {
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        assert_eq!(extract_xpub(xpub), Some(xpub));
+        assert_eq!(extract_xpub(&format!("wpkh([d34db33f/84'/0'/0']{}/0/*)", xpub)), Some(xpub));
+        assert_eq!(extract_xpub("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"), None);
+    }
+}
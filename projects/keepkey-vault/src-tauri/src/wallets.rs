@@ -0,0 +1,238 @@
+use base58::FromBase58;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Preference key holding the configured wallets
+const WALLETS_PREFERENCE_KEY: &str = "wallets";
+
+/// Serialized BIP-32 extended key length (before the 4-byte checksum)
+const EXTENDED_KEY_LEN: usize = 78;
+
+/// An extended public key tracked without a device
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchOnlyXpub {
+    pub label: String,
+    /// Coin the key belongs to, e.g. "Bitcoin"
+    pub coin_name: String,
+    pub xpub: String,
+    /// Account derivation path, if known (e.g. "m/84'/0'/0'")
+    #[serde(default)]
+    pub derivation_path: Option<String>,
+    #[serde(default)]
+    pub script_type: Option<String>,
+}
+
+/// A named group of devices and watch-only keys
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Wallet {
+    /// Assigned on creation when empty
+    #[serde(default)]
+    pub id: String,
+    pub name: String,
+    #[serde(default)]
+    pub device_ids: Vec<String>,
+    #[serde(default)]
+    pub watch_only: Vec<WatchOnlyXpub>,
+}
+
+/// A device in a wallet and whether it's plugged in
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletDevice {
+    pub device_id: String,
+    pub label: Option<String>,
+    pub connected: bool,
+    /// Other devices holding the same seed; their accounts are only listed once
+    pub shares_seed_with: Vec<String>,
+}
+
+/// One account (xpub or address) known for a wallet
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletAccount {
+    /// Device ID the account was derived from, or None for watch-only keys
+    pub device_id: Option<String>,
+    pub label: Option<String>,
+    /// User-chosen account name, if any
+    pub name: Option<String>,
+    pub coin_name: String,
+    pub derivation_path: Option<String>,
+    pub script_type: Option<String>,
+    pub xpub: Option<String>,
+    pub address: Option<String>,
+}
+
+/// A wallet with its devices and accounts aggregated
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WalletSummary {
+    #[serde(flatten)]
+    pub wallet: Wallet,
+    pub devices: Vec<WalletDevice>,
+    pub accounts: Vec<WalletAccount>,
+}
+
+/// Load all configured wallets from preferences
+pub fn get_wallets() -> Vec<Wallet> {
+    crate::preferences::get_as(WALLETS_PREFERENCE_KEY).unwrap_or_default()
+}
+
+/// Find a wallet by id
+pub fn find_wallet(wallet_id: &str) -> Option<Wallet> {
+    get_wallets().into_iter().find(|w| w.id == wallet_id)
+}
+
+fn save_wallets(wallets: &[Wallet]) -> Result<(), String> {
+    crate::preferences::set(WALLETS_PREFERENCE_KEY, serde_json::json!(wallets))
+}
+
+/// Check that a string is a well-formed base58check extended public key
+pub(crate) fn validate_xpub(xpub: &str) -> Result<(), String> {
+    let bytes = xpub.from_base58().map_err(|_| format!("Invalid base58 in xpub: {}", xpub))?;
+    if bytes.len() != EXTENDED_KEY_LEN + 4 {
+        return Err(format!("Not an extended public key: {}", xpub));
+    }
+
+    let (payload, checksum) = bytes.split_at(EXTENDED_KEY_LEN);
+    let hash = crate::slip132::sha256d(payload);
+    if &hash[..4] != checksum {
+        return Err(format!("Bad checksum in xpub: {}", xpub));
+    }
+
+    // Private keys start with a zero byte in the key data field
+    if payload[45] == 0 {
+        return Err("Watch-only wallets take extended public keys, not private keys".to_string());
+    }
+
+    Ok(())
+}
+
+fn validate_wallet(wallet: &Wallet) -> Result<(), String> {
+    if wallet.name.trim().is_empty() {
+        return Err("Wallet name is required".to_string());
+    }
+    for watch_only in &wallet.watch_only {
+        validate_xpub(&watch_only.xpub)?;
+    }
+    Ok(())
+}
+
+/// Add a wallet, or replace the existing wallet with the same id
+pub fn save_wallet(mut wallet: Wallet) -> Result<Wallet, String> {
+    if wallet.id.is_empty() {
+        wallet.id = uuid::Uuid::new_v4().to_string();
+    }
+    validate_wallet(&wallet)?;
+
+    // A device belongs to at most one wallet
+    let mut wallets = get_wallets();
+    for other in wallets.iter_mut().filter(|w| w.id != wallet.id) {
+        other.device_ids.retain(|id| !wallet.device_ids.contains(id));
+    }
+
+    match wallets.iter_mut().find(|w| w.id == wallet.id) {
+        Some(existing) => *existing = wallet.clone(),
+        None => wallets.push(wallet.clone()),
+    }
+
+    save_wallets(&wallets)?;
+    log::info!("👛 Saved wallet {} ({} devices, {} watch-only)", wallet.name, wallet.device_ids.len(), wallet.watch_only.len());
+    Ok(wallet)
+}
+
+/// Remove a wallet by id; its devices are not affected
+pub fn remove_wallet(wallet_id: &str) -> Result<bool, String> {
+    let mut wallets = get_wallets();
+    let before = wallets.len();
+    wallets.retain(|w| w.id != wallet_id);
+    let removed = wallets.len() != before;
+    if removed {
+        save_wallets(&wallets)?;
+    }
+    Ok(removed)
+}
+
+fn is_same_account(account: &WalletAccount, pubkey: &crate::cache::CachedPubkey) -> bool {
+    account.coin_name == pubkey.coin_name
+        && account.derivation_path.as_deref() == Some(pubkey.derivation_path.as_str())
+        && (account.xpub.is_some() || account.address.is_some())
+        && account.xpub == pubkey.xpub
+        && account.address == pubkey.address
+}
+
+/// Aggregate a wallet's devices and cached accounts
+pub async fn summarize(wallet: Wallet, cache: Option<&crate::cache::CacheManager>) -> WalletSummary {
+    let connected = tokio::task::spawn_blocking(keepkey_rust::features::list_connected_devices)
+        .await
+        .unwrap_or_default();
+
+    let duplicates = match cache {
+        Some(cache) => crate::device::duplicate_seeds::find_duplicates(cache).await.unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let mut devices = Vec::new();
+    let mut accounts: Vec<WalletAccount> = Vec::new();
+
+    for device_id in &wallet.device_ids {
+        let mut label = None;
+        if let Some(cache) = cache {
+            label = cache.get_cache_metadata(device_id).await.and_then(|m| m.label);
+            let names = crate::accounts::names(cache, device_id).await;
+
+            for pubkey in cache.list_cached_pubkeys(device_id).await.unwrap_or_default() {
+                // A device sharing a seed with one already listed would double count
+                if accounts.iter().any(|a| is_same_account(a, &pubkey)) {
+                    continue;
+                }
+                accounts.push(WalletAccount {
+                    device_id: Some(device_id.clone()),
+                    label: label.clone(),
+                    name: names.get(&pubkey.derivation_path).cloned(),
+                    coin_name: pubkey.coin_name,
+                    derivation_path: Some(pubkey.derivation_path),
+                    script_type: pubkey.script_type,
+                    xpub: pubkey.xpub,
+                    address: pubkey.address,
+                });
+            }
+        }
+
+        devices.push(WalletDevice {
+            device_id: device_id.clone(),
+            label,
+            connected: connected.iter().any(|d| &d.unique_id == device_id),
+            shares_seed_with: crate::device::duplicate_seeds::shared_with(&duplicates, device_id),
+        });
+    }
+
+    for watch_only in &wallet.watch_only {
+        accounts.push(WalletAccount {
+            device_id: None,
+            label: Some(watch_only.label.clone()),
+            name: None,
+            coin_name: watch_only.coin_name.clone(),
+            derivation_path: watch_only.derivation_path.clone(),
+            script_type: watch_only.script_type.clone(),
+            xpub: Some(watch_only.xpub.clone()),
+            address: None,
+        });
+    }
+
+    WalletSummary { wallet, devices, accounts }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_xpub() {
+        // BIP-32 test vector 1, chain m
+        assert!(validate_xpub("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8").is_ok());
+        assert!(validate_xpub("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet9").is_err());
+        assert!(validate_xpub("xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi").is_err());
+    }
+}
@@ -0,0 +1,164 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+/// Label of the window whose title shows the summary
+const MAIN_WINDOW: &str = "main";
+
+/// Title used when no summary is shown
+const BASE_TITLE: &str = "KeepKey Vault";
+
+/// Preference key toggling the summary
+const PREFERENCE_KEY: &str = "portfolio_in_title";
+
+/// Preference key choosing USD, BTC or sats for portfolio totals
+const DENOMINATION_KEY: &str = "portfolio_denomination";
+
+/// The change shown is relative to the first total seen in this window
+const BASELINE_WINDOW_SECS: i64 = 24 * 60 * 60;
+
+/// Portfolio value in USD, and in BTC when a BTC price was known
+#[derive(Debug, Clone, Copy)]
+struct Totals {
+    usd: f64,
+    btc: Option<f64>,
+}
+
+/// First totals of the current baseline window and when they were seen
+#[derive(Debug, Clone, Copy)]
+struct Baseline {
+    at: i64,
+    totals: Totals,
+}
+
+/// Unit portfolio totals are shown in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Denomination {
+    Usd,
+    Btc,
+    Sats,
+}
+
+lazy_static::lazy_static! {
+    static ref BASELINE: Mutex<Option<Baseline>> = Mutex::new(None);
+    /// Last totals pushed by the frontend, re-applied when a preference changes
+    static ref LAST_TOTALS: Mutex<Option<Totals>> = Mutex::new(None);
+}
+
+pub fn is_enabled() -> bool {
+    crate::preferences::get_as(PREFERENCE_KEY).unwrap_or(false)
+}
+
+fn denomination() -> Denomination {
+    match crate::preferences::get_as::<String>(DENOMINATION_KEY).as_deref() {
+        Some("btc") => Denomination::Btc,
+        Some("sats") => Denomination::Sats,
+        _ => Denomination::Usd,
+    }
+}
+
+/// Re-apply the title whenever the preferences are toggled
+pub fn init(app: AppHandle) {
+    let mut changes = crate::preferences::subscribe();
+    tauri::async_runtime::spawn(async move {
+        while let Ok(change) = changes.recv().await {
+            if change.key == PREFERENCE_KEY || change.key == DENOMINATION_KEY {
+                apply(&app);
+            }
+        }
+    });
+}
+
+/// Record the latest portfolio total and refresh the window title. With a
+/// BTC price the total can also be shown in BTC or sats.
+pub fn update_portfolio_total(app: &AppHandle, total_usd: f64, btc_price_usd: Option<f64>) -> Result<String, String> {
+    if !total_usd.is_finite() || total_usd < 0.0 {
+        return Err("Portfolio total must be a non-negative number".to_string());
+    }
+    let totals = Totals {
+        usd: total_usd,
+        btc: btc_price_usd.filter(|price| price.is_finite() && *price > 0.0).map(|price| total_usd / price),
+    };
+
+    let now = chrono::Utc::now().timestamp();
+    {
+        let mut baseline = BASELINE.lock().unwrap();
+        match *baseline {
+            Some(b) if now - b.at < BASELINE_WINDOW_SECS => {}
+            _ => *baseline = Some(Baseline { at: now, totals }),
+        }
+    }
+
+    *LAST_TOTALS.lock().unwrap() = Some(totals);
+    apply(app);
+    Ok(current_summary().unwrap_or_default())
+}
+
+/// Summary of the last totals in the preferred denomination
+fn current_summary() -> Option<String> {
+    let totals = (*LAST_TOTALS.lock().unwrap())?;
+    let baseline = BASELINE.lock().unwrap().map(|b| b.totals);
+    Some(match (denomination(), totals.btc) {
+        // Without a BTC price the summary falls back to USD
+        (Denomination::Usd, _) | (_, None) => format_summary(totals.usd, baseline.map(|b| b.usd), Denomination::Usd),
+        (denomination, Some(btc)) => format_summary(btc, baseline.and_then(|b| b.btc), denomination),
+    })
+}
+
+fn apply(app: &AppHandle) {
+    let Some(window) = app.get_webview_window(MAIN_WINDOW) else {
+        return;
+    };
+    let title = match current_summary() {
+        Some(summary) if is_enabled() => format!("{} — {}", BASE_TITLE, summary),
+        _ => BASE_TITLE.to_string(),
+    };
+    if let Err(e) = window.set_title(&title) {
+        log::warn!("Failed to set window title: {}", e);
+    }
+}
+
+/// "$12,340", "₿0.1234" or "12,340,000 sats"; BTC and sats take a BTC amount
+fn format_total(value: f64, denomination: Denomination) -> String {
+    match denomination {
+        Denomination::Usd => format!("${}", group_thousands(value.round() as u64)),
+        Denomination::Btc => format!("₿{:.4}", value),
+        Denomination::Sats => format!("{} sats", group_thousands((value * 100_000_000.0).round() as u64)),
+    }
+}
+
+/// "$12,340 ▲1.2%"
+fn format_summary(total: f64, baseline: Option<f64>, denomination: Denomination) -> String {
+    let formatted = format_total(total, denomination);
+    let Some(baseline) = baseline.filter(|b| *b > 0.0) else {
+        return formatted;
+    };
+    let change = (total - baseline) / baseline * 100.0;
+    let arrow = if change >= 0.0 { '▲' } else { '▼' };
+    format!("{} {}{:.1}%", formatted, arrow, change.abs())
+}
+
+fn group_thousands(value: u64) -> String {
+    let digits = value.to_string();
+    let mut grouped = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(c);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_summary() {
+        assert_eq!(format_summary(12_340.0, Some(12_194.0), Denomination::Usd), "$12,340 ▲1.2%");
+        assert_eq!(format_summary(950.4, Some(1_000.0), Denomination::Usd), "$950 ▼5.0%");
+        assert_eq!(format_summary(1_234_567.0, Some(0.0), Denomination::Usd), "$1,234,567");
+        assert_eq!(format_summary(0.12346, None, Denomination::Btc), "₿0.1235");
+        assert_eq!(format_summary(0.1234, Some(0.1), Denomination::Sats), "12,340,000 sats ▲23.4%");
+    }
+}
@@ -0,0 +1,197 @@
+//! Additional vault windows and per-window event routing.
+//!
+//! Besides the main window, users can open a portfolio window and a signing
+//! window, each optionally bound to one device. Events emitted through
+//! [`emit_routed`] go to the windows they concern: signing prompts go to a
+//! signing window when one is open, and device-specific events skip windows
+//! bound to a different device.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, WebviewUrl, WebviewWindowBuilder, WindowEvent};
+
+/// Label of the window created from tauri.conf.json
+const MAIN_WINDOW: &str = "main";
+
+/// Events that belong in a signing window when one is open
+const SIGNING_EVENT_PREFIXES: &[&str] = &["batch:", "device:pin-", "device:passphrase", "signing:"];
+
+/// What an additional window is for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowRole {
+    Portfolio,
+    Signing,
+}
+
+impl WindowRole {
+    fn as_str(self) -> &'static str {
+        match self {
+            WindowRole::Portfolio => "portfolio",
+            WindowRole::Signing => "signing",
+        }
+    }
+
+    fn title(self) -> &'static str {
+        match self {
+            WindowRole::Portfolio => "KeepKey Vault — Portfolio",
+            WindowRole::Signing => "KeepKey Vault — Signing",
+        }
+    }
+}
+
+/// An open additional window and the device it follows
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowContext {
+    pub label: String,
+    pub role: WindowRole,
+    /// Device the window is bound to; None follows every device
+    pub device_id: Option<String>,
+}
+
+lazy_static::lazy_static! {
+    static ref WINDOWS: Mutex<HashMap<String, WindowContext>> = Mutex::new(HashMap::new());
+}
+
+/// Window labels may only contain alphanumerics and `-/:_`
+fn window_label(role: WindowRole, device_id: Option<&str>) -> String {
+    let device: String = device_id
+        .unwrap_or("all")
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{}-{}", role.as_str(), device)
+}
+
+/// Open (or focus) the window for a role, optionally bound to a device
+pub fn open_window(app: &AppHandle, role: WindowRole, device_id: Option<String>) -> Result<WindowContext, String> {
+    let label = window_label(role, device_id.as_deref());
+    let context = WindowContext { label: label.clone(), role, device_id: device_id.clone() };
+
+    if let Some(window) = app.get_webview_window(&label) {
+        window.set_focus().map_err(|e| format!("Failed to focus window: {}", e))?;
+        return Ok(context);
+    }
+
+    // The frontend reads its role and device from the query string
+    let mut url = format!("index.html?window={}", role.as_str());
+    if let Some(device_id) = &device_id {
+        let encoded: String = url::form_urlencoded::byte_serialize(device_id.as_bytes()).collect();
+        url.push_str(&format!("&deviceId={}", encoded));
+    }
+
+    let window = WebviewWindowBuilder::new(app, &label, WebviewUrl::App(url.into()))
+        .title(role.title())
+        .inner_size(if role == WindowRole::Signing { 520.0 } else { 1100.0 }, 760.0)
+        .resizable(true)
+        .build()
+        .map_err(|e| format!("Failed to open {} window: {}", role.as_str(), e))?;
+
+    WINDOWS.lock().unwrap().insert(label.clone(), context.clone());
+    let closed_label = label.clone();
+    window.on_window_event(move |event| {
+        if let WindowEvent::Destroyed = event {
+            WINDOWS.lock().unwrap().remove(&closed_label);
+        }
+    });
+
+    log::info!("🪟 Opened {} window {}", role.as_str(), label);
+    Ok(context)
+}
+
+/// Rebind an open window to another device (or to every device)
+pub fn set_window_device(label: &str, device_id: Option<String>) -> Result<WindowContext, String> {
+    let mut windows = WINDOWS.lock().unwrap();
+    let context = windows
+        .get_mut(label)
+        .ok_or_else(|| format!("No vault window {}", label))?;
+    context.device_id = device_id;
+    Ok(context.clone())
+}
+
+pub fn list_windows() -> Vec<WindowContext> {
+    WINDOWS.lock().unwrap().values().cloned().collect()
+}
+
+fn is_signing_event(event_name: &str) -> bool {
+    SIGNING_EVENT_PREFIXES.iter().any(|prefix| event_name.starts_with(prefix))
+}
+
+fn payload_device_id(payload: &serde_json::Value) -> Option<&str> {
+    payload
+        .get("deviceId")
+        .or_else(|| payload.get("device_id"))
+        .and_then(|v| v.as_str())
+}
+
+/// Labels an event should be delivered to, or None to broadcast as before
+fn route(windows: &[WindowContext], event_name: &str, device_id: Option<&str>) -> Option<Vec<String>> {
+    if windows.is_empty() {
+        return None;
+    }
+    let follows = |w: &WindowContext| match (&w.device_id, device_id) {
+        (Some(bound), Some(device_id)) => bound == device_id,
+        _ => true,
+    };
+
+    if is_signing_event(event_name) {
+        let signing: Vec<String> = windows
+            .iter()
+            .filter(|w| w.role == WindowRole::Signing && follows(w))
+            .map(|w| w.label.clone())
+            .collect();
+        if !signing.is_empty() {
+            return Some(signing);
+        }
+    }
+
+    let mut targets = vec![MAIN_WINDOW.to_string()];
+    targets.extend(
+        windows
+            .iter()
+            .filter(|w| w.role != WindowRole::Signing || !is_signing_event(event_name))
+            .filter(|w| follows(w))
+            .map(|w| w.label.clone()),
+    );
+    Some(targets)
+}
+
+/// Emit an event to the windows it concerns
+pub fn emit_routed(app: &AppHandle, event_name: &str, payload: &serde_json::Value) -> tauri::Result<()> {
+    let windows = list_windows();
+    match route(&windows, event_name, payload_device_id(payload)) {
+        None => app.emit(event_name, payload),
+        Some(targets) => {
+            for label in targets {
+                app.emit_to(label.as_str(), event_name, payload)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(role: WindowRole, device_id: Option<&str>) -> WindowContext {
+        WindowContext {
+            label: window_label(role, device_id),
+            role,
+            device_id: device_id.map(String::from),
+        }
+    }
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route(&[], "device:ready", Some("A")), None);
+
+        let windows = vec![window(WindowRole::Signing, Some("A")), window(WindowRole::Portfolio, Some("B"))];
+        assert_eq!(route(&windows, "batch:approval-requested", Some("A")), Some(vec!["signing-A".to_string()]));
+        // No signing window for B, so its prompts stay in the main window
+        assert_eq!(route(&windows, "device:pin-unlock-needed", Some("B")), Some(vec!["main".to_string(), "portfolio-B".to_string()]));
+        assert_eq!(route(&windows, "device:ready", Some("A")), Some(vec!["main".to_string(), "signing-A".to_string()]));
+    }
+}
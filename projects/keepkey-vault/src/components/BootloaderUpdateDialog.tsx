@@ -10,7 +10,7 @@ import {
 import { FaShieldAlt, FaCheckCircle } from 'react-icons/fa'
 import { useState } from 'react'
 import { invoke } from '@tauri-apps/api/core'
-import type { BootloaderCheck } from '../types/device'
+import type { BootloaderCheck, BootloaderPreflight } from '../types/device'
 
 interface BootloaderUpdateDialogProps {
   isOpen: boolean
@@ -33,10 +33,22 @@ export const BootloaderUpdateDialog = ({
     setError(null)
     
     try {
+      // Verify image hash, bootloader mode and USB stability before flashing
+      const preflight = await invoke<BootloaderPreflight>('bootloader_update_preflight', {
+        deviceId,
+        targetVersion: bootloaderCheck.latestVersion
+      })
+      
+      if (!preflight.passed || !preflight.confirmToken) {
+        const failed = preflight.checks.filter(check => !check.passed)
+        throw new Error(failed.map(check => check.detail).join('\n') || 'Pre-flight checks failed')
+      }
+      
       // Call the Tauri command to update the bootloader
       const success = await invoke('update_device_bootloader', {
         deviceId,
-        targetVersion: bootloaderCheck.latestVersion
+        targetVersion: bootloaderCheck.latestVersion,
+        confirmToken: preflight.confirmToken
       })
       
       if (success) {
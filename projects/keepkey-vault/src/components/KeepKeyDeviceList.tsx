@@ -100,8 +100,14 @@ export const KeepKeyDeviceList = ({
     setWipingDevice(deviceId)
     
     try {
-      console.log('🗑️ [KeepKeyDeviceList] Invoking wipe_device command...')
-      await invoke('wipe_device', { deviceId })
+      const challenge = await invoke<{ token: string; labelRequired: boolean }>('request_wipe_challenge', { deviceId })
+      let confirmLabel: string | null = null
+      if (challenge.labelRequired) {
+        confirmLabel = window.prompt('Type the device label to confirm the wipe')
+        if (confirmLabel === null) return
+      }
+      console.log('🗑️ [KeepKeyDeviceList] Invoking wipe_device command - confirm on the device...')
+      await invoke('wipe_device', { deviceId, token: challenge.token, confirmLabel })
       console.log('✅ [KeepKeyDeviceList] Device wiped successfully!')
       
       // Refresh device list to reflect changes
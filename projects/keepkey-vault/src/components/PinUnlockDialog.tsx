@@ -1,7 +1,10 @@
 import { useState, useEffect, useCallback } from 'react'
 import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
 import { Button, Text, HStack, Icon, VStack, Box, Spinner, SimpleGrid, Heading } from '@chakra-ui/react'
 import { FaCircle, FaExclamationTriangle, FaTimes, FaCheckCircle, FaSync, FaBackspace } from 'react-icons/fa'
+import { toDeviceError } from '../types/device'
+import type { PinLockout } from '../types/pin'
 
 interface PinUnlockDialogProps {
   isOpen: boolean
@@ -17,6 +20,16 @@ interface PinUnlockDialogProps {
 // We need to send these exact numbers when the user clicks each position
 const PIN_MATRIX_LAYOUT = [7, 8, 9, 4, 5, 6, 1, 2, 3] as const
 
+// "45 seconds", "8 minutes", "3 hours"
+const describeWait = (secs: number): string => {
+  const [amount, unit] = secs < 90
+    ? [secs, 'second']
+    : secs < 5400
+      ? [Math.round(secs / 60), 'minute']
+      : [Math.round(secs / 3600), 'hour']
+  return `${amount} ${unit}${amount === 1 ? '' : 's'}`
+}
+
 export const PinUnlockDialog = ({ isOpen, deviceId, onUnlocked, onClose }: PinUnlockDialogProps) => {
   const [pinPositions, setPinPositions] = useState<number[]>([])
   const [isLoading, setIsLoading] = useState(false)
@@ -24,6 +37,19 @@ export const PinUnlockDialog = ({ isOpen, deviceId, onUnlocked, onClose }: PinUn
   const [step, setStep] = useState<'verifying' | 'trigger' | 'enter' | 'submitting' | 'success'>('verifying')
   const [retryCount, setRetryCount] = useState(0)
   const [deviceReadyStatus, setDeviceReadyStatus] = useState<string>('Checking device...')
+  const [lockoutSecs, setLockoutSecs] = useState(0)
+
+  // The device delays each attempt after a wrong PIN; the backend counts it down
+  useEffect(() => {
+    const unlistenPromise = listen<PinLockout>('pin:lockout-countdown', (event) => {
+      if (event.payload.deviceId === deviceId) {
+        setLockoutSecs(event.payload.remainingSecs)
+      }
+    })
+    return () => {
+      unlistenPromise.then(unlisten => unlisten())
+    }
+  }, [deviceId])
 
   // Debug component lifecycle
   useEffect(() => {
@@ -99,36 +125,26 @@ export const PinUnlockDialog = ({ isOpen, deviceId, onUnlocked, onClose }: PinUn
     } catch (err: any) {
       console.error('❌ PIN trigger failed:', err)
       
-      const errorStr = String(err).toLowerCase()
-      
-      // Check if device is already showing PIN matrix (expected "failure")
-      if (errorStr.includes('unknown message') || errorStr.includes('failure: unknown message')) {
-        console.log('🔐 Device is already in PIN mode (expected behavior), proceeding to PIN entry')
-        setStep('enter')
-        setDeviceReadyStatus('PIN matrix ready')
-        setError(null)
-        return
-      }
-      
-      // Check if this is a device communication issue
-      if (errorStr.includes('device not found') || errorStr.includes('not connected')) {
-        setError('Device disconnected. Please reconnect your KeepKey and try again.')
-        setStep('trigger')
-      } else if (errorStr.includes('device already in use') || errorStr.includes('claimed')) {
-        setError('Device is being used by another application. Please close other wallet software and try again.')
-        setStep('trigger')
-      } else if (errorStr.includes('timeout')) {
-        setError('Device communication timeout. Please check your connection and try again.')
-        setStep('trigger')
-      } else {
-        // For other errors, allow retry but show the specific error
-        const userFriendlyError = errorStr.includes('failed to trigger pin request') 
-          ? 'Unable to request PIN from device. Please check your device screen and try again.'
-          : `Communication error: ${err}`
-        
-        setError(userFriendlyError)
-        setStep('trigger')
+      // The backend already treats "device is showing the PIN matrix" as success
+      const deviceError = toDeviceError(err)
+      switch (deviceError.code) {
+        case 'DEVICE_NOT_FOUND':
+          setError('Device disconnected. Please reconnect your KeepKey and try again.')
+          break
+        case 'DEVICE_BUSY':
+          setError('Device is being used by another application. Please close other wallet software and try again.')
+          break
+        case 'TIMEOUT':
+          setError('Device communication timeout. Please check your connection and try again.')
+          break
+        case 'DEVICE_FAILURE':
+          setError('Unable to request PIN from device. Please check your device screen and try again.')
+          break
+        default:
+          // For other errors, allow retry but show the specific error
+          setError(`Communication error: ${deviceError.message}`)
       }
+      setStep('trigger')
       
       setDeviceReadyStatus('PIN request failed')
     } finally {
@@ -181,23 +197,23 @@ export const PinUnlockDialog = ({ isOpen, deviceId, onUnlocked, onClose }: PinUn
       console.error('❌ PIN submission failed:', err)
       
       // This is a real PIN validation error - show it clearly
-      const errorStr = String(err)
-      if (errorStr.toLowerCase().includes('incorrect') || errorStr.toLowerCase().includes('invalid') || errorStr.toLowerCase().includes('wrong')) {
-        setError('Incorrect PIN. Please check your device screen and try again.')
+      const deviceError = toDeviceError(err)
+      if (deviceError.code === 'PIN_INVALID') {
+        setError(deviceError.message)
         setRetryCount(prev => prev + 1)
         
         // If too many failed attempts, warn user about device lockout
         if (retryCount >= 2) {
           setError('Incorrect PIN. Warning: Too many failed attempts may temporarily lock your device!')
         }
-      } else if (errorStr.toLowerCase().includes('device not found')) {
+      } else if (deviceError.code === 'DEVICE_NOT_FOUND') {
         setError('Device disconnected during PIN entry. Please reconnect and try again.')
-      } else if (errorStr.toLowerCase().includes('locked') || errorStr.toLowerCase().includes('too many')) {
-        setError('Device is temporarily locked due to too many failed PIN attempts. Please wait and try again later.')
+      } else if (deviceError.code === 'ACTION_CANCELLED') {
+        setError('PIN entry was cancelled on the device. Please try again.')
         setStep('trigger')
         return
       } else {
-        setError(`PIN verification failed: ${err}`)
+        setError(`PIN verification failed: ${deviceError.message}`)
       }
       
       // Reset to PIN entry step and clear entered PIN
@@ -423,16 +439,21 @@ export const PinUnlockDialog = ({ isOpen, deviceId, onUnlocked, onClose }: PinUn
                       boxShadow: "lg",
                     }}
                     transition="all 0.2s"
-                    disabled={pinPositions.length === 0}
+                    disabled={pinPositions.length === 0 || lockoutSecs > 0}
                     flex={2}
                   >
-                    Unlock
+                    {lockoutSecs > 0 ? `Wait ${describeWait(lockoutSecs)}` : 'Unlock'}
                   </Button>
                 </HStack>
 
                 <Text fontSize="xs" color="gray.500" textAlign="center">
                   Use the scrambled layout shown on your device
                 </Text>
+                {lockoutSecs > 0 && (
+                  <Text fontSize="xs" color="orange.300" textAlign="center">
+                    Too many wrong PINs. Try again in {describeWait(lockoutSecs)}.
+                  </Text>
+                )}
               </VStack>
             )}
 
@@ -10,7 +10,9 @@ import {
 } from '@chakra-ui/react';
 import { FaPaperPlane, FaDownload } from 'react-icons/fa';
 import { SiBitcoin } from 'react-icons/si';
+import { invoke } from '@tauri-apps/api/core';
 import { useWallet } from '../contexts/WalletContext';
+import type { Denomination } from '../types/wallet';
 
 interface PortfolioProps {
   onNavigate?: (action: 'send' | 'receive') => void;
@@ -20,6 +22,13 @@ export const Portfolio: React.FC<PortfolioProps> = ({ onNavigate }) => {
   const { portfolio, loading, error, refreshPortfolio } = useWallet();
   const [showStartButton, setShowStartButton] = React.useState(false);
   const [syncingTime, setSyncingTime] = React.useState(0);
+  const [denomination, setDenomination] = React.useState<Denomination>('usd');
+
+  React.useEffect(() => {
+    invoke<Denomination | null>('get_preference', { key: 'portfolio_denomination' })
+      .then(value => setDenomination(value ?? 'usd'))
+      .catch(err => console.warn('Failed to read portfolio denomination:', err));
+  }, []);
 
   console.log('portfolio: ', portfolio);
 
@@ -60,6 +69,19 @@ export const Portfolio: React.FC<PortfolioProps> = ({ onNavigate }) => {
     });
   };
 
+  // Total in the preferred unit; falls back to USD until a BTC price is known
+  const formatTotal = () => {
+    if (!portfolio) return { value: '$0.00', label: 'Total USD Value' };
+    const btc = portfolio.total_value_btc;
+    if (denomination === 'btc' && btc !== undefined) {
+      return { value: `${btc.toFixed(8)} BTC`, label: 'Total Value in BTC' };
+    }
+    if (denomination === 'sats' && btc !== undefined) {
+      return { value: `${Math.round(btc * 1e8).toLocaleString('en-US')} sats`, label: 'Total Value in Sats' };
+    }
+    return { value: `$${formatUsd(portfolio.total_value_usd)}`, label: 'Total USD Value' };
+  };
+
   // Debug function to inspect USD values
   const debugUsdValues = () => {
     console.group('🔍 DEBUG: USD Values Analysis');
@@ -161,7 +183,7 @@ export const Portfolio: React.FC<PortfolioProps> = ({ onNavigate }) => {
               <Button 
                 colorScheme="blue" 
                 size="lg" 
-                onClick={refreshPortfolio}
+                onClick={() => refreshPortfolio()}
                 _hover={{ transform: 'scale(1.05)' }}
                 transition="all 0.2s"
                 fontWeight="bold"
@@ -210,14 +232,19 @@ export const Portfolio: React.FC<PortfolioProps> = ({ onNavigate }) => {
           <SiBitcoin />
         </Box>
         
-        {/* USD Balance */}
+        {/* Total Value */}
         <Box textAlign="center">
           <Text fontSize="3xl" fontWeight="bold" color="white">
-            ${formatUsd(portfolio.total_value_usd)}
+            {formatTotal().value}
           </Text>
           <Text fontSize="md" color="gray.400" mt={1}>
-            Total USD Value
+            {formatTotal().label}
           </Text>
+          {portfolio.stale_networks && portfolio.stale_networks.length > 0 && (
+            <Text fontSize="xs" color="yellow.400" mt={1}>
+              Some balances could not be refreshed and may be outdated
+            </Text>
+          )}
         </Box>
         
         {/* BTC Balance */}
@@ -228,6 +255,11 @@ export const Portfolio: React.FC<PortfolioProps> = ({ onNavigate }) => {
           <Text fontSize="md" color="gray.400" mt={1}>
             Bitcoin Balance
           </Text>
+          {btcAssets.some(asset => asset.reconciliation?.mismatch) && (
+            <Text fontSize="xs" color="yellow.400" mt={1}>
+              A second data source reports a different balance
+            </Text>
+          )}
         </Box>
         
         {/* Send/Receive Buttons */}
@@ -18,6 +18,7 @@ import { SiBitcoin } from 'react-icons/si';
 import { useWallet } from '../contexts/WalletContext';
 import { PioneerAPI, DeviceQueueAPI } from '../lib/api';
 import { createUnsignedUxtoTx } from '../lib/createUnsignedUxtoTx';
+import { CoinSelectionStrategy, DEFAULT_COIN_SELECTION_STRATEGY } from '../lib/coinSelection';
 
 interface SendPageProps {
   onBack: () => void;
@@ -62,6 +63,8 @@ const Send: React.FC<SendPageProps> = ({ onBack }) => {
   const [amountCurrency, setAmountCurrency] = useState<'BTC' | 'USD'>('BTC');
   const [btcPrice, setBtcPrice] = useState<number>(43000);
   const [isMaxSend, setIsMaxSend] = useState(false);
+  // Rate locked when a USD-denominated send is reviewed
+  const [paymentIntent, setPaymentIntent] = useState<{ id: string; cryptoAmount: string; lockedRate: number } | null>(null);
   const [isShowingHex, setIsShowingHex] = useState(false); // Hex collapsed by default
   
   // Broadcast state
@@ -206,8 +209,18 @@ const Send: React.FC<SendPageProps> = ({ onBack }) => {
     return currentStep !== 'compose' && currentStep !== 'complete';
   };
 
+  const releasePaymentIntent = () => {
+    if (paymentIntent) {
+      import('@tauri-apps/api/core')
+        .then(({ invoke }) => invoke('cancel_payment_intent', { id: paymentIntent.id }))
+        .catch(error => console.warn('Failed to cancel payment intent:', error));
+      setPaymentIntent(null);
+    }
+  };
+
   const handleStepBack = () => {
     if (currentStep === 'review') {
+      releasePaymentIntent();
       setCurrentStep('compose');
       setError(null);
     } else if (currentStep === 'sign') {
@@ -255,6 +268,21 @@ const Send: React.FC<SendPageProps> = ({ onBack }) => {
 
       console.log('🔄 Building transaction...');
 
+      // Lock the quoted rate so the BTC amount can't silently change before signing
+      if (amountCurrency === 'USD' && !isMaxSend) {
+        const { invoke } = await import('@tauri-apps/api/core');
+        const intent = await invoke<{ id: string; cryptoAmount: string; lockedRate: number }>('create_payment_intent', {
+          request: {
+            caip: 'bip122:000000000019d6689c085ae165831e93/slip44:0',
+            recipient: recipientAddress,
+            fiatCurrency: 'USD',
+            fiatAmount: amount,
+            rate: btcPrice,
+          },
+        });
+        setPaymentIntent(intent);
+      }
+
       const selectedFeeRateValue = feeRates[feeRate];
       const estimatedFee = (250 * selectedFeeRateValue) / 100000000; // ~250 vBytes typical tx
       const amountInSats = Math.round(sendAmountInBtc * 100000000);
@@ -374,12 +402,30 @@ console.debug('[Send] deviceId from device.unique_id:', deviceId);
       
                     // Use the proper transaction builder with real input selection
        console.log('⚙️ Building transaction with proper coinselect algorithm...');
-        const sendAmountBtc = amountCurrency === 'USD' ? convertUsdToBtc(parseFloat(amount)) : parseFloat(amount); // Amount in BTC (not satoshis)
+      const { invoke } = await import('@tauri-apps/api/core');
+      
+      // Re-check a locked USD rate and warn if it moved past the threshold
+      if (paymentIntent) {
+        const check = await invoke<{ warning?: string }>('confirm_payment_intent', { id: paymentIntent.id, currentRate: btcPrice });
+        if (check.warning) {
+          if (!window.confirm(`${check.warning}. Send ${paymentIntent.cryptoAmount} BTC anyway?`)) {
+            throw new Error('Send cancelled because the exchange rate moved');
+          }
+          await invoke('confirm_payment_intent', { id: paymentIntent.id, currentRate: btcPrice, accept: true });
+        }
+      }
+      
+        const sendAmountBtc = paymentIntent
+          ? parseFloat(paymentIntent.cryptoAmount) // Amount fixed at the locked rate
+          : amountCurrency === 'USD' ? convertUsdToBtc(parseFloat(amount)) : parseFloat(amount); // Amount in BTC (not satoshis)
       
       console.log(`💰 Transaction type: ${isMaxSend ? 'MAX SEND (coinSelectSplit)' : 'REGULAR SEND (coinSelect)'}`);
       console.log(`💰 Send amount: ${sendAmountBtc} BTC (${isMaxSend ? 'will be calculated by coinSelectSplit' : 'fixed amount'})`);
       
       
+      const strategy = await invoke<CoinSelectionStrategy | null>('get_typed_preference', { key: 'coin_selection_strategy' })
+        .catch(() => null) || DEFAULT_COIN_SELECTION_STRATEGY;
+      
       const unsignedTx = await createUnsignedUxtoTx(
         btcXpubs[0].caip, // Use first Bitcoin CAIP (any will work since they're all Bitcoin)
         recipientAddress,
@@ -388,7 +434,9 @@ console.debug('[Send] deviceId from device.unique_id:', deviceId);
         pubkeys,
         pioneer,
         null, // keepKeySdk (not needed for signing)
-        isMaxSend // Use tracked max send state instead of hardcoded false
+        isMaxSend, // Use tracked max send state instead of hardcoded false
+        deviceId,
+        strategy
       );
       
       console.log('📊 Transaction built by coinselect:');
@@ -468,6 +516,7 @@ console.debug('[Send] deviceId from device.unique_id:', deviceId);
     setError(null);
     setSuccess(null);
     setIsMaxSend(false);
+    setPaymentIntent(null);
     // Reset broadcast state
     setIsBroadcasting(false);
     setBroadcastSuccess(false);
@@ -15,7 +15,7 @@ import { KeepKeyDeviceList } from './KeepKeyDeviceList'
 import { BootloaderUpdateDialog } from './BootloaderUpdateDialog'
 import { FirmwareUpdateDialog } from './FirmwareUpdateDialog'
 import SeedVerificationWizard from './SeedVerificationWizard/SeedVerificationWizard'
-import type { DeviceStatus } from '../types/device'
+import type { BootloaderPreflight, DeviceStatus } from '../types/device'
 import { invoke } from '@tauri-apps/api/core'
 import holdAndConnectSvg from '../assets/svg/hold-and-connect.svg'
 import { useFirmwareUpdateWizard, useWalletCreationWizard } from '../contexts/DialogContext'
@@ -176,15 +176,25 @@ export const SettingsDialog = ({ isOpen, onClose }: SettingsDialogProps) => {
     setSelectedDeviceId(deviceId)
     
     try {
-      // Directly call the bootloader update command with a target version
-      // Using "2.1.0" as the target version (from device_update.rs LATEST_BOOTLOADER_VERSION)
+      // Target the latest bootloader from the release catalog
+      const catalog = await invoke('get_firmware_releases') as { latestBootloader: string }
+      const targetVersion = catalog.latestBootloader
+      
+      // Verify image hash, bootloader mode and USB stability before flashing
+      const preflight = await invoke('bootloader_update_preflight', { deviceId, targetVersion }) as BootloaderPreflight
+      if (!preflight.passed || !preflight.confirmToken) {
+        const failed = preflight.checks.filter(check => !check.passed)
+        throw new Error(failed.map(check => check.detail).join('\n') || 'Pre-flight checks failed')
+      }
+      
       console.log('Calling update_device_bootloader with deviceId:', deviceId)
       
       // Call the backend command to update the bootloader
       // This will internally create a high-priority blocking action
       const result = await invoke('update_device_bootloader', { 
         deviceId, 
-        targetVersion: "2.1.0" 
+        targetVersion,
+        confirmToken: preflight.confirmToken
       }) as boolean
       
       console.log('Bootloader update result:', result)
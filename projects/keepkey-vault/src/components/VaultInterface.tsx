@@ -3,6 +3,7 @@ import { Box, Flex, Button, Text, HStack, useDisclosure } from '@chakra-ui/react
 import { FaTh, FaGlobe, FaWallet, FaCog, FaQuestionCircle } from 'react-icons/fa';
 import { listen } from '@tauri-apps/api/event';
 import { invoke } from '@tauri-apps/api/core';
+import { relaunch } from '@tauri-apps/plugin-process';
 import splashBg from '../assets/splash-bg.png';
 import { SettingsDialog } from './SettingsDialog';
 import { AppsView, BrowserView, PairingsView, VaultView, AssetView } from './views';
@@ -93,6 +94,102 @@ export const VaultInterface = () => {
     },
   ];
 
+  // Ask once for a whole batch of transactions queued through the REST API
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    listen('batch:approval-requested', async (event) => {
+      const { batchId, items } = event.payload as {
+        batchId: string;
+        items: Array<{ index: number; kind: string; network: string; recipients: string[]; amount: string }>;
+      };
+      const lines = items.map(item =>
+        `${item.index + 1}. ${item.kind} ${item.network}: ${item.amount} to ${item.recipients.join(', ') || 'self'}`
+      );
+      const approved = window.confirm(
+        `Sign ${items.length} transactions? Each one still needs confirming on the device.\n\n${lines.join('\n')}`
+      );
+      try {
+        await invoke('respond_batch_approval', { batchId, approved });
+      } catch (error) {
+        console.error('Failed to respond to batch approval:', error);
+      }
+    }).then(fn => { unlisten = fn; });
+
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  // Pairing through the REST API only succeeds once the user approves it here
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    listen('pairing:approval-requested', async (event) => {
+      const { requestId, name, origin } = event.payload as { requestId: string; name: string; origin: string };
+      const approved = window.confirm(
+        `Allow "${name}" (${origin}) to pair with KeepKey Vault?\n\nIt will be able to call the vault API from that origin.`
+      );
+      try {
+        await invoke('respond_pairing_approval', { requestId, approved });
+      } catch (error) {
+        console.error('Failed to respond to pairing approval:', error);
+      }
+    }).then(fn => { unlisten = fn; });
+
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  // Repeated startup crashes put the backend in safe mode; offer a way out
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    listen('app:safe-mode', async (event) => {
+      const { failedStartups, diagnostics } = event.payload as { failedStartups: number; diagnostics: unknown };
+      console.warn('Safe mode diagnostics:', diagnostics);
+      const reset = window.confirm(
+        `KeepKey Vault did not start properly ${failedStartups} times in a row and is running in safe mode ` +
+        `(no automatic cache refresh, no vault proxy).\n\nClear the local cache and restart?`
+      );
+      try {
+        if (reset) {
+          const resetPreferences = window.confirm('Also reset preferences to their defaults? A backup copy is kept.');
+          await invoke('safe_mode_reset', { clearCache: true, resetPreferences });
+        }
+        if (reset || window.confirm('Leave safe mode on the next restart?')) {
+          await invoke('exit_safe_mode');
+          await relaunch();
+        }
+      } catch (error) {
+        console.error('Failed to leave safe mode:', error);
+      }
+    }).then(fn => { unlisten = fn; });
+
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
+  // Two devices holding the same seed would double count balances
+  useEffect(() => {
+    let unlisten: (() => void) | undefined;
+
+    listen('device:duplicate-seed', (event) => {
+      const { deviceId, sharesSeedWith } = event.payload as { deviceId: string; sharesSeedWith: string[] };
+      console.warn(`Device ${deviceId} shares its seed with`, sharesSeedWith);
+      window.alert(
+        `This KeepKey holds the same recovery seed as ${sharesSeedWith.length === 1 ? 'another paired device' : `${sharesSeedWith.length} other paired devices`}. ` +
+        `Its accounts are only counted once in wallet totals.`
+      );
+    }).then(fn => { unlisten = fn; });
+
+    return () => {
+      if (unlisten) unlisten();
+    };
+  }, []);
+
   // Listen for backend view change commands
   useEffect(() => {
     let unlisten: (() => void) | undefined;
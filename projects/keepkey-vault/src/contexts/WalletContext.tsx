@@ -8,10 +8,11 @@
 
 import React, { createContext, useContext, useState, useEffect, useRef, ReactNode, useCallback } from 'react';
 import { listen } from '@tauri-apps/api/event';
+import { invoke } from '@tauri-apps/api/core';
 
 // Import organized types and services
-import { Asset, Portfolio, QueueStatus } from '../types';
-import { PortfolioAPI, DeviceQueueAPI, PioneerAPI } from '../lib';
+import { Asset, AssetReconciliation, Portfolio, QueueStatus } from '../types';
+import { PortfolioAPI, DeviceQueueAPI, PioneerAPI, PioneerPortfolioResponse, isTestnetCaip } from '../lib';
 
 const TAG = " | WalletContext | ";
 
@@ -21,6 +22,37 @@ const pendingReceiveRequests: Map<string, AddrResolver> = new Map();
 const pendingSigningRequests: Map<string, { resolve: (signedTx: string) => void; reject: (error: any) => void }> = new Map();
 // ----------------------------------------------------------------------------------------------------------------
 
+// ---- Last good portfolio slices, kept across restarts -----------------------------------------------------
+// Only public balances are stored (no xpubs), keyed per device
+const SLICES_STORAGE_PREFIX = 'keepkey-vault:portfolio-slices:';
+
+const loadStoredSlices = (deviceId: string | null): Map<string, PioneerPortfolioResponse[]> => {
+  if (!deviceId) return new Map();
+  try {
+    const stored = localStorage.getItem(SLICES_STORAGE_PREFIX + deviceId);
+    return new Map(stored ? JSON.parse(stored) : []);
+  } catch (error) {
+    console.warn(TAG, 'Ignoring unreadable stored portfolio slices:', error);
+    return new Map();
+  }
+};
+
+const storeSlices = (deviceId: string | null, slices: Map<string, PioneerPortfolioResponse[]>) => {
+  if (!deviceId) return;
+  try {
+    localStorage.setItem(SLICES_STORAGE_PREFIX + deviceId, JSON.stringify(Array.from(slices.entries())));
+  } catch (error) {
+    console.warn(TAG, 'Failed to store portfolio slices:', error);
+  }
+};
+// ----------------------------------------------------------------------------------------------------------------
+
+// Part of the portfolio to refetch; nothing selected means everything
+export interface PortfolioRefreshSelection {
+  network?: string; // CAIP-2
+  caip?: string;    // CAIP-19
+}
+
 // Context Type
 interface WalletContextType {
   portfolio: Portfolio | null;
@@ -30,7 +62,7 @@ interface WalletContextType {
   isSync: boolean;
   lastReceiveAddress: string | null;
   fetchedXpubs: Array<{path: string, xpub: string, caip: string}>;
-  refreshPortfolio: () => Promise<void>;
+  refreshPortfolio: (selection?: PortfolioRefreshSelection) => Promise<void>;
   selectAsset: (asset: Asset | null) => void;
   sendAsset: (toAddress: string, amount: string) => Promise<boolean>;
   getReceiveAddress: () => Promise<string | null>;
@@ -85,8 +117,19 @@ export const WalletProvider: React.FC<WalletProviderProps> = ({ children }) => {
   // Store fetched xpubs in memory (not database for v2)
   const [fetchedXpubs, setFetchedXpubs] = useState<Array<{path: string, xpub: string, caip: string}>>([]);
 
-  const refreshPortfolio = useCallback(async () => {
+  // Last successful portfolio entries per network, shown (marked stale) when a network's fetch fails;
+  // persisted so a failing network still shows its last balances after a restart
+  const lastGoodSlicesRef = useRef(new Map<string, PioneerPortfolioResponse[]>());
+  const lastGoodSlicesDeviceRef = useRef<string | null>(null);
+
+  // Device the in-memory xpubs (and so the portfolio) belong to
+  const portfolioDeviceRef = useRef<string | null>(null);
+
+  const refreshPortfolio = useCallback(async (selection?: PortfolioRefreshSelection) => {
     const tag = TAG + " | refreshPortfolio | ";
+    // A selected network (or asset, whose network it implies) is refetched alone;
+    // the other networks keep their last fetched entries
+    const onlyNetwork = selection?.network || selection?.caip?.split('/')[0];
     setLoading(true);
     
     try {
@@ -108,15 +151,52 @@ export const WalletProvider: React.FC<WalletProviderProps> = ({ children }) => {
       }
       
       // Convert in-memory xpubs to Pioneer API format and fetch portfolio
-      const requests = fetchedXpubs.map(x => ({
-        caip: x.caip,
-        pubkey: x.xpub
-      }));
+      const requests = fetchedXpubs
+        .filter(x => !onlyNetwork || x.caip.split('/')[0] === onlyNetwork)
+        .map(x => ({
+          caip: x.caip,
+          pubkey: x.xpub
+        }));
+      if (onlyNetwork && requests.length === 0) {
+        console.warn(tag, `No xpubs for network ${onlyNetwork}, nothing to refresh`);
+        return;
+      }
       
       console.log(tag, 'Calling Pioneer API with in-memory xpubs:', requests);
       
-      // Call Pioneer API directly with in-memory xpubs
-      const portfolioData = await PioneerAPI.getPortfolio(requests);
+      const deviceId = portfolioDeviceRef.current;
+      if (lastGoodSlicesDeviceRef.current !== deviceId) {
+        lastGoodSlicesRef.current = loadStoredSlices(deviceId);
+        lastGoodSlicesDeviceRef.current = deviceId;
+      }
+
+      // Call Pioneer API per network so one failing chain doesn't blank the whole portfolio
+      const slices = await PioneerAPI.getPortfolioByNetwork(requests);
+      if (onlyNetwork) {
+        for (const [network, entries] of lastGoodSlicesRef.current) {
+          if (network !== onlyNetwork) slices.push({ network, entries });
+        }
+      }
+      const portfolioData: PioneerPortfolioResponse[] = [];
+      const staleNetworks: string[] = [];
+      for (const slice of slices) {
+        if (slice.error) {
+          staleNetworks.push(slice.network);
+          portfolioData.push(...(lastGoodSlicesRef.current.get(slice.network) || []));
+        } else {
+          lastGoodSlicesRef.current.set(slice.network, slice.entries);
+          portfolioData.push(...slice.entries);
+        }
+      }
+      if (staleNetworks.length < slices.length) {
+        storeSlices(deviceId, lastGoodSlicesRef.current);
+      }
+      if (staleNetworks.length === slices.length && portfolioData.length === 0) {
+        throw new Error(slices[0]?.error || 'Portfolio fetch failed');
+      }
+      if (staleNetworks.length > 0) {
+        console.warn(tag, 'Showing last known balances for networks that failed to refresh:', staleNetworks);
+      }
       
       // Transform to portfolio format (simplified version of PortfolioAPI.transformToPortfolio)
       let totalValueUsd = 0;
@@ -157,9 +237,14 @@ export const WalletProvider: React.FC<WalletProviderProps> = ({ children }) => {
           });
         }
         
-        totalValueUsd += valueUsd;
+        if (!isTestnetCaip(item.caip)) {
+          totalValueUsd += valueUsd;
+        }
       }
 
+      // BTC cross-rate for valuing the portfolio in BTC
+      const btcPriceUsd = symbolGroups.get('BTC')?.priceUsd || 0;
+
       // Convert to assets
       for (const [symbol, group] of symbolGroups) {
         assets.push({
@@ -167,6 +252,7 @@ export const WalletProvider: React.FC<WalletProviderProps> = ({ children }) => {
           name: symbol === 'BTC' ? 'Bitcoin' : symbol,
           balance: group.balance.toString(),
           value_usd: group.valueUsd,
+          value_btc: btcPriceUsd > 0 ? group.valueUsd / btcPriceUsd : undefined,
           network_id: 'bitcoin',
           caip: group.caip,
           price_usd: group.priceUsd,
@@ -176,12 +262,52 @@ export const WalletProvider: React.FC<WalletProviderProps> = ({ children }) => {
 
       const portfolio: Portfolio = {
         total_value_usd: totalValueUsd.toFixed(2),
+        total_value_btc: btcPriceUsd > 0 ? totalValueUsd / btcPriceUsd : undefined,
+        btc_price_usd: btcPriceUsd || undefined,
         assets,
-        networks
+        networks,
+        stale_networks: staleNetworks
       };
       
       setPortfolio(portfolio);
       setError(null);
+
+      // Cross-check against a second provider (returns nothing unless enabled); attached once it answers
+      invoke<AssetReconciliation[]>('reconcile_portfolio', {
+        balances: portfolioData.map(({ caip, pubkey, balance }) => ({ caip, pubkey, balance }))
+      }).then(checks => {
+        if (checks.length === 0) return;
+        const mismatched = checks.filter(c => c.mismatch).map(c => c.caip);
+        if (mismatched.length > 0) {
+          console.warn(tag, 'Balances disagree with the second provider for:', mismatched);
+        }
+        setPortfolio(current => current && {
+          ...current,
+          assets: current.assets.map(asset => {
+            const reconciliation = checks.find(c => c.caip === asset.caip);
+            return reconciliation ? { ...asset, reconciliation } : asset;
+          })
+        });
+      }).catch(err =>
+        console.warn(tag, 'Failed to reconcile balances:', err)
+      );
+
+      // Window title summary (ignored unless the preference is on), goal progress and balance changes
+      invoke('update_portfolio_summary', {
+        totalUsd: totalValueUsd,
+        totalBtc: symbolGroups.get('BTC')?.balance ?? null,
+        btcPriceUsd: btcPriceUsd || null,
+        // Compared with this device's previous refresh to emit portfolio:changed
+        deviceId: portfolioDeviceRef.current,
+        assets: assets.map(asset => ({
+          caip: asset.caip,
+          symbol: asset.symbol,
+          balance: parseFloat(asset.balance) || 0,
+          valueUsd: asset.value_usd
+        }))
+      }).catch(err =>
+        console.warn(tag, 'Failed to update portfolio summary:', err)
+      );
       console.log(tag, 'Portfolio refreshed successfully:', portfolio);
       
     } catch (error) {
@@ -594,6 +720,7 @@ export const WalletProvider: React.FC<WalletProviderProps> = ({ children }) => {
             const pathInfo = requiredPaths.find(p => p.path === xpubResponse.path);
             
             if (pathInfo) {
+              portfolioDeviceRef.current = device_id;
               const xpubData = {
                 path: xpubResponse.path,
                 xpub: xpubResponse.xpub,
@@ -811,6 +938,17 @@ export const WalletProvider: React.FC<WalletProviderProps> = ({ children }) => {
     };
   }, []);
 
+  // Refresh requested over the REST API (POST /api/portfolio/refresh)
+  useEffect(() => {
+    const unlistenRefresh = listen<PortfolioRefreshSelection>('portfolio:refresh-requested', (event) => {
+      console.log(TAG, '🔄 Portfolio refresh requested:', event.payload);
+      refreshPortfolio(event.payload);
+    });
+    return () => {
+      unlistenRefresh.then(fn => fn());
+    };
+  }, [refreshPortfolio]);
+
   // Watch fetchedXpubs and refresh portfolio when all expected xpubs are present
   useEffect(() => {
     const tag = TAG + " | fetchedXpubs useEffect | ";
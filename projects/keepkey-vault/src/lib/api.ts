@@ -7,6 +7,18 @@ import { QueueStatus } from '../types/queue';
 const TAG = " | API | ";
 const PIONEER_BASE_URL = 'https://pioneers.dev';
 const CACHE_TTL_MINUTES = 10;
+// Per-network portfolio requests give up after this, so one slow chain can't stall the refresh
+const PORTFOLIO_SLICE_TIMEOUT_MS = 15000;
+
+// Sandbox networks (Bitcoin testnet/signet, Sepolia) - never counted in USD totals
+export const TESTNET_NETWORKS = [
+  'bip122:000000000933ea01ad0ee984209779ba',
+  'bip122:00000008819873e925422c1ff0f99f7c',
+  'eip155:11155111',
+];
+
+export const isTestnetCaip = (caip: string): boolean =>
+  TESTNET_NETWORKS.includes(caip.split('/')[0]);
 
 
 // Pioneer API Types
@@ -24,6 +36,13 @@ export interface PioneerPortfolioResponse {
   symbol: string;
 }
 
+// Portfolio entries for one network (CAIP-2); error is set when the fetch failed
+export interface PioneerPortfolioSlice {
+  network: string;
+  entries: PioneerPortfolioResponse[];
+  error?: string;
+}
+
 export interface PioneerFeeRateResponse {
   fastest: number;
   fast: number;
@@ -64,13 +83,73 @@ export interface BalanceCache {
   last_updated: number;
 }
 
+// Path of a Pioneer URL with xpubs and other long keys masked, for usage tracking
+const pioneerEndpoint = (url: string = ''): string =>
+  url
+    .replace(PIONEER_BASE_URL, '')
+    .split('?')[0]
+    .split('/')
+    .map(segment => (segment.length > 40 ? ':key' : segment))
+    .join('/');
+
+// Every Pioneer request is reported to the backend's provider usage log
+const pioneerClient = axios.create();
+const requestStartedAt = new WeakMap<object, number>();
+
+// The API key lives in the OS keychain; read it once per session
+let pioneerApiKey: Promise<string | null> | null = null;
+const getPioneerApiKey = (): Promise<string | null> => {
+  if (!pioneerApiKey) {
+    pioneerApiKey = invoke<string | null>('get_pioneer_api_key').catch(() => null);
+  }
+  return pioneerApiKey;
+};
+
+pioneerClient.interceptors.request.use(async config => {
+  requestStartedAt.set(config, Date.now());
+  const apiKey = await getPioneerApiKey();
+  if (apiKey) {
+    config.headers.Authorization = apiKey;
+  }
+  return config;
+});
+
+const reportPioneerRequest = (config: any, status: number | null, bytes: number, error: string | null) => {
+  const startedAt = (config && requestStartedAt.get(config)) ?? Date.now();
+  invoke('record_provider_request', {
+    provider: 'pioneer',
+    endpoint: pioneerEndpoint(config?.url),
+    status,
+    bytes,
+    latencyMs: Date.now() - startedAt,
+    error,
+  }).catch(() => {
+    // Usage tracking must never break the request itself
+  });
+};
+
+pioneerClient.interceptors.response.use(
+  response => {
+    const length = Number(response.headers['content-length']);
+    const bytes = Number.isFinite(length) ? length : JSON.stringify(response.data ?? '').length;
+    reportPioneerRequest(response.config, response.status, bytes, null);
+    return response;
+  },
+  error => {
+    if (axios.isAxiosError(error)) {
+      reportPioneerRequest(error.config, error.response?.status ?? null, 0, error.response ? null : error.message);
+    }
+    return Promise.reject(error);
+  }
+);
+
 export class PioneerAPI {
   static async getPortfolio(requests: PioneerPortfolioRequest[]): Promise<PioneerPortfolioResponse[]> {
     try {
       console.log('🌐 Calling Pioneer portfolio API with', requests.length, 'xpubs');
       console.log('📤 Requests:', requests);
       
-      const response = await axios.post(
+      const response = await pioneerClient.post(
         `${PIONEER_BASE_URL}/api/v1/portfolio`,
         requests,
         { 
@@ -91,12 +170,44 @@ export class PioneerAPI {
     }
   }
 
+  static async getPortfolioByNetwork(
+    requests: PioneerPortfolioRequest[],
+    timeoutMs: number = PORTFOLIO_SLICE_TIMEOUT_MS
+  ): Promise<PioneerPortfolioSlice[]> {
+    // Group xpubs by network (CAIP-2) and fetch each group on its own
+    const byNetwork = new Map<string, PioneerPortfolioRequest[]>();
+    for (const request of requests) {
+      const network = request.caip.split('/')[0];
+      byNetwork.set(network, [...(byNetwork.get(network) || []), request]);
+    }
+
+    return Promise.all(Array.from(byNetwork, async ([network, slice]): Promise<PioneerPortfolioSlice> => {
+      try {
+        const response = await pioneerClient.post(
+          `${PIONEER_BASE_URL}/api/v1/portfolio`,
+          slice,
+          {
+            headers: {
+              'Content-Type': 'application/json',
+              'accept': 'application/json'
+            },
+            timeout: timeoutMs
+          }
+        );
+        return { network, entries: response.data };
+      } catch (error) {
+        console.warn(`⚠️ Pioneer portfolio fetch failed for ${network}:`, error);
+        return { network, entries: [], error: error instanceof Error ? error.message : String(error) };
+      }
+    }));
+  }
+
   static async getFeeRates(caip: string): Promise<PioneerFeeRateResponse> {
     try {
       console.log('💰 Getting fee rates for', caip);
       
       const encodedCaip = encodeURIComponent(caip);
-      const response = await axios.get(
+      const response = await pioneerClient.get(
         `${PIONEER_BASE_URL}/api/v1/GetFeeRate/${encodedCaip}`,
         { 
           headers: { 'accept': 'application/json' },
@@ -117,7 +228,7 @@ export class PioneerAPI {
       console.log('🏠 Getting receive address for', coin, 'xpub');
       
       // Use REAL Pioneer API endpoint from test-pioneer-live.js
-      const response = await axios.get(
+      const response = await pioneerClient.get(
         `${PIONEER_BASE_URL}/api/v1/getNewAddress/BTC/${xpub}`,
         { 
           headers: { 'accept': 'application/json' },
@@ -138,7 +249,7 @@ export class PioneerAPI {
       console.log('🪙 Getting UTXOs for', network, 'xpub:', xpub.substring(0, 20) + '...');
       
       // Use REAL Pioneer API endpoint from test-pioneer-live.js
-      const response = await axios.get(
+      const response = await pioneerClient.get(
         `${PIONEER_BASE_URL}/api/v1/listUnspent/BTC/${xpub}`,
         { 
           headers: { 'accept': 'application/json' },
@@ -159,7 +270,7 @@ export class PioneerAPI {
       console.log('🔄 Getting change address for', network, 'xpub:', xpub.substring(0, 20) + '...');
       
       // Use REAL Pioneer API endpoint from test-pioneer-live.js
-      const response = await axios.get(
+      const response = await pioneerClient.get(
         `${PIONEER_BASE_URL}/api/v1/getChangeAddress/BTC/${xpub}`,
         { 
           headers: { 'accept': 'application/json' },
@@ -181,7 +292,7 @@ export class PioneerAPI {
       console.log('📡 Transaction hex length:', serializedTx.length);
       console.log('📡 Transaction hex preview:', serializedTx.substring(0, 80) + '...');
       
-      const response = await axios.post(
+      const response = await pioneerClient.post(
         `${PIONEER_BASE_URL}/api/v1/broadcast`,
         {
           networkId,
@@ -311,9 +422,14 @@ export class PortfolioAPI {
         });
       }
       
-      totalValueUsd += valueUsd;
+      if (!isTestnetCaip(item.caip)) {
+        totalValueUsd += valueUsd;
+      }
     }
 
+    // BTC cross-rate for valuing the portfolio in BTC
+    const btcPriceUsd = symbolGroups.get('BTC')?.priceUsd || 0;
+
     // Convert to assets
     for (const [symbol, group] of symbolGroups) {
       assets.push({
@@ -321,6 +437,7 @@ export class PortfolioAPI {
         name: symbol === 'BTC' ? 'Bitcoin' : symbol,
         balance: group.balance.toString(),
         value_usd: group.valueUsd,
+        value_btc: btcPriceUsd > 0 ? group.valueUsd / btcPriceUsd : undefined,
         network_id: 'bitcoin',
         caip: group.caip,
         price_usd: group.priceUsd,
@@ -332,6 +449,8 @@ export class PortfolioAPI {
 
     return {
       total_value_usd: totalValueUsd.toFixed(2),
+      total_value_btc: btcPriceUsd > 0 ? totalValueUsd / btcPriceUsd : undefined,
+      btc_price_usd: btcPriceUsd || undefined,
       assets,
       networks
     };
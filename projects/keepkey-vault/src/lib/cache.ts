@@ -65,6 +65,7 @@ export class CacheAPI {
 
   /**
    * Clear all cached data for a specific device
+   * The data can be brought back with restoreCache for 14 days
    */
   static async clearCache(deviceId: string): Promise<void> {
     try {
@@ -75,6 +76,18 @@ export class CacheAPI {
     }
   }
 
+  /**
+   * Undo clearCache, returning the number of restored entries
+   */
+  static async restoreCache(deviceId: string): Promise<number> {
+    try {
+      return await invoke('restore_device_cache', { deviceId });
+    } catch (error) {
+      console.error('Failed to restore device cache:', error);
+      throw error;
+    }
+  }
+
   /**
    * Get cached public keys for a device directly from SQLite
    * This demonstrates frontend direct database access
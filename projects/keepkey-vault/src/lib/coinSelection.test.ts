@@ -0,0 +1,58 @@
+import { describe, expect, it } from 'vitest';
+import { inputBytes, transactionBytes } from 'coinselect/utils';
+
+import { branchAndBound, groupByAddress, selectCoins } from './coinSelection';
+
+const FEE_RATE = 10;
+const PAY_TO = '3LRW7jeCvQCRdPF8S3yUCfRAx4eqXFmdcr';
+
+const utxo = (vout: number, value: number, extra: Record<string, unknown> = {}) => ({
+  txid: `${vout}`.padStart(64, '0'),
+  vout,
+  value,
+  ...extra,
+});
+
+describe('branchAndBound', () => {
+  it('finds a changeless input set that exactly covers the target', () => {
+    const outputs = [{ address: PAY_TO, value: 50000 }];
+    const target = 50000 + transactionBytes([], outputs) * FEE_RATE;
+    // Two inputs whose effective values add up to the target exactly
+    const fee = inputBytes({}) * FEE_RATE;
+    const utxos = [utxo(0, 30000 + fee), utxo(1, target - 30000 + fee), utxo(2, 90000)];
+
+    const selected = branchAndBound(utxos, target, FEE_RATE);
+    expect(selected?.map(u => u.vout).sort()).toEqual([0, 1]);
+
+    const result = selectCoins('bnb', utxos, outputs, FEE_RATE);
+    expect(result.inputs?.map(u => u.vout).sort()).toEqual([0, 1]);
+    expect(result.outputs).toHaveLength(1);
+  });
+
+  it('returns null when no set lands within the cost of change', () => {
+    expect(branchAndBound([utxo(0, 10_000_000)], 50000, FEE_RATE)).toBeNull();
+  });
+});
+
+describe('selectCoins', () => {
+  it('falls back to regular selection with change when no exact match exists', () => {
+    const outputs = [{ address: PAY_TO, value: 50000 }];
+    const result = selectCoins('bnb', [utxo(0, 10_000_000)], outputs, FEE_RATE);
+    expect(result.inputs).toHaveLength(1);
+    expect(result.outputs).toHaveLength(2);
+  });
+});
+
+describe('groupByAddress', () => {
+  it('groups by address, then by derivation path, then per outpoint', () => {
+    const groups = groupByAddress([
+      utxo(0, 1000, { address: 'bc1qa' }),
+      utxo(1, 1000, { address: 'bc1qa' }),
+      utxo(2, 1000, { path: "m/84'/0'/0'/0/3" }),
+      utxo(3, 1000, { path: "m/84'/0'/0'/0/3" }),
+      utxo(4, 1000),
+      utxo(5, 1000),
+    ]);
+    expect(groups.map(group => group.map(u => u.vout))).toEqual([[0, 1], [2, 3], [4], [5]]);
+  });
+});
@@ -0,0 +1,135 @@
+/*
+    Coin selection strategies for the UTXO tx builder
+
+    Naive selection (largest-first, whatever the indexer returns first) links
+    unrelated addresses and creates needless change. Each strategy here orders
+    or narrows the candidate set, then hands it to coinselect for fee math.
+*/
+
+import coinSelect from 'coinselect';
+import coinSelectAccumulative from 'coinselect/accumulative';
+import coinSelectBlackjack from 'coinselect/blackjack';
+import { finalize, inputBytes, outputBytes, transactionBytes } from 'coinselect/utils';
+
+export type CoinSelectionStrategy = 'bnb' | 'avoid-reuse' | 'oldest-first' | 'default';
+
+export const DEFAULT_COIN_SELECTION_STRATEGY: CoinSelectionStrategy = 'bnb';
+
+const BNB_MAX_TRIES = 100000;
+
+interface SelectionOutput {
+  address?: string;
+  value?: number;
+}
+
+// Sizes come from coinselect's own model, so a set the search accepts is
+// costed the same way when coinselect finalizes it
+const effectiveValue = (utxo: any, feeRate: number) => utxo.value - inputBytes(utxo) * feeRate;
+
+/**
+ * Branch-and-bound search for an input set that pays the target without a change output
+ * (changeless transactions don't reveal which output is ours). Returns null if none exists.
+ */
+export function branchAndBound(utxos: any[], target: number, feeRate: number): any[] | null {
+  const costOfChange = (outputBytes({}) + inputBytes({})) * feeRate;
+  const candidates = utxos
+    .map(utxo => ({ utxo, value: effectiveValue(utxo, feeRate) }))
+    .filter(c => c.value > 0)
+    .sort((a, b) => b.value - a.value);
+
+  // Remaining value after each index, to prune branches that can never reach the target
+  const remaining: number[] = new Array(candidates.length + 1).fill(0);
+  for (let i = candidates.length - 1; i >= 0; i--) {
+    remaining[i] = remaining[i + 1] + candidates[i].value;
+  }
+
+  let tries = 0;
+  let best: number[] | null = null;
+  let bestWaste = Infinity;
+  const selected: number[] = [];
+
+  const search = (index: number, total: number) => {
+    if (++tries > BNB_MAX_TRIES) return;
+    if (total > target + costOfChange) return;
+    if (total >= target) {
+      const waste = total - target;
+      if (waste < bestWaste) {
+        bestWaste = waste;
+        best = [...selected];
+      }
+      return;
+    }
+    if (index >= candidates.length || total + remaining[index] < target) return;
+
+    selected.push(index);
+    search(index + 1, total + candidates[index].value);
+    selected.pop();
+    search(index + 1, total);
+  };
+
+  search(0, 0);
+  return best ? (best as number[]).map(i => candidates[i].utxo) : null;
+}
+
+/**
+ * Group UTXOs by address and spend whole groups, so an address is never left
+ * half-spent (which would tie the leftover to this transaction later).
+ *
+ * Pioneer sometimes omits `address`. Within one account the derivation path
+ * identifies the address just as well, so it is used instead; a UTXO with
+ * neither forms a group of its own.
+ */
+export function groupByAddress(utxos: any[]): any[][] {
+  const groups = new Map<string, any[]>();
+  for (const utxo of utxos) {
+    const key = utxo.address || (utxo.path && `path:${utxo.path}`) || `${utxo.txid}:${utxo.vout}`;
+    groups.set(key, [...(groups.get(key) || []), utxo]);
+  }
+  return Array.from(groups.values());
+}
+
+/**
+ * Pick inputs for a regular (non-max) send using the given strategy
+ */
+export function selectCoins(
+  strategy: CoinSelectionStrategy,
+  utxos: any[],
+  outputs: SelectionOutput[],
+  feeRate: number,
+): { inputs?: any[]; outputs?: SelectionOutput[]; fee: number } {
+  switch (strategy) {
+    case 'bnb': {
+      const amount = outputs.reduce((sum, o) => sum + (o.value || 0), 0);
+      const target = amount + transactionBytes([], outputs) * feeRate;
+      const exact = branchAndBound(utxos, target, feeRate);
+      if (exact) {
+        const result = coinSelectBlackjack(exact, outputs, feeRate);
+        if (result.inputs && result.outputs) return result;
+      }
+      return coinSelect(utxos, outputs, feeRate);
+    }
+    case 'avoid-reuse': {
+      // Add whole address groups, smallest first, so as few addresses as possible get linked
+      const groups = groupByAddress(utxos).sort(
+        (a, b) => a.reduce((s, u) => s + u.value, 0) - b.reduce((s, u) => s + u.value, 0),
+      );
+      const amount = outputs.reduce((sum, o) => sum + (o.value || 0), 0);
+      const single = groups.find(group => group.reduce((s, u) => s + u.value, 0) >= amount);
+      const ordered = single ? [single, ...groups.filter(g => g !== single)] : groups;
+
+      const inputs: any[] = [];
+      for (const group of ordered) {
+        inputs.push(...group);
+        const result = finalize(inputs, outputs, feeRate);
+        if (result.inputs && result.outputs) return result;
+      }
+      return { fee: 0 };
+    }
+    case 'oldest-first': {
+      const byAge = [...utxos].sort((a, b) => (b.confirmations || 0) - (a.confirmations || 0));
+      return coinSelectAccumulative(byAge, outputs, feeRate);
+    }
+    default:
+      return coinSelect(utxos, outputs, feeRate);
+  }
+}
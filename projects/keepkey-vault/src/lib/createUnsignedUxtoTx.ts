@@ -1,7 +1,8 @@
 
+import { invoke } from '@tauri-apps/api/core';
 import { bip32ToAddressNList } from '@pioneer-platform/pioneer-coins';
-import coinSelect from 'coinselect';
 import coinSelectSplit from 'coinselect/split';
+import { CoinSelectionStrategy, DEFAULT_COIN_SELECTION_STRATEGY, selectCoins } from './coinSelection';
 
 export async function createUnsignedUxtoTx(
   caip: string,
@@ -12,6 +13,8 @@ export async function createUnsignedUxtoTx(
   pioneer: any,
   keepKeySdk: any,
   isMax: boolean, // Added isMax parameter
+  deviceId: string, // Device being spent from, for its frozen UTXOs
+  strategy: CoinSelectionStrategy = DEFAULT_COIN_SELECTION_STRATEGY,
 ): Promise<any> {
   let tag = ' | createUnsignedUxtoTx | ';
 
@@ -63,6 +66,17 @@ export async function createUnsignedUxtoTx(
       }
       utxos.push(...utxosResp);
     }
+    // Frozen UTXOs (coin control) are never spent; if they can't be read, don't build the tx
+    const frozen = new Set(await invoke<string[]>('get_frozen_utxos', { deviceId }).catch((e) => {
+      throw Error(`Failed to read frozen UTXOs: ${e}`);
+    }));
+    const frozenCount = utxos.length;
+    for (let i = utxos.length - 1; i >= 0; i--) {
+      if (frozen.has(`${utxos[i].txid}:${utxos[i].vout}`)) utxos.splice(i, 1);
+    }
+    if (frozenCount !== utxos.length) {
+      console.log(`${tag} Skipping ${frozenCount - utxos.length} frozen UTXOs`);
+    }
     if (!utxos || utxos.length === 0) throw Error('No UTXOs found');
 
     // Debug: Log the UTXOs to see their structure
@@ -161,8 +175,8 @@ export async function createUnsignedUxtoTx(
       console.log(`${tag} Using coinSelectSplit for max send`);
       result = coinSelectSplit(utxos, [{ address: to }], effectiveFeeRate);
     } else {
-      console.log(`${tag} Using coinSelect for regular send`);
-      result = coinSelect(utxos, [{ address: to, value: amount }], effectiveFeeRate);
+      console.log(`${tag} Using ${strategy} coin selection for regular send`);
+      result = selectCoins(strategy, utxos, [{ address: to, value: amount }], effectiveFeeRate);
     }
     
     console.log(`${tag} Coinselect result:`, {
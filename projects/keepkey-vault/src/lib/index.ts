@@ -1,3 +1,4 @@
 // Re-export all services from their respective files
 export * from "./api";
-export * from "./cache";
\ No newline at end of file
+export * from "./cache";
+export * from "./coinSelection";
@@ -42,4 +42,20 @@ declare module 'coinselect/split' {
 
   function coinSelectSplit(utxos: Input[], outputs: Output[], feeRate: number): Result;
   export default coinSelectSplit;
-} 
\ No newline at end of file
+} 
+declare module 'coinselect/accumulative' {
+  function coinSelectAccumulative(utxos: any[], outputs: any[], feeRate: number): { inputs?: any[]; outputs?: any[]; fee: number };
+  export default coinSelectAccumulative;
+}
+
+declare module 'coinselect/blackjack' {
+  function coinSelectBlackjack(utxos: any[], outputs: any[], feeRate: number): { inputs?: any[]; outputs?: any[]; fee: number };
+  export default coinSelectBlackjack;
+}
+
+declare module 'coinselect/utils' {
+  export function finalize(inputs: any[], outputs: any[], feeRate: number): { inputs?: any[]; outputs?: any[]; fee: number };
+  export function inputBytes(input: any): number;
+  export function outputBytes(output: any): number;
+  export function transactionBytes(inputs: any[], outputs: any[]): number;
+}
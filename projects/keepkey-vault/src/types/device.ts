@@ -8,6 +8,21 @@ export interface BootloaderCheck {
   needsUpdate: boolean
 }
 
+export interface PreflightCheck {
+  name: string
+  passed: boolean
+  detail: string
+}
+
+export interface BootloaderPreflight {
+  deviceId: string
+  targetVersion: string
+  passed: boolean
+  checks: PreflightCheck[]
+  confirmToken?: string
+  tokenExpiresAt?: number
+}
+
 export interface FirmwareCheck {
   currentVersion: string
   latestVersion: string
@@ -57,4 +72,32 @@ export interface DeviceFeatures {
   wipeCodeProtection: boolean
   autoLockDelayMs?: number
   policies: string[]
-} 
\ No newline at end of file
+} 
+// Stable codes for device failures, matching DeviceErrorCode in src-tauri/src/device/errors.rs
+export type DeviceErrorCode =
+  | 'DEVICE_NOT_FOUND'
+  | 'DEVICE_BUSY'
+  | 'PIN_REQUIRED'
+  | 'PIN_INVALID'
+  | 'PASSPHRASE_REQUIRED'
+  | 'ACTION_CANCELLED'
+  | 'TIMEOUT'
+  | 'TRANSPORT_ERROR'
+  | 'FIRMWARE_TOO_OLD'
+  | 'NOT_INITIALIZED'
+  | 'INVALID_REQUEST'
+  | 'DEVICE_FAILURE'
+  | 'DEVICE_ERROR'
+
+export interface DeviceError {
+  code: DeviceErrorCode
+  message: string
+}
+
+// Commands that return structured errors reject with a DeviceError; older ones still reject with a string
+export const toDeviceError = (err: unknown): DeviceError => {
+  if (err && typeof err === 'object' && 'code' in err && 'message' in err) {
+    return err as DeviceError
+  }
+  return { code: 'DEVICE_ERROR', message: err instanceof Error ? err.message : String(err) }
+}
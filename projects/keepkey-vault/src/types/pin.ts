@@ -5,6 +5,7 @@ export interface PinCreationSession {
   session_id: string;
   current_step: PinStep;
   is_active: boolean;
+  lockout?: PinLockout | null;
 }
 
 export enum PinStep {
@@ -31,4 +32,12 @@ export interface PinMatrixState {
   positions: PinPosition[];
   maxLength: number;
   isScrambled: boolean;
-} 
\ No newline at end of file
+} 
+// Estimated firmware backoff after rejected PINs (payload of pin:lockout-countdown)
+export interface PinLockout {
+  deviceId: string;
+  failedAttempts: number;
+  waitSecs: number;
+  remainingSecs: number;
+  retryAt: number;
+}
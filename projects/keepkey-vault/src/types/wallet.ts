@@ -3,10 +3,27 @@ export interface Asset {
   name: string;
   balance: string;
   value_usd: number;
+  // Value in BTC at the current BTC price, when one is known
+  value_btc?: number;
   network_id: string;
   caip: string;
   price_usd?: number;
   change_24h?: number;
+  // Set when balance reconciliation is on and this asset was checked against a second provider
+  reconciliation?: AssetReconciliation;
+}
+
+// Balance of an asset according to a second provider (the reconcile_portfolio command)
+export interface AssetReconciliation {
+  caip: string;
+  accounts: number;
+  primarySats: number;
+  provider: string;
+  balance: number | null;
+  differenceSats: number | null;
+  toleranceSats: number;
+  mismatch: boolean;
+  error: string | null;
 }
 
 export interface Network {
@@ -19,6 +36,14 @@ export interface Network {
 
 export interface Portfolio {
   total_value_usd: string;
+  // Whole portfolio valued in BTC, when a BTC price is known
+  total_value_btc?: number;
+  btc_price_usd?: number;
   assets: Asset[];
   networks: Network[];
-} 
\ No newline at end of file
+  // Networks whose latest fetch failed; their figures are from the last successful refresh
+  stale_networks?: string[];
+}
+
+// Unit portfolio totals are shown in (the portfolio_denomination preference)
+export type Denomination = 'usd' | 'btc' | 'sats';